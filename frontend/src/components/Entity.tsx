@@ -31,6 +31,8 @@ function getMaterialProps(material?: string, color?: string) {
       return { ...base, metalness: 0.0, roughness: 0.95 }
     case 'Ice':
       return { ...base, metalness: 0.1, roughness: 0.1, transparent: true, opacity: 0.8 }
+    case 'Water':
+      return { ...base, metalness: 0.1, roughness: 0.1, transparent: true, opacity: 0.5 }
     case 'ForceField':
       return { ...base, transparent: true, opacity: 0.3, emissive: color, emissiveIntensity: 0.5 }
     case 'Grass':
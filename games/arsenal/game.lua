@@ -3,6 +3,7 @@
 
 local RunService = game:GetService("RunService")
 local Players = game:GetService("Players")
+local RewardService = game:GetService("RewardService")
 
 --------------------------------------------------------------------------------
 -- CONFIGURATION
@@ -50,6 +51,9 @@ end
 --------------------------------------------------------------------------------
 
 local function createArena()
+    -- Arena geometry is tagged Static: PathfindingService (and NavigateTo) route around it
+    -- and agents fetch it once from /map instead of every observation
+
     -- Floor
     local floor = Instance.new("Part")
     floor.Name = "Floor"
@@ -57,6 +61,7 @@ local function createArena()
     floor.Position = Vector3.new(0, -1, 0)
     floor.Anchored = true
     floor.Color = Color3.fromRGB(180, 180, 185)
+    floor:AddTag("Static")
     floor.Parent = Workspace
 
     -- Walls (invisible barriers)
@@ -74,6 +79,7 @@ local function createArena()
         wall.Anchored = true
         wall.Transparency = 1
         wall.CanCollide = true
+        wall:AddTag("Static")
         wall.Parent = Workspace
     end
 
@@ -84,6 +90,7 @@ local function createArena()
     centerPlatform.Position = Vector3.new(0, 1, 0)
     centerPlatform.Anchored = true
     centerPlatform.Color = Color3.fromRGB(100, 140, 180)
+    centerPlatform:AddTag("Static")
     centerPlatform.Parent = Workspace
 
     -- Corner platforms
@@ -100,6 +107,7 @@ local function createArena()
         platform.Position = pos
         platform.Anchored = true
         platform.Color = Color3.fromRGB(180, 120, 120)
+        platform:AddTag("Static")
         platform.Parent = Workspace
 
         -- Platform support
@@ -109,6 +117,7 @@ local function createArena()
         pSupport.Position = pos - Vector3.new(0, 6, 0)
         pSupport.Anchored = true
         pSupport.Color = Color3.fromRGB(150, 100, 100)
+        pSupport:AddTag("Static")
         pSupport.Parent = Workspace
     end
 
@@ -130,6 +139,7 @@ local function createArena()
         cover.Position = pos
         cover.Anchored = true
         cover.Color = Color3.fromRGB(180, 180, 190)
+        cover:AddTag("Static")
         cover.Parent = Workspace
     end
 
@@ -147,6 +157,7 @@ local function createArena()
         bridge.Size = data[2]
         bridge.Anchored = true
         bridge.Color = Color3.fromRGB(130, 150, 180)
+        bridge:AddTag("Static")
         bridge.Parent = Workspace
     end
 
@@ -284,6 +295,8 @@ local function onPlayerKilled(killer, victim)
     -- Update stats
     killer:SetAttribute("Kills", killer:GetAttribute("Kills") + 1)
     victim:SetAttribute("Deaths", victim:GetAttribute("Deaths") + 1)
+    RewardService:AddReward(killer, 1, "kill")
+    RewardService:AddReward(victim, -1, "death")
 
     print("[KILL] " .. killer.Name .. " -> " .. victim.Name)
 
@@ -4,6 +4,7 @@
 
 local RunService = game:GetService("RunService")
 local Players = game:GetService("Players")
+local RewardService = game:GetService("RewardService")
 
 --------------------------------------------------------------------------------
 -- CONFIGURATION
@@ -448,6 +449,10 @@ local function checkFinishLine()
 
                 print("[FINISH] " .. player.Name .. " finished in position #" .. finishCount)
 
+                -- Earlier finishes score higher; first place wins
+                RewardService:AddReward(player, MAX_PLAYERS - finishCount + 1, "finish")
+                RewardService:EndEpisode(player, finishCount == 1 and "win" or "finished")
+
                 -- Update all players with finish count
                 for _, p in ipairs(Players:GetPlayers()) do
                     p:SetAttribute("PlayersFinished", finishCount)
@@ -602,6 +607,7 @@ local function updateRacing(dt)
             if playerStates[player.UserId] == "racing" then
                 playerStates[player.UserId] = "dnf"
                 player:SetAttribute("Status", "dnf")
+                RewardService:EndEpisode(player, "dnf")
             end
         end
 
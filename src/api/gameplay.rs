@@ -26,6 +26,7 @@ use crate::db::models::Game;
 use crate::game::{
     self,
     instance::{MapInfo, PlayerObservation, SpectatorObservation},
+    lockstep::{StepRequest, StepResult},
     GameManagerHandle,
 };
 
@@ -118,6 +119,12 @@ pub fn routes(
         .route("/games/{id}/input", post(send_input))
         .layer(GovernorLayer::new(governor_conf));
 
+    // LOCKSTEP ROUTES: Require auth, not rate-limited (each call blocks until
+    // the round runs, so the simulation itself paces the caller)
+    let lockstep_routes = Router::new()
+        .route("/games/{id}/step", post(step))
+        .route("/games/{id}/reset", post(reset));
+
     // PUBLIC ROUTES: No auth, no rate limit
     // Add new public endpoints here
     let public_routes = Router::new()
@@ -130,6 +137,7 @@ pub fn routes(
     // DO NOT add routes here - use agent_routes or public_routes above
     Router::new()
         .merge(agent_routes)
+        .merge(lockstep_routes)
         .merge(public_routes)
         .with_state(state)
 }
@@ -293,6 +301,81 @@ async fn send_input(
     Ok(Json(observation))
 }
 
+/// POST /games/{id}/step - Submit actions for a lockstep instance and advance it
+/// Returns once every agent in the instance has stepped and the ticks have run
+async fn step(
+    State(state): State<GameplayState>,
+    Path(game_id): Path<Uuid>,
+    headers: HeaderMap,
+    Json(request): Json<StepRequest>,
+) -> Result<Json<StepResult>, (StatusCode, String)> {
+    let api_key = extract_api_key(&headers)
+        .ok_or((StatusCode::UNAUTHORIZED, "Missing Authorization header".to_string()))?;
+
+    let agent_id = get_agent_id_from_api_key(&api_key, &state.api_key_cache, &state.pool).await?;
+
+    // The last agent to submit runs the whole round, so keep it off the async workers
+    let game_manager = state.game_manager.clone();
+    let result = tokio::task::spawn_blocking(move || game::submit_step(&game_manager, game_id, agent_id, request))
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .map_err(|e| (StatusCode::BAD_REQUEST, e))?
+        .await
+        .map_err(|_| (StatusCode::CONFLICT, "Step cancelled before the round ran".to_string()))?;
+
+    Ok(Json(result))
+}
+
+/// Body of POST /games/{id}/reset
+#[derive(Deserialize, Default)]
+pub struct ResetRequest {
+    /// Exposed to the game script as game.LaunchParams
+    #[serde(default)]
+    pub params: Option<serde_json::Value>,
+}
+
+/// POST /games/{id}/reset - Restart the episode in the agent's lockstep instance
+/// Returns the agent's observation in the fresh world
+async fn reset(
+    State(state): State<GameplayState>,
+    Path(game_id): Path<Uuid>,
+    headers: HeaderMap,
+    payload: Option<Json<ResetRequest>>,
+) -> Result<Json<PlayerObservation>, (StatusCode, String)> {
+    let api_key = extract_api_key(&headers)
+        .ok_or((StatusCode::UNAUTHORIZED, "Missing Authorization header".to_string()))?;
+
+    let agent_id = get_agent_id_from_api_key(&api_key, &state.api_key_cache, &state.pool).await?;
+    let Json(payload) = payload.unwrap_or_default();
+
+    let instance_id = game::get_player_instance(&state.game_manager, agent_id, game_id)
+        .ok_or((StatusCode::BAD_REQUEST, "Not in any instance of this game".to_string()))?;
+
+    // Resetting a shared realtime match would pull the rug out from other players
+    let lockstep = state
+        .game_manager
+        .instances
+        .get(&instance_id)
+        .is_some_and(|h| h.read().lockstep);
+    if !lockstep {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "Reset is only available in lockstep instances".to_string(),
+        ));
+    }
+
+    let game_manager = state.game_manager.clone();
+    tokio::task::spawn_blocking(move || game::reset_instance(&game_manager, instance_id, payload.params))
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+
+    let observation = game::get_observation(&state.game_manager, game_id, agent_id)
+        .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+
+    Ok(Json(observation))
+}
+
 /// WebSocket endpoint for spectating game state in real-time
 async fn spectate_ws(
     State(state): State<GameplayState>,
@@ -334,6 +334,16 @@ async fn update_game(
 // Join / Leave
 // =============================================================================
 
+#[derive(Deserialize, Default)]
+struct JoinGameRequest {
+    /// Join (or create) an instance that advances only through POST /games/{id}/step
+    #[serde(default)]
+    lockstep: bool,
+    /// Join (or create) a deterministic instance with this seed
+    #[serde(default)]
+    seed: Option<u64>,
+}
+
 #[derive(Serialize)]
 struct JoinGameResponse {
     success: bool,
@@ -345,7 +355,10 @@ async fn join_game(
     State(state): State<GamesState>,
     Path(game_id): Path<Uuid>,
     headers: HeaderMap,
+    payload: Option<Json<JoinGameRequest>>,
 ) -> Result<Json<JoinGameResponse>, (StatusCode, String)> {
+    let Json(payload) = payload.unwrap_or_default();
+
     let api_key = extract_api_key(&headers)
         .ok_or((StatusCode::UNAUTHORIZED, "Missing Authorization header".to_string()))?;
 
@@ -367,11 +380,15 @@ async fn join_game(
     let max_players = db_game.max_players as u32;
 
     // Find or create instance with capacity
-    let result = game::find_or_create_instance(
+    let result = game::find_or_create_instance_with_options(
         &state.game_manager,
         game_id,
         max_players,
         db_game.script_code.as_deref(),
+        game::InstanceOptions {
+            seed: payload.seed,
+            lockstep: payload.lockstep,
+        },
     );
 
     // Join the instance
@@ -4,12 +4,13 @@ mod chat;
 mod games;
 mod gameplay;
 
-use axum::{routing::get, Router};
+use axum::{routing::get, Json, Router};
 use dashmap::DashMap;
 use sqlx::PgPool;
 use std::sync::Arc;
 use uuid::Uuid;
 
+use crate::game::lua::api_dump::{self, ApiDump};
 use crate::game::GameManagerHandle;
 use crate::r2::R2Client;
 
@@ -26,6 +27,7 @@ pub fn routes(
 
     let mut router = Router::new()
         .route("/health", get(health))
+        .route("/api-dump", get(get_api_dump))
         .merge(agents::routes(pool.clone(), api_key_cache.clone()))
         .merge(games::routes(
             pool.clone(),
@@ -55,3 +57,8 @@ pub fn routes(
 async fn health() -> &'static str {
     r#"{"status":"ok"}"#
 }
+
+/// The scripting API, as `clawblox api-dump` prints it
+async fn get_api_dump() -> Json<ApiDump> {
+    Json(api_dump::api_dump())
+}
@@ -17,7 +17,8 @@ use serde::{Deserialize, Serialize};
 use std::io::Write;
 use std::net::SocketAddr;
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::Duration;
 use tokio::time::interval;
@@ -28,13 +29,18 @@ use uuid::Uuid;
 use libc;
 
 use include_dir::{include_dir, Dir};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
 use tower_http::services::ServeDir;
 
 use clawblox::config::WorldConfig;
 use clawblox::game::{
-    self, find_or_create_instance,
+    self, create_instance,
     instance::{ErrorMode, PlayerObservation, SpectatorObservation},
-    GameManager, GameManagerHandle,
+    lockstep::{StepRequest, StepResult},
+    replay::{self, Replay, Replayer},
+    restore_instance,
+    snapshot::InstanceSnapshot,
+    GameManager, GameManagerHandle, GameManagerState, InstanceOptions,
 };
 
 static DOCS_DIR: Dir = include_dir!("$CARGO_MANIFEST_DIR/docs");
@@ -65,6 +71,32 @@ enum Commands {
         /// Run as daemon (internal use only)
         #[arg(long, hide = true)]
         daemon: bool,
+        /// Record the session to a replay file
+        #[arg(long)]
+        record: Option<PathBuf>,
+        /// Advance only when every joined agent calls POST /step (for training)
+        #[arg(long)]
+        lockstep: bool,
+        /// Keep a snapshot of the running instance in this file (rewritten every few seconds and on exit)
+        #[arg(long)]
+        snapshot: Option<PathBuf>,
+        /// Start from a snapshot written by --snapshot instead of a fresh instance
+        #[arg(long)]
+        restore: Option<PathBuf>,
+    },
+    /// Re-simulate a recorded session and serve it for spectating
+    Replay {
+        /// Replay file written by `clawblox run --record`
+        file: PathBuf,
+        /// Path to the game directory the session was recorded from
+        #[arg(long, default_value = ".")]
+        path: PathBuf,
+        /// Port to serve the spectate endpoints on
+        #[arg(short, long, default_value = "8080")]
+        port: u16,
+        /// Only check the re-simulation against the recorded keyframes, then exit
+        #[arg(long)]
+        verify: bool,
     },
     /// Log in or register an account on clawblox.com
     Login {
@@ -97,6 +129,15 @@ enum Commands {
     },
     /// Fetch latest engine docs from GitHub into ./docs/
     Docs,
+    /// Print the scripting API as JSON, or as Luau type definitions
+    ApiDump {
+        /// Emit a luau-lsp definitions file instead of JSON
+        #[arg(long)]
+        luau: bool,
+        /// Write to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
 }
 
 fn main() {
@@ -104,7 +145,21 @@ fn main() {
 
     match cli.command {
         Commands::Init { name } => init_project(name),
-        Commands::Run { path, port, daemon } => run_game(path, port, daemon),
+        Commands::Run {
+            path,
+            port,
+            daemon,
+            record,
+            lockstep,
+            snapshot,
+            restore,
+        } => run_game(path, port, daemon, record, lockstep, snapshot, restore),
+        Commands::Replay {
+            file,
+            path,
+            port,
+            verify,
+        } => replay_game(file, path, port, verify),
         Commands::Login {
             name,
             api_key,
@@ -117,6 +172,7 @@ fn main() {
         } => deploy_game(path, api_key, server),
         Commands::Install { target: _ } => install_cli(),
         Commands::Docs => fetch_docs(),
+        Commands::ApiDump { luau, output } => api_dump(luau, output),
     }
 }
 
@@ -220,6 +276,28 @@ fn fetch_docs() {
     }
 }
 
+// =============================================================================
+// API Dump Command
+// =============================================================================
+
+fn api_dump(luau: bool, output: Option<PathBuf>) {
+    let contents = if luau {
+        game::lua::api_dump::luau_definitions()
+    } else {
+        let dump = game::lua::api_dump::api_dump();
+        serde_json::to_string_pretty(&dump).expect("Failed to serialize API dump") + "\n"
+    };
+    match output {
+        Some(path) => {
+            if let Err(e) = std::fs::write(&path, contents) {
+                eprintln!("Error: Failed to write {}: {}", path.display(), e);
+                std::process::exit(1);
+            }
+        }
+        None => print!("{}", contents),
+    }
+}
+
 // =============================================================================
 // Init Command
 // =============================================================================
@@ -338,6 +416,7 @@ name = "{}"
 description = "A new Clawblox game"
 max_players = 8
 game_type = "lua"
+# seed = 1234  # uncomment for deterministic simulation
 
 [scripts]
 main = "main.lua"
@@ -497,6 +576,25 @@ Move to a position on the map.
     std::fs::create_dir_all(project_dir.join("assets"))
         .expect("Failed to create assets directory");
 
+    // Type definitions for luau-lsp, so editors can autocomplete the API
+    std::fs::write(
+        project_dir.join("clawblox.d.luau"),
+        game::lua::api_dump::luau_definitions(),
+    )
+    .expect("Failed to create clawblox.d.luau");
+    let vscode_dir = project_dir.join(".vscode");
+    let settings_path = vscode_dir.join("settings.json");
+    if !settings_path.exists() {
+        let settings = r#"{
+    "luau-lsp.platform.type": "standard",
+    "luau-lsp.types.definitionFiles": ["clawblox.d.luau"],
+    "files.associations": { "*.lua": "luau" }
+}
+"#;
+        std::fs::create_dir_all(&vscode_dir).expect("Failed to create .vscode directory");
+        std::fs::write(&settings_path, settings).expect("Failed to create .vscode/settings.json");
+    }
+
     // Create .gitignore
     let gitignore = ".clawblox/\n.clawblox.log\n";
     let gitignore_path = project_dir.join(".gitignore");
@@ -643,10 +741,9 @@ fn deploy_game(path: PathBuf, api_key_override: Option<String>, server_override:
         std::process::exit(1);
     });
 
-    // Read Lua script
-    let script_path = path.join(&config.scripts.main);
-    let script_code = std::fs::read_to_string(&script_path).unwrap_or_else(|e| {
-        eprintln!("Error reading {}: {}", script_path.display(), e);
+    // Read the Lua script, with the game's other Lua files bundled in as modules
+    let script_code = config.load_script(&path).unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
         std::process::exit(1);
     });
 
@@ -926,7 +1023,15 @@ fn write_pid_file_for(path: &Path, pid: u32) {
     let _ = std::fs::write(path, pid.to_string());
 }
 
-fn run_game(path: PathBuf, port: u16, daemon: bool) {
+fn run_game(
+    path: PathBuf,
+    port: u16,
+    daemon: bool,
+    record: Option<PathBuf>,
+    lockstep: bool,
+    snapshot: Option<PathBuf>,
+    restore: Option<PathBuf>,
+) {
     let path = std::fs::canonicalize(&path).unwrap_or_else(|_| {
         eprintln!("Error: Path '{}' does not exist", path.display());
         std::process::exit(1);
@@ -947,8 +1052,23 @@ fn run_game(path: PathBuf, port: u16, daemon: bool) {
         });
 
         let exe = std::env::current_exe().expect("Failed to get current executable path");
-        let child = std::process::Command::new(exe)
-            .args(["run", &path.to_string_lossy(), "--port", &port.to_string(), "--daemon"])
+        let mut command = std::process::Command::new(exe);
+        command.args(["run", &path.to_string_lossy(), "--port", &port.to_string(), "--daemon"]);
+        // Resolve file arguments against our cwd so the daemon uses the files the user named
+        let cwd = std::env::current_dir().unwrap_or_default();
+        if let Some(record) = &record {
+            command.arg("--record").arg(cwd.join(record));
+        }
+        if lockstep {
+            command.arg("--lockstep");
+        }
+        if let Some(snapshot) = &snapshot {
+            command.arg("--snapshot").arg(cwd.join(snapshot));
+        }
+        if let Some(restore) = &restore {
+            command.arg("--restore").arg(cwd.join(restore));
+        }
+        let child = command
             .stdout(log_file)
             .stderr(log_file_stderr)
             .stdin(std::process::Stdio::null())
@@ -975,10 +1095,9 @@ fn run_game(path: PathBuf, port: u16, daemon: bool) {
         std::process::exit(1);
     });
 
-    // Load the Lua script
-    let script_path = path.join(&config.scripts.main);
-    let script = std::fs::read_to_string(&script_path).unwrap_or_else(|e| {
-        eprintln!("Error loading {}: {}", script_path.display(), e);
+    // Load the Lua script and its modules
+    let script = config.load_script(&path).unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
         std::process::exit(1);
     });
 
@@ -997,29 +1116,96 @@ fn run_game(path: PathBuf, port: u16, daemon: bool) {
     // Create game manager without database (Halt mode: stop on first Lua error)
     let (game_manager, game_handle) = GameManager::new_without_db(60, ErrorMode::Halt);
 
-    // Use a random game_id for this session
-    let game_id = Uuid::new_v4();
+    let sessions: Arc<DashMap<String, (Uuid, String)>> = Arc::new(DashMap::new());
+
+    let (game_id, instance_id, lockstep) = match &restore {
+        Some(restore_path) => {
+            let mut saved = InstanceSnapshot::from_file(restore_path).unwrap_or_else(|e| {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            });
+            if replay::script_hash(&saved.script) != replay::script_hash(&script) {
+                println!("Warning: {} has changed since the snapshot; restoring with the current script", config.scripts.main);
+            }
+            saved.script = script.clone();
+
+            let instance_id = restore_instance(&game_handle, &saved).unwrap_or_else(|e| {
+                eprintln!("Error: could not restore {}: {}", restore_path.display(), e);
+                std::process::exit(1);
+            });
+            println!("Restored {} at tick {}", restore_path.display(), saved.tick);
+            // Restored agents keep playing with their agent_id as session token
+            for player in &saved.players {
+                sessions.insert(player.agent_id.to_string(), (player.agent_id, player.name.clone()));
+                println!("  {} (session {})", player.name, player.agent_id);
+            }
+            if saved.lockstep {
+                println!("Lockstep: the game advances when every joined agent calls POST /step");
+            }
+            (saved.game_id, instance_id, saved.lockstep)
+        }
+        None => {
+            // Use a random game_id for this session
+            let game_id = Uuid::new_v4();
 
-    // Create the instance
-    let result = find_or_create_instance(&game_handle, game_id, config.max_players, Some(&script));
-    let instance_id = result.instance_id;
+            // Create the instance (deterministic when world.toml sets a seed)
+            if let Some(seed) = config.seed {
+                println!("Seed: {} (deterministic mode)", seed);
+            }
+            if lockstep {
+                println!("Lockstep: the game advances when every joined agent calls POST /step");
+            }
+            let options = InstanceOptions {
+                seed: config.seed,
+                lockstep,
+            };
+            let instance_id = create_instance(&game_handle, game_id, config.max_players, Some(&script), options);
+            (game_id, instance_id, lockstep)
+        }
+    };
     println!("Instance: {}", instance_id);
 
+    if let Some(record_path) = &record {
+        let handle = game_handle.instances.get(&instance_id).map(|h| h.clone());
+        let result = match handle {
+            Some(handle) => handle.write().start_recording(record_path),
+            None => Err(std::io::Error::other("instance not found")),
+        };
+        match result {
+            Ok(()) => println!("Recording to {}", record_path.display()),
+            Err(e) => eprintln!("Error: could not record to {}: {}", record_path.display(), e),
+        }
+    }
+
     // Run game loop in background thread
     thread::spawn(move || {
         game_manager.run();
     });
 
+    // Keep the watcher alive for the lifetime of the server
+    let _watcher = watch_scripts(&path, game_handle.clone(), instance_id);
+
+    if let Some(snapshot_path) = &snapshot {
+        println!("Snapshotting to {}", snapshot_path.display());
+        let handle = game_handle.clone();
+        let snapshot_path = snapshot_path.clone();
+        thread::spawn(move || loop {
+            thread::sleep(LOCAL_SNAPSHOT_INTERVAL);
+            write_local_snapshot(&handle, instance_id, &snapshot_path);
+        });
+    }
+
     // Start HTTP server
     let rt = tokio::runtime::Runtime::new().unwrap();
     rt.block_on(async {
         let state = LocalState {
             game_id,
             instance_id,
-            game_handle,
+            game_handle: game_handle.clone(),
             skill_md,
-            sessions: Arc::new(DashMap::new()),
+            sessions,
             log_file: path.join(".clawblox.log"),
+            playback: None,
         };
 
         let cors = CorsLayer::new()
@@ -1032,6 +1218,8 @@ fn run_game(path: PathBuf, port: u16, daemon: bool) {
             .route("/spectate/ws", get(local_spectate_ws))
             .route("/join", post(local_join))
             .route("/input", post(local_input))
+            .route("/step", post(local_step))
+            .route("/reset", post(local_reset))
             .route("/observe", get(local_observe))
             .route("/skill.md", get(local_skill))
             .nest_service("/assets", ServeDir::new(path.join("assets")))
@@ -1047,6 +1235,10 @@ fn run_game(path: PathBuf, port: u16, daemon: bool) {
         println!("  POST /join?name=X  - Join game, returns session token");
         println!("  POST /input        - Send input (requires X-Session header)");
         println!("  GET  /observe      - Player observation (requires X-Session header)");
+        if lockstep {
+            println!("  POST /step         - Submit actions and advance N ticks (requires X-Session header)");
+        }
+        println!("  POST /reset        - Restart the episode, keeping players (requires X-Session header)");
         println!("  GET  /skill.md     - Game skill definition");
 
         let listener = tokio::net::TcpListener::bind(addr).await.unwrap_or_else(|e| {
@@ -1074,12 +1266,266 @@ fn run_game(path: PathBuf, port: u16, daemon: bool) {
         // Also clean up here in case shutdown_signal didn't fire
         delete_pid_file(&pid_file);
     });
+
+    if let Some(snapshot_path) = &snapshot {
+        write_local_snapshot(&game_handle, instance_id, snapshot_path);
+    }
+}
+
+/// How long to wait for more file events before reloading, so one save
+/// (often several writes) triggers one reload
+const RELOAD_DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Whether a change to this file should reload the game script
+fn is_script_file(path: &Path) -> bool {
+    matches!(path.extension().and_then(|e| e.to_str()), Some("lua" | "luau"))
+        || path.file_name().is_some_and(|name| name == "world.toml")
+}
+
+/// Watches the game directory and reloads the instance's script when a script
+/// file or world.toml changes. Connected agents keep their sessions; their
+/// players are re-added to the new world. A script that fails to compile is
+/// reported and the running one keeps going.
+fn watch_scripts(path: &Path, game_handle: GameManagerHandle, instance_id: Uuid) -> Option<RecommendedWatcher> {
+    let (tx, rx) = std::sync::mpsc::channel::<notify::Result<notify::Event>>();
+    let mut watcher = match notify::recommended_watcher(tx) {
+        Ok(watcher) => watcher,
+        Err(e) => {
+            eprintln!("Warning: could not watch for script changes: {}", e);
+            return None;
+        }
+    };
+    if let Err(e) = watcher.watch(path, RecursiveMode::Recursive) {
+        eprintln!("Warning: could not watch {}: {}", path.display(), e);
+        return None;
+    }
+    println!("Watching {} for script changes", path.display());
+
+    let path = path.to_path_buf();
+    thread::spawn(move || {
+        let changed = |event: notify::Result<notify::Event>| {
+            event.is_ok_and(|event| {
+                !matches!(event.kind, notify::EventKind::Access(_)) && event.paths.iter().any(|p| is_script_file(p))
+            })
+        };
+        while let Ok(event) = rx.recv() {
+            if !changed(event) {
+                continue;
+            }
+            // Swallow the rest of this save
+            thread::sleep(RELOAD_DEBOUNCE);
+            while rx.try_recv().is_ok() {}
+            reload_script(&path, &game_handle, instance_id);
+        }
+    });
+    Some(watcher)
+}
+
+fn reload_script(path: &Path, game_handle: &GameManagerHandle, instance_id: Uuid) {
+    let config = match WorldConfig::from_game_dir(path) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("[Reload] Error loading world.toml, keeping the running script: {}", e);
+            return;
+        }
+    };
+    let script = match config.load_script(path) {
+        Ok(script) => script,
+        Err(e) => {
+            eprintln!("[Reload] {}, keeping the running script", e);
+            return;
+        }
+    };
+
+    match game::reload_instance(game_handle, instance_id, &script) {
+        Ok(()) => println!("[Reload] Reloaded {}", config.scripts.main),
+        Err(e) => eprintln!("[Reload] {}: {}", config.scripts.main, e),
+    }
+}
+
+/// How often `clawblox run --snapshot` rewrites its snapshot
+const LOCAL_SNAPSHOT_INTERVAL: Duration = Duration::from_secs(5);
+
+fn write_local_snapshot(game_handle: &GameManagerHandle, instance_id: Uuid, path: &Path) {
+    let Some(handle) = game_handle.instances.get(&instance_id).map(|h| h.clone()) else {
+        return;
+    };
+    let snapshot = handle.read().snapshot();
+    if let Err(e) = snapshot.and_then(|s| s.write_to(path)) {
+        eprintln!("[Snapshot] Failed to write {}: {}", path.display(), e);
+    }
+}
+
+fn replay_game(file: PathBuf, path: PathBuf, port: u16, verify: bool) {
+    let replay = Replay::from_file(&file).unwrap_or_else(|e| {
+        eprintln!("Error loading {}: {}", file.display(), e);
+        std::process::exit(1);
+    });
+
+    let config = WorldConfig::from_game_dir(&path).unwrap_or_else(|e| {
+        eprintln!("Error loading world.toml: {}", e);
+        std::process::exit(1);
+    });
+    let script = config.load_script(&path).unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    });
+
+    let keyframes = replay.keyframe_count();
+    if replay.header.seed.is_none() {
+        println!("Warning: session was recorded without a seed; replay may drift");
+    }
+    println!(
+        "Replaying {} ({} ticks, {} keyframes)",
+        file.display(),
+        replay.end_tick(),
+        keyframes
+    );
+
+    // Headless pass over the whole recording first
+    let mut replayer = Replayer::new(replay, &script).unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    });
+    let divergences = replayer.verify();
+    if let Some(err) = &replayer.instance().halted_error {
+        eprintln!("Replay halted at tick {}: {}", replayer.tick(), err);
+    }
+    if divergences.is_empty() {
+        println!("Verified: no divergence from recorded keyframes");
+    } else {
+        for divergence in &divergences {
+            eprintln!("Divergence: {}", divergence);
+        }
+        eprintln!("{} of {} keyframes diverged", divergences.len(), keyframes);
+    }
+    if verify {
+        std::process::exit(if divergences.is_empty() { 0 } else { 1 });
+    }
+
+    // Serve playback on the spectate endpoints
+    replayer.seek(0);
+    let game_handle: GameManagerHandle = Arc::new(GameManagerState::new(None, ErrorMode::Continue, true));
+    let instance_id = Uuid::new_v4();
+    let playback = Arc::new(PlaybackControl {
+        paused: AtomicBool::new(false),
+        seek_to: Mutex::new(None),
+        tick: AtomicU64::new(0),
+        end_tick: replayer.end_tick(),
+    });
+
+    {
+        let game_handle = Arc::clone(&game_handle);
+        let playback = Arc::clone(&playback);
+        thread::spawn(move || {
+            let tick_duration = Duration::from_micros(1_000_000 / 60);
+            loop {
+                let start = std::time::Instant::now();
+                let seek_to = playback.seek_to.lock().unwrap().take();
+                if let Some(tick) = seek_to {
+                    replayer.seek(tick);
+                } else if !playback.paused.load(Ordering::Relaxed) && !replayer.finished() {
+                    if let Some(divergence) = replayer.step() {
+                        eprintln!("Divergence: {}", divergence);
+                    }
+                }
+                playback.tick.store(replayer.tick(), Ordering::Relaxed);
+                game_handle
+                    .spectator_cache
+                    .insert(instance_id, replayer.instance().get_spectator_observation());
+
+                if let Some(remaining) = tick_duration.checked_sub(start.elapsed()) {
+                    thread::sleep(remaining);
+                }
+            }
+        });
+    }
+
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    rt.block_on(async {
+        let state = LocalState {
+            game_id: Uuid::new_v4(),
+            instance_id,
+            game_handle,
+            skill_md: None,
+            sessions: Arc::new(DashMap::new()),
+            log_file: path.join(".clawblox.log"),
+            playback: Some(playback),
+        };
+
+        let cors = CorsLayer::new()
+            .allow_origin(Any)
+            .allow_methods(Any)
+            .allow_headers(Any);
+
+        let app = Router::new()
+            .route("/spectate", get(local_spectate))
+            .route("/spectate/ws", get(local_spectate_ws))
+            .route("/replay", get(local_replay_status))
+            .nest_service("/assets", ServeDir::new(path.join("assets")))
+            .nest_service("/static", ServeDir::new(path.join("static")))
+            .with_state(state)
+            .layer(cors);
+
+        let addr = SocketAddr::from(([0, 0, 0, 0], port));
+        println!();
+        println!("Replay server running on http://localhost:{}", port);
+        println!();
+        println!("Endpoints:");
+        println!("  GET  /spectate/ws  - Playback stream; send {{\"type\":\"pause\"}}, {{\"type\":\"play\"}} or {{\"type\":\"seek\",\"tick\":N}}");
+        println!("  GET  /replay       - Playback position");
+
+        let listener = tokio::net::TcpListener::bind(addr).await.unwrap_or_else(|e| {
+            eprintln!(
+                "Error: Port {} is already in use. Try --port <PORT>",
+                port
+            );
+            eprintln!("Details: {}", e);
+            std::process::exit(1);
+        });
+
+        axum::serve(listener, app)
+            .with_graceful_shutdown(shutdown_signal())
+            .await
+            .unwrap_or_else(|e| {
+                eprintln!("Server error: {}", e);
+            });
+    });
 }
 
 // =============================================================================
 // Local Server State & Handlers
 // =============================================================================
 
+/// Shared between the replay playback thread and spectate clients
+struct PlaybackControl {
+    paused: AtomicBool,
+    /// Pending seek, applied by the playback thread on its next tick
+    seek_to: Mutex<Option<u64>>,
+    /// Tick currently being shown
+    tick: AtomicU64,
+    end_tick: u64,
+}
+
+/// Playback commands accepted on the spectate WebSocket during `clawblox replay`
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum PlaybackCommand {
+    Pause,
+    Play,
+    Seek { tick: u64 },
+}
+
+impl PlaybackControl {
+    fn apply(&self, command: PlaybackCommand) {
+        match command {
+            PlaybackCommand::Pause => self.paused.store(true, Ordering::Relaxed),
+            PlaybackCommand::Play => self.paused.store(false, Ordering::Relaxed),
+            PlaybackCommand::Seek { tick } => *self.seek_to.lock().unwrap() = Some(tick),
+        }
+    }
+}
+
 #[derive(Clone)]
 struct LocalState {
     game_id: Uuid,
@@ -1088,6 +1534,7 @@ struct LocalState {
     skill_md: Option<String>,
     sessions: Arc<DashMap<String, (Uuid, String)>>, // token -> (agent_id, name)
     log_file: PathBuf,
+    playback: Option<Arc<PlaybackControl>>, // set when serving `clawblox replay`
 }
 
 /// Check if the game instance is halted due to a Lua error.
@@ -1202,6 +1649,63 @@ async fn local_input(
     Ok(Json(observation))
 }
 
+async fn local_step(
+    State(state): State<LocalState>,
+    headers: axum::http::HeaderMap,
+    Json(request): Json<StepRequest>,
+) -> Result<Json<StepResult>, (axum::http::StatusCode, String)> {
+    if let Some(err) = check_halted(&state) {
+        return Err((axum::http::StatusCode::SERVICE_UNAVAILABLE, err));
+    }
+
+    let (agent_id, _) = get_session(&state, &headers)?;
+
+    // The last agent to submit runs the whole round, so keep it off the async workers
+    let game_handle = state.game_handle.clone();
+    let game_id = state.game_id;
+    let result = tokio::task::spawn_blocking(move || game::submit_step(&game_handle, game_id, agent_id, request))
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .map_err(|e| (axum::http::StatusCode::BAD_REQUEST, e))?
+        .await
+        .map_err(|_| {
+            (
+                axum::http::StatusCode::CONFLICT,
+                "Step cancelled before the round ran".to_string(),
+            )
+        })?;
+
+    Ok(Json(result))
+}
+
+#[derive(Deserialize, Default)]
+struct ResetRequest {
+    #[serde(default)]
+    params: Option<serde_json::Value>,
+}
+
+async fn local_reset(
+    State(state): State<LocalState>,
+    headers: axum::http::HeaderMap,
+    payload: Option<Json<ResetRequest>>,
+) -> Result<Json<PlayerObservation>, (axum::http::StatusCode, String)> {
+    let (agent_id, _) = get_session(&state, &headers)?;
+    let Json(payload) = payload.unwrap_or_default();
+
+    // Reset also recovers a halted instance, so no check_halted here
+    let game_handle = state.game_handle.clone();
+    let instance_id = state.instance_id;
+    tokio::task::spawn_blocking(move || game::reset_instance(&game_handle, instance_id, payload.params))
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .map_err(|e| (axum::http::StatusCode::SERVICE_UNAVAILABLE, e))?;
+
+    let observation = game::get_observation(&state.game_handle, state.game_id, agent_id)
+        .map_err(|e| (axum::http::StatusCode::BAD_REQUEST, e))?;
+
+    Ok(Json(observation))
+}
+
 async fn local_observe(
     State(state): State<LocalState>,
     headers: axum::http::HeaderMap,
@@ -1260,6 +1764,20 @@ async fn local_skill(State(state): State<LocalState>) -> impl IntoResponse {
     }
 }
 
+async fn local_replay_status(
+    State(state): State<LocalState>,
+) -> Result<Json<serde_json::Value>, (axum::http::StatusCode, String)> {
+    let playback = state
+        .playback
+        .as_ref()
+        .ok_or((axum::http::StatusCode::NOT_FOUND, "Not a replay".to_string()))?;
+    Ok(Json(serde_json::json!({
+        "tick": playback.tick.load(Ordering::Relaxed),
+        "end_tick": playback.end_tick,
+        "paused": playback.paused.load(Ordering::Relaxed),
+    })))
+}
+
 async fn local_spectate_ws(
     State(state): State<LocalState>,
     ws: WebSocketUpgrade,
@@ -1294,6 +1812,12 @@ async fn handle_spectate_ws(socket: WebSocket, state: LocalState) {
                             break;
                         }
                     }
+                    Some(Ok(Message::Text(text))) if state.playback.is_some() => {
+                        match serde_json::from_str::<PlaybackCommand>(&text) {
+                            Ok(command) => state.playback.as_ref().unwrap().apply(command),
+                            Err(e) => eprintln!("Ignoring playback command {:?}: {}", text.as_str(), e),
+                        }
+                    }
                     _ => {}
                 }
             }
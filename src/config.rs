@@ -3,6 +3,8 @@
 use serde::Deserialize;
 use std::path::Path;
 
+use crate::game::lua::modules;
+
 /// Scripts configuration section
 #[derive(Debug, Clone, Deserialize)]
 pub struct ScriptsConfig {
@@ -39,6 +41,9 @@ pub struct WorldConfig {
     /// Scripts configuration
     #[serde(default)]
     pub scripts: ScriptsConfig,
+    /// Seed for deterministic simulation (time and randomness follow the tick counter)
+    #[serde(default)]
+    pub seed: Option<u64>,
 }
 
 fn default_max_players() -> u32 {
@@ -65,6 +70,13 @@ impl WorldConfig {
         let config_path = game_dir.join("world.toml");
         Self::from_file(&config_path)
     }
+
+    /// Load the game's script from a game directory: the main script with the
+    /// game's other Lua files bundled in as modules (see `game::lua::modules`)
+    pub fn load_script(&self, game_dir: &Path) -> Result<String, WorldConfigError> {
+        modules::bundle_game_dir(game_dir, &self.scripts.main)
+            .map_err(|e| WorldConfigError::IoError(game_dir.to_path_buf(), e))
+    }
 }
 
 /// Errors that can occur when loading world configuration
@@ -102,6 +114,7 @@ mod tests {
         assert_eq!(config.name, "Test Game");
         assert_eq!(config.max_players, 8);
         assert_eq!(config.game_type, "lua");
+        assert_eq!(config.seed, None);
     }
 
     #[test]
@@ -111,6 +124,7 @@ mod tests {
             description = "A test game"
             max_players = 16
             game_type = "lua"
+            seed = 1234
 
             [scripts]
             main = "main.lua"
@@ -121,5 +135,6 @@ mod tests {
         assert_eq!(config.description, Some("A test game".to_string()));
         assert_eq!(config.max_players, 16);
         assert_eq!(config.scripts.main, "main.lua");
+        assert_eq!(config.seed, Some(1234));
     }
 }
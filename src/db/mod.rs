@@ -22,12 +22,15 @@ pub async fn create_pool() -> Result<PgPool, sqlx::Error> {
 
 /// Cleans up orphaned instances from DB on startup.
 /// Called when server restarts - any instances that were "running" in DB but no longer
-/// exist in memory are orphaned.
-pub async fn reconcile_instances(pool: &PgPool) -> Result<(), sqlx::Error> {
+/// exist in memory are orphaned. `restored` are the instances brought back from
+/// snapshots; they and their player associations are kept.
+pub async fn reconcile_instances(pool: &PgPool, restored: &[Uuid]) -> Result<(), sqlx::Error> {
     // Mark all running/waiting instances as orphaned
     let result = sqlx::query(
-        "UPDATE game_instances SET status = 'orphaned' WHERE status IN ('running', 'waiting', 'playing')"
+        "UPDATE game_instances SET status = 'orphaned'
+         WHERE status IN ('running', 'waiting', 'playing') AND id != ALL($1)"
     )
+    .bind(restored)
     .execute(pool)
     .await?;
 
@@ -18,6 +18,12 @@ pub mod physics {
     /// Character capsule total height
     pub const CHARACTER_HEIGHT: f32 = 2.0;
 
+    /// Radius of a player character's capsule
+    pub const PLAYER_CHARACTER_RADIUS: f32 = 1.0;
+
+    /// Total height of a player character's capsule
+    pub const PLAYER_CHARACTER_HEIGHT: f32 = 5.0;
+
     /// Character spawn height above ground
     pub const CHARACTER_SPAWN_HEIGHT: f32 = 3.0;
 
@@ -30,6 +36,14 @@ pub mod physics {
     /// Character controller snap to ground distance
     pub const SNAP_TO_GROUND: f32 = 0.2;
 
+    /// Fraction of horizontal movement lifted off the ground plane when walking on slopes
+    /// (keeps the controller from treating slope-parallel motion as touching the ramp)
+    pub const SLOPE_WALK_LIFT: f32 = 0.01;
+
+    /// Contact skin on character capsules so resting on a floor registers as a touch
+    /// (must exceed the controller's 0.05 stud offset)
+    pub const CHARACTER_TOUCH_SKIN: f32 = 0.1;
+
     /// Air control multiplier (fraction of walk speed applied while airborne)
     /// With walk_speed=16, air_time≈1.39s: 16*0.6*1.39 ≈ 13.3 studs max jump distance
     pub const AIR_CONTROL: f32 = 0.6;
@@ -37,6 +51,44 @@ pub mod physics {
     /// Minimum vertical velocity to trigger air control (avoids slowing walking over bumps)
     pub const AIR_CONTROL_THRESHOLD: f32 = 2.0;
 
+    /// Gain of a Motor actuator driving a constraint toward its target velocity
+    pub const MOTOR_VELOCITY_GAIN: f32 = 10.0;
+
+    /// Stiffness of a Servo actuator pulling a constraint toward its target
+    pub const SERVO_STIFFNESS: f32 = 100.0;
+
+    /// Damping of a Servo actuator (keeps it from oscillating around the target)
+    pub const SERVO_DAMPING: f32 = 20.0;
+
+    /// Shortest rope a RopeConstraint builds (Rapier needs a positive length)
+    pub const MIN_ROPE_LENGTH: f32 = 0.01;
+
+    /// How fast a character's knockback dies down on the ground (studs/s²)
+    pub const KNOCKBACK_GROUND_DECEL: f32 = 60.0;
+
+    /// How fast a character's knockback dies down in the air (studs/s²)
+    pub const KNOCKBACK_AIR_DECEL: f32 = 5.0;
+
+    /// Fraction of gravity a character in water feels
+    pub const SWIM_GRAVITY_SCALE: f32 = 0.1;
+
+    /// Fastest a character sinks in water (studs/s)
+    pub const SWIM_SINK_SPEED: f32 = 2.0;
+
+    /// Upward speed a jump gives a character in water (studs/s)
+    pub const SWIM_STROKE_SPEED: f32 = 10.0;
+
+    /// Floor friction at and above which characters have full grip
+    pub const FULL_TRACTION_FRICTION: f32 = 0.2;
+
+    /// How fast a character's walking velocity can change on a slippery floor
+    /// (studs/s²), scaled by the floor's traction
+    pub const CHARACTER_GRIP_ACCEL: f32 = 100.0;
+
+    /// Fraction of its density a Massless part keeps, so its collider still
+    /// has valid mass properties
+    pub const MASSLESS_DENSITY_SCALE: f32 = 0.001;
+
     /// Small epsilon for float comparisons
     pub const EPSILON: f32 = 0.001;
 }
@@ -60,4 +112,35 @@ pub mod humanoid {
 
     /// Default hip height
     pub const DEFAULT_HIP_HEIGHT: f32 = 2.0;
+
+    /// Seconds MoveTo walks toward a target before giving up (MoveToFinished(false))
+    pub const MOVE_TO_TIMEOUT: f32 = 8.0;
+}
+
+/// Pathfinding defaults (Roblox-compatible agent parameters)
+pub mod pathfinding {
+    /// Default agent radius (studs)
+    pub const DEFAULT_AGENT_RADIUS: f32 = 2.0;
+
+    /// Default agent height (studs)
+    pub const DEFAULT_AGENT_HEIGHT: f32 = 5.0;
+
+    /// Default greatest distance between walk waypoints
+    pub const DEFAULT_WAYPOINT_SPACING: f32 = 4.0;
+
+    /// Side of a navmesh grid cell (studs); large maps get coarser cells
+    pub const CELL_SIZE: f32 = 1.0;
+
+    /// Most grid columns a navmesh bakes before it coarsens its cells
+    pub const MAX_COLUMNS: usize = 250_000;
+
+    /// Highest ledge a path jumps up, leaving room under the default jump height
+    /// for the character to clear the edge
+    pub const MAX_JUMP_CLIMB: f32 = 5.5;
+
+    /// Furthest drop a path walks off
+    pub const MAX_DROP: f32 = 20.0;
+
+    /// Extra cost of a jump, in studs of walking, so paths only jump to save distance
+    pub const JUMP_COST: f32 = 4.0;
 }
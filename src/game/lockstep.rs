@@ -0,0 +1,53 @@
+//! Lockstep stepping for training and evaluation.
+//!
+//! A lockstep instance is not advanced by the 60 Hz game loop. Each agent in it
+//! submits a step (inputs plus a tick count); once every player has submitted,
+//! the round runs immediately, as fast as the CPU allows, and each agent gets
+//! back its observation after the round. A round that has waited out the
+//! instance's timeout for stalled agents runs with the steps it has; the
+//! agents that did not submit sit it out.
+
+use serde::{Deserialize, Serialize};
+
+use super::instance::PlayerObservation;
+
+/// Upper bound on the ticks a single step may request (10 seconds of game time)
+pub const MAX_STEP_TICKS: u32 = 600;
+
+/// An input applied at the start of a step, in the same shape as `/input`
+#[derive(Debug, Clone, Deserialize)]
+pub struct StepAction {
+    #[serde(rename = "type")]
+    pub input_type: String,
+    #[serde(default)]
+    pub data: serde_json::Value,
+}
+
+/// One agent's submission for a lockstep round
+#[derive(Debug, Clone, Deserialize)]
+pub struct StepRequest {
+    /// Inputs queued before the first tick of the round
+    #[serde(default)]
+    pub actions: Vec<StepAction>,
+    /// Ticks to advance; the round runs the smallest count submitted by its agents
+    #[serde(default = "default_step_ticks")]
+    pub ticks: u32,
+}
+
+fn default_step_ticks() -> u32 {
+    1
+}
+
+/// What an agent gets back once its round has run
+#[derive(Debug, Clone, Serialize)]
+pub struct StepResult {
+    /// Ticks the round actually advanced
+    pub ticks: u64,
+    /// Reward the agent earned during the round (change in its RewardService total)
+    pub reward: f64,
+    /// Observation after the round; None once the agent is no longer in the instance
+    pub observation: Option<PlayerObservation>,
+    /// True when the agent's episode is over (ended by the script, left, kicked,
+    /// game finished or halted)
+    pub done: bool,
+}
@@ -0,0 +1,1298 @@
+//! Machine-readable description of the scripting API
+//!
+//! Backs `clawblox api-dump`, `GET /api/v1/api-dump` and the Luau definitions
+//! `clawblox init` writes for luau-lsp. Instance classes, their properties and
+//! their events come from the reflection registry; methods, services,
+//! datatypes and globals are declared here as Luau-style signatures such as
+//! `"FindFirstChild(name: string, recursive: boolean?) -> Instance?"`. The
+//! tests check every declared member against a live VM so the two can't drift.
+
+use serde::Serialize;
+
+use super::instance::InstanceData;
+use super::reflection;
+use super::types::enums;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiDump {
+    pub version: &'static str,
+    pub classes: Vec<ClassDump>,
+    pub services: Vec<ObjectDump>,
+    pub datatypes: Vec<ObjectDump>,
+    pub enums: Vec<EnumDump>,
+    pub globals: Vec<GlobalDump>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ClassDump {
+    pub name: &'static str,
+    pub superclass: Option<&'static str>,
+    /// Whether Instance.new can create it
+    pub creatable: bool,
+    /// Whether game:GetService returns an instance of it
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub service: bool,
+    /// Members the class declares (inherited ones are on its superclasses)
+    pub properties: Vec<PropertyDump>,
+    pub methods: Vec<FunctionDump>,
+    pub events: Vec<EventDump>,
+    /// Values of all its properties on a new instance, for creatable classes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub defaults: Option<serde_json::Map<String, serde_json::Value>>,
+}
+
+/// A service, or a datatype or object scripts get from the API
+#[derive(Debug, Clone, Serialize)]
+pub struct ObjectDump {
+    pub name: &'static str,
+    /// Functions on the global table of the same name, e.g. `Vector3.new`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub constructors: Vec<FunctionDump>,
+    /// Values on the global table of the same name, e.g. `Vector3.zero`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub constants: Vec<PropertyDump>,
+    pub properties: Vec<PropertyDump>,
+    pub methods: Vec<FunctionDump>,
+    pub events: Vec<EventDump>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PropertyDump {
+    pub name: &'static str,
+    #[serde(rename = "type")]
+    pub value_type: String,
+    pub read_only: bool,
+    pub replicated: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FunctionDump {
+    pub name: &'static str,
+    pub params: Vec<ParamDump>,
+    pub returns: Option<&'static str>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ParamDump {
+    pub name: &'static str,
+    #[serde(rename = "type")]
+    pub value_type: &'static str,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EventDump {
+    pub name: &'static str,
+    pub params: Vec<ParamDump>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EnumDump {
+    pub name: &'static str,
+    pub items: Vec<&'static str>,
+}
+
+/// A global variable, or a global library of functions such as `task`
+#[derive(Debug, Clone, Serialize)]
+pub struct GlobalDump {
+    pub name: &'static str,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub value_type: Option<&'static str>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub functions: Vec<FunctionDump>,
+}
+
+// =============================================================================
+// Declarations
+// =============================================================================
+
+/// Methods of Instance classes, by the class that declares them
+const INSTANCE_METHODS: &[(&str, &[&str])] = &[
+    (
+        "Instance",
+        &[
+            "Clone() -> Instance",
+            "Destroy()",
+            "FindFirstChild(name: string, recursive: boolean?) -> Instance?",
+            "FindFirstChildOfClass(className: string) -> Instance?",
+            "GetChildren() -> {Instance}",
+            "GetDescendants() -> {Instance}",
+            "IsA(className: string) -> boolean",
+            "IsDescendantOf(ancestor: Instance) -> boolean",
+            "SetAttribute(name: string, value: any)",
+            "GetAttribute(name: string) -> any",
+            "GetAttributes() -> {[string]: any}",
+            "GetAttributeChangedSignal(name: string) -> RBXScriptSignal",
+            "GetPropertyChangedSignal(property: string) -> RBXScriptSignal",
+            "AddTag(tag: string)",
+            "HasTag(tag: string) -> boolean",
+            "RemoveTag(tag: string)",
+            "GetTags() -> {string}",
+        ],
+    ),
+    (
+        "BasePart",
+        &[
+            "ApplyImpulse(impulse: Vector3)",
+            "ApplyImpulseAtPosition(impulse: Vector3, position: Vector3)",
+            "ApplyAngularImpulse(impulse: Vector3)",
+            "GetMass() -> number",
+        ],
+    ),
+    (
+        "Humanoid",
+        &[
+            "TakeDamage(amount: number)",
+            "Move(direction: Vector3, relativeToCamera: boolean?)",
+            "MoveTo(location: Vector3, part: BasePart?)",
+            "CancelMoveTo()",
+            "Jump()",
+            "GetState() -> EnumHumanoidStateType",
+            "ChangeState(state: EnumHumanoidStateType)",
+        ],
+    ),
+    (
+        "Model",
+        &[
+            "GetPrimaryPartCFrame() -> CFrame?",
+            "SetPrimaryPartCFrame(cframe: CFrame)",
+        ],
+    ),
+    ("Player", &["LoadCharacter()", "Kick(message: string?)"]),
+];
+
+/// Members of a service or datatype
+struct Object {
+    name: &'static str,
+    constructors: &'static [&'static str],
+    properties: &'static [Property],
+    methods: &'static [&'static str],
+    events: &'static [&'static str],
+}
+
+/// A property signature and whether scripts can assign it
+struct Property(&'static str, bool);
+
+const fn ro(signature: &'static str) -> Property {
+    Property(signature, false)
+}
+
+const fn rw(signature: &'static str) -> Property {
+    Property(signature, true)
+}
+
+const NAMED: &[Property] = &[ro("Name: string"), ro("ClassName: string")];
+
+/// Services, by the name game:GetService takes
+const SERVICES: &[Object] = &[
+    Object {
+        name: "Workspace",
+        constructors: &[],
+        properties: &[
+            ro("Name: string"),
+            ro("ClassName: string"),
+            rw("Gravity: number"),
+            ro("CurrentCamera: Camera?"),
+        ],
+        methods: &[
+            "Raycast(origin: Vector3, direction: Vector3, params: RaycastParams?) -> RaycastResult?",
+            "Spherecast(position: Vector3, radius: number, direction: Vector3, params: RaycastParams?) -> RaycastResult?",
+            "Blockcast(cframe: CFrame, size: Vector3, direction: Vector3, params: RaycastParams?) -> RaycastResult?",
+            "Shapecast(part: BasePart, direction: Vector3, params: RaycastParams?) -> RaycastResult?",
+            "GetPartsInPart(part: BasePart, params: OverlapParams?) -> {BasePart}",
+            "GetPartBoundsInBox(cframe: CFrame, size: Vector3, params: OverlapParams?) -> {BasePart}",
+            "GetPartBoundsInRadius(position: Vector3, radius: number, params: OverlapParams?) -> {BasePart}",
+            "GetChildren() -> {Instance}",
+            "GetDescendants() -> {Instance}",
+            "FindFirstChild(name: string, recursive: boolean?) -> Instance?",
+            "FindFirstChildOfClass(className: string) -> Instance?",
+        ],
+        events: &[],
+    },
+    Object {
+        name: "Players",
+        constructors: &[],
+        properties: &[
+            ro("Name: string"),
+            ro("ClassName: string"),
+            ro("LocalPlayer: Player?"),
+            ro("MaxPlayers: number"),
+        ],
+        methods: &[
+            "GetPlayers() -> {Player}",
+            "GetPlayerByUserId(userId: number) -> Player?",
+            "GetPlayerFromCharacter(character: Model) -> Player?",
+            "GetChildren() -> {Player}",
+            "FindFirstChild(name: string) -> Player?",
+        ],
+        events: &["PlayerAdded(player: Player)", "PlayerRemoving(player: Player)"],
+    },
+    Object {
+        name: "RunService",
+        constructors: &[],
+        properties: NAMED,
+        methods: &["IsServer() -> boolean", "IsClient() -> boolean"],
+        events: &["Heartbeat(deltaTime: number)", "Stepped(time: number, deltaTime: number)"],
+    },
+    Object {
+        name: "AgentInputService",
+        constructors: &[],
+        properties: NAMED,
+        methods: &[
+            "GetInputs(player: Player) -> {{type: string, data: any}}",
+            "HasPendingInputs(player: Player) -> boolean",
+        ],
+        events: &["InputReceived(player: Player, inputType: string, data: any)"],
+    },
+    Object {
+        name: "DataStoreService",
+        constructors: &[],
+        properties: &[],
+        methods: &[
+            "GetDataStore(name: string) -> DataStore",
+            "GetOrderedDataStore(name: string) -> OrderedDataStore",
+        ],
+        events: &[],
+    },
+    Object {
+        name: "RewardService",
+        constructors: &[],
+        properties: NAMED,
+        methods: &[
+            "AddReward(player: Player, value: number, reason: string?)",
+            "EndEpisode(player: Player, outcome: string?)",
+            "GetTotalReward(player: Player) -> number",
+            "GetStats(player: Player) -> {[string]: any}",
+        ],
+        events: &[],
+    },
+    Object {
+        name: "TweenService",
+        constructors: &[],
+        properties: NAMED,
+        methods: &[
+            "Create(instance: Instance, tweenInfo: TweenInfo, goals: {[string]: any}) -> Tween",
+            "GetValue(alpha: number, style: EnumEasingStyle, direction: EnumEasingDirection) -> number",
+        ],
+        events: &[],
+    },
+    Object {
+        name: "CollectionService",
+        constructors: &[],
+        properties: NAMED,
+        methods: &[
+            "GetTagged(tag: string) -> {Instance}",
+            "GetInstanceAddedSignal(tag: string) -> RBXScriptSignal",
+            "GetInstanceRemovedSignal(tag: string) -> RBXScriptSignal",
+            "AddTag(instance: Instance, tag: string)",
+            "RemoveTag(instance: Instance, tag: string)",
+            "HasTag(instance: Instance, tag: string) -> boolean",
+            "GetTags(instance: Instance) -> {string}",
+            "GetAllTags() -> {string}",
+        ],
+        events: &[],
+    },
+    Object {
+        name: "PhysicsService",
+        constructors: &[],
+        properties: NAMED,
+        methods: &[
+            "RegisterCollisionGroup(name: string)",
+            "UnregisterCollisionGroup(name: string)",
+            "CollisionGroupSetCollidable(group1: string, group2: string, collidable: boolean)",
+            "CollisionGroupsAreCollidable(group1: string, group2: string) -> boolean",
+            "IsCollisionGroupRegistered(name: string) -> boolean",
+            "GetRegisteredCollisionGroups() -> {{id: number, name: string, mask: number}}",
+            "GetMaxCollisionGroups() -> number",
+        ],
+        events: &[],
+    },
+    Object {
+        name: "PathfindingService",
+        constructors: &[],
+        properties: NAMED,
+        methods: &[
+            "CreatePath(params: {AgentRadius: number?, AgentHeight: number?, AgentCanJump: boolean?, WaypointSpacing: number?}?) -> Path",
+        ],
+        events: &[],
+    },
+    Object {
+        name: "HttpService",
+        constructors: &[],
+        properties: NAMED,
+        methods: &[
+            "JSONEncode(value: any) -> string",
+            "JSONDecode(json: string) -> any",
+            "GenerateGUID(wrapInCurlyBraces: boolean?) -> string",
+        ],
+        events: &[],
+    },
+];
+
+/// Services game:GetService returns as the instance of the class of that name
+const INSTANCE_SERVICES: &[&str] = &["ServerScriptService"];
+
+/// Value types, and objects scripts only get from other calls
+const DATATYPES: &[Object] = &[
+    Object {
+        name: "Vector3",
+        constructors: &[
+            "new(x: number?, y: number?, z: number?) -> Vector3",
+            "zero: Vector3",
+            "one: Vector3",
+            "xAxis: Vector3",
+            "yAxis: Vector3",
+            "zAxis: Vector3",
+        ],
+        properties: &[
+            ro("X: number"),
+            ro("Y: number"),
+            ro("Z: number"),
+            ro("Magnitude: number"),
+            ro("Unit: Vector3"),
+        ],
+        methods: &[
+            "Dot(other: Vector3) -> number",
+            "Cross(other: Vector3) -> Vector3",
+            "Lerp(goal: Vector3, alpha: number) -> Vector3",
+            "FuzzyEq(other: Vector3, epsilon: number?) -> boolean",
+        ],
+        events: &[],
+    },
+    Object {
+        name: "CFrame",
+        constructors: &[
+            "new(...: number | Vector3) -> CFrame",
+            "lookAt(position: Vector3, target: Vector3, up: Vector3?) -> CFrame",
+            "fromEulerAnglesXYZ(rx: number, ry: number, rz: number) -> CFrame",
+            "Angles(rx: number, ry: number, rz: number) -> CFrame",
+        ],
+        properties: &[
+            ro("Position: Vector3"),
+            ro("LookVector: Vector3"),
+            ro("RightVector: Vector3"),
+            ro("UpVector: Vector3"),
+            ro("X: number"),
+            ro("Y: number"),
+            ro("Z: number"),
+        ],
+        methods: &[
+            "Inverse() -> CFrame",
+            "Lerp(goal: CFrame, alpha: number) -> CFrame",
+            "ToWorldSpace(cframe: CFrame) -> CFrame",
+            "ToObjectSpace(cframe: CFrame) -> CFrame",
+            "PointToWorldSpace(point: Vector3) -> Vector3",
+            "PointToObjectSpace(point: Vector3) -> Vector3",
+            "GetComponents() -> ...number",
+        ],
+        events: &[],
+    },
+    Object {
+        name: "Color3",
+        constructors: &[
+            "new(r: number?, g: number?, b: number?) -> Color3",
+            "fromRGB(r: number, g: number, b: number) -> Color3",
+            "fromHSV(h: number, s: number, v: number) -> Color3",
+            "fromHex(hex: string) -> Color3",
+        ],
+        properties: &[ro("R: number"), ro("G: number"), ro("B: number")],
+        methods: &[
+            "Lerp(goal: Color3, alpha: number) -> Color3",
+            "ToHSV() -> (number, number, number)",
+            "ToHex() -> string",
+        ],
+        events: &[],
+    },
+    Object {
+        name: "UDim",
+        constructors: &["new(scale: number, offset: number) -> UDim"],
+        properties: &[ro("Scale: number"), ro("Offset: number")],
+        methods: &[],
+        events: &[],
+    },
+    Object {
+        name: "UDim2",
+        constructors: &[
+            "new(xScale: number, xOffset: number, yScale: number, yOffset: number) -> UDim2",
+            "fromScale(x: number, y: number) -> UDim2",
+            "fromOffset(x: number, y: number) -> UDim2",
+        ],
+        properties: &[
+            ro("X: UDim"),
+            ro("Y: UDim"),
+            ro("Width: UDim"),
+            ro("Height: UDim"),
+        ],
+        methods: &["Lerp(goal: UDim2, alpha: number) -> UDim2"],
+        events: &[],
+    },
+    Object {
+        name: "Random",
+        constructors: &["new(seed: number?) -> Random"],
+        properties: &[],
+        methods: &[
+            "NextNumber(min: number?, max: number?) -> number",
+            "NextInteger(min: number, max: number) -> number",
+            "NextUnitVector() -> Vector3",
+            "Shuffle(t: {any})",
+            "Clone() -> Random",
+        ],
+        events: &[],
+    },
+    Object {
+        name: "TweenInfo",
+        constructors: &[
+            "new(time: number?, easingStyle: EnumEasingStyle?, easingDirection: EnumEasingDirection?, repeatCount: number?, reverses: boolean?, delayTime: number?) -> TweenInfo",
+        ],
+        properties: &[
+            ro("Time: number"),
+            ro("EasingStyle: EnumEasingStyle"),
+            ro("EasingDirection: EnumEasingDirection"),
+            ro("RepeatCount: number"),
+            ro("Reverses: boolean"),
+            ro("DelayTime: number"),
+        ],
+        methods: &[],
+        events: &[],
+    },
+    Object {
+        name: "PhysicalProperties",
+        constructors: &[
+            "new(densityOrMaterial: number | EnumMaterial, friction: number?, elasticity: number?, frictionWeight: number?, elasticityWeight: number?) -> PhysicalProperties",
+        ],
+        properties: &[
+            ro("Density: number"),
+            ro("Friction: number"),
+            ro("Elasticity: number"),
+            ro("FrictionWeight: number"),
+            ro("ElasticityWeight: number"),
+        ],
+        methods: &[],
+        events: &[],
+    },
+    Object {
+        name: "RaycastParams",
+        constructors: &["new() -> RaycastParams"],
+        properties: &[
+            rw("FilterType: EnumRaycastFilterType"),
+            rw("FilterDescendantsInstances: {Instance}"),
+            rw("IgnoreWater: boolean"),
+            rw("CollisionGroup: string"),
+        ],
+        methods: &[],
+        events: &[],
+    },
+    Object {
+        name: "OverlapParams",
+        constructors: &["new() -> OverlapParams"],
+        properties: &[
+            rw("FilterType: EnumRaycastFilterType"),
+            rw("FilterDescendantsInstances: {Instance}"),
+            rw("MaxParts: number"),
+            rw("CollisionGroup: string"),
+            rw("RespectCanCollide: boolean"),
+        ],
+        methods: &[],
+        events: &[],
+    },
+    Object {
+        name: "RaycastResult",
+        constructors: &[],
+        properties: &[
+            ro("Instance: BasePart"),
+            ro("Position: Vector3"),
+            ro("Normal: Vector3"),
+            ro("Distance: number"),
+        ],
+        methods: &[],
+        events: &[],
+    },
+    Object {
+        name: "PathWaypoint",
+        constructors: &[
+            "new(position: Vector3?, action: EnumPathWaypointAction?, label: string?) -> PathWaypoint",
+        ],
+        properties: &[
+            ro("Position: Vector3"),
+            ro("Action: EnumPathWaypointAction"),
+            ro("Label: string"),
+        ],
+        methods: &[],
+        events: &[],
+    },
+    Object {
+        name: "Path",
+        constructors: &[],
+        properties: &[
+            ro("Name: string"),
+            ro("ClassName: string"),
+            ro("Status: EnumPathStatus"),
+        ],
+        methods: &[
+            "ComputeAsync(start: Vector3, finish: Vector3)",
+            "GetWaypoints() -> {PathWaypoint}",
+        ],
+        events: &[],
+    },
+    Object {
+        name: "Tween",
+        constructors: &[],
+        properties: &[
+            ro("Name: string"),
+            ro("ClassName: string"),
+            ro("Instance: Instance"),
+            ro("TweenInfo: TweenInfo"),
+            ro("PlaybackState: EnumPlaybackState"),
+        ],
+        methods: &["Play()", "Pause()", "Cancel()"],
+        events: &["Completed(playbackState: EnumPlaybackState)"],
+    },
+    Object {
+        name: "DataStore",
+        constructors: &[],
+        properties: &[],
+        methods: &[
+            "GetAsync(key: string) -> any",
+            "SetAsync(key: string, value: any)",
+            "RemoveAsync(key: string) -> any",
+            "UpdateAsync(key: string, transform: (any) -> any) -> any",
+        ],
+        events: &[],
+    },
+    Object {
+        name: "OrderedDataStore",
+        constructors: &[],
+        properties: &[],
+        methods: &[
+            "GetAsync(key: string) -> any",
+            "SetAsync(key: string, value: any)",
+            "GetSortedAsync(ascending: boolean, limit: number) -> {{key: string, value: any}}",
+        ],
+        events: &[],
+    },
+    Object {
+        name: "RBXScriptSignal",
+        constructors: &[],
+        properties: &[],
+        methods: &[
+            "Connect(callback: (...any) -> ()) -> RBXScriptConnection",
+            "Once(callback: (...any) -> ()) -> RBXScriptConnection",
+            "Wait() -> ...any",
+        ],
+        events: &[],
+    },
+    Object {
+        name: "RBXScriptConnection",
+        constructors: &[],
+        properties: &[ro("Connected: boolean")],
+        methods: &["Disconnect()"],
+        events: &[],
+    },
+    Object {
+        name: "DataModel",
+        constructors: &[],
+        properties: &[
+            ro("Workspace: Workspace"),
+            ro("Players: Players"),
+            ro("ServerScriptService: ServerScriptService"),
+            ro("LaunchParams: {[string]: any}"),
+        ],
+        methods: &["GetService(name: string) -> any"],
+        events: &[],
+    },
+];
+
+/// Global variables, as (name, Luau type)
+const GLOBALS: &[(&str, &str)] = &[
+    ("game", "DataModel"),
+    ("Workspace", "Workspace"),
+    ("Players", "Players"),
+    ("script", "Script"),
+];
+
+/// Global functions, and libraries of functions by library name
+const GLOBAL_FUNCTIONS: &[(Option<&str>, &[&str])] = &[
+    (
+        None,
+        &[
+            "tick() -> number",
+            "wait(seconds: number?) -> number",
+            "print(...: any)",
+            "warn(...: any)",
+            "require(module: ModuleScript) -> any",
+        ],
+    ),
+    (
+        Some("task"),
+        &[
+            "spawn(f: ((...any) -> ...any) | thread, ...: any) -> thread",
+            "delay(seconds: number, f: (...any) -> ...any, ...: any) -> thread",
+            "defer(f: ((...any) -> ...any) | thread, ...: any) -> thread",
+            "wait(seconds: number?) -> number",
+            "cancel(thread: thread)",
+        ],
+    ),
+    (
+        Some("Instance"),
+        &["new(className: string, parent: Instance?) -> Instance"],
+    ),
+];
+
+// =============================================================================
+// Signatures
+// =============================================================================
+
+/// Splits on commas outside brackets, so function types stay whole
+fn split_top_level(list: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in list.char_indices() {
+        match c {
+            '(' | '{' | '[' => depth += 1,
+            ')' | '}' | ']' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(list[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    let last = list[start..].trim();
+    if !last.is_empty() {
+        parts.push(last);
+    }
+    parts
+}
+
+/// Parses `name: type`
+fn parse_typed(signature: &'static str) -> (&'static str, &'static str) {
+    let (name, value_type) = signature
+        .split_once(':')
+        .unwrap_or_else(|| panic!("missing type in {signature:?}"));
+    (name.trim(), value_type.trim())
+}
+
+/// Parses `Name(param: type, ...) -> returns`
+fn parse_function(signature: &'static str) -> FunctionDump {
+    let open = signature
+        .find('(')
+        .unwrap_or_else(|| panic!("missing parameters in {signature:?}"));
+    let mut depth = 0;
+    let close = signature[open..]
+        .char_indices()
+        .find_map(|(i, c)| {
+            match c {
+                '(' => depth += 1,
+                ')' => depth -= 1,
+                _ => {}
+            }
+            (depth == 0).then_some(open + i)
+        })
+        .unwrap_or_else(|| panic!("unbalanced parameters in {signature:?}"));
+    let returns = signature[close + 1..]
+        .trim()
+        .strip_prefix("->")
+        .map(str::trim);
+    FunctionDump {
+        name: &signature[..open],
+        params: split_top_level(&signature[open + 1..close])
+            .into_iter()
+            .map(|param| {
+                let (name, value_type) = parse_typed(param);
+                ParamDump { name, value_type }
+            })
+            .collect(),
+        returns,
+    }
+}
+
+fn parse_event(signature: &'static str) -> EventDump {
+    let FunctionDump { name, params, .. } = parse_function(signature);
+    EventDump { name, params }
+}
+
+fn property_dump(property: &Property) -> PropertyDump {
+    let (name, value_type) = parse_typed(property.0);
+    PropertyDump {
+        name,
+        value_type: value_type.to_string(),
+        read_only: !property.1,
+        replicated: false,
+    }
+}
+
+fn object_dump(object: &Object) -> ObjectDump {
+    let (functions, values): (Vec<_>, Vec<_>) = object
+        .constructors
+        .iter()
+        .copied()
+        .partition(|signature| signature.contains('('));
+    ObjectDump {
+        name: object.name,
+        constructors: functions.into_iter().map(parse_function).collect(),
+        constants: values.into_iter().map(|s| property_dump(&ro(s))).collect(),
+        properties: object.properties.iter().map(property_dump).collect(),
+        methods: object.methods.iter().map(|s| parse_function(s)).collect(),
+        events: object.events.iter().map(|s| parse_event(s)).collect(),
+    }
+}
+
+fn class_dump(name: &'static str, superclass: Option<&'static str>) -> ClassDump {
+    let creatable = reflection::creatable_class(name);
+    let defaults = creatable.map(|class| {
+        let data = InstanceData::for_class(class, name);
+        reflection::properties_of(class)
+            .map(|p| {
+                let value = p
+                    .get(&data)
+                    .map_or(serde_json::Value::Null, |v| v.to_json());
+                (p.name.to_string(), value)
+            })
+            .collect()
+    });
+    let methods = INSTANCE_METHODS
+        .iter()
+        .filter(|(class, _)| *class == name)
+        .flat_map(|(_, methods)| methods.iter().map(|s| parse_function(s)))
+        .collect();
+    ClassDump {
+        name,
+        superclass,
+        creatable: creatable.is_some(),
+        service: INSTANCE_SERVICES.contains(&name),
+        properties: reflection::declared_properties(name)
+            .map(|p| PropertyDump {
+                name: p.name,
+                value_type: p.value_type.luau_type(),
+                read_only: p.read_only(),
+                replicated: p.replicated,
+            })
+            .collect(),
+        methods,
+        events: reflection::declared_events(name)
+            .map(|e| EventDump {
+                name: e.name,
+                params: e
+                    .params
+                    .iter()
+                    .map(|&(name, value_type)| ParamDump { name, value_type })
+                    .collect(),
+            })
+            .collect(),
+        defaults,
+    }
+}
+
+/// Describes the whole scripting API
+pub fn api_dump() -> ApiDump {
+    let mut globals: Vec<GlobalDump> = GLOBALS
+        .iter()
+        .map(|&(name, value_type)| GlobalDump {
+            name,
+            value_type: Some(value_type),
+            functions: Vec::new(),
+        })
+        .collect();
+    for &(library, functions) in GLOBAL_FUNCTIONS {
+        let functions = functions.iter().map(|s| parse_function(s));
+        match library {
+            Some(name) => globals.push(GlobalDump {
+                name,
+                value_type: None,
+                functions: functions.collect(),
+            }),
+            None => globals.extend(functions.map(|f| GlobalDump {
+                name: f.name,
+                value_type: None,
+                functions: vec![f],
+            })),
+        }
+    }
+
+    ApiDump {
+        version: env!("CARGO_PKG_VERSION"),
+        classes: reflection::classes()
+            .iter()
+            .map(|&(name, superclass)| class_dump(name, superclass))
+            .collect(),
+        services: SERVICES.iter().map(object_dump).collect(),
+        datatypes: DATATYPES.iter().map(object_dump).collect(),
+        enums: enums::enum_items()
+            .into_iter()
+            .map(|(name, items)| EnumDump { name, items })
+            .collect(),
+        globals,
+    }
+}
+
+// =============================================================================
+// Luau definitions
+// =============================================================================
+
+fn luau_params(params: &[ParamDump]) -> String {
+    params
+        .iter()
+        .map(|p| format!("{}: {}", p.name, p.value_type))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn luau_returns(returns: Option<&str>) -> &str {
+    returns.unwrap_or("()")
+}
+
+/// `(params) -> returns`, the type of a function value
+fn luau_function_type(function: &FunctionDump) -> String {
+    let params: Vec<_> = function
+        .params
+        .iter()
+        .map(|p| match (p.name, p.value_type.contains('|')) {
+            // Function types take unnamed variadics
+            ("...", true) => format!("...({})", p.value_type),
+            ("...", false) => format!("...{}", p.value_type),
+            _ => format!("{}: {}", p.name, p.value_type),
+        })
+        .collect();
+    format!(
+        "({}) -> {}",
+        params.join(", "),
+        luau_returns(function.returns)
+    )
+}
+
+/// `declare class` block; `extra` is appended before its `end`
+fn luau_class(
+    out: &mut String,
+    name: &str,
+    superclass: Option<&str>,
+    members: (&[PropertyDump], &[FunctionDump], &[EventDump]),
+    extra: &str,
+) {
+    let (properties, methods, events) = members;
+    match superclass {
+        Some(superclass) => out.push_str(&format!("declare class {name} extends {superclass}\n")),
+        None => out.push_str(&format!("declare class {name}\n")),
+    }
+    for property in properties {
+        out.push_str(&format!("\t{}: {}\n", property.name, property.value_type));
+    }
+    for event in events {
+        out.push_str(&format!("\t{}: RBXScriptSignal\n", event.name));
+    }
+    for method in methods {
+        let params = luau_params(&method.params);
+        let separator = if params.is_empty() { "" } else { ", " };
+        out.push_str(&format!(
+            "\tfunction {}(self{separator}{params}): {}\n",
+            method.name,
+            luau_returns(method.returns)
+        ));
+    }
+    out.push_str(extra);
+    out.push_str("end\n\n");
+}
+
+/// Luau definitions file for luau-lsp, generated from the API dump
+pub fn luau_definitions() -> String {
+    let dump = api_dump();
+    let mut out = format!(
+        "-- Clawblox {} scripting API, generated by `clawblox api-dump --luau`.\n\
+         -- Point luau-lsp at this file with `luau-lsp.types.definitionFiles`.\n\n",
+        dump.version
+    );
+
+    out.push_str("declare class EnumItem end\n\n");
+    for item in &dump.enums {
+        out.push_str(&format!(
+            "declare class Enum{} extends EnumItem end\n",
+            item.name
+        ));
+    }
+    out.push('\n');
+
+    // One GetService overload per service so it returns the right type
+    let get_service: String = dump
+        .services
+        .iter()
+        .map(|s| s.name)
+        .chain(INSTANCE_SERVICES.iter().copied())
+        .map(|name| format!("\tfunction GetService(self, name: \"{name}\"): {name}\n"))
+        .collect();
+    for datatype in &dump.datatypes {
+        let is_data_model = datatype.name == "DataModel";
+        let methods: Vec<_> = datatype
+            .methods
+            .iter()
+            .filter(|m| !(is_data_model && m.name == "GetService"))
+            .cloned()
+            .collect();
+        luau_class(
+            &mut out,
+            datatype.name,
+            None,
+            (&datatype.properties, &methods, &datatype.events),
+            if is_data_model { &get_service } else { "" },
+        );
+    }
+
+    // Service objects replace the classes of the same name, whose instances
+    // scripts never see
+    let classes = dump
+        .classes
+        .iter()
+        .filter(|c| !dump.services.iter().any(|s| s.name == c.name));
+    for class in classes {
+        luau_class(
+            &mut out,
+            class.name,
+            class.superclass,
+            (&class.properties, &class.methods, &class.events),
+            "",
+        );
+    }
+
+    for service in &dump.services {
+        luau_class(
+            &mut out,
+            service.name,
+            None,
+            (&service.properties, &service.methods, &service.events),
+            "",
+        );
+    }
+
+    let constructible = dump
+        .datatypes
+        .iter()
+        .filter(|d| !d.constructors.is_empty() || !d.constants.is_empty());
+    for datatype in constructible {
+        let mut members: Vec<String> = datatype
+            .constructors
+            .iter()
+            .map(|f| format!("{}: {}", f.name, luau_function_type(f)))
+            .collect();
+        members.extend(
+            datatype
+                .constants
+                .iter()
+                .map(|c| format!("{}: {}", c.name, c.value_type)),
+        );
+        out.push_str(&format!("declare {}: {{\n", datatype.name));
+        for member in members {
+            out.push_str(&format!("\t{member},\n"));
+        }
+        out.push_str("}\n\n");
+    }
+
+    out.push_str("declare Enum: {\n");
+    for item in &dump.enums {
+        out.push_str(&format!("\t{}: {{\n", item.name));
+        for name in &item.items {
+            out.push_str(&format!("\t\t{name}: Enum{},\n", item.name));
+        }
+        out.push_str("\t},\n");
+    }
+    out.push_str("}\n\n");
+
+    for global in &dump.globals {
+        match (global.value_type, global.functions.as_slice()) {
+            (Some(value_type), _) => {
+                out.push_str(&format!("declare {}: {value_type}\n", global.name));
+            }
+            (None, [function]) if function.name == global.name => {
+                out.push_str(&format!(
+                    "declare function {}({}): {}\n",
+                    function.name,
+                    luau_params(&function.params),
+                    luau_returns(function.returns)
+                ));
+            }
+            (None, functions) => {
+                out.push_str(&format!("declare {}: {{\n", global.name));
+                for function in functions {
+                    out.push_str(&format!(
+                        "\t{}: {},\n",
+                        function.name,
+                        luau_function_type(function)
+                    ));
+                }
+                out.push_str("}\n");
+            }
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::lua::LuaRuntime;
+    use uuid::Uuid;
+
+    fn test_runtime() -> LuaRuntime {
+        LuaRuntime::new(Uuid::new_v4(), None).expect("Failed to create runtime")
+    }
+
+    /// Checks that each member is set on the object `expr` evaluates to and
+    /// that methods are functions; returns the members that aren't
+    fn missing_members(
+        runtime: &LuaRuntime,
+        expr: &str,
+        properties: &[&str],
+        methods: &[&str],
+    ) -> Vec<String> {
+        let check = runtime
+            .lua()
+            .load(format!(
+                r#"
+                local object = {expr}
+                return function(key, is_method)
+                    local ok, value = pcall(function() return object[key] end)
+                    if is_method then
+                        return ok and type(value) == "function"
+                    end
+                    return ok and value ~= nil
+                end
+                "#
+            ))
+            .eval::<mlua::Function>()
+            .unwrap_or_else(|e| panic!("{expr}: {e}"));
+        let mut missing = Vec::new();
+        for (members, is_method) in [(properties, false), (methods, true)] {
+            for name in members {
+                if !check.call::<bool>((*name, is_method)).unwrap() {
+                    missing.push(format!("{expr}.{name}"));
+                }
+            }
+        }
+        missing
+    }
+
+    #[test]
+    fn test_declared_members_exist() {
+        let mut runtime = test_runtime();
+        runtime.load_script("").unwrap();
+        runtime.add_player(1, "Tester");
+        let dump = api_dump();
+        let mut missing = Vec::new();
+
+        let bogus = missing_members(
+            &runtime,
+            "game:GetService('RunService')",
+            &["Bogus"],
+            &["Bogus"],
+        );
+        assert_eq!(bogus.len(), 2, "unknown members must be reported");
+
+        let instance_of = |class: &str| match class {
+            "Player" => "game:GetService('Players'):GetPlayers()[1]".to_string(),
+            "Instance" => "Instance.new('Folder')".to_string(),
+            class => format!("Instance.new('{class}')"),
+        };
+        for (class, methods) in INSTANCE_METHODS {
+            let names: Vec<_> = methods.iter().map(|s| parse_function(s).name).collect();
+            missing.extend(missing_members(&runtime, &instance_of(class), &[], &names));
+        }
+
+        for service in &dump.services {
+            let expr = format!("game:GetService('{}')", service.name);
+            let properties: Vec<_> = service
+                .properties
+                .iter()
+                .filter(|p| !p.value_type.ends_with('?'))
+                .map(|p| p.name)
+                .chain(service.events.iter().map(|e| e.name))
+                .collect();
+            let methods: Vec<_> = service.methods.iter().map(|m| m.name).collect();
+            missing.extend(missing_members(&runtime, &expr, &properties, &methods));
+        }
+        for name in INSTANCE_SERVICES {
+            let class_name: Option<String> = runtime
+                .lua()
+                .load(format!(
+                    "local s = game:GetService('{name}') return s and s.ClassName"
+                ))
+                .eval()
+                .unwrap();
+            assert_eq!(class_name.as_deref(), Some(*name));
+        }
+
+        let datatypes = [
+            ("Vector3", "Vector3.new(1, 2, 3)"),
+            ("CFrame", "CFrame.new()"),
+            ("Color3", "Color3.new()"),
+            ("UDim", "UDim.new(0, 0)"),
+            ("UDim2", "UDim2.new(0, 0, 0, 0)"),
+            ("Random", "Random.new(1)"),
+            ("TweenInfo", "TweenInfo.new()"),
+            ("PhysicalProperties", "PhysicalProperties.new(1, 0.3, 0.5)"),
+            ("RaycastParams", "RaycastParams.new()"),
+            ("OverlapParams", "OverlapParams.new()"),
+            ("PathWaypoint", "PathWaypoint.new()"),
+            ("Path", "game:GetService('PathfindingService'):CreatePath()"),
+            (
+                "Tween",
+                "game:GetService('TweenService'):Create(Instance.new('Part'), TweenInfo.new(), {})",
+            ),
+            ("RBXScriptSignal", "Instance.new('Part').Touched"),
+            (
+                "RBXScriptConnection",
+                "Instance.new('Part').Touched:Connect(function() end)",
+            ),
+            ("DataModel", "game"),
+        ];
+        for (name, expr) in datatypes {
+            let datatype = dump.datatypes.iter().find(|d| d.name == name).unwrap();
+            let properties: Vec<_> = datatype
+                .properties
+                .iter()
+                .filter(|p| !p.value_type.ends_with('?'))
+                .map(|p| p.name)
+                .chain(datatype.events.iter().map(|e| e.name))
+                .collect();
+            let methods: Vec<_> = datatype.methods.iter().map(|m| m.name).collect();
+            missing.extend(missing_members(&runtime, expr, &properties, &methods));
+
+            let constructors: Vec<_> = datatype.constructors.iter().map(|f| f.name).collect();
+            let constants: Vec<_> = datatype.constants.iter().map(|c| c.name).collect();
+            missing.extend(missing_members(&runtime, name, &constants, &constructors));
+        }
+
+        for global in &dump.globals {
+            let functions: Vec<_> = global.functions.iter().map(|f| f.name).collect();
+            let check = match global.value_type {
+                Some(_) => format!("return {} ~= nil", global.name),
+                None if functions == [global.name] => {
+                    format!("return type({}) == 'function'", global.name)
+                }
+                None => {
+                    missing.extend(missing_members(&runtime, global.name, &[], &functions));
+                    continue;
+                }
+            };
+            if !runtime.lua().load(check).eval::<bool>().unwrap() {
+                missing.push(global.name.to_string());
+            }
+        }
+
+        assert!(missing.is_empty(), "declared but missing: {missing:?}");
+    }
+
+    #[test]
+    fn test_enums_match_lua() {
+        let runtime = test_runtime();
+        for (name, items) in enums::enum_items() {
+            for item in &items {
+                let found: String = runtime
+                    .lua()
+                    .load(format!("return tostring(Enum.{name}.{item})"))
+                    .eval()
+                    .unwrap();
+                assert_eq!(found, format!("Enum.{name}.{item}"));
+            }
+            let count: usize = runtime
+                .lua()
+                .load(format!(
+                    "local n = 0 for _ in Enum.{name} do n += 1 end return n"
+                ))
+                .eval()
+                .unwrap();
+            assert_eq!(count, items.len(), "Enum.{name}");
+        }
+        let count: usize = runtime
+            .lua()
+            .load("local n = 0 for _ in Enum do n += 1 end return n")
+            .eval()
+            .unwrap();
+        assert_eq!(count, enums::enum_items().len());
+    }
+
+    #[test]
+    fn test_api_dump() {
+        let dump = api_dump();
+        let class = |name: &str| dump.classes.iter().find(|c| c.name == name).unwrap();
+
+        let base_part = class("BasePart");
+        assert!(!base_part.creatable);
+        assert!(base_part
+            .properties
+            .iter()
+            .any(|p| p.name == "Anchored" && !p.read_only && p.value_type == "boolean"));
+        let touched = base_part
+            .events
+            .iter()
+            .find(|e| e.name == "Touched")
+            .unwrap();
+        assert_eq!(touched.params[0].value_type, "BasePart");
+
+        let part = class("Part");
+        assert_eq!(part.superclass, Some("BasePart"));
+        let defaults = part.defaults.as_ref().unwrap();
+        assert_eq!(defaults["Size"], serde_json::json!([4.0, 1.0, 2.0]));
+        assert_eq!(defaults["Parent"], serde_json::Value::Null);
+
+        let find = class("Instance")
+            .methods
+            .iter()
+            .find(|m| m.name == "FindFirstChild")
+            .unwrap();
+        assert_eq!(find.params[1].name, "recursive");
+        assert_eq!(find.params[1].value_type, "boolean?");
+        assert_eq!(find.returns, Some("Instance?"));
+
+        assert_eq!(class("TextButton").superclass, Some("GuiButton"));
+        assert!(class("Model")
+            .properties
+            .iter()
+            .any(|p| p.name == "PrimaryPart" && p.value_type == "BasePart?"));
+    }
+
+    #[test]
+    fn test_split_top_level() {
+        assert_eq!(
+            split_top_level("f: ((...any) -> ...any) | thread, ...: any"),
+            ["f: ((...any) -> ...any) | thread", "...: any"]
+        );
+        assert!(split_top_level("").is_empty());
+    }
+
+    #[test]
+    fn test_luau_definitions() {
+        let definitions = luau_definitions();
+        assert!(definitions.contains("declare class Part extends BasePart\n"));
+        assert!(definitions.contains("\tPrimaryPart: BasePart?\n"));
+        assert!(definitions.contains(
+            "\tfunction FindFirstChild(self, name: string, recursive: boolean?): Instance?\n"
+        ));
+        assert!(definitions.contains("\tTouched: RBXScriptSignal\n"));
+        assert!(definitions.contains("\tfunction GetService(self, name: \"Players\"): Players\n"));
+        assert!(definitions.contains("declare class ServerScriptService extends Instance\n"));
+        assert_eq!(definitions.matches("declare class Workspace\n").count(), 1);
+        assert!(!definitions.contains("declare class Workspace extends"));
+        assert!(definitions.contains("\t\tNeon: EnumMaterial,\n"));
+        assert!(definitions.contains("declare game: DataModel\n"));
+        assert!(definitions.contains("declare function print(...: any): ()\n"));
+        assert!(
+            definitions.contains("\tnew: (className: string, parent: Instance?) -> Instance,\n")
+        );
+        // Every class is declared before it is used as a superclass
+        for &(name, superclass) in reflection::classes() {
+            let Some(superclass) = superclass else {
+                continue;
+            };
+            let Some(used) =
+                definitions.find(&format!("declare class {name} extends {superclass}\n"))
+            else {
+                continue;
+            };
+            let declared = definitions
+                .find(&format!("declare class {superclass}\n"))
+                .or_else(|| definitions.find(&format!("declare class {superclass} extends")));
+            assert!(
+                declared.is_some_and(|d| d < used),
+                "{name} extends {superclass}"
+            );
+        }
+    }
+}
@@ -0,0 +1,127 @@
+//! Time and randomness sources for a Lua VM.
+//!
+//! By default scripts see wall-clock time and OS entropy. A seeded instance
+//! instead derives both from its seed and the simulation tick, so two runs fed
+//! the same inputs produce the same trajectories.
+
+use mlua::Lua;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+/// Unix time simulated clocks start at (2024-01-01T00:00:00Z)
+const SIMULATED_EPOCH: f64 = 1_704_067_200.0;
+
+#[derive(Debug, Clone, Copy)]
+enum ClockMode {
+    /// Seconds since the given instant
+    Wall(Instant),
+    /// Seconds of simulated time, advanced once per tick
+    Simulated(f64),
+}
+
+/// Time source behind tick(), os.clock(), os.time() and task scheduling.
+///
+/// Clones share state, so switching a runtime to simulated time also switches
+/// every closure that captured its clock.
+#[derive(Debug, Clone)]
+pub struct ScriptClock {
+    mode: Arc<Mutex<ClockMode>>,
+}
+
+impl ScriptClock {
+    pub fn wall() -> Self {
+        Self {
+            mode: Arc::new(Mutex::new(ClockMode::Wall(Instant::now()))),
+        }
+    }
+
+    /// Switches to simulated time starting at zero.
+    pub fn set_simulated(&self) {
+        *self.mode.lock().unwrap() = ClockMode::Simulated(0.0);
+    }
+
+    pub fn is_simulated(&self) -> bool {
+        matches!(*self.mode.lock().unwrap(), ClockMode::Simulated(_))
+    }
+
+    /// Seconds since the clock started
+    pub fn now(&self) -> f64 {
+        match *self.mode.lock().unwrap() {
+            ClockMode::Wall(start) => start.elapsed().as_secs_f64(),
+            ClockMode::Simulated(now) => now,
+        }
+    }
+
+    /// Whole seconds since the Unix epoch on a simulated clock, counted from a
+    /// fixed start date. None on wall-clock time, where os.time() reads the system clock.
+    pub fn simulated_unix_time(&self) -> Option<f64> {
+        match *self.mode.lock().unwrap() {
+            ClockMode::Wall(_) => None,
+            ClockMode::Simulated(now) => Some(SIMULATED_EPOCH + now.floor()),
+        }
+    }
+
+    /// Moves the clock to `seconds`, keeping its mode; used when restoring a snapshot.
+    pub fn set_time(&self, seconds: f64) {
+        let mut mode = self.mode.lock().unwrap();
+        *mode = match *mode {
+            ClockMode::Wall(_) => {
+                let now = Instant::now();
+                ClockMode::Wall(now.checked_sub(Duration::from_secs_f64(seconds)).unwrap_or(now))
+            }
+            ClockMode::Simulated(_) => ClockMode::Simulated(seconds),
+        };
+    }
+
+    /// Advances simulated time by one tick. No-op for wall-clock time.
+    pub fn advance(&self, dt: f64) {
+        if let ClockMode::Simulated(now) = &mut *self.mode.lock().unwrap() {
+            *now += dt;
+        }
+    }
+}
+
+/// Random source behind math.random, Random.new() and HttpService:GenerateGUID().
+#[derive(Debug, Clone)]
+pub struct ScriptRng {
+    rng: Arc<Mutex<StdRng>>,
+}
+
+impl ScriptRng {
+    pub fn from_entropy() -> Self {
+        Self {
+            rng: Arc::new(Mutex::new(StdRng::from_entropy())),
+        }
+    }
+
+    /// Restarts the sequence from `seed`.
+    pub fn reseed(&self, seed: u64) {
+        *self.rng.lock().unwrap() = StdRng::seed_from_u64(seed);
+    }
+
+    /// Uniform float in [0, 1)
+    pub fn next_f64(&self) -> f64 {
+        self.rng.lock().unwrap().gen()
+    }
+
+    /// Seed for a child generator (e.g. Random.new() without a seed)
+    pub fn next_seed(&self) -> u64 {
+        self.rng.lock().unwrap().gen()
+    }
+
+    /// Version 4 UUID drawn from this generator
+    pub fn guid(&self) -> Uuid {
+        uuid::Builder::from_random_bytes(self.rng.lock().unwrap().gen()).into_uuid()
+    }
+}
+
+/// The VM's random source, stored as app data by LuaRuntime.
+/// Falls back to fresh entropy for bare Lua states (e.g. in unit tests).
+pub fn script_rng(lua: &Lua) -> ScriptRng {
+    lua.app_data_ref::<ScriptRng>()
+        .map(|rng| rng.clone())
+        .unwrap_or_else(ScriptRng::from_entropy)
+}
@@ -121,7 +121,7 @@ impl RBXScriptSignal {
     }
 
     pub fn connect(&self, lua: &Lua, callback: Function) -> Result<RBXScriptConnection> {
-        let key = lua.create_registry_value(callback)?;
+        let key = lua.create_registry_value(callback.clone())?;
         let connection = RBXScriptConnection::new(Arc::clone(&self.inner));
         let id = connection.id();
 
@@ -130,12 +130,13 @@ impl RBXScriptSignal {
             callback: key,
             once: false,
         });
+        super::scripts::track_connection(lua, &callback, &connection);
 
         Ok(connection)
     }
 
     pub fn once(&self, lua: &Lua, callback: Function) -> Result<RBXScriptConnection> {
-        let key = lua.create_registry_value(callback)?;
+        let key = lua.create_registry_value(callback.clone())?;
         let connection = RBXScriptConnection::new(Arc::clone(&self.inner));
         let id = connection.id();
 
@@ -144,6 +145,7 @@ impl RBXScriptSignal {
             callback: key,
             once: true,
         });
+        super::scripts::track_connection(lua, &callback, &connection);
 
         Ok(connection)
     }
@@ -1,25 +1,87 @@
-use mlua::{FromLua, Lua, Result, UserData, UserDataFields, UserDataMethods, Value};
-use std::collections::{HashMap, HashSet};
+use mlua::{FromLua, IntoLua, Lua, Result, UserData, UserDataFields, UserDataMethods, Value};
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex, Weak};
 
 use crate::game::constants::humanoid as humanoid_consts;
+use crate::game::physics::part_mass;
 use super::events::{create_signal, RBXScriptSignal};
+use super::reflection::{self, PropertyValue};
 use super::runtime::Game;
-use super::services::WorkspaceService;
-use super::types::{CFrame, Color3, Material, PartType, UDim2, Vector3};
+use super::services::collection_service::{fire_pending, CollectionIndexHandle};
+use super::services::physics_service::DEFAULT_COLLISION_GROUP;
+use super::services::workspace_index::WorkspaceIndexHandle;
+use super::types::{
+    ActuatorRelativeTo, ActuatorType, CFrame, Color3, HumanoidStateType, Material, PartType,
+    PathWaypoint, PhysicalProperties, PositionAlignmentMode, TextXAlignment, TextYAlignment, UDim2,
+    Vector3,
+};
 
 static INSTANCE_ID: AtomicU64 = AtomicU64::new(1);
 
+/// First id handed out by a scoped allocator; keeps scoped ids clear of the
+/// global counter while staying below 2^53 so they survive a trip through Lua
+const SCOPED_INSTANCE_ID_BASE: u64 = 1 << 52;
+
+thread_local! {
+    static SCOPED_INSTANCE_IDS: RefCell<Option<Arc<AtomicU64>>> = const { RefCell::new(None) };
+}
+
 pub type InstanceRef = Arc<Mutex<InstanceData>>;
 pub type WeakInstanceRef = Weak<Mutex<InstanceData>>;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct InstanceId(pub u64);
 
 impl InstanceId {
     pub fn new() -> Self {
-        Self(INSTANCE_ID.fetch_add(1, Ordering::SeqCst))
+        let scoped = SCOPED_INSTANCE_IDS.with(|ids| {
+            ids.borrow()
+                .as_ref()
+                .map(|counter| counter.fetch_add(1, Ordering::SeqCst))
+        });
+        Self(scoped.unwrap_or_else(|| INSTANCE_ID.fetch_add(1, Ordering::SeqCst)))
+    }
+}
+
+/// A per-game id sequence, so a seeded instance assigns the same ids on every
+/// run regardless of what other games in the process are doing.
+#[derive(Debug, Clone)]
+pub struct InstanceIdAllocator {
+    next: Arc<AtomicU64>,
+}
+
+impl InstanceIdAllocator {
+    pub fn new() -> Self {
+        Self {
+            next: Arc::new(AtomicU64::new(SCOPED_INSTANCE_ID_BASE)),
+        }
+    }
+
+    /// Routes InstanceId::new() on this thread to this allocator until the scope is dropped.
+    pub fn enter(&self) -> InstanceIdScope {
+        let previous = SCOPED_INSTANCE_IDS.with(|ids| ids.replace(Some(self.next.clone())));
+        InstanceIdScope { previous }
+    }
+}
+
+impl Default for InstanceIdAllocator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Restores the previously active allocator when dropped.
+pub struct InstanceIdScope {
+    previous: Option<Arc<AtomicU64>>,
+}
+
+impl Drop for InstanceIdScope {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        SCOPED_INSTANCE_IDS.with(|ids| *ids.borrow_mut() = previous);
     }
 }
 
@@ -29,7 +91,7 @@ impl Default for InstanceId {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ClassName {
     Instance,
     BasePart,
@@ -42,8 +104,21 @@ pub enum ClassName {
     Players,
     RunService,
     Camera,
+    ServerScriptService,
+    // Scripts
+    Script,
+    ModuleScript,
     // Constraints
+    Attachment,
     Weld,
+    HingeConstraint,
+    RopeConstraint,
+    SpringConstraint,
+    BallSocketConstraint,
+    PrismaticConstraint,
+    LinearVelocity,
+    VectorForce,
+    AlignPosition,
     // GUI classes
     BillboardGui,
     PlayerGui,
@@ -56,6 +131,41 @@ pub enum ClassName {
 }
 
 impl ClassName {
+    pub const ALL: &'static [ClassName] = &[
+        ClassName::Instance,
+        ClassName::BasePart,
+        ClassName::Part,
+        ClassName::Model,
+        ClassName::Humanoid,
+        ClassName::Player,
+        ClassName::Folder,
+        ClassName::Workspace,
+        ClassName::Players,
+        ClassName::RunService,
+        ClassName::Camera,
+        ClassName::ServerScriptService,
+        ClassName::Script,
+        ClassName::ModuleScript,
+        ClassName::Attachment,
+        ClassName::Weld,
+        ClassName::HingeConstraint,
+        ClassName::RopeConstraint,
+        ClassName::SpringConstraint,
+        ClassName::BallSocketConstraint,
+        ClassName::PrismaticConstraint,
+        ClassName::LinearVelocity,
+        ClassName::VectorForce,
+        ClassName::AlignPosition,
+        ClassName::BillboardGui,
+        ClassName::PlayerGui,
+        ClassName::ScreenGui,
+        ClassName::Frame,
+        ClassName::TextLabel,
+        ClassName::TextButton,
+        ClassName::ImageLabel,
+        ClassName::ImageButton,
+    ];
+
     pub fn as_str(&self) -> &'static str {
         match self {
             ClassName::Instance => "Instance",
@@ -69,7 +179,19 @@ impl ClassName {
             ClassName::Players => "Players",
             ClassName::RunService => "RunService",
             ClassName::Camera => "Camera",
+            ClassName::ServerScriptService => "ServerScriptService",
+            ClassName::Script => "Script",
+            ClassName::ModuleScript => "ModuleScript",
+            ClassName::Attachment => "Attachment",
             ClassName::Weld => "Weld",
+            ClassName::HingeConstraint => "HingeConstraint",
+            ClassName::RopeConstraint => "RopeConstraint",
+            ClassName::SpringConstraint => "SpringConstraint",
+            ClassName::BallSocketConstraint => "BallSocketConstraint",
+            ClassName::PrismaticConstraint => "PrismaticConstraint",
+            ClassName::LinearVelocity => "LinearVelocity",
+            ClassName::VectorForce => "VectorForce",
+            ClassName::AlignPosition => "AlignPosition",
             ClassName::BillboardGui => "BillboardGui",
             ClassName::PlayerGui => "PlayerGui",
             ClassName::ScreenGui => "ScreenGui",
@@ -81,56 +203,16 @@ impl ClassName {
         }
     }
 
+    /// Whether the class is `class_name` or inherits from it (see `reflection::superclass`)
     pub fn is_a(&self, class_name: &str) -> bool {
-        match class_name {
-            "Instance" => true,
-            "BasePart" => matches!(self, ClassName::BasePart | ClassName::Part),
-            "Part" => matches!(self, ClassName::Part),
-            "Model" => matches!(self, ClassName::Model),
-            "Humanoid" => matches!(self, ClassName::Humanoid),
-            "Player" => matches!(self, ClassName::Player),
-            "Folder" => matches!(self, ClassName::Folder),
-            "Workspace" => matches!(self, ClassName::Workspace),
-            "Players" => matches!(self, ClassName::Players),
-            "RunService" => matches!(self, ClassName::RunService),
-            "Camera" => matches!(self, ClassName::Camera),
-            // Constraints
-            "Constraint" => matches!(self, ClassName::Weld),
-            "Weld" => matches!(self, ClassName::Weld),
-            // BillboardGui (3D GUI attached to parts)
-            "BillboardGui" => matches!(self, ClassName::BillboardGui),
-            // GUI hierarchy: GuiBase2d is base for all 2D GUI elements
-            "GuiBase2d" => matches!(
-                self,
-                ClassName::ScreenGui
-                    | ClassName::Frame
-                    | ClassName::TextLabel
-                    | ClassName::TextButton
-                    | ClassName::ImageLabel
-                    | ClassName::ImageButton
-            ),
-            // LayerCollector: ScreenGui inherits from this
-            "LayerCollector" => matches!(self, ClassName::ScreenGui),
-            // GuiObject: Frame, TextLabel, TextButton, ImageLabel, ImageButton
-            "GuiObject" => matches!(
-                self,
-                ClassName::Frame
-                    | ClassName::TextLabel
-                    | ClassName::TextButton
-                    | ClassName::ImageLabel
-                    | ClassName::ImageButton
-            ),
-            // GuiButton: TextButton, ImageButton
-            "GuiButton" => matches!(self, ClassName::TextButton | ClassName::ImageButton),
-            "PlayerGui" => matches!(self, ClassName::PlayerGui),
-            "ScreenGui" => matches!(self, ClassName::ScreenGui),
-            "Frame" => matches!(self, ClassName::Frame),
-            "TextLabel" => matches!(self, ClassName::TextLabel),
-            "TextButton" => matches!(self, ClassName::TextButton),
-            "ImageLabel" => matches!(self, ClassName::ImageLabel),
-            "ImageButton" => matches!(self, ClassName::ImageButton),
-            _ => self.as_str() == class_name,
+        let mut class = Some(self.as_str());
+        while let Some(name) = class {
+            if name == class_name {
+                return true;
+            }
+            class = super::reflection::superclass(name);
         }
+        false
     }
 
     /// Check if this class can be a valid parent for GUI elements
@@ -174,6 +256,7 @@ pub struct InstanceData {
     pub child_removed: RBXScriptSignal,
     pub destroying: RBXScriptSignal,
     pub attribute_changed: RBXScriptSignal,
+    pub change_signals: ChangeSignals,
 
     pub part_data: Option<PartData>,
     pub humanoid_data: Option<HumanoidData>,
@@ -181,12 +264,43 @@ pub struct InstanceData {
     pub model_data: Option<ModelData>,
     pub gui_data: Option<GuiObjectData>,
     pub weld_data: Option<WeldData>,
+    pub attachment_data: Option<AttachmentData>,
+    pub constraint_data: Option<ConstraintData>,
     pub billboard_gui_data: Option<BillboardGuiData>,
+    pub script_data: Option<ScriptData>,
+
+    /// Tag index of the game; set only on the service instances at its roots
+    pub collection: Option<CollectionIndexHandle>,
+    /// Parts, Humanoids and joints of the workspace; set only on Workspace
+    pub workspace_index: Option<WorkspaceIndexHandle>,
 
     destroyed: bool,
 }
 
-#[derive(Debug, Clone)]
+/// Signals of Changed, GetPropertyChangedSignal and GetAttributeChangedSignal,
+/// created the first time a script asks for them
+#[derive(Default)]
+pub struct ChangeSignals {
+    changed: Option<RBXScriptSignal>,
+    properties: HashMap<String, RBXScriptSignal>,
+    attributes: HashMap<String, RBXScriptSignal>,
+}
+
+impl ChangeSignals {
+    /// The Changed signal, created on first use
+    pub fn changed(&mut self) -> RBXScriptSignal {
+        self.changed.get_or_insert_with(|| create_signal("Changed")).clone()
+    }
+
+    /// Whether a script is connected to changes of `property`
+    pub fn is_listening(&self, property: &str) -> bool {
+        self.changed.as_ref().is_some_and(|s| s.connection_count() > 0)
+            || self.properties.get(property).is_some_and(|s| s.connection_count() > 0)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value")]
 pub enum AttributeValue {
     String(String),
     Number(f64),
@@ -228,9 +342,21 @@ pub struct PartData {
     pub transparency: f32,
     pub color: Color3,
     pub material: Material,
+    /// Overrides the material's density, friction and elasticity when set
+    pub custom_physical_properties: Option<PhysicalProperties>,
+    /// Adds (almost) no mass, so welded or pushed assemblies are not weighed down by it
+    pub massless: bool,
+    /// Name of the PhysicsService collision group the part is in
+    pub collision_group: String,
     pub velocity: Vector3,
+    pub angular_velocity: Vector3,
     pub shape: PartType,
     pub position_dirty: bool,
+    /// Velocity or AssemblyAngularVelocity was set by a script and not yet sent to physics
+    pub velocity_dirty: bool,
+    /// Impulses from ApplyImpulse and friends, applied on the next physics step
+    pub impulse: Vector3,
+    pub angular_impulse: Vector3,
 
     pub touched: RBXScriptSignal,
     pub touch_ended: RBXScriptSignal,
@@ -248,15 +374,56 @@ impl Default for PartData {
             transparency: 0.0,
             color: Color3::new(0.6, 0.6, 0.6),
             material: Material::Plastic,
+            custom_physical_properties: None,
+            massless: false,
+            collision_group: DEFAULT_COLLISION_GROUP.to_string(),
             velocity: Vector3::zero(),
+            angular_velocity: Vector3::zero(),
             shape: PartType::Block,
             position_dirty: false,
+            velocity_dirty: false,
+            impulse: Vector3::zero(),
+            angular_impulse: Vector3::zero(),
             touched: create_signal("Touched"),
             touch_ended: create_signal("TouchEnded"),
         }
     }
 }
 
+impl PartData {
+    /// The properties physics uses: the custom ones, or the material's defaults
+    pub fn physical_properties(&self) -> PhysicalProperties {
+        self.custom_physical_properties
+            .unwrap_or_else(|| PhysicalProperties::from_material(self.material))
+    }
+
+    /// Queues an impulse at a world position, or at the center of mass when None.
+    /// Off-center impulses also spin the part.
+    pub fn queue_impulse(&mut self, impulse: Vector3, position: Option<Vector3>) {
+        self.impulse = Vector3::new(
+            self.impulse.x + impulse.x,
+            self.impulse.y + impulse.y,
+            self.impulse.z + impulse.z,
+        );
+        if let Some(position) = position {
+            let arm = Vector3::new(
+                position.x - self.position.x,
+                position.y - self.position.y,
+                position.z - self.position.z,
+            );
+            self.queue_angular_impulse(arm.cross(&impulse));
+        }
+    }
+
+    pub fn queue_angular_impulse(&mut self, impulse: Vector3) {
+        self.angular_impulse = Vector3::new(
+            self.angular_impulse.x + impulse.x,
+            self.angular_impulse.y + impulse.y,
+            self.angular_impulse.z + impulse.z,
+        );
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct HumanoidData {
     pub health: f32,
@@ -272,10 +439,28 @@ pub struct HumanoidData {
     pub cancel_move_to: bool,
     /// Jump requested by Jump() — consumed by physics each frame
     pub jump_requested: bool,
+    /// Rest of the path a NavigateTo agent input is following, walked one
+    /// waypoint at a time once the current target is reached
+    pub path_waypoints: VecDeque<PathWaypoint>,
+    /// State the character controller put the humanoid in last tick
+    pub state: HumanoidStateType,
+    /// Set by ChangeState(Physics): the controller stops walking and jumping
+    /// the character until ChangeState hands it back
+    pub physics_controlled: bool,
+    /// Material of the floor under the character, Air while off the ground
+    pub floor_material: Material,
+    /// Speed last reported by Running or Swimming
+    pub reported_speed: f32,
 
     pub died: RBXScriptSignal,
     pub health_changed: RBXScriptSignal,
     pub move_to_finished: RBXScriptSignal,
+    pub state_changed: RBXScriptSignal,
+    pub running: RBXScriptSignal,
+    pub jumping: RBXScriptSignal,
+    pub free_falling: RBXScriptSignal,
+    pub landed: RBXScriptSignal,
+    pub swimming: RBXScriptSignal,
 }
 
 impl Default for HumanoidData {
@@ -291,9 +476,20 @@ impl Default for HumanoidData {
             move_to_target: None,
             cancel_move_to: false,
             jump_requested: false,
+            path_waypoints: VecDeque::new(),
+            state: HumanoidStateType::Running,
+            physics_controlled: false,
+            floor_material: Material::Air,
+            reported_speed: 0.0,
             died: create_signal("Died"),
             health_changed: create_signal("HealthChanged"),
             move_to_finished: create_signal("MoveToFinished"),
+            state_changed: create_signal("StateChanged"),
+            running: create_signal("Running"),
+            jumping: create_signal("Jumping"),
+            free_falling: create_signal("FreeFalling"),
+            landed: create_signal("Landed"),
+            swimming: create_signal("Swimming"),
         }
     }
 }
@@ -333,6 +529,15 @@ impl Default for ModelData {
     }
 }
 
+/// Data for script instances (Script, ModuleScript)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScriptData {
+    pub source: String,
+    /// Script only: keeps the script from running
+    #[serde(default)]
+    pub disabled: bool,
+}
+
 /// Data for Weld constraints
 #[derive(Debug, Clone)]
 pub struct WeldData {
@@ -355,6 +560,158 @@ impl Default for WeldData {
     }
 }
 
+/// Data for Attachment instances
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AttachmentData {
+    /// Relative to the parent part
+    pub cframe: CFrame,
+}
+
+/// The part an attachment is parented to and the attachment's CFrame relative
+/// to it, if the attachment still exists and is in a BasePart
+pub fn attachment_part(attachment: &Option<WeakInstanceRef>) -> Option<(InstanceRef, CFrame)> {
+    let attachment = attachment.as_ref()?.upgrade()?;
+    let data = attachment.lock().unwrap();
+    let cframe = data.attachment_data.as_ref()?.cframe;
+    let part = data.parent.as_ref()?.upgrade()?;
+    drop(data);
+    part.lock().unwrap().part_data.as_ref()?;
+    Some((part, cframe))
+}
+
+/// An attachment's CFrame in world space
+pub fn attachment_world_cframe(attachment: &Option<WeakInstanceRef>) -> Option<CFrame> {
+    let (part, cframe) = attachment_part(attachment)?;
+    let part_cframe = part.lock().unwrap().part_data.as_ref()?.cframe;
+    Some(part_cframe.multiply(&cframe))
+}
+
+/// Whether two indexes an instance was found in are the same one
+fn same_index<T>(a: &Option<Arc<Mutex<T>>>, b: &Option<Arc<Mutex<T>>>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+/// Data for the attachment-based constraints (HingeConstraint, RopeConstraint,
+/// SpringConstraint, BallSocketConstraint, PrismaticConstraint) and movers
+/// (LinearVelocity, VectorForce, AlignPosition). Each class uses the fields
+/// its properties map to.
+#[derive(Debug, Clone)]
+pub struct ConstraintData {
+    pub attachment0: Option<WeakInstanceRef>,
+    pub attachment1: Option<WeakInstanceRef>,
+    pub enabled: bool,
+    // Hinge and prismatic: hinge angles are in degrees, prismatic positions in studs
+    pub actuator_type: ActuatorType,
+    /// AngularVelocity (radians/s) or Velocity (studs/s) of the motor
+    pub velocity: f32,
+    /// MotorMaxTorque or MotorMaxForce
+    pub motor_max_force: f32,
+    /// TargetAngle or TargetPosition of the servo
+    pub target: f32,
+    /// ServoMaxTorque or ServoMaxForce
+    pub servo_max_force: f32,
+    pub limits_enabled: bool,
+    /// LowerAngle or LowerLimit
+    pub lower_limit: f32,
+    /// UpperAngle or UpperLimit
+    pub upper_limit: f32,
+    // Rope
+    pub length: f32,
+    // Spring
+    pub free_length: f32,
+    pub stiffness: f32,
+    pub damping: f32,
+    // Movers
+    /// RelativeTo of LinearVelocity and VectorForce
+    pub relative_to: ActuatorRelativeTo,
+    /// VectorVelocity of LinearVelocity
+    pub vector_velocity: Vector3,
+    /// Force of VectorForce
+    pub force: Vector3,
+    pub apply_at_center_of_mass: bool,
+    /// MaxForce of LinearVelocity and AlignPosition
+    pub max_force: f32,
+    /// Mode of AlignPosition
+    pub alignment_mode: PositionAlignmentMode,
+    /// Position a one-attachment AlignPosition pulls toward
+    pub position: Vector3,
+    pub max_velocity: f32,
+    pub responsiveness: f32,
+    pub rigidity_enabled: bool,
+}
+
+impl ConstraintData {
+    pub fn new(class: ClassName) -> Self {
+        let (lower_limit, upper_limit) = match class {
+            ClassName::HingeConstraint => (-45.0, 45.0),
+            _ => (-5.0, 5.0),
+        };
+        Self {
+            attachment0: None,
+            attachment1: None,
+            enabled: true,
+            actuator_type: ActuatorType::None,
+            velocity: 0.0,
+            motor_max_force: 0.0,
+            target: 0.0,
+            servo_max_force: 0.0,
+            limits_enabled: false,
+            lower_limit,
+            upper_limit,
+            length: 5.0,
+            free_length: 1.0,
+            stiffness: 0.0,
+            damping: 0.0,
+            relative_to: match class {
+                ClassName::VectorForce => ActuatorRelativeTo::Attachment0,
+                _ => ActuatorRelativeTo::World,
+            },
+            vector_velocity: Vector3::zero(),
+            force: Vector3::zero(),
+            apply_at_center_of_mass: false,
+            max_force: match class {
+                ClassName::AlignPosition => 10000.0,
+                _ => 1000.0,
+            },
+            alignment_mode: PositionAlignmentMode::TwoAttachment,
+            position: Vector3::zero(),
+            max_velocity: f32::MAX,
+            responsiveness: 10.0,
+            rigidity_enabled: false,
+        }
+    }
+
+    /// Attachment1's CFrame relative to Attachment0's, if both are in parts
+    fn relative_cframe(&self) -> Option<CFrame> {
+        let cframe0 = attachment_world_cframe(&self.attachment0)?;
+        let cframe1 = attachment_world_cframe(&self.attachment1)?;
+        Some(cframe0.to_object_space(&cframe1))
+    }
+
+    /// Distance between the attachments (CurrentDistance, CurrentLength)
+    pub fn current_distance(&self) -> f32 {
+        self.relative_cframe()
+            .map_or(0.0, |cframe| cframe.position.magnitude())
+    }
+
+    /// Rotation of Attachment1 about Attachment0's X axis in degrees (CurrentAngle)
+    pub fn current_angle(&self) -> f32 {
+        self.relative_cframe().map_or(0.0, |cframe| {
+            let r = cframe.rotation;
+            r[2][1].atan2(r[1][1]).to_degrees()
+        })
+    }
+
+    /// Offset of Attachment1 along Attachment0's X axis (CurrentPosition)
+    pub fn current_position(&self) -> f32 {
+        self.relative_cframe().map_or(0.0, |cframe| cframe.position.x)
+    }
+}
+
 /// Data for BillboardGui (3D GUI that floats above parts)
 #[derive(Debug, Clone)]
 pub struct BillboardGuiData {
@@ -378,7 +735,7 @@ impl Default for BillboardGuiData {
 }
 
 /// Data for GUI objects (Frame, TextLabel, TextButton, etc.)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GuiObjectData {
     // Layout properties
     pub position: UDim2,
@@ -414,30 +771,19 @@ pub struct GuiObjectData {
     pub ignore_gui_inset: bool,
     pub enabled: bool,
 
-    // Events (for GuiButton types)
+    // Events (for GuiButton types; not part of snapshots)
+    #[serde(skip)]
     pub mouse_button1_click: Option<RBXScriptSignal>,
+    #[serde(skip)]
     pub mouse_button1_down: Option<RBXScriptSignal>,
+    #[serde(skip)]
     pub mouse_button1_up: Option<RBXScriptSignal>,
+    #[serde(skip)]
     pub mouse_enter: Option<RBXScriptSignal>,
+    #[serde(skip)]
     pub mouse_leave: Option<RBXScriptSignal>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
-pub enum TextXAlignment {
-    Left,
-    #[default]
-    Center,
-    Right,
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
-pub enum TextYAlignment {
-    Top,
-    #[default]
-    Center,
-    Bottom,
-}
-
 impl Default for GuiObjectData {
     fn default() -> Self {
         Self {
@@ -560,23 +906,88 @@ impl InstanceData {
             child_removed: create_signal("ChildRemoved"),
             destroying: create_signal("Destroying"),
             attribute_changed: create_signal("AttributeChanged"),
+            change_signals: ChangeSignals::default(),
             part_data: None,
             humanoid_data: None,
             player_data: None,
             model_data: None,
             gui_data: None,
             weld_data: None,
+            attachment_data: None,
+            constraint_data: None,
             billboard_gui_data: None,
+            script_data: None,
+            collection: None,
+            workspace_index: None,
             destroyed: false,
         }
     }
 
+    /// A new instance of `class` with the class's data and defaults. Players
+    /// need a user id, so they come from `new_player` instead.
+    pub fn for_class(class: ClassName, name: &str) -> Self {
+        match class {
+            ClassName::Part | ClassName::BasePart => {
+                let mut inst = Self::new(class, name);
+                inst.part_data = Some(PartData::default());
+                inst
+            }
+            ClassName::Model => Self::new_model(name),
+            ClassName::Humanoid => Self::new_humanoid(name),
+            ClassName::Script => Self::new_script(name),
+            ClassName::ModuleScript => Self::new_module_script(name),
+            ClassName::Attachment => Self::new_attachment(name),
+            ClassName::Weld => Self::new_weld(name),
+            ClassName::HingeConstraint
+            | ClassName::RopeConstraint
+            | ClassName::SpringConstraint
+            | ClassName::BallSocketConstraint
+            | ClassName::PrismaticConstraint
+            | ClassName::LinearVelocity
+            | ClassName::VectorForce
+            | ClassName::AlignPosition => Self::new_constraint(class, name),
+            ClassName::BillboardGui => Self::new_billboard_gui(name),
+            ClassName::ScreenGui => Self::new_screen_gui(name),
+            ClassName::Frame => Self::new_frame(name),
+            ClassName::TextLabel => Self::new_text_label(name),
+            ClassName::TextButton => Self::new_text_button(name),
+            ClassName::ImageLabel => Self::new_image_label(name),
+            ClassName::ImageButton => Self::new_image_button(name),
+            ClassName::PlayerGui => Self::new_player_gui(name),
+            _ => Self::new(class, name),
+        }
+    }
+
     pub fn new_weld(name: &str) -> Self {
         let mut inst = Self::new(ClassName::Weld, name);
         inst.weld_data = Some(WeldData::default());
         inst
     }
 
+    pub fn new_attachment(name: &str) -> Self {
+        let mut inst = Self::new(ClassName::Attachment, name);
+        inst.attachment_data = Some(AttachmentData::default());
+        inst
+    }
+
+    pub fn new_constraint(class: ClassName, name: &str) -> Self {
+        let mut inst = Self::new(class, name);
+        inst.constraint_data = Some(ConstraintData::new(class));
+        inst
+    }
+
+    pub fn new_script(name: &str) -> Self {
+        let mut inst = Self::new(ClassName::Script, name);
+        inst.script_data = Some(ScriptData::default());
+        inst
+    }
+
+    pub fn new_module_script(name: &str) -> Self {
+        let mut inst = Self::new(ClassName::ModuleScript, name);
+        inst.script_data = Some(ScriptData::default());
+        inst
+    }
+
     pub fn new_billboard_gui(name: &str) -> Self {
         let mut inst = Self::new(ClassName::BillboardGui, name);
         inst.billboard_gui_data = Some(BillboardGuiData::default());
@@ -704,7 +1115,22 @@ impl Instance {
             .map(Instance::from_ref)
     }
 
+    /// Dot-separated path from the root of its tree, e.g. `Workspace.Tower.Script`
+    pub fn full_name(&self) -> String {
+        let mut names = vec![self.name()];
+        let mut current = self.parent();
+        while let Some(parent) = current {
+            names.push(parent.name());
+            current = parent.parent();
+        }
+        names.reverse();
+        names.join(".")
+    }
+
     pub fn set_parent(&self, parent: Option<&Instance>) {
+        let old_index = self.collection_index();
+        let old_workspace = self.workspace_index();
+
         if let Some(old_parent) = self.parent() {
             let my_id = self.id();
             old_parent
@@ -726,6 +1152,58 @@ impl Instance {
         } else {
             self.data.lock().unwrap().parent = None;
         }
+
+        // Entering or leaving the game updates its tag index
+        let new_index = self.collection_index();
+        if !same_index(&old_index, &new_index) {
+            if let Some(old) = old_index {
+                old.lock().unwrap().remove_tree(self);
+            }
+            if let Some(new) = new_index {
+                new.lock().unwrap().insert_tree(self);
+            }
+        }
+
+        // and entering or leaving the workspace its workspace index
+        let new_workspace = self.workspace_index();
+        if !same_index(&old_workspace, &new_workspace) {
+            if let Some(old) = old_workspace {
+                old.lock().unwrap().remove_tree(self);
+            }
+            if let Some(new) = new_workspace {
+                new.lock().unwrap().insert_tree(self);
+            }
+        }
+    }
+
+    /// Tag index of the game this instance is in, if it is in one
+    fn collection_index(&self) -> Option<CollectionIndexHandle> {
+        self.find_index(|data| &data.collection)
+    }
+
+    /// Index of the workspace this instance is in, if it is in one
+    pub fn workspace_index(&self) -> Option<WorkspaceIndexHandle> {
+        self.find_index(|data| &data.workspace_index)
+    }
+
+    /// Index carried by this instance or its nearest ancestor that has one
+    fn find_index<T: Clone>(&self, index: fn(&InstanceData) -> &Option<T>) -> Option<T> {
+        let mut current = Some(self.clone());
+        while let Some(instance) = current {
+            if let Some(index) = index(&instance.data.lock().unwrap()) {
+                return Some(index.clone());
+            }
+            current = instance.parent();
+        }
+        None
+    }
+
+    /// Queues this part for a refresh in its workspace's spatial index, after
+    /// its position, shape or query filters changed
+    pub fn mark_spatial_dirty(&self) {
+        if let Some(index) = self.workspace_index() {
+            index.lock().unwrap().mark_dirty(self.id());
+        }
     }
 
     pub fn get_children(&self) -> Vec<Instance> {
@@ -788,6 +1266,11 @@ impl Instance {
                 return Ok(());
             }
             data.destroyed = true;
+            if data.class_name == ClassName::Script {
+                let id = data.id;
+                drop(data);
+                super::scripts::stop_script(lua, id);
+            }
         }
 
         let destroying = self.data.lock().unwrap().destroying.clone();
@@ -802,39 +1285,110 @@ impl Instance {
         Ok(())
     }
 
+    /// Stops the instance if it is a disabled Script. An enabled script
+    /// (re)starts on the next tick.
+    pub fn stop_if_disabled(&self, lua: &Lua) {
+        let (id, disabled) = {
+            let data = self.data.lock().unwrap();
+            let disabled = data.class_name == ClassName::Script
+                && data.script_data.as_ref().is_some_and(|s| s.disabled);
+            (data.id, disabled)
+        };
+        if disabled {
+            super::scripts::stop_script(lua, id);
+        }
+    }
+
+    /// Copies the instance and its descendants: attributes, tags and the
+    /// properties reflection marks as cloned. The copy has no parent and its
+    /// own signals, and references into the copied tree (PrimaryPart, weld
+    /// parts, constraint attachments, Adornee) point at the copies.
     pub fn clone_instance(&self) -> Instance {
-        let data = self.data.lock().unwrap();
-        let mut new_data = InstanceData::new(data.class_name, &data.name);
-        new_data.attributes = data.attributes.clone();
-        new_data.tags = data.tags.clone();
-        new_data.part_data = data.part_data.clone();
-        new_data.humanoid_data = data.humanoid_data.clone();
-        new_data.model_data = data.model_data.clone();
-
-        // Clone GUI data but create fresh signals to avoid sharing handlers
-        if let Some(gui) = &data.gui_data {
-            let mut cloned_gui = gui.clone();
-            // Create new signals for the cloned instance
-            if cloned_gui.mouse_button1_click.is_some() {
-                cloned_gui.mouse_button1_click = Some(create_signal("MouseButton1Click"));
-                cloned_gui.mouse_button1_down = Some(create_signal("MouseButton1Down"));
-                cloned_gui.mouse_button1_up = Some(create_signal("MouseButton1Up"));
-                cloned_gui.mouse_enter = Some(create_signal("MouseEnter"));
-                cloned_gui.mouse_leave = Some(create_signal("MouseLeave"));
+        let mut copies = HashMap::new();
+        let clone = self.clone_tree(&mut copies);
+
+        for copy in copies.values() {
+            let class = copy.class_name();
+            for property in reflection::properties_of(class).filter(|p| p.cloned()) {
+                let Some(PropertyValue::Instance(Some(target))) =
+                    property.get(&copy.data.lock().unwrap())
+                else {
+                    continue;
+                };
+                let target_copy = target
+                    .upgrade()
+                    .and_then(|data| copies.get(&data.lock().unwrap().id));
+                if let Some(target_copy) = target_copy {
+                    property.apply(copy, PropertyValue::Instance(Some(target_copy.weak_ref())));
+                }
             }
-            new_data.gui_data = Some(cloned_gui);
         }
 
-        drop(data);
+        clone
+    }
 
-        let new_instance = Instance::from_data(new_data);
+    /// Clones the tree without fixing up references, recording each copy by
+    /// the id of its original
+    fn clone_tree(&self, copies: &mut HashMap<InstanceId, Instance>) -> Instance {
+        let copy = {
+            let data = self.data.lock().unwrap();
+            let mut copy = InstanceData::for_class(data.class_name, &data.name);
+            copy.attributes = data.attributes.clone();
+            copy.tags = data.tags.clone();
+            Instance::from_data(copy)
+        };
+
+        for property in reflection::properties_of(self.class_name()).filter(|p| p.cloned()) {
+            let value = property.get(&self.data.lock().unwrap());
+            if let Some(value) = value {
+                property.apply(&copy, value);
+            }
+        }
+        copies.insert(self.id(), copy.clone());
 
         for child in self.get_children() {
-            let cloned_child = child.clone_instance();
-            cloned_child.set_parent(Some(&new_instance));
+            child.clone_tree(copies).set_parent(Some(&copy));
         }
 
-        new_instance
+        copy
+    }
+
+    /// Sets a property the way a script assignment does: converts the value,
+    /// runs the property's side effects and fires its changed signals if the
+    /// value changed. Properties the class doesn't have, and values a lenient
+    /// property can't convert, are ignored.
+    pub fn set_property(&self, lua: &Lua, name: &str, value: Value) -> Result<()> {
+        let Some(property) = reflection::find_property(self.class_name(), name) else {
+            return Ok(());
+        };
+        if property.read_only() {
+            return Err(mlua::Error::runtime(format!("{name} is read-only")));
+        }
+        let type_name = value.type_name();
+        let value = match property.value_type.from_lua(value, lua) {
+            Ok(value) => value,
+            Err(_) if property.lenient => return Ok(()),
+            Err(_) => {
+                return Err(mlua::Error::runtime(format!(
+                    "{name}: expected {}, got {type_name}",
+                    property.value_type.name()
+                )))
+            }
+        };
+
+        // Old value is only needed to decide whether to fire the change signals
+        let old = self
+            .is_listening(name)
+            .then(|| property.get(&self.data.lock().unwrap()));
+        property.apply(self, value);
+        property.after_set(lua, self)?;
+        if let Some(old) = old {
+            let new = property.get(&self.data.lock().unwrap());
+            if new != old {
+                self.fire_property_changed(lua, name)?;
+            }
+        }
+        Ok(())
     }
 
     pub fn set_attribute(&self, name: &str, value: AttributeValue) {
@@ -845,6 +1399,76 @@ impl Instance {
             .insert(name.to_string(), value);
     }
 
+    pub fn changed_signal(&self) -> RBXScriptSignal {
+        self.data.lock().unwrap().change_signals.changed()
+    }
+
+    pub fn property_changed_signal(&self, property: &str) -> RBXScriptSignal {
+        self.data
+            .lock()
+            .unwrap()
+            .change_signals
+            .properties
+            .entry(property.to_string())
+            .or_insert_with(|| create_signal(property))
+            .clone()
+    }
+
+    pub fn attribute_changed_signal(&self, attribute: &str) -> RBXScriptSignal {
+        self.data
+            .lock()
+            .unwrap()
+            .change_signals
+            .attributes
+            .entry(attribute.to_string())
+            .or_insert_with(|| create_signal(attribute))
+            .clone()
+    }
+
+    /// Whether a script is connected to changes of `property`
+    pub fn is_listening(&self, property: &str) -> bool {
+        self.data.lock().unwrap().change_signals.is_listening(property)
+    }
+
+    /// Fires the property's changed signal and Changed(property)
+    pub fn fire_property_changed(&self, lua: &Lua, property: &str) -> Result<()> {
+        let (changed, signal) = {
+            let data = self.data.lock().unwrap();
+            (
+                data.change_signals.changed.clone(),
+                data.change_signals.properties.get(property).cloned(),
+            )
+        };
+        if let Some(signal) = signal {
+            let threads = signal.fire_as_coroutines(lua, mlua::MultiValue::new())?;
+            crate::game::lua::events::track_yielded_threads(lua, threads)?;
+        }
+        if let Some(changed) = changed {
+            let args = mlua::MultiValue::from_iter([Value::String(lua.create_string(property)?)]);
+            let threads = changed.fire_as_coroutines(lua, args)?;
+            crate::game::lua::events::track_yielded_threads(lua, threads)?;
+        }
+        Ok(())
+    }
+
+    /// Fires the attribute's changed signal and AttributeChanged(attribute)
+    fn fire_attribute_changed(&self, lua: &Lua, attribute: &str) -> Result<()> {
+        let (changed, signal) = {
+            let data = self.data.lock().unwrap();
+            (
+                data.attribute_changed.clone(),
+                data.change_signals.attributes.get(attribute).cloned(),
+            )
+        };
+        if let Some(signal) = signal {
+            let threads = signal.fire_as_coroutines(lua, mlua::MultiValue::new())?;
+            crate::game::lua::events::track_yielded_threads(lua, threads)?;
+        }
+        let args = mlua::MultiValue::from_iter([Value::String(lua.create_string(attribute)?)]);
+        let threads = changed.fire_as_coroutines(lua, args)?;
+        crate::game::lua::events::track_yielded_threads(lua, threads)
+    }
+
     pub fn get_attribute(&self, name: &str) -> Option<AttributeValue> {
         self.data.lock().unwrap().attributes.get(name).cloned()
     }
@@ -854,7 +1478,12 @@ impl Instance {
     }
 
     pub fn add_tag(&self, tag: &str) {
-        self.data.lock().unwrap().tags.insert(tag.to_string());
+        let added = self.data.lock().unwrap().tags.insert(tag.to_string());
+        if added {
+            if let Some(index) = self.collection_index() {
+                index.lock().unwrap().insert(tag, self);
+            }
+        }
     }
 
     pub fn has_tag(&self, tag: &str) -> bool {
@@ -862,7 +1491,23 @@ impl Instance {
     }
 
     pub fn remove_tag(&self, tag: &str) {
-        self.data.lock().unwrap().tags.remove(tag);
+        let removed = self.data.lock().unwrap().tags.remove(tag);
+        if removed {
+            if let Some(index) = self.collection_index() {
+                index.lock().unwrap().remove(tag, self);
+            }
+        }
+    }
+
+    /// Replaces all tags, keeping the game's tag index current
+    pub fn set_tags(&self, tags: &HashSet<String>) {
+        let current = self.get_tags();
+        for tag in current.difference(tags) {
+            self.remove_tag(tag);
+        }
+        for tag in tags.difference(&current) {
+            self.add_tag(tag);
+        }
     }
 
     pub fn get_tags(&self) -> HashSet<String> {
@@ -888,953 +1533,130 @@ impl FromLua for Instance {
 }
 
 impl UserData for Instance {
+    /// Properties and events come from the reflection registry. A name the
+    /// instance's class doesn't have reads as the child of that name.
     fn add_fields<F: UserDataFields<Self>>(fields: &mut F) {
-        fields.add_field_method_get("Name", |_, this| Ok(this.name()));
-        fields.add_field_method_set("Name", |_, this, name: String| {
-            this.set_name(&name);
-            Ok(())
-        });
+        for name in reflection::property_names() {
+            fields.add_field_method_get(name, move |lua, this| {
+                let data = this.data.lock().unwrap();
+                match reflection::find_property(data.class_name, name) {
+                    Some(property) => property.get(&data).into_lua(lua),
+                    None => {
+                        drop(data);
+                        this.find_first_child(name, false).into_lua(lua)
+                    }
+                }
+            });
+        }
 
-        fields.add_field_method_get("ClassName", |_, this| {
-            Ok(this.class_name().as_str().to_string())
-        });
+        // The userdata isn't borrowed while the setter runs, so changed
+        // handlers can use the instance freely
+        for name in reflection::writable_property_names() {
+            fields.add_field_function_set(name, move |lua, ud: mlua::AnyUserData, value: Value| {
+                let this = ud.borrow::<Instance>()?.clone();
+                this.set_property(lua, name, value)
+            });
+        }
 
-        fields.add_field_method_get("Parent", |_, this| Ok(this.parent()));
-        fields.add_field_method_set("Parent", |_, this, parent: Value| {
-            match parent {
-                Value::Nil => {
-                    this.set_parent(None);
-                }
-                Value::UserData(ud) => {
-                    if let Ok(inst) = ud.borrow::<Instance>() {
-                        this.set_parent(Some(&inst));
-                    } else if let Ok(ws) = ud.borrow::<WorkspaceService>() {
-                        this.set_parent(Some(&ws.instance));
-                    } else {
-                        return Err(mlua::Error::runtime("Parent must be an Instance or nil"));
+        for name in reflection::event_names() {
+            fields.add_field_method_get(name, move |lua, this| {
+                let mut data = this.data.lock().unwrap();
+                match reflection::find_event(data.class_name, name) {
+                    Some(event) => event.get(&mut data).into_lua(lua),
+                    None => {
+                        drop(data);
+                        this.find_first_child(name, false).into_lua(lua)
                     }
                 }
-                _ => return Err(mlua::Error::runtime("Parent must be an Instance or nil")),
-            }
-            Ok(())
-        });
+            });
+        }
+    }
 
-        fields.add_field_method_get("ChildAdded", |_, this| {
-            Ok(this.data.lock().unwrap().child_added.clone())
+    fn add_methods<M: UserDataMethods<Self>>(methods: &mut M) {
+        methods.add_method("Clone", |lua, this, ()| {
+            let clone = this.clone_instance();
+            super::scripts::register_script_tree(lua, &clone);
+            Ok(clone)
         });
-        fields.add_field_method_get("ChildRemoved", |_, this| {
-            Ok(this.data.lock().unwrap().child_removed.clone())
+
+        methods.add_method("Destroy", |lua, this, ()| {
+            this.destroy(lua)?;
+            fire_pending(lua)
         });
-        fields.add_field_method_get("Destroying", |_, this| {
-            Ok(this.data.lock().unwrap().destroying.clone())
+
+        methods.add_method(
+            "FindFirstChild",
+            |_, this, (name, recursive): (String, Option<bool>)| {
+                Ok(this.find_first_child(&name, recursive.unwrap_or(false)))
+            },
+        );
+
+        methods.add_method("FindFirstChildOfClass", |_, this, class_name: String| {
+            Ok(this.find_first_child_of_class(&class_name))
         });
-        fields.add_field_method_get("AttributeChanged", |_, this| {
-            Ok(this.data.lock().unwrap().attribute_changed.clone())
+
+        methods.add_method("GetChildren", |_, this, ()| Ok(this.get_children()));
+
+        methods.add_method("GetDescendants", |_, this, ()| Ok(this.get_descendants()));
+
+        methods.add_method("IsA", |_, this, class_name: String| {
+            Ok(this.is_a(&class_name))
         });
 
-        // Position: Vector3 for Parts, UDim2 for GUI objects
-        fields.add_field_method_get("Position", |lua, this| {
-            let data = this.data.lock().unwrap();
-            if let Some(part) = &data.part_data {
-                Ok(Value::UserData(lua.create_userdata(part.position)?))
-            } else if let Some(gui) = &data.gui_data {
-                Ok(Value::UserData(lua.create_userdata(gui.position)?))
-            } else {
-                Ok(Value::Nil)
-            }
+        methods.add_method("IsDescendantOf", |_, this, ancestor: Instance| {
+            Ok(this.is_descendant_of(&ancestor))
         });
-        fields.add_field_method_set("Position", |_, this, value: Value| {
-            let mut data = this.data.lock().unwrap();
-            match value {
+
+        methods.add_method("SetAttribute", |lua, this, (name, value): (String, Value)| {
+            let attr_value = match value {
+                Value::Nil => AttributeValue::Nil,
+                Value::Boolean(b) => AttributeValue::Bool(b),
+                Value::Integer(n) => AttributeValue::Number(n as f64),
+                Value::Number(n) => AttributeValue::Number(n),
+                Value::String(s) => AttributeValue::String(s.to_str()?.to_string()),
                 Value::UserData(ud) => {
-                    if let Ok(pos) = ud.borrow::<Vector3>() {
-                        if let Some(part) = &mut data.part_data {
-                            part.position = *pos;
-                            part.cframe.position = *pos;
-                            part.position_dirty = true;
-                        }
-                    } else if let Ok(pos) = ud.borrow::<UDim2>() {
-                        if let Some(gui) = &mut data.gui_data {
-                            gui.position = *pos;
-                        }
+                    if let Ok(v) = ud.borrow::<Vector3>() {
+                        AttributeValue::Vector3(*v)
+                    } else if let Ok(c) = ud.borrow::<Color3>() {
+                        AttributeValue::Color3(*c)
+                    } else {
+                        return Err(mlua::Error::runtime("Unsupported attribute type"));
                     }
                 }
-                _ => {}
+                _ => return Err(mlua::Error::runtime("Unsupported attribute type")),
+            };
+            let old = this.get_attribute(&name).map_or(serde_json::Value::Null, |v| v.to_json());
+            let changed = old != attr_value.to_json();
+            this.set_attribute(&name, attr_value);
+            if changed {
+                this.fire_attribute_changed(lua, &name)?;
             }
             Ok(())
         });
 
-        fields.add_field_method_get("CFrame", |_, this| {
-            let data = this.data.lock().unwrap();
-            Ok(data.part_data.as_ref().map(|p| p.cframe))
+        methods.add_method("GetAttributeChangedSignal", |_, this, name: String| {
+            Ok(this.attribute_changed_signal(&name))
         });
-        fields.add_field_method_set("CFrame", |_, this, cf: CFrame| {
-            let mut data = this.data.lock().unwrap();
-            if let Some(part) = &mut data.part_data {
-                part.cframe = cf;
-                part.position = cf.position;
-                part.position_dirty = true;
+
+        methods.add_method("GetPropertyChangedSignal", |_, this, property: String| {
+            if reflection::find_property(this.class_name(), &property).is_none() {
+                return Err(mlua::Error::runtime(format!(
+                    "{} is not a valid property name.",
+                    property
+                )));
             }
-            Ok(())
+            Ok(this.property_changed_signal(&property))
         });
 
-        // Size: Vector3 for Parts, UDim2 for GUI objects
-        fields.add_field_method_get("Size", |lua, this| {
-            let data = this.data.lock().unwrap();
-            if let Some(part) = &data.part_data {
-                Ok(Value::UserData(lua.create_userdata(part.size)?))
-            } else if let Some(gui) = &data.gui_data {
-                Ok(Value::UserData(lua.create_userdata(gui.size)?))
-            } else {
-                Ok(Value::Nil)
-            }
-        });
-        fields.add_field_method_set("Size", |_, this, value: Value| {
-            let mut data = this.data.lock().unwrap();
-            match value {
-                Value::UserData(ud) => {
-                    if let Ok(size) = ud.borrow::<Vector3>() {
-                        if let Some(part) = &mut data.part_data {
-                            part.size = *size;
-                        }
-                    } else if let Ok(size) = ud.borrow::<UDim2>() {
-                        if let Some(gui) = &mut data.gui_data {
-                            gui.size = *size;
-                        }
-                    }
-                }
-                _ => {}
-            }
-            Ok(())
-        });
-
-        fields.add_field_method_get("Anchored", |_, this| {
-            let data = this.data.lock().unwrap();
-            Ok(data.part_data.as_ref().map(|p| p.anchored))
-        });
-        fields.add_field_method_set("Anchored", |_, this, anchored: bool| {
-            let mut data = this.data.lock().unwrap();
-            if let Some(part) = &mut data.part_data {
-                part.anchored = anchored;
-            }
-            Ok(())
-        });
-
-        fields.add_field_method_get("CanCollide", |_, this| {
-            let data = this.data.lock().unwrap();
-            Ok(data.part_data.as_ref().map(|p| p.can_collide))
-        });
-        fields.add_field_method_set("CanCollide", |_, this, can_collide: bool| {
-            let mut data = this.data.lock().unwrap();
-            if let Some(part) = &mut data.part_data {
-                part.can_collide = can_collide;
-            }
-            Ok(())
-        });
-
-        fields.add_field_method_get("CanTouch", |_, this| {
-            let data = this.data.lock().unwrap();
-            Ok(data.part_data.as_ref().map(|p| p.can_touch))
-        });
-        fields.add_field_method_set("CanTouch", |_, this, can_touch: bool| {
-            let mut data = this.data.lock().unwrap();
-            if let Some(part) = &mut data.part_data {
-                part.can_touch = can_touch;
-            }
-            Ok(())
-        });
-
-        fields.add_field_method_get("Transparency", |_, this| {
-            let data = this.data.lock().unwrap();
-            Ok(data.part_data.as_ref().map(|p| p.transparency))
-        });
-        fields.add_field_method_set("Transparency", |_, this, transparency: f32| {
-            let mut data = this.data.lock().unwrap();
-            if let Some(part) = &mut data.part_data {
-                part.transparency = transparency.clamp(0.0, 1.0);
-            }
-            Ok(())
-        });
-
-        fields.add_field_method_get("Color", |_, this| {
-            let data = this.data.lock().unwrap();
-            Ok(data.part_data.as_ref().map(|p| p.color))
-        });
-        fields.add_field_method_set("Color", |_, this, color: Color3| {
-            let mut data = this.data.lock().unwrap();
-            if let Some(part) = &mut data.part_data {
-                part.color = color;
-            }
-            Ok(())
-        });
-
-        fields.add_field_method_get("Material", |_, this| {
-            let data = this.data.lock().unwrap();
-            Ok(data.part_data.as_ref().map(|p| p.material))
-        });
-        fields.add_field_method_set("Material", |_, this, material: Material| {
-            let mut data = this.data.lock().unwrap();
-            if let Some(part) = &mut data.part_data {
-                part.material = material;
-            }
-            Ok(())
-        });
-
-        fields.add_field_method_get("Velocity", |_, this| {
-            let data = this.data.lock().unwrap();
-            Ok(data.part_data.as_ref().map(|p| p.velocity))
-        });
-        fields.add_field_method_set("Velocity", |_, this, velocity: Vector3| {
-            let mut data = this.data.lock().unwrap();
-            if let Some(part) = &mut data.part_data {
-                part.velocity = velocity;
-            }
-            Ok(())
-        });
-
-        fields.add_field_method_get("AssemblyLinearVelocity", |_, this| {
-            let data = this.data.lock().unwrap();
-            Ok(data.part_data.as_ref().map(|p| p.velocity))
-        });
-
-        fields.add_field_method_get("Shape", |_, this| {
-            let data = this.data.lock().unwrap();
-            Ok(data.part_data.as_ref().map(|p| p.shape))
-        });
-        fields.add_field_method_set("Shape", |_, this, shape: PartType| {
-            let mut data = this.data.lock().unwrap();
-            if let Some(part) = &mut data.part_data {
-                part.shape = shape;
-            }
-            Ok(())
-        });
-
-        fields.add_field_method_get("Touched", |_, this| {
-            let data = this.data.lock().unwrap();
-            Ok(data.part_data.as_ref().map(|p| p.touched.clone()))
-        });
-
-        fields.add_field_method_get("TouchEnded", |_, this| {
-            let data = this.data.lock().unwrap();
-            Ok(data.part_data.as_ref().map(|p| p.touch_ended.clone()))
-        });
-
-        fields.add_field_method_get("Health", |_, this| {
-            let data = this.data.lock().unwrap();
-            Ok(data.humanoid_data.as_ref().map(|h| h.health))
-        });
-        fields.add_field_method_set("Health", |_, this, health: f32| {
-            let mut data = this.data.lock().unwrap();
-            if let Some(humanoid) = &mut data.humanoid_data {
-                humanoid.health = health.max(0.0).min(humanoid.max_health);
-            }
-            Ok(())
-        });
-
-        fields.add_field_method_get("MaxHealth", |_, this| {
-            let data = this.data.lock().unwrap();
-            Ok(data.humanoid_data.as_ref().map(|h| h.max_health))
-        });
-        fields.add_field_method_set("MaxHealth", |_, this, max_health: f32| {
-            let mut data = this.data.lock().unwrap();
-            if let Some(humanoid) = &mut data.humanoid_data {
-                humanoid.max_health = max_health.max(0.0);
-            }
-            Ok(())
-        });
-
-        fields.add_field_method_get("WalkSpeed", |_, this| {
-            let data = this.data.lock().unwrap();
-            Ok(data.humanoid_data.as_ref().map(|h| h.walk_speed))
-        });
-        fields.add_field_method_set("WalkSpeed", |_, this, walk_speed: f32| {
-            let mut data = this.data.lock().unwrap();
-            if let Some(humanoid) = &mut data.humanoid_data {
-                humanoid.walk_speed = walk_speed.max(0.0);
-            }
-            Ok(())
-        });
-
-        fields.add_field_method_get("JumpPower", |_, this| {
-            let data = this.data.lock().unwrap();
-            Ok(data.humanoid_data.as_ref().map(|h| h.jump_power))
-        });
-        fields.add_field_method_set("JumpPower", |_, this, jump_power: f32| {
-            let mut data = this.data.lock().unwrap();
-            if let Some(humanoid) = &mut data.humanoid_data {
-                humanoid.jump_power = jump_power.max(0.0);
-            }
-            Ok(())
-        });
-
-        fields.add_field_method_get("JumpHeight", |_, this| {
-            let data = this.data.lock().unwrap();
-            Ok(data.humanoid_data.as_ref().map(|h| h.jump_height))
-        });
-        fields.add_field_method_set("JumpHeight", |_, this, jump_height: f32| {
-            let mut data = this.data.lock().unwrap();
-            if let Some(humanoid) = &mut data.humanoid_data {
-                humanoid.jump_height = jump_height.max(0.0);
-            }
-            Ok(())
-        });
-
-        fields.add_field_method_get("AutoRotate", |_, this| {
-            let data = this.data.lock().unwrap();
-            Ok(data.humanoid_data.as_ref().map(|h| h.auto_rotate))
-        });
-        fields.add_field_method_set("AutoRotate", |_, this, auto_rotate: bool| {
-            let mut data = this.data.lock().unwrap();
-            if let Some(humanoid) = &mut data.humanoid_data {
-                humanoid.auto_rotate = auto_rotate;
-            }
-            Ok(())
-        });
-
-        fields.add_field_method_get("HipHeight", |_, this| {
-            let data = this.data.lock().unwrap();
-            Ok(data.humanoid_data.as_ref().map(|h| h.hip_height))
-        });
-        fields.add_field_method_set("HipHeight", |_, this, hip_height: f32| {
-            let mut data = this.data.lock().unwrap();
-            if let Some(humanoid) = &mut data.humanoid_data {
-                humanoid.hip_height = hip_height;
-            }
-            Ok(())
-        });
-
-        fields.add_field_method_get("Died", |_, this| {
-            let data = this.data.lock().unwrap();
-            Ok(data.humanoid_data.as_ref().map(|h| h.died.clone()))
-        });
-
-        fields.add_field_method_get("HealthChanged", |_, this| {
-            let data = this.data.lock().unwrap();
-            Ok(data.humanoid_data.as_ref().map(|h| h.health_changed.clone()))
-        });
-
-        fields.add_field_method_get("MoveToFinished", |_, this| {
-            let data = this.data.lock().unwrap();
-            Ok(data
-                .humanoid_data
-                .as_ref()
-                .map(|h| h.move_to_finished.clone()))
-        });
-
-        fields.add_field_method_get("UserId", |_, this| {
-            let data = this.data.lock().unwrap();
-            // Return as f64 to ensure it's a Lua number type (not Integer) for table key compatibility
-            Ok(data.player_data.as_ref().map(|p| p.user_id as f64))
-        });
-
-        fields.add_field_method_get("DisplayName", |_, this| {
-            let data = this.data.lock().unwrap();
-            Ok(data.player_data.as_ref().map(|p| p.display_name.clone()))
-        });
-
-        fields.add_field_method_get("Character", |_, this| {
-            let data = this.data.lock().unwrap();
-            Ok(data
-                .player_data
-                .as_ref()
-                .and_then(|p| p.character.as_ref())
-                .and_then(|w| w.upgrade())
-                .map(Instance::from_ref))
-        });
-
-        fields.add_field_method_get("CharacterAdded", |_, this| {
-            let data = this.data.lock().unwrap();
-            Ok(data
-                .player_data
-                .as_ref()
-                .map(|p| p.character_added.clone()))
-        });
-
-        fields.add_field_method_get("CharacterRemoving", |_, this| {
-            let data = this.data.lock().unwrap();
-            Ok(data
-                .player_data
-                .as_ref()
-                .map(|p| p.character_removing.clone()))
-        });
-
-        fields.add_field_method_get("PlayerGui", |_, this| {
-            let data = this.data.lock().unwrap();
-            Ok(data
-                .player_data
-                .as_ref()
-                .and_then(|p| p.player_gui.as_ref())
-                .and_then(|w| w.upgrade())
-                .map(Instance::from_ref))
-        });
-
-        fields.add_field_method_get("PrimaryPart", |_, this| {
-            let data = this.data.lock().unwrap();
-            Ok(data
-                .model_data
-                .as_ref()
-                .and_then(|m| m.primary_part.as_ref())
-                .and_then(|w| w.upgrade())
-                .map(Instance::from_ref))
-        });
-        fields.add_field_method_set("PrimaryPart", |_, this, part: Option<Instance>| {
-            let mut data = this.data.lock().unwrap();
-            if let Some(model) = &mut data.model_data {
-                model.primary_part = part.map(|p| Arc::downgrade(&p.data));
-            }
-            Ok(())
-        });
-
-        // ========== Weld Properties ==========
-
-        fields.add_field_method_get("Part0", |_, this| {
-            let data = this.data.lock().unwrap();
-            Ok(data
-                .weld_data
-                .as_ref()
-                .and_then(|w| w.part0.as_ref())
-                .and_then(|w| w.upgrade())
-                .map(Instance::from_ref))
-        });
-        fields.add_field_method_set("Part0", |_, this, part: Option<Instance>| {
-            let mut data = this.data.lock().unwrap();
-            if let Some(weld) = &mut data.weld_data {
-                weld.part0 = part.map(|p| Arc::downgrade(&p.data));
-            }
-            Ok(())
-        });
-
-        fields.add_field_method_get("Part1", |_, this| {
-            let data = this.data.lock().unwrap();
-            Ok(data
-                .weld_data
-                .as_ref()
-                .and_then(|w| w.part1.as_ref())
-                .and_then(|w| w.upgrade())
-                .map(Instance::from_ref))
-        });
-        fields.add_field_method_set("Part1", |_, this, part: Option<Instance>| {
-            let mut data = this.data.lock().unwrap();
-            if let Some(weld) = &mut data.weld_data {
-                weld.part1 = part.map(|p| Arc::downgrade(&p.data));
-            }
-            Ok(())
-        });
-
-        fields.add_field_method_get("C0", |_, this| {
-            let data = this.data.lock().unwrap();
-            Ok(data.weld_data.as_ref().map(|w| w.c0))
-        });
-        fields.add_field_method_set("C0", |_, this, cframe: CFrame| {
-            let mut data = this.data.lock().unwrap();
-            if let Some(weld) = &mut data.weld_data {
-                weld.c0 = cframe;
-            }
-            Ok(())
-        });
-
-        fields.add_field_method_get("C1", |_, this| {
-            let data = this.data.lock().unwrap();
-            Ok(data.weld_data.as_ref().map(|w| w.c1))
-        });
-        fields.add_field_method_set("C1", |_, this, cframe: CFrame| {
-            let mut data = this.data.lock().unwrap();
-            if let Some(weld) = &mut data.weld_data {
-                weld.c1 = cframe;
-            }
-            Ok(())
-        });
-
-        fields.add_field_method_get("Enabled", |_, this| {
-            let data = this.data.lock().unwrap();
-            // Return Enabled for Welds, BillboardGui, or existing gui_data.enabled
-            if let Some(weld) = &data.weld_data {
-                return Ok(Some(weld.enabled));
-            }
-            if let Some(billboard) = &data.billboard_gui_data {
-                return Ok(Some(billboard.enabled));
-            }
-            if let Some(gui) = &data.gui_data {
-                return Ok(Some(gui.enabled));
-            }
-            Ok(None)
-        });
-
-        // ========== BillboardGui Properties ==========
-
-        fields.add_field_method_get("StudsOffset", |_, this| {
-            let data = this.data.lock().unwrap();
-            Ok(data.billboard_gui_data.as_ref().map(|b| b.studs_offset))
-        });
-        fields.add_field_method_set("StudsOffset", |_, this, offset: Vector3| {
-            let mut data = this.data.lock().unwrap();
-            if let Some(billboard) = &mut data.billboard_gui_data {
-                billboard.studs_offset = offset;
-            }
-            Ok(())
-        });
-
-        fields.add_field_method_get("AlwaysOnTop", |_, this| {
-            let data = this.data.lock().unwrap();
-            Ok(data.billboard_gui_data.as_ref().map(|b| b.always_on_top))
-        });
-        fields.add_field_method_set("AlwaysOnTop", |_, this, value: bool| {
-            let mut data = this.data.lock().unwrap();
-            if let Some(billboard) = &mut data.billboard_gui_data {
-                billboard.always_on_top = value;
-            }
-            Ok(())
-        });
-
-        fields.add_field_method_get("Adornee", |_, this| {
-            let data = this.data.lock().unwrap();
-            Ok(data
-                .billboard_gui_data
-                .as_ref()
-                .and_then(|b| b.adornee.as_ref())
-                .and_then(|w| w.upgrade())
-                .map(Instance::from_ref))
-        });
-        fields.add_field_method_set("Adornee", |_, this, part: Option<Instance>| {
-            let mut data = this.data.lock().unwrap();
-            if let Some(billboard) = &mut data.billboard_gui_data {
-                billboard.adornee = part.map(|p| Arc::downgrade(&p.data));
-            }
-            Ok(())
-        });
-
-        // ========== GUI Properties ==========
-
-        // AnchorPoint (GuiObject)
-        fields.add_field_method_get("AnchorPoint", |lua, this| {
-            let data = this.data.lock().unwrap();
-            if let Some(gui) = &data.gui_data {
-                let table = lua.create_table()?;
-                table.set("X", gui.anchor_point.0)?;
-                table.set("Y", gui.anchor_point.1)?;
-                Ok(Value::Table(table))
-            } else {
-                Ok(Value::Nil)
-            }
-        });
-        fields.add_field_method_set("AnchorPoint", |_, this, value: Value| {
-            let mut data = this.data.lock().unwrap();
-            if let Some(gui) = &mut data.gui_data {
-                if let Value::Table(t) = value {
-                    let x: f32 = t.get("X").unwrap_or(0.0);
-                    let y: f32 = t.get("Y").unwrap_or(0.0);
-                    gui.anchor_point = (x, y);
-                }
-            }
-            Ok(())
-        });
-
-        // Rotation (GuiObject) - in degrees
-        fields.add_field_method_get("Rotation", |_, this| {
-            let data = this.data.lock().unwrap();
-            Ok(data.gui_data.as_ref().map(|g| g.rotation))
-        });
-        fields.add_field_method_set("Rotation", |_, this, rotation: f32| {
-            let mut data = this.data.lock().unwrap();
-            if let Some(gui) = &mut data.gui_data {
-                gui.rotation = rotation;
-            }
-            Ok(())
-        });
-
-        // ZIndex (GuiObject)
-        fields.add_field_method_get("ZIndex", |_, this| {
-            let data = this.data.lock().unwrap();
-            Ok(data.gui_data.as_ref().map(|g| g.z_index))
-        });
-        fields.add_field_method_set("ZIndex", |_, this, z_index: i32| {
-            let mut data = this.data.lock().unwrap();
-            if let Some(gui) = &mut data.gui_data {
-                gui.z_index = z_index;
-            }
-            Ok(())
-        });
-
-        // LayoutOrder (GuiObject)
-        fields.add_field_method_get("LayoutOrder", |_, this| {
-            let data = this.data.lock().unwrap();
-            Ok(data.gui_data.as_ref().map(|g| g.layout_order))
-        });
-        fields.add_field_method_set("LayoutOrder", |_, this, layout_order: i32| {
-            let mut data = this.data.lock().unwrap();
-            if let Some(gui) = &mut data.gui_data {
-                gui.layout_order = layout_order;
-            }
-            Ok(())
-        });
-
-        // Visible (GuiObject)
-        fields.add_field_method_get("Visible", |_, this| {
-            let data = this.data.lock().unwrap();
-            Ok(data.gui_data.as_ref().map(|g| g.visible))
-        });
-        fields.add_field_method_set("Visible", |_, this, visible: bool| {
-            let mut data = this.data.lock().unwrap();
-            if let Some(gui) = &mut data.gui_data {
-                gui.visible = visible;
-            }
-            Ok(())
-        });
-
-        // BackgroundColor3 (GuiObject)
-        fields.add_field_method_get("BackgroundColor3", |_, this| {
-            let data = this.data.lock().unwrap();
-            Ok(data.gui_data.as_ref().map(|g| g.background_color))
-        });
-        fields.add_field_method_set("BackgroundColor3", |_, this, color: Color3| {
-            let mut data = this.data.lock().unwrap();
-            if let Some(gui) = &mut data.gui_data {
-                gui.background_color = color;
-            }
-            Ok(())
-        });
-
-        // BackgroundTransparency (GuiObject)
-        fields.add_field_method_get("BackgroundTransparency", |_, this| {
-            let data = this.data.lock().unwrap();
-            Ok(data.gui_data.as_ref().map(|g| g.background_transparency))
-        });
-        fields.add_field_method_set("BackgroundTransparency", |_, this, transparency: f32| {
-            let mut data = this.data.lock().unwrap();
-            if let Some(gui) = &mut data.gui_data {
-                gui.background_transparency = transparency.clamp(0.0, 1.0);
-            }
-            Ok(())
-        });
-
-        // BorderColor3 (GuiObject)
-        fields.add_field_method_get("BorderColor3", |_, this| {
-            let data = this.data.lock().unwrap();
-            Ok(data.gui_data.as_ref().map(|g| g.border_color))
-        });
-        fields.add_field_method_set("BorderColor3", |_, this, color: Color3| {
-            let mut data = this.data.lock().unwrap();
-            if let Some(gui) = &mut data.gui_data {
-                gui.border_color = color;
-            }
-            Ok(())
-        });
-
-        // BorderSizePixel (GuiObject)
-        fields.add_field_method_get("BorderSizePixel", |_, this| {
-            let data = this.data.lock().unwrap();
-            Ok(data.gui_data.as_ref().map(|g| g.border_size_pixel))
-        });
-        fields.add_field_method_set("BorderSizePixel", |_, this, size: i32| {
-            let mut data = this.data.lock().unwrap();
-            if let Some(gui) = &mut data.gui_data {
-                gui.border_size_pixel = size.max(0);
-            }
-            Ok(())
-        });
-
-        // Text (TextLabel, TextButton)
-        fields.add_field_method_get("Text", |_, this| {
-            let data = this.data.lock().unwrap();
-            Ok(data.gui_data.as_ref().and_then(|g| g.text.clone()))
-        });
-        fields.add_field_method_set("Text", |_, this, text: String| {
-            let mut data = this.data.lock().unwrap();
-            if let Some(gui) = &mut data.gui_data {
-                gui.text = Some(text);
-            }
-            Ok(())
-        });
-
-        // TextColor3 (TextLabel, TextButton)
-        fields.add_field_method_get("TextColor3", |_, this| {
-            let data = this.data.lock().unwrap();
-            Ok(data.gui_data.as_ref().and_then(|g| g.text_color))
-        });
-        fields.add_field_method_set("TextColor3", |_, this, color: Color3| {
-            let mut data = this.data.lock().unwrap();
-            if let Some(gui) = &mut data.gui_data {
-                gui.text_color = Some(color);
-            }
-            Ok(())
-        });
-
-        // TextSize (TextLabel, TextButton)
-        fields.add_field_method_get("TextSize", |_, this| {
-            let data = this.data.lock().unwrap();
-            Ok(data.gui_data.as_ref().and_then(|g| g.text_size))
-        });
-        fields.add_field_method_set("TextSize", |_, this, size: f32| {
-            let mut data = this.data.lock().unwrap();
-            if let Some(gui) = &mut data.gui_data {
-                gui.text_size = Some(size.max(1.0));
-            }
-            Ok(())
-        });
-
-        // TextTransparency (TextLabel, TextButton)
-        fields.add_field_method_get("TextTransparency", |_, this| {
-            let data = this.data.lock().unwrap();
-            Ok(data.gui_data.as_ref().and_then(|g| g.text_transparency))
-        });
-        fields.add_field_method_set("TextTransparency", |_, this, transparency: f32| {
-            let mut data = this.data.lock().unwrap();
-            if let Some(gui) = &mut data.gui_data {
-                gui.text_transparency = Some(transparency.clamp(0.0, 1.0));
-            }
-            Ok(())
-        });
-
-        // TextScaled (TextLabel, TextButton)
-        fields.add_field_method_get("TextScaled", |_, this| {
-            let data = this.data.lock().unwrap();
-            Ok(data.gui_data.as_ref().map(|g| g.text_scaled))
-        });
-        fields.add_field_method_set("TextScaled", |_, this, scaled: bool| {
-            let mut data = this.data.lock().unwrap();
-            if let Some(gui) = &mut data.gui_data {
-                gui.text_scaled = scaled;
-            }
-            Ok(())
-        });
-
-        // TextXAlignment (TextLabel, TextButton)
-        fields.add_field_method_get("TextXAlignment", |lua, this| {
-            let data = this.data.lock().unwrap();
-            if let Some(gui) = &data.gui_data {
-                let alignment = match gui.text_x_alignment {
-                    TextXAlignment::Left => "Left",
-                    TextXAlignment::Center => "Center",
-                    TextXAlignment::Right => "Right",
-                };
-                Ok(Value::String(lua.create_string(alignment)?))
-            } else {
-                Ok(Value::Nil)
-            }
-        });
-        fields.add_field_method_set("TextXAlignment", |_, this, alignment: Value| {
-            let mut data = this.data.lock().unwrap();
-            if let Some(gui) = &mut data.gui_data {
-                if let Value::String(s) = alignment {
-                    if let Ok(str_val) = s.to_str() {
-                        gui.text_x_alignment = match str_val.as_ref() {
-                            "Left" => TextXAlignment::Left,
-                            "Right" => TextXAlignment::Right,
-                            _ => TextXAlignment::Center,
-                        };
-                    }
-                }
-            }
-            Ok(())
-        });
-
-        // TextYAlignment (TextLabel, TextButton)
-        fields.add_field_method_get("TextYAlignment", |lua, this| {
-            let data = this.data.lock().unwrap();
-            if let Some(gui) = &data.gui_data {
-                let alignment = match gui.text_y_alignment {
-                    TextYAlignment::Top => "Top",
-                    TextYAlignment::Center => "Center",
-                    TextYAlignment::Bottom => "Bottom",
-                };
-                Ok(Value::String(lua.create_string(alignment)?))
-            } else {
-                Ok(Value::Nil)
-            }
-        });
-        fields.add_field_method_set("TextYAlignment", |_, this, alignment: Value| {
-            let mut data = this.data.lock().unwrap();
-            if let Some(gui) = &mut data.gui_data {
-                if let Value::String(s) = alignment {
-                    if let Ok(str_val) = s.to_str() {
-                        gui.text_y_alignment = match str_val.as_ref() {
-                            "Top" => TextYAlignment::Top,
-                            "Bottom" => TextYAlignment::Bottom,
-                            _ => TextYAlignment::Center,
-                        };
-                    }
-                }
-            }
-            Ok(())
-        });
-
-        // Image (ImageLabel, ImageButton)
-        fields.add_field_method_get("Image", |_, this| {
-            let data = this.data.lock().unwrap();
-            Ok(data.gui_data.as_ref().and_then(|g| g.image.clone()))
-        });
-        fields.add_field_method_set("Image", |_, this, image: String| {
-            let mut data = this.data.lock().unwrap();
-            if let Some(gui) = &mut data.gui_data {
-                gui.image = Some(image);
-            }
-            Ok(())
-        });
-
-        // ImageColor3 (ImageLabel, ImageButton)
-        fields.add_field_method_get("ImageColor3", |_, this| {
-            let data = this.data.lock().unwrap();
-            Ok(data.gui_data.as_ref().and_then(|g| g.image_color))
-        });
-        fields.add_field_method_set("ImageColor3", |_, this, color: Color3| {
-            let mut data = this.data.lock().unwrap();
-            if let Some(gui) = &mut data.gui_data {
-                gui.image_color = Some(color);
-            }
-            Ok(())
-        });
-
-        // ImageTransparency (ImageLabel, ImageButton)
-        fields.add_field_method_get("ImageTransparency", |_, this| {
-            let data = this.data.lock().unwrap();
-            Ok(data.gui_data.as_ref().and_then(|g| g.image_transparency))
-        });
-        fields.add_field_method_set("ImageTransparency", |_, this, transparency: f32| {
-            let mut data = this.data.lock().unwrap();
-            if let Some(gui) = &mut data.gui_data {
-                gui.image_transparency = Some(transparency.clamp(0.0, 1.0));
-            }
-            Ok(())
-        });
-
-        // DisplayOrder (ScreenGui)
-        fields.add_field_method_get("DisplayOrder", |_, this| {
-            let data = this.data.lock().unwrap();
-            Ok(data.gui_data.as_ref().map(|g| g.display_order))
-        });
-        fields.add_field_method_set("DisplayOrder", |_, this, order: i32| {
-            let mut data = this.data.lock().unwrap();
-            if let Some(gui) = &mut data.gui_data {
-                gui.display_order = order;
-            }
-            Ok(())
-        });
-
-        // IgnoreGuiInset (ScreenGui)
-        fields.add_field_method_get("IgnoreGuiInset", |_, this| {
-            let data = this.data.lock().unwrap();
-            Ok(data.gui_data.as_ref().map(|g| g.ignore_gui_inset))
-        });
-        fields.add_field_method_set("IgnoreGuiInset", |_, this, ignore: bool| {
-            let mut data = this.data.lock().unwrap();
-            if let Some(gui) = &mut data.gui_data {
-                gui.ignore_gui_inset = ignore;
-            }
-            Ok(())
-        });
-
-        // Enabled (ScreenGui)
-        fields.add_field_method_get("Enabled", |_, this| {
-            let data = this.data.lock().unwrap();
-            Ok(data.gui_data.as_ref().map(|g| g.enabled))
-        });
-        fields.add_field_method_set("Enabled", |_, this, enabled: bool| {
-            let mut data = this.data.lock().unwrap();
-            if let Some(gui) = &mut data.gui_data {
-                gui.enabled = enabled;
-            }
-            Ok(())
-        });
-
-        // MouseButton1Click (GuiButton)
-        fields.add_field_method_get("MouseButton1Click", |_, this| {
-            let data = this.data.lock().unwrap();
-            Ok(data
-                .gui_data
-                .as_ref()
-                .and_then(|g| g.mouse_button1_click.clone()))
-        });
-
-        // MouseButton1Down (GuiButton)
-        fields.add_field_method_get("MouseButton1Down", |_, this| {
-            let data = this.data.lock().unwrap();
-            Ok(data
-                .gui_data
-                .as_ref()
-                .and_then(|g| g.mouse_button1_down.clone()))
-        });
-
-        // MouseButton1Up (GuiButton)
-        fields.add_field_method_get("MouseButton1Up", |_, this| {
-            let data = this.data.lock().unwrap();
-            Ok(data
-                .gui_data
-                .as_ref()
-                .and_then(|g| g.mouse_button1_up.clone()))
-        });
-
-        // MouseEnter (GuiButton)
-        fields.add_field_method_get("MouseEnter", |_, this| {
-            let data = this.data.lock().unwrap();
-            Ok(data.gui_data.as_ref().and_then(|g| g.mouse_enter.clone()))
-        });
-
-        // MouseLeave (GuiButton)
-        fields.add_field_method_get("MouseLeave", |_, this| {
-            let data = this.data.lock().unwrap();
-            Ok(data.gui_data.as_ref().and_then(|g| g.mouse_leave.clone()))
-        });
-    }
-
-    fn add_methods<M: UserDataMethods<Self>>(methods: &mut M) {
-        methods.add_method("Clone", |_, this, ()| Ok(this.clone_instance()));
-
-        methods.add_method("Destroy", |lua, this, ()| this.destroy(lua));
-
-        methods.add_method(
-            "FindFirstChild",
-            |_, this, (name, recursive): (String, Option<bool>)| {
-                Ok(this.find_first_child(&name, recursive.unwrap_or(false)))
-            },
-        );
-
-        methods.add_method("FindFirstChildOfClass", |_, this, class_name: String| {
-            Ok(this.find_first_child_of_class(&class_name))
-        });
-
-        methods.add_method("GetChildren", |_, this, ()| Ok(this.get_children()));
-
-        methods.add_method("GetDescendants", |_, this, ()| Ok(this.get_descendants()));
-
-        methods.add_method("IsA", |_, this, class_name: String| {
-            Ok(this.is_a(&class_name))
-        });
-
-        methods.add_method("IsDescendantOf", |_, this, ancestor: Instance| {
-            Ok(this.is_descendant_of(&ancestor))
-        });
-
-        methods.add_method("SetAttribute", |_, this, (name, value): (String, Value)| {
-            let attr_value = match value {
-                Value::Nil => AttributeValue::Nil,
-                Value::Boolean(b) => AttributeValue::Bool(b),
-                Value::Integer(n) => AttributeValue::Number(n as f64),
-                Value::Number(n) => AttributeValue::Number(n),
-                Value::String(s) => AttributeValue::String(s.to_str()?.to_string()),
-                Value::UserData(ud) => {
-                    if let Ok(v) = ud.borrow::<Vector3>() {
-                        AttributeValue::Vector3(*v)
-                    } else if let Ok(c) = ud.borrow::<Color3>() {
-                        AttributeValue::Color3(*c)
-                    } else {
-                        return Err(mlua::Error::runtime("Unsupported attribute type"));
-                    }
-                }
-                _ => return Err(mlua::Error::runtime("Unsupported attribute type")),
-            };
-            this.set_attribute(&name, attr_value);
-            Ok(())
-        });
-
-        methods.add_method("GetAttribute", |lua, this, name: String| {
-            match this.get_attribute(&name) {
-                Some(AttributeValue::Nil) => Ok(Value::Nil),
-                Some(AttributeValue::Bool(b)) => Ok(Value::Boolean(b)),
-                Some(AttributeValue::Number(n)) => Ok(Value::Number(n)),
-                Some(AttributeValue::String(s)) => Ok(Value::String(lua.create_string(&s)?)),
-                Some(AttributeValue::Vector3(v)) => Ok(Value::UserData(lua.create_userdata(v)?)),
-                Some(AttributeValue::Color3(c)) => Ok(Value::UserData(lua.create_userdata(c)?)),
-                None => Ok(Value::Nil),
+        methods.add_method("GetAttribute", |lua, this, name: String| {
+            match this.get_attribute(&name) {
+                Some(AttributeValue::Nil) => Ok(Value::Nil),
+                Some(AttributeValue::Bool(b)) => Ok(Value::Boolean(b)),
+                Some(AttributeValue::Number(n)) => Ok(Value::Number(n)),
+                Some(AttributeValue::String(s)) => Ok(Value::String(lua.create_string(&s)?)),
+                Some(AttributeValue::Vector3(v)) => Ok(Value::UserData(lua.create_userdata(v)?)),
+                Some(AttributeValue::Color3(c)) => Ok(Value::UserData(lua.create_userdata(c)?)),
+                None => Ok(Value::Nil),
             }
         });
 
@@ -1855,18 +1677,18 @@ impl UserData for Instance {
         });
 
         // Tag methods (Roblox CollectionService-style tags)
-        methods.add_method("AddTag", |_, this, tag: String| {
+        methods.add_method("AddTag", |lua, this, tag: String| {
             this.add_tag(&tag);
-            Ok(())
+            fire_pending(lua)
         });
 
         methods.add_method("HasTag", |_, this, tag: String| {
             Ok(this.has_tag(&tag))
         });
 
-        methods.add_method("RemoveTag", |_, this, tag: String| {
+        methods.add_method("RemoveTag", |lua, this, tag: String| {
             this.remove_tag(&tag);
-            Ok(())
+            fire_pending(lua)
         });
 
         methods.add_method("GetTags", |lua, this, ()| {
@@ -1878,6 +1700,40 @@ impl UserData for Instance {
             Ok(table)
         });
 
+        methods.add_method("ApplyImpulse", |_, this, impulse: Vector3| {
+            let mut data = this.data.lock().unwrap();
+            if let Some(part) = &mut data.part_data {
+                part.queue_impulse(impulse, None);
+            }
+            Ok(())
+        });
+
+        methods.add_method(
+            "ApplyImpulseAtPosition",
+            |_, this, (impulse, position): (Vector3, Vector3)| {
+                let mut data = this.data.lock().unwrap();
+                if let Some(part) = &mut data.part_data {
+                    part.queue_impulse(impulse, Some(position));
+                }
+                Ok(())
+            },
+        );
+
+        methods.add_method("ApplyAngularImpulse", |_, this, impulse: Vector3| {
+            let mut data = this.data.lock().unwrap();
+            if let Some(part) = &mut data.part_data {
+                part.queue_angular_impulse(impulse);
+            }
+            Ok(())
+        });
+
+        methods.add_method("GetMass", |_, this, ()| {
+            let data = this.data.lock().unwrap();
+            Ok(data.part_data.as_ref().map_or(0.0, |part| {
+                part_mass(part.shape, part.size.to_array(), part.physical_properties().density)
+            }))
+        });
+
         methods.add_method("TakeDamage", |lua, this, amount: f32| {
             let (old_health, new_health, health_changed, died) = {
                 let mut data = this.data.lock().unwrap();
@@ -1901,6 +1757,7 @@ impl UserData for Instance {
                     mlua::MultiValue::from_iter([Value::Number(new_health as f64)]),
                 )?;
                 crate::game::lua::events::track_yielded_threads(lua, threads)?;
+                this.fire_property_changed(lua, "Health")?;
                 if new_health <= 0.0 && old_health > 0.0 {
                     let threads = died.fire_as_coroutines(lua, mlua::MultiValue::new())?;
                     crate::game::lua::events::track_yielded_threads(lua, threads)?;
@@ -1928,6 +1785,7 @@ impl UserData for Instance {
                         position.z
                     );
                     humanoid.move_to_target = Some(position);
+                    humanoid.path_waypoints.clear();
                 } else {
                     eprintln!("[Humanoid WARN] MoveTo called on non-humanoid instance");
                 }
@@ -1939,6 +1797,7 @@ impl UserData for Instance {
             let mut data = this.data.lock().unwrap();
             if let Some(humanoid) = &mut data.humanoid_data {
                 humanoid.move_to_target = None;
+                humanoid.path_waypoints.clear();
                 humanoid.cancel_move_to = true; // Signal to clear physics target
             }
             Ok(())
@@ -1954,6 +1813,24 @@ impl UserData for Instance {
             Ok(())
         });
 
+        methods.add_method("GetState", |_, this, ()| {
+            let data = this.data.lock().unwrap();
+            Ok(data.humanoid_data.as_ref().map(|humanoid| humanoid.state))
+        });
+
+        // Physics takes the character off its controller; any other state hands
+        // it back, and the controller picks the state on the next tick
+        methods.add_method("ChangeState", |_, this, state: HumanoidStateType| {
+            let mut data = this.data.lock().unwrap();
+            if let Some(humanoid) = &mut data.humanoid_data {
+                humanoid.physics_controlled = state == HumanoidStateType::Physics;
+                if state == HumanoidStateType::Jumping {
+                    humanoid.jump_requested = true;
+                }
+            }
+            Ok(())
+        });
+
         methods.add_method("GetPrimaryPartCFrame", |_, this, ()| {
             let data = this.data.lock().unwrap();
             if let Some(model) = &data.model_data {
@@ -1996,6 +1873,8 @@ impl UserData for Instance {
                     if let Some(part) = &mut data.part_data {
                         part.cframe = part.cframe.multiply(&offset);
                         part.position = part.cframe.position;
+                        drop(data);
+                        descendant.mark_spatial_dirty();
                     }
                 }
             }
@@ -2050,33 +1929,14 @@ pub fn register_instance(lua: &Lua) -> Result<()> {
     instance_table.set(
         "new",
         lua.create_function(
-            |_, (class_name, parent): (String, Option<Instance>)| -> Result<Instance> {
-                let instance = match class_name.as_str() {
-                    "Part" => Instance::from_data(InstanceData::new_part("Part")),
-                    "Model" => Instance::from_data(InstanceData::new_model("Model")),
-                    "Humanoid" => Instance::from_data(InstanceData::new_humanoid("Humanoid")),
-                    "Folder" => Instance::new(ClassName::Folder, "Folder"),
-                    // Constraints
-                    "Weld" => Instance::from_data(InstanceData::new_weld("Weld")),
-                    // 3D GUI
-                    "BillboardGui" => {
-                        Instance::from_data(InstanceData::new_billboard_gui("BillboardGui"))
-                    }
-                    // GUI classes
-                    "ScreenGui" => Instance::from_data(InstanceData::new_screen_gui("ScreenGui")),
-                    "Frame" => Instance::from_data(InstanceData::new_frame("Frame")),
-                    "TextLabel" => Instance::from_data(InstanceData::new_text_label("TextLabel")),
-                    "TextButton" => {
-                        Instance::from_data(InstanceData::new_text_button("TextButton"))
-                    }
-                    "ImageLabel" => {
-                        Instance::from_data(InstanceData::new_image_label("ImageLabel"))
-                    }
-                    "ImageButton" => {
-                        Instance::from_data(InstanceData::new_image_button("ImageButton"))
-                    }
-                    _ => Instance::new(ClassName::Instance, &class_name),
+            |lua, (class_name, parent): (String, Option<Instance>)| -> Result<Instance> {
+                let instance = match reflection::creatable_class(&class_name) {
+                    Some(class) => Instance::from_data(InstanceData::for_class(class, &class_name)),
+                    None => Instance::new(ClassName::Instance, &class_name),
                 };
+                if instance.class_name() == ClassName::Script {
+                    super::scripts::register_script_tree(lua, &instance);
+                }
 
                 if let Some(parent) = parent {
                     instance.set_parent(Some(&parent));
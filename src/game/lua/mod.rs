@@ -1,7 +1,12 @@
+pub mod api_dump;
+pub mod determinism;
 pub mod events;
 pub mod instance;
+pub mod modules;
+pub mod reflection;
 pub mod runtime;
+pub mod scripts;
 pub mod services;
 pub mod types;
 
-pub use runtime::{KickRequest, LuaRuntime};
+pub use runtime::{KickRequest, LuaRuntime, ScheduledTask, ScriptLimits};
@@ -0,0 +1,332 @@
+//! Multi-file games: bundled modules, ModuleScript and `require`.
+//!
+//! A game's script is a single source string everywhere it is stored (the
+//! games table, replays, snapshots), so the other Lua files of a game are
+//! appended to the main script, each starting with a marker line:
+//!
+//! ```lua
+//! -- main.lua
+//! local Weapons = require("weapons")
+//! --!clawblox:module weapons.lua
+//! return { sword = 10 }
+//! ```
+//!
+//! On load every bundled file becomes a ModuleScript under ServerScriptService
+//! (`lib/util.lua` -> `ServerScriptService.lib.util`), or a Script if it is
+//! named `*.server.lua`. `require` accepts a
+//! ModuleScript or a path relative to the game directory, runs each module
+//! once per VM and returns the cached result afterwards.
+
+use mlua::{Lua, MultiValue, RegistryKey, Result, Value};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use super::instance::{ClassName, Instance, InstanceData, InstanceId};
+
+/// Starts a bundled module; the rest of the line is its path in the game directory
+pub const MODULE_MARKER: &str = "--!clawblox:module ";
+
+/// A module file carried in a bundled script
+#[derive(Debug, Clone, PartialEq)]
+pub struct BundledModule<'a> {
+    pub path: &'a str,
+    pub source: &'a str,
+}
+
+/// Splits a bundled script into the main script and its modules.
+/// A script without markers is all main script.
+pub fn split_bundle(source: &str) -> (&str, Vec<BundledModule<'_>>) {
+    let mut main_end = source.len();
+    let mut modules = Vec::new();
+    // (path, start of body) of the module being read
+    let mut current: Option<(&str, usize)> = None;
+
+    let mut offset = 0;
+    for line in source.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+        let Some(path) = line.strip_prefix(MODULE_MARKER) else {
+            continue;
+        };
+        match current.take() {
+            Some((path, body)) => modules.push(BundledModule {
+                path,
+                source: &source[body..start],
+            }),
+            None => main_end = start,
+        }
+        current = Some((path.trim(), offset));
+    }
+    if let Some((path, body)) = current {
+        modules.push(BundledModule {
+            path,
+            source: &source[body..],
+        });
+    }
+
+    (&source[..main_end], modules)
+}
+
+/// Appends modules (path, source) to a main script; the inverse of `split_bundle`
+pub fn bundle(main: &str, modules: &[(String, String)]) -> String {
+    let mut bundled = main.to_string();
+    for (path, source) in modules {
+        if !bundled.is_empty() && !bundled.ends_with('\n') {
+            bundled.push('\n');
+        }
+        bundled.push_str(MODULE_MARKER);
+        bundled.push_str(path);
+        bundled.push('\n');
+        bundled.push_str(source);
+    }
+    bundled
+}
+
+/// Reads `main` from a game directory and bundles every other `.lua`/`.luau`
+/// file found under it (hidden directories, assets, static and `.d.luau`
+/// definition files are skipped)
+pub fn bundle_game_dir(game_dir: &Path, main: &str) -> std::io::Result<String> {
+    // Name the file in errors, since a game reads many
+    let read = |path: &str| {
+        std::fs::read_to_string(game_dir.join(path))
+            .map_err(|e| std::io::Error::new(e.kind(), format!("{}: {}", path, e)))
+    };
+    let main_source = read(main)?;
+
+    let mut files = Vec::new();
+    collect_lua_files(game_dir, game_dir, &mut files)?;
+    files.sort();
+
+    let main_path = main.replace('\\', "/");
+    let mut modules = Vec::new();
+    for path in files {
+        if path == main_path.trim_start_matches("./") {
+            continue;
+        }
+        let source = read(&path)?;
+        modules.push((path, source));
+    }
+    Ok(bundle(&main_source, &modules))
+}
+
+fn collect_lua_files(root: &Path, dir: &Path, files: &mut Vec<String>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if name.starts_with('.') {
+            continue;
+        }
+        if entry.file_type()?.is_dir() {
+            if name != "assets" && name != "static" {
+                collect_lua_files(root, &path, files)?;
+            }
+        } else if name.ends_with(".d.luau") {
+            // Type definitions for the editor (`clawblox init` writes one), not game code
+            continue;
+        } else if matches!(path.extension().and_then(|e| e.to_str()), Some("lua" | "luau")) {
+            if let Ok(relative) = path.strip_prefix(root) {
+                let relative: Vec<_> = relative.iter().map(|c| c.to_string_lossy()).collect();
+                files.push(relative.join("/"));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Module path without its extension, split into its folders and name
+fn module_path_parts(path: &str) -> Vec<&str> {
+    let path = path.trim_start_matches("./");
+    let path = path
+        .strip_suffix(".luau")
+        .or_else(|| path.strip_suffix(".lua"))
+        .unwrap_or(path);
+    path.split('/').filter(|part| !part.is_empty() && *part != ".").collect()
+}
+
+/// Creates a ModuleScript for each bundled module under `root`, with Folders
+/// for the directories in its path. Files named `*.server.lua` become Scripts
+/// instead, which run on their own (see `scripts`).
+pub fn install_modules(root: &Instance, modules: &[BundledModule]) {
+    for module in modules {
+        let parts = module_path_parts(module.path);
+        let Some((name, folders)) = parts.split_last() else {
+            continue;
+        };
+        let (name, is_script) = match name.strip_suffix(".server") {
+            Some(name) => (name, true),
+            None => (*name, false),
+        };
+
+        let mut parent = root.clone();
+        for folder in folders {
+            parent = match parent.find_first_child(folder, false) {
+                Some(existing) => existing,
+                None => {
+                    let created = Instance::new(ClassName::Folder, folder);
+                    created.set_parent(Some(&parent));
+                    created
+                }
+            };
+        }
+
+        let mut data = if is_script {
+            InstanceData::new_script(name)
+        } else {
+            InstanceData::new_module_script(name)
+        };
+        if let Some(script) = &mut data.script_data {
+            script.source = module.source.to_string();
+        }
+        Instance::from_data(data).set_parent(Some(&parent));
+    }
+}
+
+/// Results of modules that finished loading and the chain of modules loading
+/// right now (to report cyclic requires), per VM
+#[derive(Default)]
+struct ModuleLoader {
+    loaded: HashMap<InstanceId, RegistryKey>,
+    loading: Vec<(InstanceId, String)>,
+}
+
+/// Registers the global `require`. String paths are looked up under `root`.
+pub fn register_require(lua: &Lua, root: Instance) -> Result<()> {
+    let loader = Arc::new(Mutex::new(ModuleLoader::default()));
+
+    let require = lua.create_function(move |lua, target: Value| -> Result<Value> {
+        let module = match &target {
+            Value::String(path) => {
+                let path = path.to_str()?.to_string();
+                find_module(&root, &path)
+                    .ok_or_else(|| mlua::Error::runtime(format!("require: module '{}' not found", path)))?
+            }
+            Value::UserData(ud) => ud.borrow::<Instance>()?.clone(),
+            other => {
+                return Err(mlua::Error::runtime(format!(
+                    "require: expected a ModuleScript or a module path, got {}",
+                    other.type_name()
+                )))
+            }
+        };
+        let Some(source) = module.data.lock().unwrap().script_data.as_ref().map(|s| s.source.clone()) else {
+            return Err(mlua::Error::runtime(format!(
+                "require: {} is not a ModuleScript",
+                module.name()
+            )));
+        };
+        let id = module.id();
+        let name = module.full_name();
+
+        {
+            let mut loader = loader.lock().unwrap();
+            if let Some(key) = loader.loaded.get(&id) {
+                return lua.registry_value(key);
+            }
+            if let Some(start) = loader.loading.iter().position(|(loading, _)| *loading == id) {
+                let mut chain: Vec<&str> = loader.loading[start..].iter().map(|(_, n)| n.as_str()).collect();
+                chain.push(&name);
+                return Err(mlua::Error::runtime(format!("require: cyclic require: {}", chain.join(" -> "))));
+            }
+            loader.loading.push((id, name.clone()));
+        }
+
+        // Each module sees itself as `script`; its globals stay its own
+        let result = (|| {
+            let env = lua.create_table()?;
+            let meta = lua.create_table()?;
+            meta.set("__index", lua.globals())?;
+            env.set_metatable(Some(meta))?;
+            env.set("script", module.clone())?;
+
+            lua.load(source)
+                .set_name(format!("={}", name))
+                .set_environment(env)
+                .call::<MultiValue>(())
+        })();
+        loader.lock().unwrap().loading.retain(|(loading, _)| *loading != id);
+
+        let mut values = result?;
+        if values.len() != 1 || matches!(values.front(), Some(Value::Nil)) {
+            return Err(mlua::Error::runtime(format!(
+                "require: module {} must return exactly one value",
+                name
+            )));
+        }
+        let value = values.pop_front().unwrap_or(Value::Nil);
+        let key = lua.create_registry_value(value.clone())?;
+        loader.lock().unwrap().loaded.insert(id, key);
+        Ok(value)
+    })?;
+
+    lua.globals().set("require", require)
+}
+
+/// Finds the ModuleScript for a path like `lib/util`, `./lib/util.lua`
+fn find_module(root: &Instance, path: &str) -> Option<Instance> {
+    let mut current = root.clone();
+    for part in module_path_parts(path) {
+        current = current.find_first_child(part, false)?;
+    }
+    current.is_a("ModuleScript").then_some(current)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundle_round_trip() {
+        let modules = vec![
+            ("weapons.lua".to_string(), "return { sword = 10 }".to_string()),
+            ("lib/util.lua".to_string(), "return {}\n".to_string()),
+        ];
+        let bundled = bundle("print(1)", &modules);
+
+        let (main, split) = split_bundle(&bundled);
+        assert_eq!(main, "print(1)\n");
+        assert_eq!(
+            split,
+            vec![
+                BundledModule { path: "weapons.lua", source: "return { sword = 10 }\n" },
+                BundledModule { path: "lib/util.lua", source: "return {}\n" },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_plain_script_has_no_modules() {
+        let (main, modules) = split_bundle("-- just main\nprint(1)\n");
+        assert_eq!(main, "-- just main\nprint(1)\n");
+        assert!(modules.is_empty());
+    }
+
+    #[test]
+    fn test_bundle_game_dir_skips_definition_files() {
+        // The layout `clawblox init` leaves behind, plus a module
+        let dir = std::env::temp_dir().join(format!("clawblox-bundle-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("lib")).unwrap();
+        std::fs::create_dir_all(dir.join("assets")).unwrap();
+        std::fs::write(dir.join("main.lua"), "local Util = require(script.Parent.lib.util)\n").unwrap();
+        std::fs::write(dir.join("lib/util.lua"), "return {}\n").unwrap();
+        std::fs::write(dir.join("clawblox.d.luau"), crate::game::lua::api_dump::luau_definitions()).unwrap();
+
+        let bundled = bundle_game_dir(&dir, "main.lua");
+        std::fs::remove_dir_all(&dir).unwrap();
+        let bundled = bundled.unwrap();
+
+        let (_, modules) = split_bundle(&bundled);
+        let paths: Vec<_> = modules.iter().map(|m| m.path).collect();
+        assert_eq!(paths, vec!["lib/util.lua"]);
+        crate::game::lua::LuaRuntime::check_syntax(&bundled).unwrap();
+    }
+
+    #[test]
+    fn test_module_path_parts() {
+        assert_eq!(module_path_parts("lib/util.lua"), vec!["lib", "util"]);
+        assert_eq!(module_path_parts("./weapons"), vec!["weapons"]);
+        assert_eq!(module_path_parts("shared/types.luau"), vec!["shared", "types"]);
+    }
+}
@@ -0,0 +1,2040 @@
+//! Reflection: the classes, properties and events of the Instance API.
+//!
+//! Each property is declared once here with its class, value type, whether
+//! spectators see it and how it is read and written on `InstanceData`. Lua
+//! property access, `Instance:Clone()`, change signals, spectator
+//! serialization, snapshots and the API dump all go through this registry, so
+//! a new property only needs its descriptor (and the docs).
+//!
+//! Defaults aren't declared twice: they are read from a new instance of the
+//! class, so the `InstanceData` constructors stay the one place that sets them.
+
+use mlua::{FromLua, IntoLua, Lua, Result, Value};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::sync::{LazyLock, Weak};
+
+use super::events::RBXScriptSignal;
+use super::instance::{ClassName, Instance, InstanceData, WeakInstanceRef};
+use super::services::collection_service::fire_pending;
+use super::services::WorkspaceService;
+use super::types::{
+    ActuatorRelativeTo, ActuatorType, CFrame, Color3, Material, PartType, PhysicalProperties,
+    PositionAlignmentMode, TextXAlignment, TextYAlignment, UDim2, Vector3,
+};
+
+// =============================================================================
+// Classes
+// =============================================================================
+
+/// Every class with its superclass, base classes first. `ClassName::is_a`
+/// walks this, including the abstract classes that have no `ClassName`.
+const CLASSES: &[(&str, Option<&str>)] = &[
+    ("Instance", None),
+    ("BasePart", Some("Instance")),
+    ("Part", Some("BasePart")),
+    ("Model", Some("Instance")),
+    ("Humanoid", Some("Instance")),
+    ("Player", Some("Instance")),
+    ("Folder", Some("Instance")),
+    ("Workspace", Some("Instance")),
+    ("Players", Some("Instance")),
+    ("RunService", Some("Instance")),
+    ("Camera", Some("Instance")),
+    ("ServerScriptService", Some("Instance")),
+    ("LuaSourceContainer", Some("Instance")),
+    ("BaseScript", Some("LuaSourceContainer")),
+    ("Script", Some("BaseScript")),
+    ("ModuleScript", Some("LuaSourceContainer")),
+    ("Attachment", Some("Instance")),
+    ("JointInstance", Some("Instance")),
+    ("Weld", Some("JointInstance")),
+    ("Constraint", Some("Instance")),
+    ("HingeConstraint", Some("Constraint")),
+    ("RopeConstraint", Some("Constraint")),
+    ("SpringConstraint", Some("Constraint")),
+    ("BallSocketConstraint", Some("Constraint")),
+    ("PrismaticConstraint", Some("Constraint")),
+    ("LinearVelocity", Some("Constraint")),
+    ("VectorForce", Some("Constraint")),
+    ("AlignPosition", Some("Constraint")),
+    ("BillboardGui", Some("Instance")),
+    ("PlayerGui", Some("Instance")),
+    ("GuiBase2d", Some("Instance")),
+    ("LayerCollector", Some("GuiBase2d")),
+    ("ScreenGui", Some("LayerCollector")),
+    ("GuiObject", Some("GuiBase2d")),
+    ("Frame", Some("GuiObject")),
+    ("TextLabel", Some("GuiObject")),
+    ("ImageLabel", Some("GuiObject")),
+    ("GuiButton", Some("GuiObject")),
+    ("TextButton", Some("GuiButton")),
+    ("ImageButton", Some("GuiButton")),
+];
+
+/// Classes Instance.new can create
+const CREATABLE: &[ClassName] = &[
+    ClassName::Part,
+    ClassName::Model,
+    ClassName::Humanoid,
+    ClassName::Folder,
+    ClassName::Script,
+    ClassName::ModuleScript,
+    ClassName::Attachment,
+    ClassName::Weld,
+    ClassName::HingeConstraint,
+    ClassName::RopeConstraint,
+    ClassName::SpringConstraint,
+    ClassName::BallSocketConstraint,
+    ClassName::PrismaticConstraint,
+    ClassName::LinearVelocity,
+    ClassName::VectorForce,
+    ClassName::AlignPosition,
+    ClassName::BillboardGui,
+    ClassName::ScreenGui,
+    ClassName::Frame,
+    ClassName::TextLabel,
+    ClassName::TextButton,
+    ClassName::ImageLabel,
+    ClassName::ImageButton,
+];
+
+pub fn superclass(class: &str) -> Option<&'static str> {
+    CLASSES
+        .iter()
+        .find(|(name, _)| *name == class)
+        .and_then(|(_, superclass)| *superclass)
+}
+
+/// The class Instance.new creates for `name`, if it can create one
+pub fn creatable_class(name: &str) -> Option<ClassName> {
+    CREATABLE
+        .iter()
+        .copied()
+        .find(|class| class.as_str() == name)
+}
+
+// =============================================================================
+// Values
+// =============================================================================
+
+/// Type of a property's value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PropertyType {
+    Bool,
+    Number,
+    Int,
+    String,
+    /// Table with X and Y fields (AnchorPoint)
+    Vector2,
+    Vector3,
+    CFrame,
+    Color3,
+    UDim2,
+    Material,
+    PartType,
+    ActuatorType,
+    ActuatorRelativeTo,
+    PositionAlignmentMode,
+    /// PhysicalProperties, or nil for the material's defaults
+    PhysicalProperties,
+    /// Read as the alignment's name ("Left", "Center", "Right"); set from the
+    /// name or an Enum.TextXAlignment item
+    TextXAlignment,
+    /// Read as the alignment's name ("Top", "Center", "Bottom"); set from the
+    /// name or an Enum.TextYAlignment item
+    TextYAlignment,
+    /// Reference to an instance of the given class, or nil
+    Instance(&'static str),
+}
+
+impl PropertyType {
+    pub fn name(&self) -> &'static str {
+        match self {
+            PropertyType::Bool => "bool",
+            PropertyType::Number => "number",
+            PropertyType::Int => "int",
+            PropertyType::String => "string",
+            PropertyType::Vector2 => "Vector2",
+            PropertyType::Vector3 => "Vector3",
+            PropertyType::CFrame => "CFrame",
+            PropertyType::Color3 => "Color3",
+            PropertyType::UDim2 => "UDim2",
+            PropertyType::Material => "Enum.Material",
+            PropertyType::PartType => "Enum.PartType",
+            PropertyType::ActuatorType => "Enum.ActuatorType",
+            PropertyType::ActuatorRelativeTo => "Enum.ActuatorRelativeTo",
+            PropertyType::PositionAlignmentMode => "Enum.PositionAlignmentMode",
+            PropertyType::PhysicalProperties => "PhysicalProperties",
+            PropertyType::TextXAlignment => "TextXAlignment",
+            PropertyType::TextYAlignment => "TextYAlignment",
+            PropertyType::Instance(class) => class,
+        }
+    }
+
+    /// The type in Luau annotation syntax, as used by the API dump and the
+    /// type definitions
+    pub fn luau_type(&self) -> String {
+        match self {
+            PropertyType::Bool => "boolean".to_string(),
+            PropertyType::Number | PropertyType::Int => "number".to_string(),
+            PropertyType::String => "string".to_string(),
+            PropertyType::Vector2 => "{ X: number, Y: number }".to_string(),
+            PropertyType::Material => "EnumMaterial".to_string(),
+            PropertyType::PartType => "EnumPartType".to_string(),
+            PropertyType::ActuatorType => "EnumActuatorType".to_string(),
+            PropertyType::ActuatorRelativeTo => "EnumActuatorRelativeTo".to_string(),
+            PropertyType::PositionAlignmentMode => "EnumPositionAlignmentMode".to_string(),
+            PropertyType::PhysicalProperties => "PhysicalProperties?".to_string(),
+            PropertyType::TextXAlignment => r#""Left" | "Center" | "Right""#.to_string(),
+            PropertyType::TextYAlignment => r#""Top" | "Center" | "Bottom""#.to_string(),
+            PropertyType::Instance(class) => format!("{class}?"),
+            other => other.name().to_string(),
+        }
+    }
+
+    /// Converts a value assigned from Lua
+    pub fn from_lua(&self, value: Value, lua: &Lua) -> Result<PropertyValue> {
+        let conversion_error = |value: &Value| mlua::Error::FromLuaConversionError {
+            from: value.type_name(),
+            to: self.name().to_string(),
+            message: None,
+        };
+        Ok(match self {
+            PropertyType::Bool => PropertyValue::Bool(bool::from_lua(value, lua)?),
+            PropertyType::Number => PropertyValue::Number(f32::from_lua(value, lua)? as f64),
+            PropertyType::Int => PropertyValue::Int(i32::from_lua(value, lua)?),
+            PropertyType::String => PropertyValue::String(String::from_lua(value, lua)?),
+            PropertyType::Vector2 => match &value {
+                Value::Table(t) => PropertyValue::Vector2(
+                    t.get::<Option<f32>>("X")?.unwrap_or(0.0),
+                    t.get::<Option<f32>>("Y")?.unwrap_or(0.0),
+                ),
+                _ => return Err(conversion_error(&value)),
+            },
+            PropertyType::Vector3 => PropertyValue::Vector3(Vector3::from_lua(value, lua)?),
+            PropertyType::CFrame => PropertyValue::CFrame(CFrame::from_lua(value, lua)?),
+            PropertyType::Color3 => PropertyValue::Color3(Color3::from_lua(value, lua)?),
+            PropertyType::UDim2 => PropertyValue::UDim2(UDim2::from_lua(value, lua)?),
+            PropertyType::Material => PropertyValue::Material(Material::from_lua(value, lua)?),
+            PropertyType::PartType => PropertyValue::PartType(PartType::from_lua(value, lua)?),
+            PropertyType::ActuatorType => {
+                PropertyValue::ActuatorType(ActuatorType::from_lua(value, lua)?)
+            }
+            PropertyType::ActuatorRelativeTo => {
+                PropertyValue::ActuatorRelativeTo(ActuatorRelativeTo::from_lua(value, lua)?)
+            }
+            PropertyType::PositionAlignmentMode => {
+                PropertyValue::PositionAlignmentMode(PositionAlignmentMode::from_lua(value, lua)?)
+            }
+            PropertyType::PhysicalProperties => PropertyValue::PhysicalProperties(
+                Option::<PhysicalProperties>::from_lua(value, lua)?,
+            ),
+            PropertyType::TextXAlignment => PropertyValue::TextXAlignment(match &value {
+                Value::String(name) => TextXAlignment::from_name(&name.to_str()?),
+                _ => TextXAlignment::from_lua(value, lua)?,
+            }),
+            PropertyType::TextYAlignment => PropertyValue::TextYAlignment(match &value {
+                Value::String(name) => TextYAlignment::from_name(&name.to_str()?),
+                _ => TextYAlignment::from_lua(value, lua)?,
+            }),
+            PropertyType::Instance(_) => match &value {
+                Value::Nil => PropertyValue::Instance(None),
+                Value::UserData(ud) => {
+                    if let Ok(instance) = ud.borrow::<Instance>() {
+                        PropertyValue::Instance(Some(instance.weak_ref()))
+                    } else if let Ok(workspace) = ud.borrow::<WorkspaceService>() {
+                        PropertyValue::Instance(Some(workspace.instance.weak_ref()))
+                    } else {
+                        return Err(conversion_error(&value));
+                    }
+                }
+                _ => return Err(conversion_error(&value)),
+            },
+        })
+    }
+}
+
+/// A property's value, outside Lua. Snapshots serialize it; instance
+/// references can't be, so they store the referenced id instead.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum PropertyValue {
+    Bool(bool),
+    Number(f64),
+    Int(i32),
+    String(String),
+    Vector2(f32, f32),
+    Vector3(Vector3),
+    CFrame(CFrame),
+    Color3(Color3),
+    UDim2(UDim2),
+    Material(Material),
+    PartType(PartType),
+    ActuatorType(ActuatorType),
+    ActuatorRelativeTo(ActuatorRelativeTo),
+    PositionAlignmentMode(PositionAlignmentMode),
+    PhysicalProperties(Option<PhysicalProperties>),
+    TextXAlignment(TextXAlignment),
+    TextYAlignment(TextYAlignment),
+    #[serde(skip)]
+    Instance(Option<WeakInstanceRef>),
+}
+
+impl PropertyValue {
+    /// JSON form for spectators and the API dump. Vectors and colors are
+    /// arrays, enums their names and instances their ids.
+    pub fn to_json(&self) -> serde_json::Value {
+        match self {
+            PropertyValue::Bool(b) => serde_json::json!(b),
+            PropertyValue::Number(n) => serde_json::json!(n),
+            PropertyValue::Int(n) => serde_json::json!(n),
+            PropertyValue::String(s) => serde_json::json!(s),
+            PropertyValue::Vector2(x, y) => serde_json::json!([x, y]),
+            PropertyValue::Vector3(v) => serde_json::json!([v.x, v.y, v.z]),
+            PropertyValue::CFrame(cf) => serde_json::json!({
+                "position": [cf.position.x, cf.position.y, cf.position.z],
+                "rotation": cf.rotation,
+            }),
+            PropertyValue::Color3(c) => serde_json::json!([c.r, c.g, c.b]),
+            PropertyValue::UDim2(u) => serde_json::json!({
+                "x_scale": u.x.scale,
+                "x_offset": u.x.offset,
+                "y_scale": u.y.scale,
+                "y_offset": u.y.offset,
+            }),
+            PropertyValue::Material(m) => serde_json::json!(m.name()),
+            PropertyValue::PartType(p) => serde_json::json!(p.name()),
+            PropertyValue::ActuatorType(a) => serde_json::json!(a.name()),
+            PropertyValue::ActuatorRelativeTo(r) => serde_json::json!(r.name()),
+            PropertyValue::PositionAlignmentMode(m) => serde_json::json!(m.name()),
+            PropertyValue::PhysicalProperties(p) => serde_json::json!(p),
+            PropertyValue::TextXAlignment(a) => serde_json::json!(a.name()),
+            PropertyValue::TextYAlignment(a) => serde_json::json!(a.name()),
+            PropertyValue::Instance(weak) => weak
+                .as_ref()
+                .and_then(Weak::upgrade)
+                .map_or(serde_json::Value::Null, |data| {
+                    serde_json::json!(data.lock().unwrap().id.0)
+                }),
+        }
+    }
+}
+
+impl PartialEq for PropertyValue {
+    fn eq(&self, other: &Self) -> bool {
+        use PropertyValue as V;
+        match (self, other) {
+            (V::Bool(a), V::Bool(b)) => a == b,
+            (V::Number(a), V::Number(b)) => a == b,
+            (V::Int(a), V::Int(b)) => a == b,
+            (V::String(a), V::String(b)) => a == b,
+            (V::Vector2(ax, ay), V::Vector2(bx, by)) => ax == bx && ay == by,
+            (V::Vector3(a), V::Vector3(b)) => a == b,
+            (V::CFrame(a), V::CFrame(b)) => a == b,
+            (V::Color3(a), V::Color3(b)) => a == b,
+            (V::UDim2(a), V::UDim2(b)) => a == b,
+            (V::Material(a), V::Material(b)) => a == b,
+            (V::PartType(a), V::PartType(b)) => a == b,
+            (V::ActuatorType(a), V::ActuatorType(b)) => a == b,
+            (V::ActuatorRelativeTo(a), V::ActuatorRelativeTo(b)) => a == b,
+            (V::PositionAlignmentMode(a), V::PositionAlignmentMode(b)) => a == b,
+            (V::PhysicalProperties(a), V::PhysicalProperties(b)) => a == b,
+            (V::TextXAlignment(a), V::TextXAlignment(b)) => a == b,
+            (V::TextYAlignment(a), V::TextYAlignment(b)) => a == b,
+            (V::Instance(a), V::Instance(b)) => match (a, b) {
+                (Some(a), Some(b)) => Weak::ptr_eq(a, b),
+                (a, b) => a.is_none() && b.is_none(),
+            },
+            _ => false,
+        }
+    }
+}
+
+impl IntoLua for PropertyValue {
+    fn into_lua(self, lua: &Lua) -> Result<Value> {
+        match self {
+            PropertyValue::Bool(b) => Ok(Value::Boolean(b)),
+            PropertyValue::Number(n) => Ok(Value::Number(n)),
+            PropertyValue::Int(n) => Ok(Value::Integer(n.into())),
+            PropertyValue::String(s) => s.into_lua(lua),
+            PropertyValue::Vector2(x, y) => {
+                let table = lua.create_table()?;
+                table.set("X", x)?;
+                table.set("Y", y)?;
+                Ok(Value::Table(table))
+            }
+            PropertyValue::Vector3(v) => v.into_lua(lua),
+            PropertyValue::CFrame(cf) => cf.into_lua(lua),
+            PropertyValue::Color3(c) => c.into_lua(lua),
+            PropertyValue::UDim2(u) => u.into_lua(lua),
+            PropertyValue::Material(m) => m.into_lua(lua),
+            PropertyValue::PartType(p) => p.into_lua(lua),
+            PropertyValue::ActuatorType(a) => a.into_lua(lua),
+            PropertyValue::ActuatorRelativeTo(r) => r.into_lua(lua),
+            PropertyValue::PositionAlignmentMode(m) => m.into_lua(lua),
+            PropertyValue::PhysicalProperties(p) => p.into_lua(lua),
+            PropertyValue::TextXAlignment(a) => a.name().into_lua(lua),
+            PropertyValue::TextYAlignment(a) => a.name().into_lua(lua),
+            PropertyValue::Instance(weak) => weak
+                .and_then(|weak| weak.upgrade())
+                .map(Instance::from_ref)
+                .into_lua(lua),
+        }
+    }
+}
+
+/// Rust types properties are declared with
+trait PropertyKind: Sized + 'static {
+    fn value_type() -> PropertyType;
+    fn into_value(self) -> PropertyValue;
+    fn from_value(value: PropertyValue) -> Option<Self>;
+}
+
+macro_rules! property_kind {
+    ($ty:ty, $variant:ident) => {
+        impl PropertyKind for $ty {
+            fn value_type() -> PropertyType {
+                PropertyType::$variant
+            }
+
+            fn into_value(self) -> PropertyValue {
+                PropertyValue::$variant(self)
+            }
+
+            fn from_value(value: PropertyValue) -> Option<Self> {
+                match value {
+                    PropertyValue::$variant(v) => Some(v),
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+property_kind!(bool, Bool);
+property_kind!(i32, Int);
+property_kind!(String, String);
+property_kind!(Vector3, Vector3);
+property_kind!(CFrame, CFrame);
+property_kind!(Color3, Color3);
+property_kind!(UDim2, UDim2);
+property_kind!(Material, Material);
+property_kind!(PartType, PartType);
+property_kind!(ActuatorType, ActuatorType);
+property_kind!(ActuatorRelativeTo, ActuatorRelativeTo);
+property_kind!(PositionAlignmentMode, PositionAlignmentMode);
+property_kind!(Option<PhysicalProperties>, PhysicalProperties);
+property_kind!(TextXAlignment, TextXAlignment);
+property_kind!(TextYAlignment, TextYAlignment);
+
+impl PropertyKind for f32 {
+    fn value_type() -> PropertyType {
+        PropertyType::Number
+    }
+
+    fn into_value(self) -> PropertyValue {
+        PropertyValue::Number(self as f64)
+    }
+
+    fn from_value(value: PropertyValue) -> Option<Self> {
+        match value {
+            PropertyValue::Number(n) => Some(n as f32),
+            _ => None,
+        }
+    }
+}
+
+impl PropertyKind for f64 {
+    fn value_type() -> PropertyType {
+        PropertyType::Number
+    }
+
+    fn into_value(self) -> PropertyValue {
+        PropertyValue::Number(self)
+    }
+
+    fn from_value(value: PropertyValue) -> Option<Self> {
+        match value {
+            PropertyValue::Number(n) => Some(n),
+            _ => None,
+        }
+    }
+}
+
+impl PropertyKind for (f32, f32) {
+    fn value_type() -> PropertyType {
+        PropertyType::Vector2
+    }
+
+    fn into_value(self) -> PropertyValue {
+        PropertyValue::Vector2(self.0, self.1)
+    }
+
+    fn from_value(value: PropertyValue) -> Option<Self> {
+        match value {
+            PropertyValue::Vector2(x, y) => Some((x, y)),
+            _ => None,
+        }
+    }
+}
+
+impl PropertyKind for Option<WeakInstanceRef> {
+    fn value_type() -> PropertyType {
+        PropertyType::Instance("Instance")
+    }
+
+    fn into_value(self) -> PropertyValue {
+        PropertyValue::Instance(self)
+    }
+
+    fn from_value(value: PropertyValue) -> Option<Self> {
+        match value {
+            PropertyValue::Instance(weak) => Some(weak),
+            _ => None,
+        }
+    }
+}
+
+// =============================================================================
+// Descriptors
+// =============================================================================
+
+type Getter = Box<dyn Fn(&InstanceData) -> Option<PropertyValue> + Send + Sync>;
+type Setter = Box<dyn Fn(&Instance, PropertyValue) + Send + Sync>;
+/// Lua-side effects of a set, run after the value is written
+type AfterSet = fn(&Lua, &Instance) -> Result<()>;
+
+pub struct PropertyDescriptor {
+    pub name: &'static str,
+    /// Class that declares the property; every class that IsA it has it
+    pub class: &'static str,
+    pub value_type: PropertyType,
+    /// Sent to spectators
+    pub replicated: bool,
+    /// Not copied by Clone even though it is writable (Parent)
+    skip_clone: bool,
+    /// Spatial queries read it, so a write marks the part dirty in the workspace index
+    spatial: bool,
+    /// Script assignments of a value it can't convert are ignored instead of raising
+    pub lenient: bool,
+    get: Getter,
+    set: Option<Setter>,
+    after_set: Option<AfterSet>,
+}
+
+impl PropertyDescriptor {
+    pub fn read_only(&self) -> bool {
+        self.set.is_none()
+    }
+
+    /// Whether Clone copies it
+    pub fn cloned(&self) -> bool {
+        self.set.is_some() && !self.skip_clone
+    }
+
+    /// The value, or None for nil
+    pub fn get(&self, data: &InstanceData) -> Option<PropertyValue> {
+        (self.get)(data)
+    }
+
+    /// Writes the value without Lua-side effects or change signals. Values of
+    /// another type and read-only properties are ignored.
+    pub fn apply(&self, instance: &Instance, value: PropertyValue) {
+        if let Some(set) = &self.set {
+            set(instance, value);
+            if self.spatial {
+                instance.mark_spatial_dirty();
+            }
+        }
+    }
+
+    /// Runs the Lua-side effects of setting the property from a script
+    pub fn after_set(&self, lua: &Lua, instance: &Instance) -> Result<()> {
+        match self.after_set {
+            Some(after_set) => after_set(lua, instance),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Builds a descriptor from typed accessors
+struct Property<T> {
+    descriptor: PropertyDescriptor,
+    value: PhantomData<fn() -> T>,
+}
+
+fn property<T: PropertyKind>(
+    name: &'static str,
+    class: &'static str,
+    get: fn(&InstanceData) -> Option<T>,
+) -> Property<T> {
+    Property {
+        descriptor: PropertyDescriptor {
+            name,
+            class,
+            value_type: T::value_type(),
+            replicated: false,
+            skip_clone: false,
+            spatial: false,
+            lenient: false,
+            get: Box::new(move |data| get(data).map(T::into_value)),
+            set: None,
+            after_set: None,
+        },
+        value: PhantomData,
+    }
+}
+
+impl<T: PropertyKind> Property<T> {
+    fn set(self, set: fn(&mut InstanceData, T)) -> Self {
+        self.set_instance(move |instance, value| set(&mut instance.data.lock().unwrap(), value))
+    }
+
+    /// For setters that need the instance rather than its data
+    fn set_instance(mut self, set: impl Fn(&Instance, T) + Send + Sync + 'static) -> Self {
+        self.descriptor.set = Some(Box::new(move |instance, value| {
+            if let Some(value) = T::from_value(value) {
+                set(instance, value);
+            }
+        }));
+        self
+    }
+
+    fn after_set(mut self, after_set: AfterSet) -> Self {
+        self.descriptor.after_set = Some(after_set);
+        self
+    }
+
+    fn replicated(mut self) -> Self {
+        self.descriptor.replicated = true;
+        self
+    }
+
+    fn skip_clone(mut self) -> Self {
+        self.descriptor.skip_clone = true;
+        self
+    }
+
+    fn spatial(mut self) -> Self {
+        self.descriptor.spatial = true;
+        self
+    }
+
+    fn lenient(mut self) -> Self {
+        self.descriptor.lenient = true;
+        self
+    }
+
+    /// Narrows an instance reference to a class, for the API dump
+    fn refers_to(mut self, class: &'static str) -> Self {
+        self.descriptor.value_type = PropertyType::Instance(class);
+        self
+    }
+}
+
+impl<T> From<Property<T>> for PropertyDescriptor {
+    fn from(property: Property<T>) -> Self {
+        property.descriptor
+    }
+}
+
+pub struct EventDescriptor {
+    pub name: &'static str,
+    /// Class that declares the event; every class that IsA it has it
+    pub class: &'static str,
+    /// Arguments handlers are called with, as (name, Luau type)
+    pub params: &'static [(&'static str, &'static str)],
+    get: fn(&mut InstanceData) -> Option<RBXScriptSignal>,
+}
+
+impl EventDescriptor {
+    pub fn get(&self, data: &mut InstanceData) -> Option<RBXScriptSignal> {
+        (self.get)(data)
+    }
+}
+
+// =============================================================================
+// Registry
+// =============================================================================
+
+struct Registry {
+    properties: Vec<PropertyDescriptor>,
+    events: Vec<EventDescriptor>,
+    /// Indices of the properties each class has, in declaration order
+    class_properties: HashMap<ClassName, Vec<usize>>,
+    property_index: HashMap<(ClassName, &'static str), usize>,
+    event_index: HashMap<(ClassName, &'static str), usize>,
+}
+
+static REGISTRY: LazyLock<Registry> = LazyLock::new(|| {
+    let properties = [
+        instance_properties(),
+        base_part_properties(),
+        humanoid_properties(),
+        player_properties(),
+        model_properties(),
+        joint_instance_properties(),
+        attachment_properties(),
+        constraint_properties(),
+        actuated_properties(
+            "HingeConstraint",
+            [
+                "AngularVelocity",
+                "MotorMaxTorque",
+                "TargetAngle",
+                "ServoMaxTorque",
+                "LowerAngle",
+                "UpperAngle",
+            ],
+        ),
+        actuated_properties(
+            "PrismaticConstraint",
+            [
+                "Velocity",
+                "MotorMaxForce",
+                "TargetPosition",
+                "ServoMaxForce",
+                "LowerLimit",
+                "UpperLimit",
+            ],
+        ),
+        mover_properties(),
+        script_properties(),
+        billboard_gui_properties(),
+        gui_properties(),
+        text_properties("TextLabel"),
+        text_properties("TextButton"),
+        image_properties("ImageLabel"),
+        image_properties("ImageButton"),
+    ]
+    .into_iter()
+    .flatten()
+    .collect::<Vec<_>>();
+    let events = events();
+
+    let mut class_properties: HashMap<ClassName, Vec<usize>> = HashMap::new();
+    let mut property_index = HashMap::new();
+    let mut event_index = HashMap::new();
+    for &class in ClassName::ALL {
+        for (i, property) in properties.iter().enumerate() {
+            if class.is_a(property.class) {
+                class_properties.entry(class).or_default().push(i);
+                property_index.insert((class, property.name), i);
+            }
+        }
+        for (i, event) in events.iter().enumerate() {
+            if class.is_a(event.class) {
+                event_index.insert((class, event.name), i);
+            }
+        }
+    }
+
+    Registry {
+        properties,
+        events,
+        class_properties,
+        property_index,
+        event_index,
+    }
+});
+
+/// The property `name` of `class`, if the class has one
+pub fn find_property(class: ClassName, name: &str) -> Option<&'static PropertyDescriptor> {
+    let registry = &*REGISTRY;
+    registry
+        .property_index
+        .get(&(class, name))
+        .map(|&i| &registry.properties[i])
+}
+
+/// All properties of `class`, including inherited ones
+pub fn properties_of(class: ClassName) -> impl Iterator<Item = &'static PropertyDescriptor> {
+    let registry = &*REGISTRY;
+    registry
+        .class_properties
+        .get(&class)
+        .into_iter()
+        .flatten()
+        .map(|&i| &registry.properties[i])
+}
+
+pub fn find_event(class: ClassName, name: &str) -> Option<&'static EventDescriptor> {
+    let registry = &*REGISTRY;
+    registry
+        .event_index
+        .get(&(class, name))
+        .map(|&i| &registry.events[i])
+}
+
+/// Every property name, each once
+pub fn property_names() -> Vec<&'static str> {
+    let mut names: Vec<&'static str> = REGISTRY.properties.iter().map(|p| p.name).collect();
+    names.sort_unstable();
+    names.dedup();
+    names
+}
+
+/// Property names that some class can write, each once
+pub fn writable_property_names() -> Vec<&'static str> {
+    let mut names: Vec<&'static str> = REGISTRY
+        .properties
+        .iter()
+        .filter(|p| !p.read_only())
+        .map(|p| p.name)
+        .collect();
+    names.sort_unstable();
+    names.dedup();
+    names
+}
+
+/// Every event name, each once
+pub fn event_names() -> Vec<&'static str> {
+    let mut names: Vec<&'static str> = REGISTRY.events.iter().map(|e| e.name).collect();
+    names.sort_unstable();
+    names.dedup();
+    names
+}
+
+/// Replicated properties of the instance as JSON, keyed by property name,
+/// leaving out the ones in `except`
+pub fn replicated_properties(
+    data: &InstanceData,
+    except: &[&str],
+) -> serde_json::Map<String, serde_json::Value> {
+    properties_of(data.class_name)
+        .filter(|p| p.replicated && !except.contains(&p.name))
+        .filter_map(|p| Some((p.name.to_string(), p.get(data)?.to_json())))
+        .collect()
+}
+
+/// Every class with its superclass, base classes first
+pub fn classes() -> &'static [(&'static str, Option<&'static str>)] {
+    CLASSES
+}
+
+/// Properties `class` declares itself, without inherited ones
+pub fn declared_properties(class: &str) -> impl Iterator<Item = &'static PropertyDescriptor> + '_ {
+    REGISTRY.properties.iter().filter(move |p| p.class == class)
+}
+
+/// Events `class` declares itself, without inherited ones
+pub fn declared_events(class: &str) -> impl Iterator<Item = &'static EventDescriptor> + '_ {
+    REGISTRY.events.iter().filter(move |e| e.class == class)
+}
+
+// =============================================================================
+// Declarations
+// =============================================================================
+
+fn instance_properties() -> Vec<PropertyDescriptor> {
+    vec![
+        property("Name", "Instance", |d| Some(d.name.clone()))
+            .set(|d, name| d.name = name)
+            .into(),
+        property("ClassName", "Instance", |d| {
+            Some(d.class_name.as_str().to_string())
+        })
+        .into(),
+        property("Parent", "Instance", |d| Some(d.parent.clone()))
+            .set_instance(|instance, parent| {
+                let parent = parent
+                    .and_then(|weak| weak.upgrade())
+                    .map(Instance::from_ref);
+                instance.set_parent(parent.as_ref());
+            })
+            .after_set(|lua, _| fire_pending(lua))
+            .skip_clone()
+            .into(),
+    ]
+}
+
+fn base_part_properties() -> Vec<PropertyDescriptor> {
+    vec![
+        property("Position", "BasePart", |d| {
+            d.part_data.as_ref().map(|p| p.position)
+        })
+        .set(|d, position| {
+            if let Some(part) = &mut d.part_data {
+                part.position = position;
+                part.cframe.position = position;
+                part.position_dirty = true;
+            }
+        })
+        .replicated()
+        .spatial()
+        .into(),
+        property("CFrame", "BasePart", |d| {
+            d.part_data.as_ref().map(|p| p.cframe)
+        })
+        .set(|d, cframe| {
+            if let Some(part) = &mut d.part_data {
+                part.cframe = cframe;
+                part.position = cframe.position;
+                part.position_dirty = true;
+            }
+        })
+        .replicated()
+        .spatial()
+        .into(),
+        property("Size", "BasePart", |d| d.part_data.as_ref().map(|p| p.size))
+            .set(|d, size| {
+                if let Some(part) = &mut d.part_data {
+                    part.size = size;
+                }
+            })
+            .replicated()
+            .spatial()
+            .into(),
+        property("Anchored", "BasePart", |d| {
+            d.part_data.as_ref().map(|p| p.anchored)
+        })
+        .set(|d, anchored| {
+            if let Some(part) = &mut d.part_data {
+                part.anchored = anchored;
+            }
+        })
+        .into(),
+        property("CanCollide", "BasePart", |d| {
+            d.part_data.as_ref().map(|p| p.can_collide)
+        })
+        .set(|d, can_collide| {
+            if let Some(part) = &mut d.part_data {
+                part.can_collide = can_collide;
+            }
+        })
+        .spatial()
+        .into(),
+        property("CanTouch", "BasePart", |d| {
+            d.part_data.as_ref().map(|p| p.can_touch)
+        })
+        .set(|d, can_touch| {
+            if let Some(part) = &mut d.part_data {
+                part.can_touch = can_touch;
+            }
+        })
+        .into(),
+        property("Transparency", "BasePart", |d| {
+            d.part_data.as_ref().map(|p| p.transparency)
+        })
+        .set(|d, transparency: f32| {
+            if let Some(part) = &mut d.part_data {
+                part.transparency = transparency.clamp(0.0, 1.0);
+            }
+        })
+        .replicated()
+        .into(),
+        property("Color", "BasePart", |d| {
+            d.part_data.as_ref().map(|p| p.color)
+        })
+        .set(|d, color| {
+            if let Some(part) = &mut d.part_data {
+                part.color = color;
+            }
+        })
+        .replicated()
+        .into(),
+        property("Material", "BasePart", |d| {
+            d.part_data.as_ref().map(|p| p.material)
+        })
+        .set(|d, material| {
+            if let Some(part) = &mut d.part_data {
+                part.material = material;
+            }
+        })
+        .replicated()
+        .into(),
+        property("CustomPhysicalProperties", "BasePart", |d| {
+            d.part_data.as_ref().map(|p| p.custom_physical_properties)
+        })
+        .set(|d, properties| {
+            if let Some(part) = &mut d.part_data {
+                part.custom_physical_properties = properties;
+            }
+        })
+        .into(),
+        property("CurrentPhysicalProperties", "BasePart", |d| {
+            d.part_data.as_ref().map(|p| Some(p.physical_properties()))
+        })
+        .into(),
+        property("Massless", "BasePart", |d| {
+            d.part_data.as_ref().map(|p| p.massless)
+        })
+        .set(|d, massless| {
+            if let Some(part) = &mut d.part_data {
+                part.massless = massless;
+            }
+        })
+        .into(),
+        property("CollisionGroup", "BasePart", |d| {
+            d.part_data.as_ref().map(|p| p.collision_group.clone())
+        })
+        .set(|d, collision_group| {
+            if let Some(part) = &mut d.part_data {
+                part.collision_group = collision_group;
+            }
+        })
+        .spatial()
+        .into(),
+        property("Velocity", "BasePart", |d| {
+            d.part_data.as_ref().map(|p| p.velocity)
+        })
+        .set(|d, velocity| {
+            if let Some(part) = &mut d.part_data {
+                part.velocity = velocity;
+                part.velocity_dirty = true;
+            }
+        })
+        .into(),
+        property("AssemblyLinearVelocity", "BasePart", |d| {
+            d.part_data.as_ref().map(|p| p.velocity)
+        })
+        .set(|d, velocity| {
+            if let Some(part) = &mut d.part_data {
+                part.velocity = velocity;
+                part.velocity_dirty = true;
+            }
+        })
+        .into(),
+        property("AssemblyAngularVelocity", "BasePart", |d| {
+            d.part_data.as_ref().map(|p| p.angular_velocity)
+        })
+        .set(|d, angular_velocity| {
+            if let Some(part) = &mut d.part_data {
+                part.angular_velocity = angular_velocity;
+                part.velocity_dirty = true;
+            }
+        })
+        .into(),
+        property("Shape", "BasePart", |d| {
+            d.part_data.as_ref().map(|p| p.shape)
+        })
+        .set(|d, shape| {
+            if let Some(part) = &mut d.part_data {
+                part.shape = shape;
+            }
+        })
+        .replicated()
+        .spatial()
+        .into(),
+    ]
+}
+
+fn humanoid_properties() -> Vec<PropertyDescriptor> {
+    vec![
+        // Before Health, so a clone's Health is clamped to the copied MaxHealth
+        property("MaxHealth", "Humanoid", |d| {
+            d.humanoid_data.as_ref().map(|h| h.max_health)
+        })
+        .set(|d, max_health: f32| {
+            if let Some(humanoid) = &mut d.humanoid_data {
+                humanoid.max_health = max_health.max(0.0);
+            }
+        })
+        .into(),
+        property("Health", "Humanoid", |d| {
+            d.humanoid_data.as_ref().map(|h| h.health)
+        })
+        .set(|d, health: f32| {
+            if let Some(humanoid) = &mut d.humanoid_data {
+                humanoid.health = health.max(0.0).min(humanoid.max_health);
+            }
+        })
+        .replicated()
+        .into(),
+        property("WalkSpeed", "Humanoid", |d| {
+            d.humanoid_data.as_ref().map(|h| h.walk_speed)
+        })
+        .set(|d, walk_speed: f32| {
+            if let Some(humanoid) = &mut d.humanoid_data {
+                humanoid.walk_speed = walk_speed.max(0.0);
+            }
+        })
+        .into(),
+        property("JumpPower", "Humanoid", |d| {
+            d.humanoid_data.as_ref().map(|h| h.jump_power)
+        })
+        .set(|d, jump_power: f32| {
+            if let Some(humanoid) = &mut d.humanoid_data {
+                humanoid.jump_power = jump_power.max(0.0);
+            }
+        })
+        .into(),
+        property("JumpHeight", "Humanoid", |d| {
+            d.humanoid_data.as_ref().map(|h| h.jump_height)
+        })
+        .set(|d, jump_height: f32| {
+            if let Some(humanoid) = &mut d.humanoid_data {
+                humanoid.jump_height = jump_height.max(0.0);
+            }
+        })
+        .into(),
+        property("AutoRotate", "Humanoid", |d| {
+            d.humanoid_data.as_ref().map(|h| h.auto_rotate)
+        })
+        .set(|d, auto_rotate| {
+            if let Some(humanoid) = &mut d.humanoid_data {
+                humanoid.auto_rotate = auto_rotate;
+            }
+        })
+        .into(),
+        property("HipHeight", "Humanoid", |d| {
+            d.humanoid_data.as_ref().map(|h| h.hip_height)
+        })
+        .set(|d, hip_height| {
+            if let Some(humanoid) = &mut d.humanoid_data {
+                humanoid.hip_height = hip_height;
+            }
+        })
+        .into(),
+        property("FloorMaterial", "Humanoid", |d| {
+            d.humanoid_data.as_ref().map(|h| h.floor_material)
+        })
+        .into(),
+    ]
+}
+
+fn player_properties() -> Vec<PropertyDescriptor> {
+    vec![
+        // A float, so it works as a table key next to numbers from Lua
+        property("UserId", "Player", |d| {
+            d.player_data.as_ref().map(|p| p.user_id as f64)
+        })
+        .into(),
+        property("DisplayName", "Player", |d| {
+            d.player_data.as_ref().map(|p| p.display_name.clone())
+        })
+        .into(),
+        property("Character", "Player", |d| {
+            d.player_data.as_ref().map(|p| p.character.clone())
+        })
+        .refers_to("Model")
+        .into(),
+        property("PlayerGui", "Player", |d| {
+            d.player_data.as_ref().map(|p| p.player_gui.clone())
+        })
+        .refers_to("PlayerGui")
+        .into(),
+    ]
+}
+
+fn model_properties() -> Vec<PropertyDescriptor> {
+    vec![property("PrimaryPart", "Model", |d| {
+        d.model_data.as_ref().map(|m| m.primary_part.clone())
+    })
+    .set(|d, part| {
+        if let Some(model) = &mut d.model_data {
+            model.primary_part = part;
+        }
+    })
+    .refers_to("BasePart")
+    .into()]
+}
+
+fn joint_instance_properties() -> Vec<PropertyDescriptor> {
+    vec![
+        property("Part0", "JointInstance", |d| {
+            d.weld_data.as_ref().map(|w| w.part0.clone())
+        })
+        .set(|d, part| {
+            if let Some(weld) = &mut d.weld_data {
+                weld.part0 = part;
+            }
+        })
+        .refers_to("BasePart")
+        .into(),
+        property("Part1", "JointInstance", |d| {
+            d.weld_data.as_ref().map(|w| w.part1.clone())
+        })
+        .set(|d, part| {
+            if let Some(weld) = &mut d.weld_data {
+                weld.part1 = part;
+            }
+        })
+        .refers_to("BasePart")
+        .into(),
+        property("C0", "JointInstance", |d| d.weld_data.as_ref().map(|w| w.c0))
+            .set(|d, cframe| {
+                if let Some(weld) = &mut d.weld_data {
+                    weld.c0 = cframe;
+                }
+            })
+            .into(),
+        property("C1", "JointInstance", |d| d.weld_data.as_ref().map(|w| w.c1))
+            .set(|d, cframe| {
+                if let Some(weld) = &mut d.weld_data {
+                    weld.c1 = cframe;
+                }
+            })
+            .into(),
+        property("Enabled", "JointInstance", |d| {
+            d.weld_data.as_ref().map(|w| w.enabled)
+        })
+        .set(|d, enabled| {
+            if let Some(weld) = &mut d.weld_data {
+                weld.enabled = enabled;
+            }
+        })
+        .into(),
+    ]
+}
+
+fn attachment_properties() -> Vec<PropertyDescriptor> {
+    vec![
+        property("CFrame", "Attachment", |d| {
+            d.attachment_data.as_ref().map(|a| a.cframe)
+        })
+        .set(|d, cframe| {
+            if let Some(attachment) = &mut d.attachment_data {
+                attachment.cframe = cframe;
+            }
+        })
+        .into(),
+        property("Position", "Attachment", |d| {
+            d.attachment_data.as_ref().map(|a| a.cframe.position)
+        })
+        .set(|d, position| {
+            if let Some(attachment) = &mut d.attachment_data {
+                attachment.cframe.position = position;
+            }
+        })
+        .into(),
+        property("WorldCFrame", "Attachment", |d| {
+            d.attachment_data.as_ref().map(|a| world_cframe(d, a.cframe))
+        })
+        .into(),
+        property("WorldPosition", "Attachment", |d| {
+            d.attachment_data
+                .as_ref()
+                .map(|a| world_cframe(d, a.cframe).position)
+        })
+        .into(),
+    ]
+}
+
+/// An attachment's CFrame in world space; relative to the world origin when
+/// it isn't in a part
+fn world_cframe(data: &InstanceData, cframe: CFrame) -> CFrame {
+    let part_cframe = data
+        .parent
+        .as_ref()
+        .and_then(Weak::upgrade)
+        .and_then(|parent| parent.lock().unwrap().part_data.as_ref().map(|p| p.cframe));
+    match part_cframe {
+        Some(part_cframe) => part_cframe.multiply(&cframe),
+        None => cframe,
+    }
+}
+
+fn constraint_properties() -> Vec<PropertyDescriptor> {
+    vec![
+        property("Attachment0", "Constraint", |d| {
+            d.constraint_data.as_ref().map(|c| c.attachment0.clone())
+        })
+        .set(|d, attachment| {
+            if let Some(constraint) = &mut d.constraint_data {
+                constraint.attachment0 = attachment;
+            }
+        })
+        .refers_to("Attachment")
+        .into(),
+        property("Attachment1", "Constraint", |d| {
+            d.constraint_data.as_ref().map(|c| c.attachment1.clone())
+        })
+        .set(|d, attachment| {
+            if let Some(constraint) = &mut d.constraint_data {
+                constraint.attachment1 = attachment;
+            }
+        })
+        .refers_to("Attachment")
+        .into(),
+        property("Enabled", "Constraint", |d| {
+            d.constraint_data.as_ref().map(|c| c.enabled)
+        })
+        .set(|d, enabled| {
+            if let Some(constraint) = &mut d.constraint_data {
+                constraint.enabled = enabled;
+            }
+        })
+        .into(),
+        property("CurrentAngle", "HingeConstraint", |d| {
+            d.constraint_data.as_ref().map(|c| c.current_angle())
+        })
+        .into(),
+        property("CurrentPosition", "PrismaticConstraint", |d| {
+            d.constraint_data.as_ref().map(|c| c.current_position())
+        })
+        .into(),
+        property("Length", "RopeConstraint", |d| {
+            d.constraint_data.as_ref().map(|c| c.length)
+        })
+        .set(|d, length: f32| {
+            if let Some(constraint) = &mut d.constraint_data {
+                constraint.length = length.max(0.0);
+            }
+        })
+        .into(),
+        property("CurrentDistance", "RopeConstraint", |d| {
+            d.constraint_data.as_ref().map(|c| c.current_distance())
+        })
+        .into(),
+        property("FreeLength", "SpringConstraint", |d| {
+            d.constraint_data.as_ref().map(|c| c.free_length)
+        })
+        .set(|d, free_length: f32| {
+            if let Some(constraint) = &mut d.constraint_data {
+                constraint.free_length = free_length.max(0.0);
+            }
+        })
+        .into(),
+        property("Stiffness", "SpringConstraint", |d| {
+            d.constraint_data.as_ref().map(|c| c.stiffness)
+        })
+        .set(|d, stiffness: f32| {
+            if let Some(constraint) = &mut d.constraint_data {
+                constraint.stiffness = stiffness.max(0.0);
+            }
+        })
+        .into(),
+        property("Damping", "SpringConstraint", |d| {
+            d.constraint_data.as_ref().map(|c| c.damping)
+        })
+        .set(|d, damping: f32| {
+            if let Some(constraint) = &mut d.constraint_data {
+                constraint.damping = damping.max(0.0);
+            }
+        })
+        .into(),
+        property("CurrentLength", "SpringConstraint", |d| {
+            d.constraint_data.as_ref().map(|c| c.current_distance())
+        })
+        .into(),
+    ]
+}
+
+/// Actuator and limit properties, shared by HingeConstraint and
+/// PrismaticConstraint under the names in `names`: motor velocity, motor max
+/// force, servo target, servo max force, lower limit and upper limit
+fn actuated_properties(class: &'static str, names: [&'static str; 6]) -> Vec<PropertyDescriptor> {
+    let [velocity, motor_max_force, target, servo_max_force, lower_limit, upper_limit] = names;
+    vec![
+        property("ActuatorType", class, |d| {
+            d.constraint_data.as_ref().map(|c| c.actuator_type)
+        })
+        .set(|d, actuator_type| {
+            if let Some(constraint) = &mut d.constraint_data {
+                constraint.actuator_type = actuator_type;
+            }
+        })
+        .into(),
+        property(velocity, class, |d| {
+            d.constraint_data.as_ref().map(|c| c.velocity)
+        })
+        .set(|d, velocity| {
+            if let Some(constraint) = &mut d.constraint_data {
+                constraint.velocity = velocity;
+            }
+        })
+        .into(),
+        property(motor_max_force, class, |d| {
+            d.constraint_data.as_ref().map(|c| c.motor_max_force)
+        })
+        .set(|d, max_force: f32| {
+            if let Some(constraint) = &mut d.constraint_data {
+                constraint.motor_max_force = max_force.max(0.0);
+            }
+        })
+        .into(),
+        property(target, class, |d| {
+            d.constraint_data.as_ref().map(|c| c.target)
+        })
+        .set(|d, target| {
+            if let Some(constraint) = &mut d.constraint_data {
+                constraint.target = target;
+            }
+        })
+        .into(),
+        property(servo_max_force, class, |d| {
+            d.constraint_data.as_ref().map(|c| c.servo_max_force)
+        })
+        .set(|d, max_force: f32| {
+            if let Some(constraint) = &mut d.constraint_data {
+                constraint.servo_max_force = max_force.max(0.0);
+            }
+        })
+        .into(),
+        property("LimitsEnabled", class, |d| {
+            d.constraint_data.as_ref().map(|c| c.limits_enabled)
+        })
+        .set(|d, limits_enabled| {
+            if let Some(constraint) = &mut d.constraint_data {
+                constraint.limits_enabled = limits_enabled;
+            }
+        })
+        .into(),
+        property(lower_limit, class, |d| {
+            d.constraint_data.as_ref().map(|c| c.lower_limit)
+        })
+        .set(|d, lower_limit| {
+            if let Some(constraint) = &mut d.constraint_data {
+                constraint.lower_limit = lower_limit;
+            }
+        })
+        .into(),
+        property(upper_limit, class, |d| {
+            d.constraint_data.as_ref().map(|c| c.upper_limit)
+        })
+        .set(|d, upper_limit| {
+            if let Some(constraint) = &mut d.constraint_data {
+                constraint.upper_limit = upper_limit;
+            }
+        })
+        .into(),
+    ]
+}
+
+/// Properties of the movers: LinearVelocity, VectorForce and AlignPosition
+fn mover_properties() -> Vec<PropertyDescriptor> {
+    vec![
+        property("VectorVelocity", "LinearVelocity", |d| {
+            d.constraint_data.as_ref().map(|c| c.vector_velocity)
+        })
+        .set(|d, vector_velocity| {
+            if let Some(constraint) = &mut d.constraint_data {
+                constraint.vector_velocity = vector_velocity;
+            }
+        })
+        .into(),
+        property("MaxForce", "LinearVelocity", |d| {
+            d.constraint_data.as_ref().map(|c| c.max_force)
+        })
+        .set(|d, max_force: f32| {
+            if let Some(constraint) = &mut d.constraint_data {
+                constraint.max_force = max_force.max(0.0);
+            }
+        })
+        .into(),
+        property("RelativeTo", "LinearVelocity", |d| {
+            d.constraint_data.as_ref().map(|c| c.relative_to)
+        })
+        .set(|d, relative_to| {
+            if let Some(constraint) = &mut d.constraint_data {
+                constraint.relative_to = relative_to;
+            }
+        })
+        .into(),
+        property("Force", "VectorForce", |d| {
+            d.constraint_data.as_ref().map(|c| c.force)
+        })
+        .set(|d, force| {
+            if let Some(constraint) = &mut d.constraint_data {
+                constraint.force = force;
+            }
+        })
+        .into(),
+        property("ApplyAtCenterOfMass", "VectorForce", |d| {
+            d.constraint_data.as_ref().map(|c| c.apply_at_center_of_mass)
+        })
+        .set(|d, apply_at_center_of_mass| {
+            if let Some(constraint) = &mut d.constraint_data {
+                constraint.apply_at_center_of_mass = apply_at_center_of_mass;
+            }
+        })
+        .into(),
+        property("RelativeTo", "VectorForce", |d| {
+            d.constraint_data.as_ref().map(|c| c.relative_to)
+        })
+        .set(|d, relative_to| {
+            if let Some(constraint) = &mut d.constraint_data {
+                constraint.relative_to = relative_to;
+            }
+        })
+        .into(),
+        property("Mode", "AlignPosition", |d| {
+            d.constraint_data.as_ref().map(|c| c.alignment_mode)
+        })
+        .set(|d, mode| {
+            if let Some(constraint) = &mut d.constraint_data {
+                constraint.alignment_mode = mode;
+            }
+        })
+        .into(),
+        property("Position", "AlignPosition", |d| {
+            d.constraint_data.as_ref().map(|c| c.position)
+        })
+        .set(|d, position| {
+            if let Some(constraint) = &mut d.constraint_data {
+                constraint.position = position;
+            }
+        })
+        .into(),
+        property("MaxForce", "AlignPosition", |d| {
+            d.constraint_data.as_ref().map(|c| c.max_force)
+        })
+        .set(|d, max_force: f32| {
+            if let Some(constraint) = &mut d.constraint_data {
+                constraint.max_force = max_force.max(0.0);
+            }
+        })
+        .into(),
+        property("MaxVelocity", "AlignPosition", |d| {
+            d.constraint_data.as_ref().map(|c| c.max_velocity)
+        })
+        .set(|d, max_velocity: f32| {
+            if let Some(constraint) = &mut d.constraint_data {
+                constraint.max_velocity = max_velocity.max(0.0);
+            }
+        })
+        .into(),
+        property("Responsiveness", "AlignPosition", |d| {
+            d.constraint_data.as_ref().map(|c| c.responsiveness)
+        })
+        .set(|d, responsiveness: f32| {
+            if let Some(constraint) = &mut d.constraint_data {
+                constraint.responsiveness = responsiveness.max(0.0);
+            }
+        })
+        .into(),
+        property("RigidityEnabled", "AlignPosition", |d| {
+            d.constraint_data.as_ref().map(|c| c.rigidity_enabled)
+        })
+        .set(|d, rigidity_enabled| {
+            if let Some(constraint) = &mut d.constraint_data {
+                constraint.rigidity_enabled = rigidity_enabled;
+            }
+        })
+        .into(),
+    ]
+}
+
+fn script_properties() -> Vec<PropertyDescriptor> {
+    vec![
+        property("Source", "LuaSourceContainer", |d| {
+            d.script_data.as_ref().map(|s| s.source.clone())
+        })
+        .set(|d, source| {
+            if let Some(script) = &mut d.script_data {
+                script.source = source;
+            }
+        })
+        .into(),
+        property("Disabled", "BaseScript", |d| {
+            d.script_data.as_ref().map(|s| s.disabled)
+        })
+        .set(|d, disabled| {
+            if let Some(script) = &mut d.script_data {
+                script.disabled = disabled;
+            }
+        })
+        .after_set(stop_if_disabled)
+        .into(),
+        property("Enabled", "BaseScript", |d| {
+            d.script_data.as_ref().map(|s| !s.disabled)
+        })
+        .set(|d, enabled: bool| {
+            if let Some(script) = &mut d.script_data {
+                script.disabled = !enabled;
+            }
+        })
+        .after_set(stop_if_disabled)
+        .into(),
+    ]
+}
+
+/// after_set of Disabled and Enabled
+fn stop_if_disabled(lua: &Lua, instance: &Instance) -> Result<()> {
+    instance.stop_if_disabled(lua);
+    Ok(())
+}
+
+fn billboard_gui_properties() -> Vec<PropertyDescriptor> {
+    vec![
+        property("Size", "BillboardGui", |d| {
+            d.billboard_gui_data.as_ref().map(|b| b.size)
+        })
+        .set(|d, size| {
+            if let Some(billboard) = &mut d.billboard_gui_data {
+                billboard.size = size;
+            }
+        })
+        .into(),
+        property("StudsOffset", "BillboardGui", |d| {
+            d.billboard_gui_data.as_ref().map(|b| b.studs_offset)
+        })
+        .set(|d, offset| {
+            if let Some(billboard) = &mut d.billboard_gui_data {
+                billboard.studs_offset = offset;
+            }
+        })
+        .replicated()
+        .into(),
+        property("AlwaysOnTop", "BillboardGui", |d| {
+            d.billboard_gui_data.as_ref().map(|b| b.always_on_top)
+        })
+        .set(|d, always_on_top| {
+            if let Some(billboard) = &mut d.billboard_gui_data {
+                billboard.always_on_top = always_on_top;
+            }
+        })
+        .replicated()
+        .into(),
+        property("Enabled", "BillboardGui", |d| {
+            d.billboard_gui_data.as_ref().map(|b| b.enabled)
+        })
+        .set(|d, enabled| {
+            if let Some(billboard) = &mut d.billboard_gui_data {
+                billboard.enabled = enabled;
+            }
+        })
+        .into(),
+        property("Adornee", "BillboardGui", |d| {
+            d.billboard_gui_data.as_ref().map(|b| b.adornee.clone())
+        })
+        .set(|d, part| {
+            if let Some(billboard) = &mut d.billboard_gui_data {
+                billboard.adornee = part;
+            }
+        })
+        .refers_to("BasePart")
+        .into(),
+    ]
+}
+
+/// Layout and appearance of 2D GUI, including ScreenGui
+fn gui_properties() -> Vec<PropertyDescriptor> {
+    vec![
+        property("Position", "GuiBase2d", |d| {
+            d.gui_data.as_ref().map(|g| g.position)
+        })
+        .set(|d, position| {
+            if let Some(gui) = &mut d.gui_data {
+                gui.position = position;
+            }
+        })
+        .replicated()
+        .into(),
+        property("Size", "GuiBase2d", |d| d.gui_data.as_ref().map(|g| g.size))
+            .set(|d, size| {
+                if let Some(gui) = &mut d.gui_data {
+                    gui.size = size;
+                }
+            })
+            .replicated()
+            .into(),
+        property("AnchorPoint", "GuiBase2d", |d| {
+            d.gui_data.as_ref().map(|g| g.anchor_point)
+        })
+        .set(|d, anchor_point| {
+            if let Some(gui) = &mut d.gui_data {
+                gui.anchor_point = anchor_point;
+            }
+        })
+        .replicated()
+        .into(),
+        // In degrees
+        property("Rotation", "GuiBase2d", |d| {
+            d.gui_data.as_ref().map(|g| g.rotation)
+        })
+        .set(|d, rotation| {
+            if let Some(gui) = &mut d.gui_data {
+                gui.rotation = rotation;
+            }
+        })
+        .replicated()
+        .into(),
+        property("ZIndex", "GuiBase2d", |d| {
+            d.gui_data.as_ref().map(|g| g.z_index)
+        })
+        .set(|d, z_index| {
+            if let Some(gui) = &mut d.gui_data {
+                gui.z_index = z_index;
+            }
+        })
+        .replicated()
+        .into(),
+        property("LayoutOrder", "GuiBase2d", |d| {
+            d.gui_data.as_ref().map(|g| g.layout_order)
+        })
+        .set(|d, layout_order| {
+            if let Some(gui) = &mut d.gui_data {
+                gui.layout_order = layout_order;
+            }
+        })
+        .into(),
+        property("Visible", "GuiBase2d", |d| {
+            d.gui_data.as_ref().map(|g| g.visible)
+        })
+        .set(|d, visible| {
+            if let Some(gui) = &mut d.gui_data {
+                gui.visible = visible;
+            }
+        })
+        .replicated()
+        .into(),
+        property("Enabled", "GuiBase2d", |d| {
+            d.gui_data.as_ref().map(|g| g.enabled)
+        })
+        .set(|d, enabled| {
+            if let Some(gui) = &mut d.gui_data {
+                gui.enabled = enabled;
+            }
+        })
+        .replicated()
+        .into(),
+        property("BackgroundColor3", "GuiBase2d", |d| {
+            d.gui_data.as_ref().map(|g| g.background_color)
+        })
+        .set(|d, color| {
+            if let Some(gui) = &mut d.gui_data {
+                gui.background_color = color;
+            }
+        })
+        .replicated()
+        .into(),
+        property("BackgroundTransparency", "GuiBase2d", |d| {
+            d.gui_data.as_ref().map(|g| g.background_transparency)
+        })
+        .set(|d, transparency: f32| {
+            if let Some(gui) = &mut d.gui_data {
+                gui.background_transparency = transparency.clamp(0.0, 1.0);
+            }
+        })
+        .replicated()
+        .into(),
+        property("BorderColor3", "GuiBase2d", |d| {
+            d.gui_data.as_ref().map(|g| g.border_color)
+        })
+        .set(|d, color| {
+            if let Some(gui) = &mut d.gui_data {
+                gui.border_color = color;
+            }
+        })
+        .replicated()
+        .into(),
+        property("BorderSizePixel", "GuiBase2d", |d| {
+            d.gui_data.as_ref().map(|g| g.border_size_pixel)
+        })
+        .set(|d, size: i32| {
+            if let Some(gui) = &mut d.gui_data {
+                gui.border_size_pixel = size.max(0);
+            }
+        })
+        .replicated()
+        .into(),
+        property("DisplayOrder", "LayerCollector", |d| {
+            d.gui_data.as_ref().map(|g| g.display_order)
+        })
+        .set(|d, order| {
+            if let Some(gui) = &mut d.gui_data {
+                gui.display_order = order;
+            }
+        })
+        .replicated()
+        .into(),
+        property("IgnoreGuiInset", "LayerCollector", |d| {
+            d.gui_data.as_ref().map(|g| g.ignore_gui_inset)
+        })
+        .set(|d, ignore| {
+            if let Some(gui) = &mut d.gui_data {
+                gui.ignore_gui_inset = ignore;
+            }
+        })
+        .into(),
+    ]
+}
+
+/// Text properties, shared by TextLabel and TextButton
+fn text_properties(class: &'static str) -> Vec<PropertyDescriptor> {
+    vec![
+        property("Text", class, |d| {
+            d.gui_data.as_ref().and_then(|g| g.text.clone())
+        })
+        .set(|d, text| {
+            if let Some(gui) = &mut d.gui_data {
+                gui.text = Some(text);
+            }
+        })
+        .replicated()
+        .into(),
+        property("TextColor3", class, |d| {
+            d.gui_data.as_ref().and_then(|g| g.text_color)
+        })
+        .set(|d, color| {
+            if let Some(gui) = &mut d.gui_data {
+                gui.text_color = Some(color);
+            }
+        })
+        .replicated()
+        .into(),
+        property("TextSize", class, |d| {
+            d.gui_data.as_ref().and_then(|g| g.text_size)
+        })
+        .set(|d, size: f32| {
+            if let Some(gui) = &mut d.gui_data {
+                gui.text_size = Some(size.max(1.0));
+            }
+        })
+        .replicated()
+        .into(),
+        property("TextTransparency", class, |d| {
+            d.gui_data.as_ref().and_then(|g| g.text_transparency)
+        })
+        .set(|d, transparency: f32| {
+            if let Some(gui) = &mut d.gui_data {
+                gui.text_transparency = Some(transparency.clamp(0.0, 1.0));
+            }
+        })
+        .replicated()
+        .into(),
+        property("TextScaled", class, |d| {
+            d.gui_data.as_ref().map(|g| g.text_scaled)
+        })
+        .set(|d, scaled| {
+            if let Some(gui) = &mut d.gui_data {
+                gui.text_scaled = scaled;
+            }
+        })
+        .replicated()
+        .into(),
+        property("TextXAlignment", class, |d| {
+            d.gui_data.as_ref().map(|g| g.text_x_alignment)
+        })
+        .set(|d, alignment| {
+            if let Some(gui) = &mut d.gui_data {
+                gui.text_x_alignment = alignment;
+            }
+        })
+        .lenient()
+        .replicated()
+        .into(),
+        property("TextYAlignment", class, |d| {
+            d.gui_data.as_ref().map(|g| g.text_y_alignment)
+        })
+        .set(|d, alignment| {
+            if let Some(gui) = &mut d.gui_data {
+                gui.text_y_alignment = alignment;
+            }
+        })
+        .lenient()
+        .replicated()
+        .into(),
+    ]
+}
+
+/// Image properties, shared by ImageLabel and ImageButton
+fn image_properties(class: &'static str) -> Vec<PropertyDescriptor> {
+    vec![
+        property("Image", class, |d| {
+            d.gui_data.as_ref().and_then(|g| g.image.clone())
+        })
+        .set(|d, image| {
+            if let Some(gui) = &mut d.gui_data {
+                gui.image = Some(image);
+            }
+        })
+        .replicated()
+        .into(),
+        property("ImageColor3", class, |d| {
+            d.gui_data.as_ref().and_then(|g| g.image_color)
+        })
+        .set(|d, color| {
+            if let Some(gui) = &mut d.gui_data {
+                gui.image_color = Some(color);
+            }
+        })
+        .replicated()
+        .into(),
+        property("ImageTransparency", class, |d| {
+            d.gui_data.as_ref().and_then(|g| g.image_transparency)
+        })
+        .set(|d, transparency: f32| {
+            if let Some(gui) = &mut d.gui_data {
+                gui.image_transparency = Some(transparency.clamp(0.0, 1.0));
+            }
+        })
+        .replicated()
+        .into(),
+    ]
+}
+
+fn events() -> Vec<EventDescriptor> {
+    let event = |name, class, params, get| EventDescriptor {
+        name,
+        class,
+        params,
+        get,
+    };
+    vec![
+        event("ChildAdded", "Instance", &[("child", "Instance")], |d| {
+            Some(d.child_added.clone())
+        }),
+        event("ChildRemoved", "Instance", &[("child", "Instance")], |d| {
+            Some(d.child_removed.clone())
+        }),
+        event("Destroying", "Instance", &[], |d| {
+            Some(d.destroying.clone())
+        }),
+        event(
+            "AttributeChanged",
+            "Instance",
+            &[("attribute", "string")],
+            |d| Some(d.attribute_changed.clone()),
+        ),
+        event("Changed", "Instance", &[("property", "string")], |d| {
+            Some(d.change_signals.changed())
+        }),
+        event("Touched", "BasePart", &[("otherPart", "BasePart")], |d| {
+            d.part_data.as_ref().map(|p| p.touched.clone())
+        }),
+        event(
+            "TouchEnded",
+            "BasePart",
+            &[("otherPart", "BasePart")],
+            |d| d.part_data.as_ref().map(|p| p.touch_ended.clone()),
+        ),
+        event("Died", "Humanoid", &[], |d| {
+            d.humanoid_data.as_ref().map(|h| h.died.clone())
+        }),
+        event("HealthChanged", "Humanoid", &[("health", "number")], |d| {
+            d.humanoid_data.as_ref().map(|h| h.health_changed.clone())
+        }),
+        event(
+            "MoveToFinished",
+            "Humanoid",
+            &[("reached", "boolean")],
+            |d| d.humanoid_data.as_ref().map(|h| h.move_to_finished.clone()),
+        ),
+        event(
+            "StateChanged",
+            "Humanoid",
+            &[("old", "EnumHumanoidStateType"), ("new", "EnumHumanoidStateType")],
+            |d| d.humanoid_data.as_ref().map(|h| h.state_changed.clone()),
+        ),
+        event("Running", "Humanoid", &[("speed", "number")], |d| {
+            d.humanoid_data.as_ref().map(|h| h.running.clone())
+        }),
+        event("Jumping", "Humanoid", &[("active", "boolean")], |d| {
+            d.humanoid_data.as_ref().map(|h| h.jumping.clone())
+        }),
+        event("FreeFalling", "Humanoid", &[("active", "boolean")], |d| {
+            d.humanoid_data.as_ref().map(|h| h.free_falling.clone())
+        }),
+        event("Landed", "Humanoid", &[], |d| {
+            d.humanoid_data.as_ref().map(|h| h.landed.clone())
+        }),
+        event("Swimming", "Humanoid", &[("speed", "number")], |d| {
+            d.humanoid_data.as_ref().map(|h| h.swimming.clone())
+        }),
+        event("CharacterAdded", "Player", &[("character", "Model")], |d| {
+            d.player_data.as_ref().map(|p| p.character_added.clone())
+        }),
+        event(
+            "CharacterRemoving",
+            "Player",
+            &[("character", "Model")],
+            |d| d.player_data.as_ref().map(|p| p.character_removing.clone()),
+        ),
+        event("MouseButton1Click", "GuiButton", &[], |d| {
+            d.gui_data
+                .as_ref()
+                .and_then(|g| g.mouse_button1_click.clone())
+        }),
+        event("MouseButton1Down", "GuiButton", &[], |d| {
+            d.gui_data
+                .as_ref()
+                .and_then(|g| g.mouse_button1_down.clone())
+        }),
+        event("MouseButton1Up", "GuiButton", &[], |d| {
+            d.gui_data.as_ref().and_then(|g| g.mouse_button1_up.clone())
+        }),
+        event("MouseEnter", "GuiButton", &[], |d| {
+            d.gui_data.as_ref().and_then(|g| g.mouse_enter.clone())
+        }),
+        event("MouseLeave", "GuiButton", &[], |d| {
+            d.gui_data.as_ref().and_then(|g| g.mouse_leave.clone())
+        }),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_a_follows_class_table() {
+        assert!(ClassName::Part.is_a("BasePart"));
+        assert!(ClassName::Part.is_a("Instance"));
+        assert!(ClassName::TextButton.is_a("GuiButton"));
+        assert!(ClassName::TextButton.is_a("GuiObject"));
+        assert!(ClassName::TextButton.is_a("GuiBase2d"));
+        assert!(ClassName::ScreenGui.is_a("LayerCollector"));
+        assert!(!ClassName::ScreenGui.is_a("GuiObject"));
+        assert!(ClassName::Script.is_a("BaseScript"));
+        assert!(ClassName::ModuleScript.is_a("LuaSourceContainer"));
+        assert!(!ClassName::ModuleScript.is_a("BaseScript"));
+        assert!(ClassName::Weld.is_a("JointInstance"));
+        assert!(!ClassName::Weld.is_a("Constraint"));
+        assert!(ClassName::HingeConstraint.is_a("Constraint"));
+        assert!(!ClassName::Folder.is_a("BasePart"));
+        assert!(!ClassName::Part.is_a("Unknown"));
+    }
+
+    #[test]
+    fn test_properties_read_on_new_instances() {
+        for &class in CREATABLE {
+            let data = InstanceData::for_class(class, class.as_str());
+            for property in properties_of(class) {
+                // Instance references start out nil
+                if matches!(property.value_type, PropertyType::Instance(_)) {
+                    continue;
+                }
+                assert!(
+                    property.get(&data).is_some(),
+                    "{}.{} has no value",
+                    class.as_str(),
+                    property.name
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_class_decides_property_type() {
+        let part_size = find_property(ClassName::Part, "Size").unwrap();
+        assert_eq!(part_size.value_type, PropertyType::Vector3);
+        let frame_size = find_property(ClassName::Frame, "Size").unwrap();
+        assert_eq!(frame_size.value_type, PropertyType::UDim2);
+        assert!(find_property(ClassName::Frame, "Text").is_none());
+        assert!(find_property(ClassName::Player, "UserId")
+            .unwrap()
+            .read_only());
+        assert!(!find_property(ClassName::Part, "Parent").unwrap().cloned());
+        assert!(find_event(ClassName::ImageButton, "MouseButton1Click").is_some());
+        assert!(find_event(ClassName::Frame, "MouseButton1Click").is_none());
+    }
+
+    #[test]
+    fn test_replicated_properties() {
+        let part = InstanceData::for_class(ClassName::Part, "Part");
+        assert_eq!(replicated_properties(&part, &[]).len(), 7);
+        assert!(replicated_properties(
+            &part,
+            &[
+                "Position",
+                "CFrame",
+                "Size",
+                "Color",
+                "Material",
+                "Shape",
+                "Transparency"
+            ]
+        )
+        .is_empty());
+
+        let label = InstanceData::for_class(ClassName::TextLabel, "Label");
+        let properties = replicated_properties(&label, &[]);
+        assert_eq!(properties["Text"], serde_json::json!(""));
+        assert_eq!(properties["TextXAlignment"], serde_json::json!("Center"));
+        assert!(!properties.contains_key("LayoutOrder"));
+    }
+}
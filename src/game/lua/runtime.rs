@@ -1,18 +1,36 @@
 use mlua::{Lua, MultiValue, ObjectLike, RegistryKey, Result, Thread, ThreadStatus, UserData, UserDataMethods, Value};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
 use std::sync::{Arc, Mutex};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use uuid::Uuid;
 
+use crate::game::constants::physics as consts;
 use crate::game::instance::ErrorMode;
 
-use super::instance::{AttributeValue, Instance, InstanceData};
+use super::determinism::{ScriptClock, ScriptRng};
+use super::instance::{AttributeValue, ClassName, Instance, InstanceData};
+use super::modules::{install_modules, register_require, split_bundle};
+use super::scripts::{adopt_main_script, is_orphaned, register_script_tree, register_scripts, run_scripts};
+use super::services::agent_input::json_to_lua_value;
+use super::services::collection_service::{fire_pending, register_collection_service};
+use super::services::navmesh::AgentParams;
 use super::services::{
-    register_raycast_params, AgentInput, AgentInputService, DataStoreService, HttpService,
-    PlayersService, RunService, WorkspaceService,
+    register_overlap_params, register_raycast_params, AgentInput, AgentInputService, CollectionService, DataStoreService, HttpService,
+    PathfindingService, PhysicsService, PlayersService, RewardService, RunService, TweenService, WorkspaceService,
 };
-use super::types::register_all_types;
+use super::types::{register_all_types, PathWaypointAction, Vector3};
 use crate::game::async_bridge::AsyncBridge;
 
+/// A thread waiting on task.wait/task.delay, as saved in instance snapshots
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScheduledTask {
+    /// Line where the thread's body function is defined (None if unknown)
+    pub line: Option<i64>,
+    /// Seconds until the thread resumes
+    pub resume_in: f64,
+}
+
 /// A request to kick a player from the game
 #[derive(Debug, Clone)]
 pub struct KickRequest {
@@ -26,24 +44,55 @@ pub struct GameDataModel {
     pub run_service: RunService,
     pub agent_input_service: AgentInputService,
     pub data_store_service: DataStoreService,
+    pub reward_service: RewardService,
+    pub tween_service: TweenService,
+    pub collection_service: CollectionService,
+    pub physics_service: PhysicsService,
+    pub pathfinding_service: PathfindingService,
+    /// Holds the game's ModuleScripts (see `modules`)
+    pub server_script_service: Instance,
+    /// Launch parameters passed to GameInstance::reset, exposed as game.LaunchParams
+    pub launch_params: serde_json::Value,
     /// Queue of pending kick requests from Lua scripts
     pub kick_requests: Vec<KickRequest>,
 }
 
 const DEFAULT_PLAYER_MODEL_URL: &str = "/static/models/player.glb";
 
+/// Agent input that walks the player's character along a PathfindingService
+/// path to `{ "position": [x, y, z] }`. The engine starts the walk; games still
+/// get the input through InputReceived.
+pub const NAVIGATE_TO_INPUT: &str = "NavigateTo";
+
 impl GameDataModel {
     pub fn new(game_id: Uuid, async_bridge: Option<Arc<AsyncBridge>>) -> Self {
         Self::with_config(game_id, 100, async_bridge)
     }
 
     pub fn with_config(game_id: Uuid, max_players: u32, async_bridge: Option<Arc<AsyncBridge>>) -> Self {
+        let physics_service = PhysicsService::new();
+        let workspace = WorkspaceService::new(physics_service.collision_groups.clone());
+        let players = PlayersService::with_max_players(max_players);
+        let server_script_service = Instance::new(ClassName::ServerScriptService, "ServerScriptService");
+        let collection_service = CollectionService::new();
+        for root in [&workspace.instance, &players.instance, &server_script_service] {
+            collection_service.add_root(root);
+        }
+        let pathfinding_service = PathfindingService::new(workspace.instance.clone(), collection_service.index.clone());
+
         Self {
-            workspace: WorkspaceService::new(),
-            players: PlayersService::with_max_players(max_players),
+            workspace,
+            players,
             run_service: RunService::new(true),
             agent_input_service: AgentInputService::new(),
             data_store_service: DataStoreService::new(game_id, async_bridge),
+            reward_service: RewardService::new(),
+            tween_service: TweenService::new(),
+            collection_service,
+            physics_service,
+            pathfinding_service,
+            server_script_service,
+            launch_params: serde_json::Value::Null,
             kick_requests: Vec::new(),
         }
     }
@@ -85,6 +134,34 @@ impl Game {
         self.data_model.lock().unwrap().data_store_service.clone()
     }
 
+    pub fn reward_service(&self) -> RewardService {
+        self.data_model.lock().unwrap().reward_service.clone()
+    }
+
+    pub fn tween_service(&self) -> TweenService {
+        self.data_model.lock().unwrap().tween_service.clone()
+    }
+
+    pub fn collection_service(&self) -> CollectionService {
+        self.data_model.lock().unwrap().collection_service.clone()
+    }
+
+    pub fn physics_service(&self) -> PhysicsService {
+        self.data_model.lock().unwrap().physics_service.clone()
+    }
+
+    pub fn pathfinding_service(&self) -> PathfindingService {
+        self.data_model.lock().unwrap().pathfinding_service.clone()
+    }
+
+    pub fn server_script_service(&self) -> Instance {
+        self.data_model.lock().unwrap().server_script_service.clone()
+    }
+
+    pub fn set_launch_params(&self, params: serde_json::Value) {
+        self.data_model.lock().unwrap().launch_params = params;
+    }
+
     /// Queue a kick request for a player (called from Lua Player:Kick())
     pub fn queue_kick(&self, user_id: u64, message: Option<String>) {
         self.data_model
@@ -114,6 +191,16 @@ impl UserData for Game {
                 "DataStoreService" => Ok(Value::UserData(
                     lua.create_userdata(dm.data_store_service.clone())?,
                 )),
+                "RewardService" => Ok(Value::UserData(lua.create_userdata(dm.reward_service.clone())?)),
+                "TweenService" => Ok(Value::UserData(lua.create_userdata(dm.tween_service.clone())?)),
+                "CollectionService" => Ok(Value::UserData(
+                    lua.create_userdata(dm.collection_service.clone())?,
+                )),
+                "PhysicsService" => Ok(Value::UserData(lua.create_userdata(dm.physics_service.clone())?)),
+                "PathfindingService" => Ok(Value::UserData(
+                    lua.create_userdata(dm.pathfinding_service.clone())?,
+                )),
+                "ServerScriptService" => Ok(Value::UserData(lua.create_userdata(dm.server_script_service.clone())?)),
                 "HttpService" => {
                     drop(dm); // Release lock before creating userdata
                     Ok(Value::UserData(lua.create_userdata(HttpService::new())?))
@@ -127,12 +214,101 @@ impl UserData for Game {
             match key.as_str() {
                 "Workspace" => Ok(Value::UserData(lua.create_userdata(dm.workspace.clone())?)),
                 "Players" => Ok(Value::UserData(lua.create_userdata(dm.players.clone())?)),
+                "ServerScriptService" => Ok(Value::UserData(lua.create_userdata(dm.server_script_service.clone())?)),
+                "LaunchParams" => match &dm.launch_params {
+                    serde_json::Value::Null => Ok(Value::Table(lua.create_table()?)),
+                    params => json_to_lua_value(lua, params),
+                },
                 _ => Ok(Value::Nil),
             }
         });
     }
 }
 
+/// Resource limits applied to a game's Luau VM.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScriptLimits {
+    /// Maximum bytes the VM may allocate (0 = unlimited)
+    pub memory_limit: usize,
+    /// Wall-clock time Lua code may run during a single tick
+    pub tick_budget: Duration,
+    /// Wall-clock time the top-level script may run while loading
+    pub load_budget: Duration,
+}
+
+impl Default for ScriptLimits {
+    fn default() -> Self {
+        Self {
+            memory_limit: 256 * 1024 * 1024,
+            tick_budget: Duration::from_millis(250),
+            load_budget: Duration::from_secs(5),
+        }
+    }
+}
+
+impl ScriptLimits {
+    /// Reads overrides from `CLAWBLOX_LUA_MEMORY_MB`, `CLAWBLOX_LUA_TICK_BUDGET_MS`
+    /// and `CLAWBLOX_LUA_LOAD_BUDGET_MS`, falling back to the defaults.
+    pub fn from_env() -> Self {
+        fn var(name: &str) -> Option<u64> {
+            std::env::var(name).ok().and_then(|v| v.trim().parse().ok())
+        }
+
+        let defaults = Self::default();
+        Self {
+            memory_limit: var("CLAWBLOX_LUA_MEMORY_MB")
+                .map(|mb| mb as usize * 1024 * 1024)
+                .unwrap_or(defaults.memory_limit),
+            tick_budget: var("CLAWBLOX_LUA_TICK_BUDGET_MS")
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.tick_budget),
+            load_budget: var("CLAWBLOX_LUA_LOAD_BUDGET_MS")
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.load_budget),
+        }
+    }
+}
+
+/// Deadline checked by the VM interrupt callback while Lua code runs.
+#[derive(Clone, Default)]
+struct ExecutionBudget {
+    /// (deadline, budget length) of the active budget, if any
+    active: Arc<Mutex<Option<(Instant, Duration)>>>,
+}
+
+impl ExecutionBudget {
+    /// Called from the Luau interrupt; raises a Lua error once the deadline has passed.
+    /// The deadline stays expired, so a `pcall` around the loop cannot swallow it for long.
+    fn check(&self) -> Result<mlua::VmState> {
+        if let Some((deadline, budget)) = *self.active.lock().unwrap() {
+            if Instant::now() >= deadline {
+                return Err(mlua::Error::runtime(format!(
+                    "script exceeded its execution budget of {} ms",
+                    budget.as_millis()
+                )));
+            }
+        }
+        Ok(mlua::VmState::Continue)
+    }
+}
+
+/// Keeps an execution budget active until dropped.
+///
+/// Guards nest: only the outermost guard sets and clears the deadline, so an
+/// inner call (e.g. PlayerAdded fired mid-tick) shares the tick's budget.
+pub struct BudgetGuard {
+    budget: ExecutionBudget,
+    armed: bool,
+}
+
+impl Drop for BudgetGuard {
+    fn drop(&mut self) {
+        if self.armed {
+            *self.budget.active.lock().unwrap() = None;
+        }
+    }
+}
+
 pub struct LuaRuntime {
     lua: Lua,
     game: Game,
@@ -140,8 +316,13 @@ pub struct LuaRuntime {
     /// Tracks yielded coroutines that need to be resumed (e.g., callbacks waiting on DataStore)
     /// Stored as RegistryKeys to prevent garbage collection
     pending_coroutines: Arc<Mutex<Vec<RegistryKey>>>,
-    /// Time when the runtime was created, used for task scheduling
-    start_time: Instant,
+    /// Time source for tick() and task scheduling
+    clock: ScriptClock,
+    /// Random source for math.random, Random and GenerateGUID
+    rng: ScriptRng,
+    /// Deadline enforced by the interrupt callback
+    budget: ExecutionBudget,
+    limits: ScriptLimits,
 }
 
 impl LuaRuntime {
@@ -164,11 +345,103 @@ impl LuaRuntime {
     pub fn with_config(game_id: Uuid, max_players: u32, async_bridge: Option<Arc<AsyncBridge>>) -> Result<Self> {
         let lua = Lua::new();
 
+        // Time origin for tick() and task scheduling; switched to simulated time by set_seed
+        let clock = ScriptClock::wall();
+        let rng = ScriptRng::from_entropy();
+        lua.set_app_data(rng.clone());
+
+        // Builtin libraries are frozen by the sandbox, so patch them first
+        let math_table = lua.globals().get::<mlua::Table>("math")?;
+        math_table.set("huge", f64::INFINITY)?;
+
+        let random_rng = rng.clone();
+        let random_fn =
+            lua.create_function(move |_, (min, max): (Option<i64>, Option<i64>)| match (min, max) {
+                (None, None) => Ok(random_rng.next_f64()),
+                (Some(max), None) => Ok((random_rng.next_f64() * max as f64).floor()),
+                (Some(min), Some(max)) => {
+                    let range = (max - min + 1) as f64;
+                    Ok((random_rng.next_f64() * range).floor() + min as f64)
+                }
+                _ => Ok(0.0),
+            })?;
+        math_table.set("random", random_fn)?;
+
+        let os_table = lua.globals().get::<mlua::Table>("os")?;
+        let clock_clock = clock.clone();
+        os_table.set("clock", lua.create_function(move |_, ()| Ok(clock_clock.now()))?)?;
+
+        // os.time() and os.date() without a time read the simulated clock when there is one
+        let os_time: mlua::Function = os_table.get("time")?;
+        let time_clock = clock.clone();
+        os_table.set(
+            "time",
+            lua.create_function(move |_, args: mlua::MultiValue| -> Result<Value> {
+                match time_clock.simulated_unix_time() {
+                    Some(now) if args.iter().all(Value::is_nil) => Ok(Value::Number(now)),
+                    _ => os_time.call(args),
+                }
+            })?,
+        )?;
+        let os_date: mlua::Function = os_table.get("date")?;
+        let date_clock = clock.clone();
+        os_table.set(
+            "date",
+            lua.create_function(move |_, (format, time): (Option<String>, Option<f64>)| -> Result<Value> {
+                let time = time.or_else(|| date_clock.simulated_unix_time());
+                os_date.call((format.unwrap_or_else(|| "%c".to_string()), time))
+            })?,
+        )?;
+
+        let table_table = lua.globals().get::<mlua::Table>("table")?;
+
+        let insert_fn = lua.create_function(
+            |_, (tbl, pos_or_val, val): (mlua::Table, Value, Option<Value>)| {
+                match val {
+                    Some(v) => {
+                        let pos: i64 = match pos_or_val {
+                            Value::Number(n) => n as i64,
+                            Value::Integer(i) => i,
+                            _ => return Err(mlua::Error::runtime("Invalid position")),
+                        };
+                        tbl.raw_insert(pos, v)?;
+                    }
+                    None => {
+                        let len = tbl.raw_len();
+                        tbl.raw_insert(len as i64 + 1, pos_or_val)?;
+                    }
+                }
+                Ok(())
+            },
+        )?;
+        table_table.set("insert", insert_fn)?;
+
+        let remove_fn =
+            lua.create_function(|_, (tbl, pos): (mlua::Table, Option<i64>)| -> Result<Value> {
+                let pos = pos.unwrap_or(tbl.raw_len() as i64);
+                let val = tbl.raw_get(pos)?;
+                tbl.raw_remove(pos)?;
+                Ok(val)
+            })?;
+        table_table.set("remove", remove_fn)?;
+
+        // Sandbox user scripts: builtin libraries become read-only and globals
+        // are written to a per-VM environment table instead of the shared one
+        lua.sandbox(true)?;
+        let env = lua.globals();
+        env.set("_G", env.clone())?;
+
+        // Abort runaway scripts once the active execution budget runs out
+        let budget = ExecutionBudget::default();
+        let interrupt_budget = budget.clone();
+        lua.set_interrupt(move |_| interrupt_budget.check());
+
         register_all_types(&lua)?;
 
         super::instance::register_instance(&lua)?;
 
         register_raycast_params(&lua)?;
+        register_overlap_params(&lua)?;
 
         let game = Game::with_config(game_id, max_players, async_bridge);
         lua.globals().set("game", game.clone())?;
@@ -177,6 +450,9 @@ impl LuaRuntime {
 
         lua.globals().set("Workspace", game.workspace())?;
         lua.globals().set("Players", game.players())?;
+        register_require(&lua, game.server_script_service())?;
+        register_scripts(&lua);
+        register_collection_service(&lua, &game.collection_service());
 
         let print_fn = lua.create_function(|_, args: MultiValue| {
             let msg: Vec<String> = args
@@ -214,26 +490,9 @@ impl LuaRuntime {
         })?;
         lua.globals().set("warn", warn_fn)?;
 
-        let math_table = lua.globals().get::<mlua::Table>("math")?;
-        math_table.set("huge", f64::INFINITY)?;
-
-        let random_fn =
-            lua.create_function(|_, (min, max): (Option<i64>, Option<i64>)| match (min, max) {
-                (None, None) => Ok(rand::random::<f64>()),
-                (Some(max), None) => Ok((rand::random::<f64>() * max as f64).floor()),
-                (Some(min), Some(max)) => {
-                    let range = (max - min + 1) as f64;
-                    Ok((rand::random::<f64>() * range).floor() + min as f64)
-                }
-                _ => Ok(0.0),
-            })?;
-        math_table.set("random", random_fn)?;
-
-        // Time origin for tick() and task scheduling
-        let start_time = Instant::now();
-
         // Add tick() global - returns time since game start
-        let tick_fn = lua.create_function(move |_, ()| Ok(start_time.elapsed().as_secs_f64()))?;
+        let tick_clock = clock.clone();
+        let tick_fn = lua.create_function(move |_, ()| Ok(tick_clock.now()))?;
         lua.globals().set("tick", tick_fn)?;
 
         // Internal tables for task scheduling (not accessible to game scripts)
@@ -249,10 +508,10 @@ impl LuaRuntime {
 
         // --- task library ---
         // Rust helper: __clawblox_schedule_wait(seconds) — schedules current thread to resume after delay
-        let schedule_wait_start = start_time;
+        let schedule_wait_clock = clock.clone();
         let schedule_wait_fn = lua.create_function(move |lua, seconds: Option<f64>| {
             let seconds = seconds.unwrap_or(0.0).max(0.0);
-            let now = schedule_wait_start.elapsed().as_secs_f64();
+            let now = schedule_wait_clock.now();
             let resume_at = now + seconds;
             let thread = lua.current_thread();
             let schedule: mlua::Table = lua.globals().get("__clawblox_thread_schedule")?;
@@ -262,9 +521,9 @@ impl LuaRuntime {
         lua.globals().set("__clawblox_schedule_wait", schedule_wait_fn)?;
 
         // Rust helper: __clawblox_now() — returns elapsed seconds since start
-        let now_start = start_time;
+        let now_clock = clock.clone();
         let now_fn = lua.create_function(move |_, ()| {
-            Ok(now_start.elapsed().as_secs_f64())
+            Ok(now_clock.now())
         })?;
         lua.globals().set("__clawblox_now", now_fn)?;
 
@@ -272,6 +531,34 @@ impl LuaRuntime {
         lua.load(r#"
             task = {}
 
+            -- Body functions of threads that have not started yet (weak keys), so
+            -- snapshots can tell where a scheduled thread came from
+            __clawblox_thread_fns = setmetatable({}, { __mode = "k" })
+
+            -- A thread's body function (its outermost frame, or the function
+            -- it will run if it hasn't started)
+            function __clawblox_thread_fn(thread)
+                local fn = __clawblox_thread_fns[thread]
+                if fn == nil then
+                    local level = 1
+                    while debug.info(thread, level, "f") ~= nil do
+                        fn = debug.info(thread, level, "f")
+                        level += 1
+                    end
+                end
+                return fn
+            end
+
+            -- Line defining a thread's body function; pairs scheduled threads
+            -- across a snapshot restore
+            function __clawblox_thread_line(thread)
+                local fn = __clawblox_thread_fn(thread)
+                if fn == nil then
+                    return nil
+                end
+                return debug.info(fn, "l")
+            end
+
             function task.spawn(func_or_thread, ...)
                 local thread
                 if type(func_or_thread) == "thread" then
@@ -293,6 +580,7 @@ impl LuaRuntime {
 
             function task.delay(seconds, func, ...)
                 local thread = coroutine.create(func)
+                __clawblox_thread_fns[thread] = func
                 local schedule = __clawblox_thread_schedule
                 local args_tbl = __clawblox_thread_args
                 local now = __clawblox_now()
@@ -312,6 +600,7 @@ impl LuaRuntime {
                     thread = func_or_thread
                 else
                     thread = coroutine.create(func_or_thread)
+                    __clawblox_thread_fns[thread] = func_or_thread
                 end
                 -- Store args if any
                 local packed = table.pack(...)
@@ -342,38 +631,6 @@ impl LuaRuntime {
             end
         "#).exec()?;
 
-        let table_table = lua.globals().get::<mlua::Table>("table")?;
-
-        let insert_fn = lua.create_function(
-            |_, (tbl, pos_or_val, val): (mlua::Table, Value, Option<Value>)| {
-                match val {
-                    Some(v) => {
-                        let pos: i64 = match pos_or_val {
-                            Value::Number(n) => n as i64,
-                            Value::Integer(i) => i,
-                            _ => return Err(mlua::Error::runtime("Invalid position")),
-                        };
-                        tbl.raw_insert(pos, v)?;
-                    }
-                    None => {
-                        let len = tbl.raw_len();
-                        tbl.raw_insert(len as i64 + 1, pos_or_val)?;
-                    }
-                }
-                Ok(())
-            },
-        )?;
-        table_table.set("insert", insert_fn)?;
-
-        let remove_fn =
-            lua.create_function(|_, (tbl, pos): (mlua::Table, Option<i64>)| -> Result<Value> {
-                let pos = pos.unwrap_or(tbl.raw_len() as i64);
-                let val = tbl.raw_get(pos)?;
-                tbl.raw_remove(pos)?;
-                Ok(val)
-            })?;
-        table_table.set("remove", remove_fn)?;
-
         let pending_coroutines = Arc::new(Mutex::new(Vec::new()));
         let track_store = pending_coroutines.clone();
         let track_fn = lua.create_function(move |lua, thread: Thread| {
@@ -390,13 +647,157 @@ impl LuaRuntime {
             game,
             script_loaded: false,
             pending_coroutines,
-            start_time,
+            clock,
+            rng,
+            budget,
+            limits: ScriptLimits::default(),
         })
     }
 
+    /// Makes the VM deterministic: time advances only with tick(dt) and all
+    /// randomness derives from `seed`. Call before load_script.
+    pub fn set_seed(&self, seed: u64) {
+        self.set_simulated_time();
+        self.rng.reseed(seed);
+    }
+
+    /// Makes time advance only with tick(dt), without fixing randomness.
+    /// Used by lockstep instances. Call before load_script.
+    pub fn set_simulated_time(&self) {
+        self.clock.set_simulated();
+    }
+
+    /// Seconds since the runtime started (simulated when seeded)
+    pub fn now(&self) -> f64 {
+        self.clock.now()
+    }
+
+    /// Threads waiting on task.wait/task.delay, in scheduling order
+    pub fn scheduled_tasks(&self) -> Result<Vec<ScheduledTask>> {
+        let now = self.clock.now();
+        let schedule: mlua::Table = self.lua.globals().get("__clawblox_thread_schedule")?;
+        let thread_line: mlua::Function = self.lua.globals().get("__clawblox_thread_line")?;
+
+        let pending = self.pending_coroutines.lock().unwrap();
+        let mut tasks = Vec::new();
+        for key in pending.iter() {
+            let Ok(thread) = self.lua.registry_value::<Thread>(key) else {
+                continue;
+            };
+            let Some(resume_at) = schedule.get::<Option<f64>>(&thread)? else {
+                continue;
+            };
+            tasks.push(ScheduledTask {
+                line: thread_line.call(&thread)?,
+                resume_in: (resume_at - now).max(0.0),
+            });
+        }
+        Ok(tasks)
+    }
+
+    /// Moves the clock to `time` (seconds) and re-times this VM's timers from a
+    /// snapshot. Each scheduled thread takes the remaining delay of the next
+    /// snapshotted task whose body function starts on the same line; unmatched
+    /// timers keep their delay relative to the new time.
+    /// Returns the number of tasks matched.
+    pub fn restore_schedule(&self, time: f64, tasks: &[ScheduledTask]) -> Result<usize> {
+        let previous = self.clock.now();
+        self.clock.set_time(time);
+
+        let mut saved: HashMap<i64, VecDeque<f64>> = HashMap::new();
+        for task in tasks {
+            if let Some(line) = task.line {
+                saved.entry(line).or_default().push_back(task.resume_in);
+            }
+        }
+
+        let schedule: mlua::Table = self.lua.globals().get("__clawblox_thread_schedule")?;
+        let thread_line: mlua::Function = self.lua.globals().get("__clawblox_thread_line")?;
+
+        let pending = self.pending_coroutines.lock().unwrap();
+        let mut matched = 0;
+        for key in pending.iter() {
+            let Ok(thread) = self.lua.registry_value::<Thread>(key) else {
+                continue;
+            };
+            let Some(resume_at) = schedule.get::<Option<f64>>(&thread)? else {
+                continue;
+            };
+            let line: Option<i64> = thread_line.call(&thread)?;
+            let resume_in = match line.and_then(|line| saved.get_mut(&line)?.pop_front()) {
+                Some(resume_in) => {
+                    matched += 1;
+                    resume_in
+                }
+                None => resume_at - previous,
+            };
+            schedule.set(&thread, time + resume_in)?;
+        }
+        Ok(matched)
+    }
+
+    /// Applies memory and execution limits to this VM.
+    pub fn set_script_limits(&mut self, limits: ScriptLimits) -> Result<()> {
+        self.lua.set_memory_limit(limits.memory_limit)?;
+        self.limits = limits;
+        Ok(())
+    }
+
+    pub fn script_limits(&self) -> ScriptLimits {
+        self.limits
+    }
+
+    /// Starts the per-tick execution budget. Lua code running past it raises an error.
+    pub fn begin_tick_budget(&self) -> BudgetGuard {
+        self.begin_budget(self.limits.tick_budget)
+    }
+
+    fn begin_budget(&self, budget: Duration) -> BudgetGuard {
+        let mut active = self.budget.active.lock().unwrap();
+        let armed = active.is_none();
+        if armed {
+            *active = Some((Instant::now() + budget, budget));
+        }
+        BudgetGuard {
+            budget: self.budget.clone(),
+            armed,
+        }
+    }
+
+    /// Compiles a script without running it, to report syntax errors before
+    /// swapping out a working VM. Bundled modules are compiled on their own,
+    /// as `require` would load them.
+    pub fn check_syntax(source: &str) -> Result<()> {
+        let lua = Lua::new();
+        let (main, modules) = split_bundle(source);
+        lua.load(main).into_function()?;
+        for module in modules {
+            lua.load(module.source)
+                .set_name(format!("={}", module.path))
+                .into_function()?;
+        }
+        Ok(())
+    }
+
     pub fn load_script(&mut self, source: &str) -> Result<()> {
+        let _budget = self.begin_budget(self.limits.load_budget);
+        let (main, modules) = split_bundle(source);
+        let server_script_service = self.game.server_script_service();
+        install_modules(&server_script_service, &modules);
+        register_script_tree(&self.lua, &server_script_service);
+
+        // The main script runs in the global environment, as ServerScriptService.Main
+        let mut main_data = InstanceData::new_script("Main");
+        if let Some(script) = &mut main_data.script_data {
+            script.source = main.to_string();
+        }
+        let main_script = Instance::from_data(main_data);
+        main_script.set_parent(Some(&server_script_service));
+        self.lua.globals().set("script", main_script.clone())?;
+        adopt_main_script(&self.lua, &main_script)?;
+
         // Run script in its own coroutine so task.wait() works at the top level
-        let func = self.lua.load(source).into_function()?;
+        let func = self.lua.load(main).into_function()?;
         let thread = self.lua.create_thread(func)?;
         match thread.resume::<()>(()) {
             Ok(()) => {
@@ -416,25 +817,44 @@ impl LuaRuntime {
             }
         }
         self.script_loaded = true;
-        Ok(())
+        self.run_scripts()
+    }
+
+    /// Starts Scripts that were enabled or parented into the game since the
+    /// last call and stops the ones that were removed (see `scripts`)
+    fn run_scripts(&self) -> Result<()> {
+        let roots = [self.game.workspace().instance, self.game.server_script_service()];
+        let yielded = run_scripts(&self.lua, &roots)?;
+        self.track_yielded_threads(yielded)
     }
 
     pub fn tick(&self, delta_time: f32) -> Result<()> {
+        self.clock.advance(delta_time as f64);
         if !self.script_loaded {
             return Ok(());
         }
+        let _budget = self.begin_tick_budget();
+
+        // 0. Start scripts parented since the last tick and fire tag signals
+        // for instances that entered or left the game outside Lua
+        self.run_scripts()?;
+        fire_pending(&self.lua)?;
 
         // 1. Resume pending coroutines (callbacks that yielded on DataStore operations, etc.)
         self.resume_pending_coroutines()?;
 
-        // 2. Fire Heartbeat as coroutines (allows callbacks to yield)
+        // 2. Advance tweens (fires Completed for the ones that finished)
+        let tween_threads = self.game.tween_service().step(&self.lua, delta_time)?;
+        self.track_yielded_threads(tween_threads)?;
+
+        // 3. Fire Heartbeat as coroutines (allows callbacks to yield)
         let heartbeat = self.game.run_service().heartbeat();
         let yielded_threads = heartbeat.fire_as_coroutines(
             &self.lua,
             MultiValue::from_iter([Value::Number(delta_time as f64)]),
         )?;
 
-        // 3. Track any newly yielded coroutines for resumption on next tick
+        // 4. Track any newly yielded coroutines for resumption on next tick
         self.track_yielded_threads(yielded_threads)?;
 
         Ok(())
@@ -443,7 +863,7 @@ impl LuaRuntime {
     /// Resumes all pending coroutines and removes completed ones.
     /// Checks cancelled threads, scheduled times, and thread args before resuming.
     fn resume_pending_coroutines(&self) -> Result<()> {
-        let now = self.start_time.elapsed().as_secs_f64();
+        let now = self.clock.now();
 
         // Get internal tables
         let schedule: mlua::Table = self.lua.globals().get("__clawblox_thread_schedule")?;
@@ -499,6 +919,13 @@ impl LuaRuntime {
                 schedule.set(thread.clone(), Value::Nil)?;
             }
 
+            // Threads of stopped scripts are dropped instead of resumed
+            if is_orphaned(&self.lua, &thread)? {
+                args_table.set(thread.clone(), Value::Nil)?;
+                let _ = self.lua.remove_registry_value(key);
+                continue;
+            }
+
             // 3. Determine resume args
             let resume_args: MultiValue = {
                 let stored_args: Value = args_table.get(thread.clone())?;
@@ -667,6 +1094,18 @@ impl LuaRuntime {
         self.game.agent_input_service()
     }
 
+    pub fn reward_service(&self) -> RewardService {
+        self.game.reward_service()
+    }
+
+    pub fn physics_service(&self) -> PhysicsService {
+        self.game.physics_service()
+    }
+
+    pub fn pathfinding_service(&self) -> PathfindingService {
+        self.game.pathfinding_service()
+    }
+
     pub fn game(&self) -> &Game {
         &self.game
     }
@@ -678,6 +1117,7 @@ impl LuaRuntime {
 
     /// Process pending agent inputs by firing InputReceived events
     pub fn process_agent_inputs(&self) -> Result<()> {
+        let _budget = self.begin_tick_budget();
         let agent_input_service = self.agent_input_service();
 
         // 1. Collect all (player, inputs) pairs WITHOUT holding locks during Lua calls
@@ -705,6 +1145,9 @@ impl LuaRuntime {
                 }
             }
             for input in inputs {
+                if input.input_type == NAVIGATE_TO_INPUT {
+                    self.navigate_to(&player, &input.data);
+                }
                 agent_input_service.fire_input_received(
                     &self.lua,
                     &player,
@@ -717,11 +1160,73 @@ impl LuaRuntime {
         Ok(())
     }
 
+    /// Starts the player's character along a path to the NavigateTo input's position.
+    /// The game loop walks it from waypoint to waypoint; a MoveTo or CancelMoveTo stops it.
+    fn navigate_to(&self, player: &Instance, data: &serde_json::Value) {
+        let name = player.name();
+        let goal = data.get("position").and_then(|p| p.as_array()).and_then(|p| match p.as_slice() {
+            [x, y, z] => Some(Vector3::new(x.as_f64()? as f32, y.as_f64()? as f32, z.as_f64()? as f32)),
+            _ => None,
+        });
+        let Some(goal) = goal else {
+            eprintln!("[NavigateTo WARN] Missing or invalid position for {}", name);
+            return;
+        };
+        let character = {
+            let data = player.data.lock().unwrap();
+            data.player_data
+                .as_ref()
+                .and_then(|pd| pd.character.as_ref())
+                .and_then(|character| character.upgrade())
+                .map(Instance::from_ref)
+        };
+        let parts = character.and_then(|character| {
+            Some((
+                character.find_first_child("HumanoidRootPart", false)?,
+                character.find_first_child_of_class("Humanoid")?,
+            ))
+        });
+        let Some((root, humanoid)) = parts else {
+            eprintln!("[NavigateTo WARN] Missing character for {}", name);
+            return;
+        };
+        let Some(start) = root.data.lock().unwrap().part_data.as_ref().map(|part| part.position) else {
+            return;
+        };
+
+        let agent = AgentParams {
+            radius: consts::PLAYER_CHARACTER_RADIUS,
+            height: consts::PLAYER_CHARACTER_HEIGHT,
+            can_jump: true,
+        };
+        // Only the corners matter to the character controller
+        let Some(waypoints) = self.pathfinding_service().find_path(agent, start, goal, f32::INFINITY) else {
+            eprintln!(
+                "[NavigateTo] No path for {} to ({:.1},{:.1},{:.1})",
+                name, goal.x, goal.y, goal.z
+            );
+            return;
+        };
+
+        let mut data = humanoid.data.lock().unwrap();
+        let Some(humanoid) = &mut data.humanoid_data else {
+            return;
+        };
+        // The first waypoint is where the character already is
+        let mut waypoints: VecDeque<_> = waypoints.into_iter().skip(1).collect();
+        if let Some(first) = waypoints.pop_front() {
+            humanoid.move_to_target = Some(first.position);
+            humanoid.jump_requested |= first.action == PathWaypointAction::Jump;
+        }
+        humanoid.path_waypoints = waypoints;
+    }
+
     pub fn lua(&self) -> &Lua {
         &self.lua
     }
 
     pub fn fire_player_added(&self, player: &Instance) -> Result<()> {
+        let _budget = self.begin_tick_budget();
         let signal = self.game.players().data.lock().unwrap().player_added.clone();
         // Use fire_as_coroutines to allow callbacks to yield (e.g., for DataStore:GetAsync)
         let yielded_threads = signal.fire_as_coroutines(
@@ -734,6 +1239,7 @@ impl LuaRuntime {
     }
 
     pub fn fire_player_removing(&self, player: &Instance) -> Result<()> {
+        let _budget = self.begin_tick_budget();
         let signal = self
             .game
             .players()
@@ -755,6 +1261,7 @@ impl LuaRuntime {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::game::lua::modules::bundle;
 
     /// Helper to create a test runtime without async bridge
     fn test_runtime() -> LuaRuntime {
@@ -786,6 +1293,221 @@ mod tests {
         assert_eq!(children[0].name(), "TestPart");
     }
 
+    #[test]
+    fn test_sandboxed_globals() {
+        let mut runtime = test_runtime();
+        runtime
+            .load_script(
+                r#"
+            _G.shared = 1
+            counter = 2
+            _G.stringFrozen = not pcall(function() string.upper = nil end)
+            _G.mathFrozen = not pcall(function() math.floor = nil end)
+        "#,
+            )
+            .expect("Failed to load script");
+
+        let globals = runtime.lua().globals();
+        assert_eq!(globals.get::<i64>("shared").unwrap(), 1);
+        assert_eq!(globals.get::<i64>("counter").unwrap(), 2);
+        assert!(globals.get::<bool>("stringFrozen").unwrap());
+        assert!(globals.get::<bool>("mathFrozen").unwrap());
+    }
+
+    #[test]
+    fn test_require_bundled_modules() {
+        let mut runtime = test_runtime();
+        let modules = vec![
+            (
+                "weapons.lua".to_string(),
+                r#"
+                loads = (loads or 0) + 1
+                local util = require(script.Parent.lib.util)
+                return { damage = util.double(5), loads = loads, name = script.Name }
+                "#
+                .to_string(),
+            ),
+            ("lib/util.lua".to_string(), "return { double = function(x) return x * 2 end }".to_string()),
+        ];
+        let source = bundle(
+            r#"
+            local a = require("weapons")
+            local b = require(game:GetService("ServerScriptService").weapons)
+            _G.damage = a.damage
+            _G.sameTable = a == b
+            _G.loads = a.loads
+            _G.name = a.name
+            _G.utilParent = game.ServerScriptService.lib.util.Parent.Name
+            "#,
+            &modules,
+        );
+        runtime.load_script(&source).expect("Failed to load script");
+
+        let globals = runtime.lua().globals();
+        assert_eq!(globals.get::<i64>("damage").unwrap(), 10);
+        assert!(globals.get::<bool>("sameTable").unwrap(), "modules run once per VM");
+        assert_eq!(globals.get::<i64>("loads").unwrap(), 1);
+        assert_eq!(globals.get::<String>("name").unwrap(), "weapons");
+        assert_eq!(globals.get::<String>("utilParent").unwrap(), "lib");
+    }
+
+    #[test]
+    fn test_require_reports_cycles() {
+        let mut runtime = test_runtime();
+        let modules = vec![
+            ("a.lua".to_string(), "return require(\"b\")".to_string()),
+            ("b.lua".to_string(), "return require(\"a\")".to_string()),
+        ];
+        let source = bundle(
+            r#"
+            local ok, err = pcall(require, "a")
+            _G.err = tostring(err)
+            local module = Instance.new("ModuleScript")
+            module.Source = "return 42"
+            _G.answer = require(module)
+            "#,
+            &modules,
+        );
+        runtime.load_script(&source).expect("Failed to load script");
+
+        let globals = runtime.lua().globals();
+        let err = globals.get::<String>("err").unwrap();
+        assert!(
+            err.contains("ServerScriptService.a -> ServerScriptService.b -> ServerScriptService.a"),
+            "unexpected error: {}",
+            err
+        );
+        assert_eq!(globals.get::<i64>("answer").unwrap(), 42);
+    }
+
+    #[test]
+    fn test_cloned_scripts_run_independently() {
+        let mut runtime = test_runtime();
+        runtime
+            .load_script(
+                r#"
+            _G.mainName = script.Name .. " in " .. script.Parent.Name
+
+            local template = Instance.new("Model")
+            template.Name = "Spinner"
+            local behaviour = Instance.new("Script")
+            behaviour.Source = [[
+                local model = script.Parent
+                spins = 0
+                game:GetService("RunService").Heartbeat:Connect(function()
+                    spins += 1
+                    model:SetAttribute("Spins", spins)
+                end)
+                while true do
+                    model:SetAttribute("Waits", (model:GetAttribute("Waits") or 0) + 1)
+                    task.wait()
+                end
+            ]]
+            behaviour.Parent = template
+
+            for i = 1, 2 do
+                local spinner = template:Clone()
+                spinner.Name = "Spinner" .. i
+                spinner.Parent = Workspace
+            end
+        "#,
+            )
+            .expect("Failed to load script");
+        let globals = runtime.lua().globals();
+        assert_eq!(globals.get::<String>("mainName").unwrap(), "Main in ServerScriptService");
+
+        let spinner = |name: &str| runtime.workspace().instance.find_first_child(name, false).unwrap();
+        let attribute = |name: &str, attribute: &str| -> Option<f64> {
+            match spinner(name).get_attribute(attribute) {
+                Some(AttributeValue::Number(n)) => Some(n),
+                _ => None,
+            }
+        };
+
+        // Both clones started in the same tick; each counts in its own globals
+        for _ in 0..3 {
+            runtime.tick(1.0 / 60.0).unwrap();
+        }
+        assert_eq!(attribute("Spinner1", "Spins"), Some(3.0));
+        assert_eq!(attribute("Spinner2", "Spins"), Some(3.0));
+        let waits = attribute("Spinner1", "Waits").unwrap();
+
+        // Destroying a script stops its connections and its threads
+        spinner("Spinner1").find_first_child("Script", false).unwrap().destroy(runtime.lua()).unwrap();
+        for _ in 0..3 {
+            runtime.tick(1.0 / 60.0).unwrap();
+        }
+        assert_eq!(attribute("Spinner1", "Spins"), Some(3.0));
+        assert_eq!(attribute("Spinner1", "Waits"), Some(waits));
+        assert_eq!(attribute("Spinner2", "Spins"), Some(6.0));
+    }
+
+    #[test]
+    fn test_disabled_script_runs_once_enabled() {
+        let mut runtime = test_runtime();
+        runtime
+            .load_script(
+                r#"
+            local counter = Instance.new("Script")
+            counter.Disabled = true
+            counter.Source = "_G.runs = (_G.runs or 0) + 1"
+            counter.Parent = Workspace
+            _G.counter = counter
+        "#,
+            )
+            .expect("Failed to load script");
+        let globals = runtime.lua().globals();
+        runtime.tick(1.0 / 60.0).unwrap();
+        assert_eq!(globals.get::<Option<i64>>("runs").unwrap(), None);
+
+        runtime.lua().load("_G.counter.Disabled = false").exec().unwrap();
+        runtime.tick(1.0 / 60.0).unwrap();
+        runtime.tick(1.0 / 60.0).unwrap();
+        assert_eq!(globals.get::<Option<i64>>("runs").unwrap(), Some(1));
+
+        // Re-enabling restarts it
+        runtime.lua().load("_G.counter.Enabled = false; _G.counter.Enabled = true").exec().unwrap();
+        runtime.tick(1.0 / 60.0).unwrap();
+        assert_eq!(globals.get::<Option<i64>>("runs").unwrap(), Some(2));
+    }
+
+    #[test]
+    fn test_load_budget_interrupts_runaway_script() {
+        let mut runtime = test_runtime();
+        runtime
+            .set_script_limits(ScriptLimits {
+                load_budget: Duration::from_millis(50),
+                ..ScriptLimits::default()
+            })
+            .unwrap();
+
+        let err = runtime
+            .load_script("while true do end")
+            .expect_err("runaway script should be interrupted");
+        assert!(err.to_string().contains("execution budget"), "unexpected error: {}", err);
+    }
+
+    #[test]
+    fn test_memory_limit() {
+        let mut runtime = test_runtime();
+        runtime
+            .set_script_limits(ScriptLimits {
+                memory_limit: 16 * 1024 * 1024,
+                ..ScriptLimits::default()
+            })
+            .unwrap();
+
+        let result = runtime.load_script(
+            r#"
+            local t = {}
+            for i = 1, 1e8 do
+                t[i] = i
+            end
+        "#,
+        );
+        assert!(matches!(result, Err(mlua::Error::MemoryError(_))), "got {:?}", result);
+    }
+
     #[test]
     fn test_heartbeat() {
         let mut runtime = test_runtime();
@@ -814,6 +1536,590 @@ mod tests {
         assert_eq!(tick_count, 10);
     }
 
+    #[test]
+    fn test_tween_moves_part_and_completes() {
+        let mut runtime = test_runtime();
+        runtime
+            .load_script(
+                r#"
+            local TweenService = game:GetService("TweenService")
+            local part = Instance.new("Part")
+            part.Name = "Wave"
+            part.Anchored = true
+            part.Position = Vector3.new(0, 5, 0)
+            part.Parent = Workspace
+
+            _G.result = nil
+            local tween = TweenService:Create(part, TweenInfo.new(1, Enum.EasingStyle.Linear), {
+                Position = Vector3.new(100, 5, 0),
+                Transparency = 1,
+            })
+            tween.Completed:Connect(function(state)
+                _G.result = tostring(state)
+            end)
+            tween:Play()
+
+            local ok, err = pcall(function()
+                TweenService:Create(part, TweenInfo.new(), { Position = 5 })
+            end)
+            _G.mismatch = not ok and string.find(tostring(err), "Vector3") ~= nil
+        "#,
+            )
+            .expect("Failed to load script");
+
+        let part = runtime.workspace().instance.find_first_child("Wave", false).unwrap();
+        let position_x = || part.data.lock().unwrap().part_data.as_ref().unwrap().position.x;
+        let globals = runtime.lua().globals();
+        assert!(globals.get::<bool>("mismatch").unwrap());
+
+        for _ in 0..30 {
+            runtime.tick(1.0 / 60.0).expect("Failed to tick");
+        }
+        assert!((position_x() - 50.0).abs() < 0.5, "halfway at {}", position_x());
+        assert_eq!(globals.get::<Value>("result").unwrap(), Value::Nil);
+
+        for _ in 0..31 {
+            runtime.tick(1.0 / 60.0).expect("Failed to tick");
+        }
+        assert!((position_x() - 100.0).abs() < 1e-3);
+        assert_eq!(globals.get::<String>("result").unwrap(), "Enum.PlaybackState.Completed");
+    }
+
+    #[test]
+    fn test_tween_pause_and_cancel() {
+        let mut runtime = test_runtime();
+        runtime
+            .load_script(
+                r#"
+            local part = Instance.new("Part")
+            part.Transparency = 0
+            part.Parent = Workspace
+            _G.part = part
+            _G.states = {}
+            _G.tween = game:GetService("TweenService"):Create(
+                part,
+                TweenInfo.new(1, Enum.EasingStyle.Linear, Enum.EasingDirection.Out, -1, true),
+                { Transparency = 1 }
+            )
+            _G.tween.Completed:Connect(function(state)
+                table.insert(_G.states, tostring(state))
+            end)
+            _G.tween:Play()
+        "#,
+            )
+            .expect("Failed to load script");
+
+        let lua = runtime.lua();
+        let transparency = || lua.load("return _G.part.Transparency").eval::<f64>().unwrap();
+        for _ in 0..15 {
+            runtime.tick(1.0 / 60.0).unwrap();
+        }
+        let paused_at = transparency();
+        assert!((paused_at - 0.25).abs() < 0.02, "got {}", paused_at);
+
+        lua.load("_G.tween:Pause()").exec().unwrap();
+        for _ in 0..15 {
+            runtime.tick(1.0 / 60.0).unwrap();
+        }
+        assert_eq!(transparency(), paused_at);
+        let state: String = lua.load("return tostring(_G.tween.PlaybackState)").eval().unwrap();
+        assert_eq!(state, "Enum.PlaybackState.Paused");
+
+        // Repeats forever until cancelled; Cancel leaves the property where it is
+        lua.load("_G.tween:Play()").exec().unwrap();
+        for _ in 0..90 {
+            runtime.tick(1.0 / 60.0).unwrap();
+        }
+        lua.load("_G.tween:Cancel()").exec().unwrap();
+        let cancelled_at = transparency();
+        runtime.tick(1.0 / 60.0).unwrap();
+        assert_eq!(transparency(), cancelled_at);
+        let states: Vec<String> = lua
+            .load("return _G.states")
+            .eval::<mlua::Table>()
+            .unwrap()
+            .sequence_values()
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(states, vec!["Enum.PlaybackState.Cancelled"]);
+    }
+
+    #[test]
+    fn test_collection_service_tracks_tagged_instances() {
+        let mut runtime = test_runtime();
+        runtime
+            .load_script(
+                r#"
+            local CollectionService = game:GetService("CollectionService")
+            _G.events = {}
+            CollectionService:GetInstanceAddedSignal("Coin"):Connect(function(inst)
+                table.insert(_G.events, "added " .. inst.Name)
+            end)
+            CollectionService:GetInstanceRemovedSignal("Coin"):Connect(function(inst)
+                table.insert(_G.events, "removed " .. inst.Name)
+            end)
+
+            -- Tagged before entering the game: indexed once parented
+            local model = Instance.new("Model")
+            model.Name = "Coins"
+            for i = 1, 2 do
+                local coin = Instance.new("Part")
+                coin.Name = "Coin" .. i
+                coin:AddTag("Coin")
+                coin.Parent = model
+            end
+            _G.before = #CollectionService:GetTagged("Coin")
+            model.Parent = Workspace
+
+            -- Tagged while in the game
+            local extra = Instance.new("Part")
+            extra.Name = "Coin3"
+            extra.Parent = Workspace
+            CollectionService:AddTag(extra, "Coin")
+            _G.extra = extra
+        "#,
+            )
+            .expect("Failed to load script");
+
+        let lua = runtime.lua();
+        let names = || -> Vec<String> {
+            runtime
+                .game()
+                .collection_service()
+                .get_tagged("Coin")
+                .iter()
+                .map(|i| i.name())
+                .collect()
+        };
+        let events = || -> Vec<String> {
+            lua.load("return _G.events")
+                .eval::<mlua::Table>()
+                .unwrap()
+                .sequence_values()
+                .collect::<Result<_>>()
+                .unwrap()
+        };
+        assert_eq!(lua.globals().get::<i64>("before").unwrap(), 0);
+        assert_eq!(names(), vec!["Coin1", "Coin2", "Coin3"]);
+        assert_eq!(events(), vec!["added Coin1", "added Coin2", "added Coin3"]);
+
+        lua.load(
+            r#"
+            local coins = Workspace:FindFirstChild("Coins")
+            coins.Coin1:Destroy()
+            _G.extra:RemoveTag("Coin")
+            _G.hasTag = game:GetService("CollectionService"):HasTag(_G.extra, "Coin")
+            coins.Parent = nil
+        "#,
+        )
+        .exec()
+        .unwrap();
+        assert!(!lua.globals().get::<bool>("hasTag").unwrap());
+        assert!(names().is_empty());
+        assert_eq!(events()[3..], ["removed Coin1", "removed Coin3", "removed Coin2"]);
+    }
+
+    #[test]
+    fn test_property_and_attribute_changed_signals() {
+        let mut runtime = test_runtime();
+        runtime
+            .load_script(
+                r#"
+            local part = Instance.new("Part")
+            part.Parent = Workspace
+            _G.events = {}
+            local function log(text)
+                table.insert(_G.events, text)
+            end
+
+            part.Changed:Connect(function(property)
+                log("Changed " .. property)
+            end)
+            part:GetPropertyChangedSignal("Transparency"):Connect(function()
+                log("Transparency " .. part.Transparency)
+                -- Setting the same value again does not fire
+                part.Transparency = part.Transparency
+            end)
+            part:GetAttributeChangedSignal("Score"):Connect(function()
+                log("Score " .. part:GetAttribute("Score"))
+            end)
+            part.AttributeChanged:Connect(function(name)
+                log("AttributeChanged " .. name)
+            end)
+
+            part.Transparency = 0.5
+            part.Transparency = 0.5
+            part.Name = "Target"
+            part:SetAttribute("Score", 3)
+            part:SetAttribute("Score", 3)
+        "#,
+            )
+            .expect("Failed to load script");
+
+        let events: Vec<String> = runtime
+            .lua()
+            .load("return _G.events")
+            .eval::<mlua::Table>()
+            .unwrap()
+            .sequence_values()
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(
+            events,
+            vec![
+                "Transparency 0.5",
+                "Changed Transparency",
+                "Changed Name",
+                "Score 3",
+                "AttributeChanged Score",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_properties_follow_class() {
+        let mut runtime = test_runtime();
+        runtime
+            .load_script(
+                r#"
+            local part = Instance.new("Part")
+            part.Size = Vector3.new(2, 3, 4)
+            part.Transparency = 2
+            assert(part.Size.Y == 3 and part.Transparency == 1)
+
+            local humanoid = Instance.new("Humanoid")
+            humanoid.MaxHealth = 50
+            humanoid.Health = 80
+            assert(humanoid.Health == 50)
+
+            local label = Instance.new("TextLabel")
+            label.Size = UDim2.new(1, 0, 0, 20)
+            label.TextXAlignment = "Left"
+            label.AnchorPoint = { X = 0.5, Y = 1 }
+            assert(label.Size.Y.Offset == 20 and label.TextXAlignment == "Left")
+            assert(label.AnchorPoint.X == 0.5)
+
+            -- Alignments take Enum items too; other values leave them unchanged
+            label.TextXAlignment = Enum.TextXAlignment.Right
+            label.TextYAlignment = Enum.TextYAlignment.Bottom
+            assert(label.TextXAlignment == "Right" and label.TextYAlignment == "Bottom")
+            label.TextXAlignment = 5
+            label.TextYAlignment = Enum.Material.Plastic
+            assert(label.TextXAlignment == "Right" and label.TextYAlignment == "Bottom")
+            assert(tostring(Enum.TextYAlignment.Top) == "Enum.TextYAlignment.Top")
+
+            local billboard = Instance.new("BillboardGui")
+            billboard.Size = UDim2.new(0, 200, 0, 50)
+            billboard.Enabled = false
+            assert(billboard.Size.X.Offset == 200 and billboard.Enabled == false)
+
+            local weld = Instance.new("Weld")
+            weld.Enabled = false
+            assert(weld.Enabled == false)
+
+            local script = Instance.new("Script")
+            script.Enabled = false
+            assert(script.Disabled == true and script.Enabled == false)
+
+            -- A property the class lacks reads as a child of that name
+            local folder = Instance.new("Folder")
+            local child = Instance.new("Part", folder)
+            child.Name = "Size"
+            assert(folder.Size == child and label.Anchored == nil)
+
+            _G.typeError = tostring(select(2, pcall(function()
+                part.Size = "big"
+            end)))
+            _G.readOnlyError = tostring(select(2, pcall(function()
+                part.ClassName = "Model"
+            end)))
+            _G.signalError = tostring(select(2, pcall(function()
+                part:GetPropertyChangedSignal("Text")
+            end)))
+        "#,
+            )
+            .expect("Failed to load script");
+
+        let globals = runtime.lua().globals();
+        let type_error: String = globals.get("typeError").unwrap();
+        assert!(type_error.contains("Size: expected Vector3, got string"), "{type_error}");
+        let read_only_error: String = globals.get("readOnlyError").unwrap();
+        assert!(read_only_error.contains("ClassName"), "{read_only_error}");
+        let signal_error: String = globals.get("signalError").unwrap();
+        assert!(signal_error.contains("Text is not a valid property name"), "{signal_error}");
+    }
+
+    #[test]
+    fn test_clone_copies_properties_and_remaps_references() {
+        let mut runtime = test_runtime();
+        runtime
+            .load_script(
+                r#"
+            local model = Instance.new("Model")
+            model:SetAttribute("Team", "Red")
+            local root = Instance.new("Part", model)
+            root.Name = "Root"
+            root.Color = Color3.new(1, 0, 0)
+            local arm = Instance.new("Part", model)
+            arm.Name = "Arm"
+            model.PrimaryPart = root
+            local weld = Instance.new("Weld", model)
+            weld.Part0 = root
+            weld.Part1 = arm
+            weld.C0 = CFrame.new(0, 1, 0)
+            local humanoid = Instance.new("Humanoid", model)
+            humanoid.MaxHealth = 200
+            humanoid.Health = 150
+
+            _G.originalTouches = 0
+            root.Touched:Connect(function()
+                _G.originalTouches += 1
+            end)
+
+            local copy = model:Clone()
+            _G.copy = copy
+            _G.original = model
+        "#,
+            )
+            .expect("Failed to load script");
+
+        let lua = runtime.lua();
+        let check: bool = lua
+            .load(
+                r#"
+            local copy, model = _G.copy, _G.original
+            local root = copy:FindFirstChild("Root")
+            local weld = copy:FindFirstChildOfClass("Weld")
+            local humanoid = copy:FindFirstChildOfClass("Humanoid")
+            return copy.Parent == nil
+                and copy:GetAttribute("Team") == "Red"
+                and root ~= model:FindFirstChild("Root")
+                and root.Color.R == 1
+                and copy.PrimaryPart == root
+                and weld.Part0 == root
+                and weld.Part1 == copy:FindFirstChild("Arm")
+                and weld.C0.Position.Y == 1
+                and humanoid.MaxHealth == 200
+                and humanoid.Health == 150
+        "#,
+            )
+            .eval()
+            .unwrap();
+        assert!(check);
+
+        // The copy has its own signals, without the original's connections
+        let copy: Instance = lua.globals().get("copy").unwrap();
+        let root = copy.find_first_child("Root", false).unwrap();
+        let touched = root.data.lock().unwrap().part_data.as_ref().unwrap().touched.clone();
+        assert_eq!(touched.connection_count(), 0);
+    }
+
+    #[test]
+    fn test_attachments_and_constraints() {
+        let mut runtime = test_runtime();
+        runtime
+            .load_script(
+                r#"
+            local model = Instance.new("Model")
+            local base = Instance.new("Part", model)
+            base.Name = "Base"
+            base.Position = Vector3.new(0, 10, 0)
+            local arm = Instance.new("Part", model)
+            arm.Name = "Arm"
+            arm.CFrame = CFrame.new(0, 10, 4) * CFrame.Angles(math.rad(30), 0, 0)
+
+            local a0 = Instance.new("Attachment", base)
+            a0.Position = Vector3.new(0, 0, 1)
+            local a1 = Instance.new("Attachment", arm)
+            a1.Position = Vector3.new(0, 0, -3)
+
+            local hinge = Instance.new("HingeConstraint", model)
+            hinge.Attachment0 = a0
+            hinge.Attachment1 = a1
+            local rope = Instance.new("RopeConstraint", model)
+            rope.Attachment0 = a0
+            local prismatic = Instance.new("PrismaticConstraint")
+
+            assert(a0.WorldPosition == Vector3.new(0, 10, 1))
+            assert(hinge.Enabled and hinge.ActuatorType == Enum.ActuatorType.None)
+            assert(hinge.LowerAngle == -45 and hinge.UpperAngle == 45)
+            assert(prismatic.LowerLimit == -5 and prismatic.ActuatorType == Enum.ActuatorType.None)
+            assert(rope.Length == 5 and rope.CurrentDistance == 0)
+            assert(hinge:IsA("Constraint") and not hinge:IsA("JointInstance"))
+
+            hinge.ActuatorType = Enum.ActuatorType.Servo
+            hinge.TargetAngle = 90
+            rope.Attachment1 = a1
+            rope.Length = -1
+
+            _G.angle = hinge.CurrentAngle
+            _G.distance = rope.CurrentDistance
+            _G.ropeLength = rope.Length
+            _G.readOnlyError = tostring(select(2, pcall(function()
+                hinge.CurrentAngle = 10
+            end)))
+
+            local copy = model:Clone()
+            local copyHinge = copy:FindFirstChildOfClass("HingeConstraint")
+            _G.remapped = copyHinge.Attachment0 == copy:FindFirstChild("Base"):FindFirstChildOfClass("Attachment")
+                and copyHinge.Attachment0 ~= a0
+                and copyHinge.ActuatorType == Enum.ActuatorType.Servo
+                and copyHinge.TargetAngle == 90
+        "#,
+            )
+            .expect("Failed to load script");
+
+        let lua = runtime.lua();
+        let angle: f32 = lua.globals().get("angle").unwrap();
+        assert!((angle - 30.0).abs() < 0.01, "CurrentAngle {angle}");
+        // a1 sits 3 studs back along the arm's tilted Z axis, near a0
+        let distance: f32 = lua.globals().get("distance").unwrap();
+        let (sin, cos) = 30f32.to_radians().sin_cos();
+        let expected = ((3.0 * sin).powi(2) + (3.0 - 3.0 * cos).powi(2)).sqrt();
+        assert!((distance - expected).abs() < 0.01, "CurrentDistance {distance}, expected {expected}");
+        let rope_length: f32 = lua.globals().get("ropeLength").unwrap();
+        assert_eq!(rope_length, 0.0);
+        let read_only_error: String = lua.globals().get("readOnlyError").unwrap();
+        assert!(read_only_error.contains("CurrentAngle"), "{read_only_error}");
+        let remapped: bool = lua.globals().get("remapped").unwrap();
+        assert!(remapped);
+    }
+
+    #[test]
+    fn test_impulse_methods_and_movers() {
+        let mut runtime = test_runtime();
+        runtime
+            .load_script(
+                r#"
+            local part = Instance.new("Part")
+            part.Name = "Crate"
+            part.Position = Vector3.new(0, 5, 0)
+            part.Parent = Workspace
+
+            -- 4 x 1 x 2 block of Plastic, 0.7 per cubic stud
+            assert(math.abs(part:GetMass() - 5.6) < 1e-4)
+            part:ApplyImpulse(Vector3.new(0, 10, 0))
+            part:ApplyImpulseAtPosition(Vector3.new(0, 0, 4), Vector3.new(0, 6, 0))
+            part:ApplyAngularImpulse(Vector3.new(1, 0, 0))
+            part.AssemblyAngularVelocity = Vector3.new(0, 2, 0)
+
+            local linear = Instance.new("LinearVelocity")
+            local force = Instance.new("VectorForce")
+            local align = Instance.new("AlignPosition")
+            assert(linear:IsA("Constraint") and linear.Enabled)
+            assert(linear.MaxForce == 1000 and linear.RelativeTo == Enum.ActuatorRelativeTo.World)
+            assert(force.RelativeTo == Enum.ActuatorRelativeTo.Attachment0)
+            assert(not force.ApplyAtCenterOfMass and force.Force == Vector3.new(0, 0, 0))
+            assert(align.Mode == Enum.PositionAlignmentMode.TwoAttachment)
+            assert(align.MaxForce == 10000 and align.Responsiveness == 10)
+            assert(not align.RigidityEnabled)
+
+            align.MaxForce = -5
+            _G.maxForce = align.MaxForce
+            _G.mode = tostring(Enum.PositionAlignmentMode.OneAttachment)
+        "#,
+            )
+            .expect("Failed to load script");
+
+        let lua = runtime.lua();
+        let max_force: f32 = lua.globals().get("maxForce").unwrap();
+        assert_eq!(max_force, 0.0);
+        let mode: String = lua.globals().get("mode").unwrap();
+        assert_eq!(mode, "Enum.PositionAlignmentMode.OneAttachment");
+
+        // Impulses wait in the part for the next physics step
+        let part = runtime.workspace().instance.find_first_child("Crate", false).unwrap();
+        let data = part.data.lock().unwrap();
+        let part_data = data.part_data.as_ref().unwrap();
+        assert_eq!(part_data.impulse.to_array(), [0.0, 10.0, 4.0]);
+        // The off-center impulse 1 stud above the center also spins the part about X
+        assert_eq!(part_data.angular_impulse.to_array(), [5.0, 0.0, 0.0]);
+        assert!(part_data.velocity_dirty);
+    }
+
+    #[test]
+    fn test_physical_properties() {
+        let mut runtime = test_runtime();
+        runtime
+            .load_script(
+                r#"
+            local part = Instance.new("Part")
+            assert(part.CustomPhysicalProperties == nil and not part.Massless)
+            assert(part.CurrentPhysicalProperties == PhysicalProperties.new(Enum.Material.Plastic))
+
+            part.Material = Enum.Material.Ice
+            local ice = part.CurrentPhysicalProperties
+            assert(ice.Friction == PhysicalProperties.new(Enum.Material.Ice).Friction)
+            assert(ice.FrictionWeight == 1 and ice.ElasticityWeight == 1)
+
+            -- Out-of-range values are clamped
+            local bouncy = PhysicalProperties.new(2, 5, 3, 4)
+            assert(bouncy.Friction == 2 and bouncy.Elasticity == 1)
+            assert(bouncy.FrictionWeight == 4 and bouncy.ElasticityWeight == 1)
+
+            part.CustomPhysicalProperties = bouncy
+            assert(part.CurrentPhysicalProperties == bouncy)
+            assert(part:GetMass() == 16)
+            _G.text = tostring(part.CustomPhysicalProperties)
+
+            local copy = part:Clone()
+            assert(copy.CustomPhysicalProperties == bouncy)
+            part.CustomPhysicalProperties = nil
+            assert(part.CurrentPhysicalProperties.Density == ice.Density)
+
+            _G.badArgs = not pcall(PhysicalProperties.new, 1, "sticky", 0)
+        "#,
+            )
+            .expect("Failed to load script");
+
+        let lua = runtime.lua();
+        let text: String = lua.globals().get("text").unwrap();
+        assert_eq!(text, "2, 2, 1, 4, 1");
+        let bad_args: bool = lua.globals().get("badArgs").unwrap();
+        assert!(bad_args);
+    }
+
+    #[test]
+    fn test_collision_groups() {
+        let mut runtime = test_runtime();
+        runtime
+            .load_script(
+                r#"
+            local PhysicsService = game:GetService("PhysicsService")
+            PhysicsService:RegisterCollisionGroup("Ghosts")
+            PhysicsService:RegisterCollisionGroup("Walls")
+            assert(PhysicsService:IsCollisionGroupRegistered("Ghosts"))
+            assert(PhysicsService:CollisionGroupsAreCollidable("Ghosts", "Walls"))
+            PhysicsService:CollisionGroupSetCollidable("Ghosts", "Walls", false)
+            assert(not PhysicsService:CollisionGroupsAreCollidable("Walls", "Ghosts"))
+            assert(PhysicsService:CollisionGroupsAreCollidable("Default", "Walls"))
+            assert(#PhysicsService:GetRegisteredCollisionGroups() == 3)
+            assert(PhysicsService:GetMaxCollisionGroups() == 16)
+            assert(not pcall(function()
+                PhysicsService:CollisionGroupSetCollidable("Ghosts", "Missing", true)
+            end))
+
+            local wall = Instance.new("Part")
+            assert(wall.CollisionGroup == "Default")
+            wall.CollisionGroup = "Walls"
+            wall.Size = Vector3.new(1, 10, 10)
+            wall.Position = Vector3.new(5, 0, 0)
+            wall.Anchored = true
+            wall.Parent = Workspace
+
+            -- Raycasts only hit parts their group collides with
+            local params = RaycastParams.new()
+            assert(Workspace:Raycast(Vector3.new(0, 0, 0), Vector3.new(10, 0, 0), params) ~= nil)
+            params.CollisionGroup = "Ghosts"
+            _G.ghostHit = Workspace:Raycast(Vector3.new(0, 0, 0), Vector3.new(10, 0, 0), params) ~= nil
+        "#,
+            )
+            .expect("Failed to load script");
+
+        let ghost_hit: bool = runtime.lua().globals().get("ghostHit").unwrap();
+        assert!(!ghost_hit);
+    }
+
     #[test]
     fn test_tick_function() {
         let runtime = test_runtime();
@@ -0,0 +1,282 @@
+//! Script instances: Lua sources that run on their own while they are in the game.
+//!
+//! A Script starts when it is enabled and inside Workspace or
+//! ServerScriptService, checked at the start of each tick (so a script parented
+//! mid-tick starts on the next one, like Roblox's deferred start). Each run gets
+//! its own environment: `script` is the Script and globals it sets stay its
+//! own, falling back to the shared globals for reads. Cloning a model with a
+//! Script inside therefore starts an independent copy of its behaviour.
+//!
+//! A Script stops when it is destroyed, disabled or leaves those services. Its
+//! connections are disconnected right away and its threads are dropped the next
+//! time they would resume; threads are matched to the run that owns them
+//! through the environment of their body function.
+
+use mlua::{Function, Lua, RegistryKey, Result, Table, Thread, ThreadStatus, Value};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use super::events::RBXScriptConnection;
+use super::instance::{ClassName, Instance, InstanceId, WeakInstanceRef};
+use crate::game::instance::ErrorMode;
+
+/// A started Script
+struct RunningScript {
+    script: WeakInstanceRef,
+    /// Environment of this run, shared by every function defined in it
+    env: RegistryKey,
+    /// Connections made by this run, disconnected when it stops
+    connections: Vec<RBXScriptConnection>,
+}
+
+/// Script instances of one VM and the ones currently running
+#[derive(Default)]
+pub struct ScriptRunner {
+    scripts: Vec<WeakInstanceRef>,
+    running: HashMap<InstanceId, RunningScript>,
+    /// Set once any script has stopped, so resumes only pay for owner checks then
+    stopped_any: bool,
+}
+
+pub type ScriptRunnerHandle = Arc<Mutex<ScriptRunner>>;
+
+/// Installs the per-VM script runner. Called once when the runtime is created.
+pub fn register_scripts(lua: &Lua) {
+    lua.set_app_data(ScriptRunnerHandle::default());
+}
+
+fn runner(lua: &Lua) -> Option<ScriptRunnerHandle> {
+    lua.app_data_ref::<ScriptRunnerHandle>().map(|r| r.clone())
+}
+
+/// Makes the runner aware of every Script in `root`'s subtree (including
+/// `root`), so they start once they are enabled and inside the game
+pub fn register_script_tree(lua: &Lua, root: &Instance) {
+    let Some(runner) = runner(lua) else {
+        return;
+    };
+    let mut found = Vec::new();
+    collect_scripts(root, &mut found);
+    if !found.is_empty() {
+        runner.lock().unwrap().scripts.extend(found);
+    }
+}
+
+fn collect_scripts(instance: &Instance, found: &mut Vec<WeakInstanceRef>) {
+    if instance.class_name() == ClassName::Script {
+        found.push(Arc::downgrade(&instance.data));
+    }
+    for child in instance.get_children() {
+        collect_scripts(&child, found);
+    }
+}
+
+/// Registers the main script, which is already running in the global environment
+pub fn adopt_main_script(lua: &Lua, script: &Instance) -> Result<()> {
+    let Some(runner) = runner(lua) else {
+        return Ok(());
+    };
+    let env = lua.create_registry_value(lua.globals())?;
+    let mut runner = runner.lock().unwrap();
+    runner.scripts.push(Arc::downgrade(&script.data));
+    runner.running.insert(
+        script.id(),
+        RunningScript {
+            script: Arc::downgrade(&script.data),
+            env,
+            connections: Vec::new(),
+        },
+    );
+    Ok(())
+}
+
+/// Whether a script should be running: enabled, not destroyed and inside one of `roots`
+fn should_run(script: &Instance, roots: &[Instance]) -> bool {
+    {
+        let data = script.data.lock().unwrap();
+        if data.is_destroyed() || data.script_data.as_ref().is_none_or(|s| s.disabled) {
+            return false;
+        }
+    }
+    roots.iter().any(|root| script.is_descendant_of(root))
+}
+
+/// Starts the scripts that should run and stops the ones that no longer should.
+/// Errors from a starting script are returned in Halt mode and logged otherwise.
+pub fn run_scripts(lua: &Lua, roots: &[Instance]) -> Result<Vec<Thread>> {
+    let Some(runner) = runner(lua) else {
+        return Ok(Vec::new());
+    };
+
+    let mut to_start = Vec::new();
+    let mut to_stop = Vec::new();
+    {
+        let mut runner = runner.lock().unwrap();
+        runner.scripts.retain(|weak| weak.strong_count() > 0);
+        for weak in &runner.scripts {
+            let Some(data) = weak.upgrade() else { continue };
+            let script = Instance::from_ref(data);
+            let id = script.id();
+            match (should_run(&script, roots), runner.running.contains_key(&id)) {
+                (true, false) => to_start.push(script),
+                (false, true) => to_stop.push(id),
+                _ => {}
+            }
+        }
+        // Runs whose Script was dropped entirely
+        let orphaned: Vec<InstanceId> = runner
+            .running
+            .iter()
+            .filter(|(_, run)| run.script.strong_count() == 0)
+            .map(|(id, _)| *id)
+            .collect();
+        to_stop.extend(orphaned);
+    }
+
+    for id in to_stop {
+        stop_script(lua, id);
+    }
+
+    let mut yielded = Vec::new();
+    for script in to_start {
+        if let Some(thread) = start_script(lua, &runner, &script)? {
+            yielded.push(thread);
+        }
+    }
+    Ok(yielded)
+}
+
+/// Runs a Script in a fresh environment. Returns its thread if it yielded.
+fn start_script(lua: &Lua, runner: &ScriptRunnerHandle, script: &Instance) -> Result<Option<Thread>> {
+    let source = script
+        .data
+        .lock()
+        .unwrap()
+        .script_data
+        .as_ref()
+        .map(|s| s.source.clone())
+        .unwrap_or_default();
+    let name = script.full_name();
+
+    let env = lua.create_table()?;
+    let meta = lua.create_table()?;
+    meta.set("__index", lua.globals())?;
+    env.set_metatable(Some(meta))?;
+    env.raw_set("script", script.clone())?;
+
+    // Register before the first resume so connections made at the top level are tracked
+    runner.lock().unwrap().running.insert(
+        script.id(),
+        RunningScript {
+            script: Arc::downgrade(&script.data),
+            env: lua.create_registry_value(env.clone())?,
+            connections: Vec::new(),
+        },
+    );
+
+    let result = lua
+        .load(source)
+        .set_name(format!("={}", name))
+        .set_environment(env)
+        .into_function()
+        .and_then(|func| {
+            let thread = lua.create_thread(func)?;
+            let result = thread.resume::<()>(());
+            Ok((thread, result))
+        });
+
+    let error = match result {
+        Ok((thread, _)) if thread.status() == ThreadStatus::Resumable => return Ok(Some(thread)),
+        Ok((_, Ok(()))) => return Ok(None),
+        Ok((_, Err(e))) | Err(e) => e,
+    };
+    let error_mode = lua.app_data_ref::<ErrorMode>().map(|m| *m).unwrap_or(ErrorMode::Continue);
+    if error_mode == ErrorMode::Halt {
+        return Err(error);
+    }
+    eprintln!("[Lua Error] Script {}: {}", name, error);
+    Ok(None)
+}
+
+/// Stops a running script: disconnects its connections and orphans its threads
+pub fn stop_script(lua: &Lua, id: InstanceId) {
+    let Some(runner) = runner(lua) else {
+        return;
+    };
+    let stopped = {
+        let mut runner = runner.lock().unwrap();
+        let stopped = runner.running.remove(&id);
+        if stopped.is_some() {
+            runner.stopped_any = true;
+        }
+        stopped
+    };
+    if let Some(run) = stopped {
+        for connection in &run.connections {
+            connection.disconnect();
+        }
+        let _ = lua.remove_registry_value(run.env);
+    }
+}
+
+/// The Script whose run defined `func`, if it was defined by a Script
+fn owner(func: &Function) -> Option<(Instance, Table)> {
+    let env = func.environment()?;
+    match env.raw_get::<Value>("script").ok()? {
+        Value::UserData(ud) => {
+            let script = ud.borrow::<Instance>().ok()?.clone();
+            (script.class_name() == ClassName::Script).then_some((script, env))
+        }
+        _ => None,
+    }
+}
+
+/// Whether `env` is the environment of the current run of `script`
+fn is_current_run(lua: &Lua, runner: &ScriptRunner, script: &Instance, env: &Table) -> bool {
+    runner
+        .running
+        .get(&script.id())
+        .and_then(|run| lua.registry_value::<Table>(&run.env).ok())
+        .is_some_and(|current| current.to_pointer() == env.to_pointer())
+}
+
+/// Records a connection made by `callback` so it is disconnected when the
+/// script that defined the callback stops
+pub fn track_connection(lua: &Lua, callback: &Function, connection: &RBXScriptConnection) {
+    let Some(runner) = runner(lua) else {
+        return;
+    };
+    let Some((script, env)) = owner(callback) else {
+        return;
+    };
+    let mut runner = runner.lock().unwrap();
+    if !is_current_run(lua, &runner, &script, &env) {
+        return;
+    }
+    if let Some(run) = runner.running.get_mut(&script.id()) {
+        // Drop connections the script disconnected itself now and then
+        if run.connections.len() >= 64 && run.connections.len().is_power_of_two() {
+            run.connections.retain(|c| c.is_connected());
+        }
+        run.connections.push(connection.clone());
+    }
+}
+
+/// Whether `thread` belongs to a Script run that has stopped
+pub fn is_orphaned(lua: &Lua, thread: &Thread) -> Result<bool> {
+    let Some(runner) = runner(lua) else {
+        return Ok(false);
+    };
+    if !runner.lock().unwrap().stopped_any {
+        return Ok(false);
+    }
+    let thread_fn: Function = lua.globals().get("__clawblox_thread_fn")?;
+    let Some(func) = thread_fn.call::<Option<Function>>(thread)? else {
+        return Ok(false);
+    };
+    let Some((script, env)) = owner(&func) else {
+        return Ok(false);
+    };
+    let runner = runner.lock().unwrap();
+    Ok(!is_current_run(lua, &runner, &script, &env))
+}
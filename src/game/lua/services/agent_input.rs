@@ -166,7 +166,7 @@ impl AgentInputService {
 }
 
 /// Convert serde_json::Value to Lua Value
-fn json_to_lua_value(lua: &Lua, value: &serde_json::Value) -> mlua::Result<Value> {
+pub fn json_to_lua_value(lua: &Lua, value: &serde_json::Value) -> mlua::Result<Value> {
     match value {
         serde_json::Value::Null => Ok(Value::Nil),
         serde_json::Value::Bool(b) => Ok(Value::Boolean(*b)),
@@ -0,0 +1,192 @@
+//! CollectionService: looks up tagged instances through an index instead of
+//! walking the tree.
+//!
+//! The index holds the tagged instances that are in the game, i.e. below one
+//! of the service instances that carry it (Workspace, Players,
+//! ServerScriptService). `Instance::set_parent`, `add_tag` and `remove_tag`
+//! keep it current, so Destroy (which unparents) drops destroyed instances too.
+//! Added/removed signals are queued by those updates and fired by
+//! `fire_pending`, right after the Lua call that caused them or at the start of
+//! the next tick for changes made from Rust.
+
+use mlua::{Lua, MultiValue, Result, UserData, UserDataFields, UserDataMethods, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+
+use crate::game::lua::events::{create_signal, track_yielded_threads, RBXScriptSignal};
+use crate::game::lua::instance::{Instance, InstanceId, WeakInstanceRef};
+
+struct TagSignals {
+    added: RBXScriptSignal,
+    removed: RBXScriptSignal,
+}
+
+/// Tagged instances in the game, per tag
+#[derive(Default)]
+pub struct CollectionIndex {
+    /// Keyed by id, so GetTagged lists instances in creation order
+    tagged: HashMap<String, BTreeMap<InstanceId, WeakInstanceRef>>,
+    /// Created on first use by GetInstanceAdded/RemovedSignal
+    signals: HashMap<String, TagSignals>,
+    /// Signal firings waiting for `fire_pending`
+    pending: Vec<(RBXScriptSignal, Instance)>,
+}
+
+pub type CollectionIndexHandle = Arc<Mutex<CollectionIndex>>;
+
+impl CollectionIndex {
+    pub fn insert(&mut self, tag: &str, instance: &Instance) {
+        let entries = self.tagged.entry(tag.to_string()).or_default();
+        if entries.insert(instance.id(), instance.weak_ref()).is_none() {
+            if let Some(signals) = self.signals.get(tag) {
+                self.pending.push((signals.added.clone(), instance.clone()));
+            }
+        }
+    }
+
+    pub fn remove(&mut self, tag: &str, instance: &Instance) {
+        let Some(entries) = self.tagged.get_mut(tag) else {
+            return;
+        };
+        if entries.remove(&instance.id()).is_some() {
+            if entries.is_empty() {
+                self.tagged.remove(tag);
+            }
+            if let Some(signals) = self.signals.get(tag) {
+                self.pending.push((signals.removed.clone(), instance.clone()));
+            }
+        }
+    }
+
+    /// Indexes the tags of `root` and its descendants (it entered the game)
+    pub fn insert_tree(&mut self, root: &Instance) {
+        for instance in std::iter::once(root.clone()).chain(root.get_descendants()) {
+            for tag in sorted_tags(&instance) {
+                self.insert(&tag, &instance);
+            }
+        }
+    }
+
+    /// Drops the tags of `root` and its descendants (it left the game)
+    pub fn remove_tree(&mut self, root: &Instance) {
+        for instance in std::iter::once(root.clone()).chain(root.get_descendants()) {
+            for tag in sorted_tags(&instance) {
+                self.remove(&tag, &instance);
+            }
+        }
+    }
+
+    pub fn tagged(&self, tag: &str) -> Vec<Instance> {
+        self.tagged
+            .get(tag)
+            .map(|entries| {
+                entries
+                    .values()
+                    .filter_map(|weak| weak.upgrade())
+                    .map(Instance::from_ref)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn signals(&mut self, tag: &str) -> &TagSignals {
+        self.signals.entry(tag.to_string()).or_insert_with(|| TagSignals {
+            added: create_signal("InstanceAdded"),
+            removed: create_signal("InstanceRemoved"),
+        })
+    }
+}
+
+/// Tags in a stable order, so signals fire the same way on every run
+fn sorted_tags(instance: &Instance) -> Vec<String> {
+    let mut tags: Vec<String> = instance.get_tags().into_iter().collect();
+    tags.sort_unstable();
+    tags
+}
+
+#[derive(Clone, Default)]
+pub struct CollectionService {
+    pub index: CollectionIndexHandle,
+}
+
+impl CollectionService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Makes `root` carry the index, so its descendants count as in the game
+    pub fn add_root(&self, root: &Instance) {
+        root.data.lock().unwrap().collection = Some(self.index.clone());
+        self.index.lock().unwrap().insert_tree(root);
+    }
+
+    pub fn get_tagged(&self, tag: &str) -> Vec<Instance> {
+        self.index.lock().unwrap().tagged(tag)
+    }
+}
+
+/// Installs the game's index so Lua-side tag and parent changes can fire its signals
+pub fn register_collection_service(lua: &Lua, service: &CollectionService) {
+    lua.set_app_data(service.index.clone());
+}
+
+/// Fires the queued InstanceAdded/InstanceRemoved signals, including ones
+/// queued by the handlers themselves
+pub fn fire_pending(lua: &Lua) -> Result<()> {
+    let Some(index) = lua.app_data_ref::<CollectionIndexHandle>().map(|i| i.clone()) else {
+        return Ok(());
+    };
+    loop {
+        let pending = std::mem::take(&mut index.lock().unwrap().pending);
+        if pending.is_empty() {
+            return Ok(());
+        }
+        for (signal, instance) in pending {
+            let threads = signal.fire_as_coroutines(lua, MultiValue::from_iter([Value::UserData(
+                lua.create_userdata(instance)?,
+            )]))?;
+            track_yielded_threads(lua, threads)?;
+        }
+    }
+}
+
+impl UserData for CollectionService {
+    fn add_fields<F: UserDataFields<Self>>(fields: &mut F) {
+        fields.add_field_method_get("Name", |_, _| Ok("CollectionService".to_string()));
+        fields.add_field_method_get("ClassName", |_, _| Ok("CollectionService".to_string()));
+    }
+
+    fn add_methods<M: UserDataMethods<Self>>(methods: &mut M) {
+        methods.add_method("GetTagged", |_, this, tag: String| Ok(this.get_tagged(&tag)));
+
+        methods.add_method("GetInstanceAddedSignal", |_, this, tag: String| {
+            Ok(this.index.lock().unwrap().signals(&tag).added.clone())
+        });
+
+        methods.add_method("GetInstanceRemovedSignal", |_, this, tag: String| {
+            Ok(this.index.lock().unwrap().signals(&tag).removed.clone())
+        });
+
+        methods.add_method("AddTag", |lua, _, (instance, tag): (Instance, String)| {
+            instance.add_tag(&tag);
+            fire_pending(lua)
+        });
+
+        methods.add_method("RemoveTag", |lua, _, (instance, tag): (Instance, String)| {
+            instance.remove_tag(&tag);
+            fire_pending(lua)
+        });
+
+        methods.add_method("HasTag", |_, _, (instance, tag): (Instance, String)| {
+            Ok(instance.has_tag(&tag))
+        });
+
+        methods.add_method("GetTags", |_, _, instance: Instance| Ok(sorted_tags(&instance)));
+
+        methods.add_method("GetAllTags", |_, this, ()| {
+            let mut tags: Vec<String> = this.index.lock().unwrap().tagged.keys().cloned().collect();
+            tags.sort_unstable();
+            Ok(tags)
+        });
+    }
+}
@@ -1,5 +1,7 @@
 use mlua::{UserData, UserDataFields, UserDataMethods, Value, Result as LuaResult, Lua};
 
+use crate::game::lua::determinism::script_rng;
+
 /// HttpService provides JSON encoding/decoding utilities
 #[derive(Clone)]
 pub struct HttpService;
@@ -129,5 +131,14 @@ impl UserData for HttpService {
                 Err(_) => Ok(Value::Nil),
             }
         });
+
+        methods.add_method("GenerateGUID", |lua, _, wrap_in_curly_braces: Option<bool>| {
+            let guid = script_rng(lua).guid().to_string().to_uppercase();
+            if wrap_in_curly_braces.unwrap_or(true) {
+                Ok(format!("{{{}}}", guid))
+            } else {
+                Ok(guid)
+            }
+        });
     }
 }
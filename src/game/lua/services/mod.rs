@@ -1,13 +1,26 @@
 pub mod agent_input;
+pub mod collection_service;
 pub mod data_store;
 pub mod http_service;
+pub mod navmesh;
+pub mod pathfinding_service;
+pub mod physics_service;
 pub mod players;
+pub mod reward_service;
 pub mod run_service;
+pub mod spatial_index;
+pub mod tween;
 pub mod workspace;
+pub mod workspace_index;
 
 pub use agent_input::{AgentInput, AgentInputService};
+pub use collection_service::CollectionService;
 pub use data_store::DataStoreService;
 pub use http_service::HttpService;
+pub use pathfinding_service::PathfindingService;
+pub use physics_service::PhysicsService;
 pub use players::PlayersService;
+pub use reward_service::{RewardService, RewardStats};
 pub use run_service::RunService;
-pub use workspace::{register_raycast_params, WorkspaceService};
+pub use tween::{Tween, TweenService};
+pub use workspace::{register_overlap_params, register_raycast_params, WorkspaceService};
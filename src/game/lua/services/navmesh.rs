@@ -0,0 +1,520 @@
+//! Grid navmesh behind PathfindingService, baked from the workspace's
+//! Static-tagged parts.
+//!
+//! The map's footprint is split into square columns. Each column keeps the
+//! surfaces an agent can stand on there: part tops that face up and leave room
+//! for the agent's radius and height above them. Paths walk between
+//! neighbouring surfaces within a step of each other, walk off ledges and, if
+//! the agent can jump, jump up onto ledges within reach.
+
+use rapier3d::prelude::*;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::hash::{Hash, Hasher};
+use std::ops::Range;
+
+use super::spatial_index::part_pose;
+use crate::game::constants::pathfinding as consts;
+use crate::game::constants::physics::AUTOSTEP_MAX_HEIGHT;
+use crate::game::lua::types::{PartType, PathWaypoint, PathWaypointAction, Vector3};
+use crate::game::physics::part_shape;
+
+/// Steepest surface an agent stands on (the character controller climbs up to 45°)
+const MIN_FLOOR_NORMAL_Y: f32 = std::f32::consts::FRAC_1_SQRT_2;
+
+/// Surfaces in a column closer than this count as one
+const SURFACE_MERGE: f32 = 0.05;
+
+/// Column steps to the eight neighbours
+const DIRECTIONS: [(i64, i64); 8] = [
+    (1, 0),
+    (-1, 0),
+    (0, 1),
+    (0, -1),
+    (1, 1),
+    (1, -1),
+    (-1, 1),
+    (-1, -1),
+];
+
+/// Size and abilities of the agents a navmesh is baked for
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AgentParams {
+    pub radius: f32,
+    pub height: f32,
+    pub can_jump: bool,
+}
+
+/// Collision geometry of a Static part
+#[derive(Debug, Clone, PartialEq)]
+pub struct StaticPart {
+    pub shape: PartType,
+    pub size: [f32; 3],
+    pub position: [f32; 3],
+    /// CFrame rotation quaternion [x, y, z, w]
+    pub rotation: [f32; 4],
+}
+
+/// Fingerprint of the static geometry; navmeshes are baked again when it changes
+pub fn geometry_signature(parts: &[StaticPart]) -> u64 {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    for part in parts {
+        part.shape.value().hash(&mut hasher);
+        for value in part.size.iter().chain(&part.position).chain(&part.rotation) {
+            value.to_bits().hash(&mut hasher);
+        }
+    }
+    hasher.finish()
+}
+
+/// A place an agent can stand
+#[derive(Debug, Clone, Copy)]
+struct Surface {
+    height: f32,
+    /// Open space above the surface's column center, up to the next part
+    headroom: f32,
+}
+
+pub struct Navmesh {
+    agent: AgentParams,
+    /// Corner of the grid with the lowest x and z
+    origin: [f32; 2],
+    cell: f32,
+    /// Columns along x and along z
+    width: usize,
+    depth: usize,
+    /// Surfaces of column `c` are `surfaces[offsets[c]..offsets[c + 1]]`, lowest first
+    offsets: Vec<usize>,
+    surfaces: Vec<Surface>,
+    /// Column of each surface
+    columns: Vec<usize>,
+    /// Top of the highest part in each column, walkable or not
+    highest: Vec<f32>,
+}
+
+/// A surface waiting in the A* open set, ordered so the heap pops the lowest estimate
+#[derive(PartialEq)]
+struct Open {
+    estimate: f32,
+    node: usize,
+}
+
+impl Eq for Open {}
+
+impl Ord for Open {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .estimate
+            .total_cmp(&self.estimate)
+            .then_with(|| other.node.cmp(&self.node))
+    }
+}
+
+impl PartialOrd for Open {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Navmesh {
+    pub fn bake(parts: &[StaticPart], agent: AgentParams) -> Self {
+        let bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+        let mut bounds: Option<Aabb> = None;
+        for part in parts {
+            let collider = ColliderBuilder::new(part_shape(part.shape, part.size))
+                .position(part_pose(Vector3::from_array(part.position), part.rotation))
+                .build();
+            let aabb = collider.compute_aabb();
+            bounds = Some(bounds.map_or(aabb, |bounds| bounds.merged(&aabb)));
+            colliders.insert(collider);
+        }
+        let mut pipeline = QueryPipeline::new();
+        pipeline.update(&colliders);
+
+        let mut navmesh = Self {
+            agent,
+            origin: [0.0; 2],
+            cell: consts::CELL_SIZE,
+            width: 0,
+            depth: 0,
+            offsets: vec![0],
+            surfaces: Vec::new(),
+            columns: Vec::new(),
+            highest: Vec::new(),
+        };
+        let Some(bounds) = bounds else {
+            return navmesh;
+        };
+
+        let extent = [bounds.maxs.x - bounds.mins.x, bounds.maxs.z - bounds.mins.z];
+        navmesh.cell = consts::CELL_SIZE.max((extent[0] * extent[1] / consts::MAX_COLUMNS as f32).sqrt());
+        navmesh.origin = [bounds.mins.x, bounds.mins.z];
+        navmesh.width = ((extent[0] / navmesh.cell).ceil() as usize).max(1);
+        navmesh.depth = ((extent[1] / navmesh.cell).ceil() as usize).max(1);
+
+        // The agent's body stands a step above the surface: anything lower is stepped over
+        let body_height = (agent.height - AUTOSTEP_MAX_HEIGHT).max(SURFACE_MERGE);
+        let body = Cylinder::new(body_height / 2.0, agent.radius);
+        let top = bounds.maxs.y + 1.0;
+        let filter = QueryFilter::new();
+
+        for column in 0..navmesh.width * navmesh.depth {
+            let [x, z] = navmesh.column_center(column);
+            let mut tops = Vec::new();
+            let down = Ray::new(point![x, top, z], vector![0.0, -1.0, 0.0]);
+            pipeline.intersections_with_ray(&bodies, &colliders, &down, Real::MAX, true, filter, |_, hit| {
+                if hit.normal.y >= MIN_FLOOR_NORMAL_Y {
+                    tops.push(top - hit.time_of_impact);
+                }
+                true
+            });
+            tops.sort_by(f32::total_cmp);
+            tops.dedup_by(|higher, lower| *higher - *lower < SURFACE_MERGE);
+            navmesh.highest.push(tops.last().copied().unwrap_or(f32::NEG_INFINITY));
+
+            for height in tops {
+                let pose = Isometry::translation(x, height + AUTOSTEP_MAX_HEIGHT + body_height / 2.0, z);
+                if pipeline
+                    .intersection_with_shape(&bodies, &colliders, &pose, &body, filter)
+                    .is_some()
+                {
+                    continue;
+                }
+                let up = Ray::new(point![x, height + SURFACE_MERGE, z], vector![0.0, 1.0, 0.0]);
+                let headroom = pipeline
+                    .cast_ray(&bodies, &colliders, &up, Real::MAX, true, filter)
+                    .map_or(f32::INFINITY, |(_, toi)| toi + SURFACE_MERGE);
+                navmesh.surfaces.push(Surface { height, headroom });
+                navmesh.columns.push(column);
+            }
+            navmesh.offsets.push(navmesh.surfaces.len());
+        }
+
+        navmesh
+    }
+
+    /// Waypoints from `start` to `goal`, or None if no path joins them. Walk
+    /// waypoints are at most `spacing` apart; a Jump waypoint is reached by
+    /// jumping while walking to it.
+    pub fn find_path(&self, start: Vector3, goal: Vector3, spacing: f32) -> Option<Vec<PathWaypoint>> {
+        let from = self.locate(start)?;
+        let to = self.locate(goal)?;
+        let route = self.search(from, to)?;
+
+        // Keep the surfaces where the route turns or jumps; start and goal stand in for its ends
+        let mut corners = vec![PathWaypoint::new(
+            Vector3::new(start.x, self.surfaces[from].height, start.z),
+            PathWaypointAction::Walk,
+        )];
+        for k in 1..route.len().saturating_sub(1) {
+            let (node, action) = route[k];
+            let turns = self.direction(route[k - 1].0, node) != self.direction(node, route[k + 1].0);
+            if turns || action == PathWaypointAction::Jump || route[k + 1].1 == PathWaypointAction::Jump {
+                corners.push(PathWaypoint::new(self.position(node), action));
+            }
+        }
+        let last_action = if route.len() > 1 {
+            route[route.len() - 1].1
+        } else {
+            PathWaypointAction::Walk
+        };
+        corners.push(PathWaypoint::new(
+            Vector3::new(goal.x, self.surfaces[to].height, goal.z),
+            last_action,
+        ));
+
+        let mut waypoints = vec![corners[0].clone()];
+        for pair in corners.windows(2) {
+            let (a, b) = (&pair[0].position, &pair[1]);
+            if b.action == PathWaypointAction::Walk {
+                let length = (b.position.x - a.x).hypot(b.position.z - a.z);
+                let pieces = (length / spacing).ceil() as usize;
+                for piece in 1..pieces {
+                    let position = a.lerp(&b.position, piece as f32 / pieces as f32);
+                    waypoints.push(PathWaypoint::new(position, PathWaypointAction::Walk));
+                }
+            }
+            waypoints.push(b.clone());
+        }
+        Some(waypoints)
+    }
+
+    /// The surface an agent at `position` stands on: in the nearest column that
+    /// has one, the highest surface no more than a step above `position`
+    fn locate(&self, position: Vector3) -> Option<usize> {
+        let ix = ((position.x - self.origin[0]) / self.cell).floor() as i64;
+        let iz = ((position.z - self.origin[1]) / self.cell).floor() as i64;
+        let reach = (self.agent.radius / self.cell).ceil() as i64 + 2;
+        let mut best: Option<(f32, usize)> = None;
+        for dz in -reach..=reach {
+            for dx in -reach..=reach {
+                let Some(column) = self.column_at(ix + dx, iz + dz) else {
+                    continue;
+                };
+                let Some(node) = self
+                    .column_surfaces(column)
+                    .rev()
+                    .find(|&node| self.surfaces[node].height <= position.y + AUTOSTEP_MAX_HEIGHT)
+                else {
+                    continue;
+                };
+                let [x, z] = self.column_center(column);
+                let distance = (x - position.x).hypot(z - position.z);
+                if best.is_none_or(|(closest, _)| distance < closest) {
+                    best = Some((distance, node));
+                }
+            }
+        }
+        best.map(|(_, node)| node)
+    }
+
+    /// A* from surface `start` to `goal`: the surfaces along the way, each with
+    /// the action that reaches it
+    fn search(&self, start: usize, goal: usize) -> Option<Vec<(usize, PathWaypointAction)>> {
+        let target = self.position(goal);
+        let estimate = |node: usize| {
+            let position = self.position(node);
+            Vector3::new(position.x - target.x, position.y - target.y, position.z - target.z).magnitude()
+        };
+
+        let mut cost = vec![f32::INFINITY; self.surfaces.len()];
+        let mut came_from: Vec<Option<(usize, PathWaypointAction)>> = vec![None; self.surfaces.len()];
+        let mut closed = vec![false; self.surfaces.len()];
+        let mut open = BinaryHeap::from([Open {
+            estimate: estimate(start),
+            node: start,
+        }]);
+        cost[start] = 0.0;
+
+        let mut edges = Vec::new();
+        while let Some(Open { node, .. }) = open.pop() {
+            if node == goal {
+                let mut route = vec![(goal, PathWaypointAction::Walk)];
+                while let Some((previous, action)) = came_from[route[route.len() - 1].0] {
+                    route.last_mut().unwrap().1 = action;
+                    route.push((previous, PathWaypointAction::Walk));
+                }
+                route.reverse();
+                return Some(route);
+            }
+            if std::mem::replace(&mut closed[node], true) {
+                continue;
+            }
+
+            edges.clear();
+            self.edges(node, &mut edges);
+            for &(next, action, length) in &edges {
+                let reached = cost[node] + length;
+                if closed[next] || reached >= cost[next] {
+                    continue;
+                }
+                cost[next] = reached;
+                came_from[next] = Some((node, action));
+                open.push(Open {
+                    estimate: reached + estimate(next),
+                    node: next,
+                });
+            }
+        }
+        None
+    }
+
+    /// Surfaces an agent can move to from surface `node`, with the action and its cost
+    fn edges(&self, node: usize, edges: &mut Vec<(usize, PathWaypointAction, f32)>) {
+        let from = self.surfaces[node];
+        let (ix, iz) = self.coords(self.columns[node]);
+        for (dx, dz) in DIRECTIONS {
+            let Some(column) = self.column_at(ix + dx, iz + dz) else {
+                continue;
+            };
+            let diagonal = dx != 0 && dz != 0;
+            // Diagonal steps need both sides open, so paths do not cut corners
+            if diagonal && !(self.can_step(from, ix + dx, iz) && self.can_step(from, ix, iz + dz)) {
+                continue;
+            }
+            let run = if diagonal {
+                self.cell * std::f32::consts::SQRT_2
+            } else {
+                self.cell
+            };
+            for next in self.column_surfaces(column) {
+                let to = self.surfaces[next];
+                let Some(action) = self.action(from, to) else {
+                    continue;
+                };
+                let length = run.hypot(to.height - from.height);
+                match action {
+                    PathWaypointAction::Walk => edges.push((next, action, length)),
+                    PathWaypointAction::Jump if !diagonal => {
+                        edges.push((next, action, length + consts::JUMP_COST))
+                    }
+                    PathWaypointAction::Jump => {}
+                }
+            }
+        }
+
+        // The agent's radius keeps it off the columns at the foot of a ledge, so
+        // jumps and drops also reach straight across them to the surface beyond
+        let reach = (self.agent.radius / self.cell).ceil() as i64 + 1;
+        for &(dx, dz) in &DIRECTIONS[..4] {
+            for distance in 2..=reach {
+                let Some(column) = self.column_at(ix + dx * distance, iz + dz * distance) else {
+                    break;
+                };
+                for next in self.column_surfaces(column) {
+                    let to = self.surfaces[next];
+                    let rise = to.height - from.height;
+                    if rise.abs() <= AUTOSTEP_MAX_HEIGHT {
+                        continue;
+                    }
+                    let Some(action) = self.action(from, to) else {
+                        continue;
+                    };
+                    // Nothing in between may stand taller than the higher of the two surfaces
+                    let ceiling = from.height.max(to.height) + AUTOSTEP_MAX_HEIGHT;
+                    let clear = (1..distance).all(|d| {
+                        let between = self.column_at(ix + dx * d, iz + dz * d).unwrap();
+                        self.highest[between] <= ceiling
+                    });
+                    if !clear {
+                        continue;
+                    }
+                    let mut length = (self.cell * distance as f32).hypot(rise);
+                    if action == PathWaypointAction::Jump {
+                        length += consts::JUMP_COST;
+                    }
+                    edges.push((next, action, length));
+                }
+            }
+        }
+    }
+
+    /// How an agent gets from surface `from` to a neighbouring surface `to`, if it can
+    fn action(&self, from: Surface, to: Surface) -> Option<PathWaypointAction> {
+        let rise = to.height - from.height;
+        if rise.abs() <= AUTOSTEP_MAX_HEIGHT {
+            return Some(PathWaypointAction::Walk);
+        }
+        // Jumping up or dropping down needs room above the lower surface for the whole move
+        let lower = if rise > 0.0 { from } else { to };
+        if lower.headroom < rise.abs() + self.agent.height {
+            return None;
+        }
+        if rise > 0.0 {
+            (self.agent.can_jump && rise <= consts::MAX_JUMP_CLIMB).then_some(PathWaypointAction::Jump)
+        } else {
+            (-rise <= consts::MAX_DROP).then_some(PathWaypointAction::Walk)
+        }
+    }
+
+    /// Whether column (ix, iz) has a surface within a step of `from`
+    fn can_step(&self, from: Surface, ix: i64, iz: i64) -> bool {
+        self.column_at(ix, iz).is_some_and(|column| {
+            self.column_surfaces(column)
+                .any(|node| (self.surfaces[node].height - from.height).abs() <= AUTOSTEP_MAX_HEIGHT)
+        })
+    }
+
+    fn column_at(&self, ix: i64, iz: i64) -> Option<usize> {
+        let in_grid = (0..self.width as i64).contains(&ix) && (0..self.depth as i64).contains(&iz);
+        in_grid.then(|| iz as usize * self.width + ix as usize)
+    }
+
+    fn coords(&self, column: usize) -> (i64, i64) {
+        ((column % self.width) as i64, (column / self.width) as i64)
+    }
+
+    fn column_center(&self, column: usize) -> [f32; 2] {
+        let (ix, iz) = self.coords(column);
+        [
+            self.origin[0] + (ix as f32 + 0.5) * self.cell,
+            self.origin[1] + (iz as f32 + 0.5) * self.cell,
+        ]
+    }
+
+    fn column_surfaces(&self, column: usize) -> Range<usize> {
+        self.offsets[column]..self.offsets[column + 1]
+    }
+
+    /// Column step from surface `from` to surface `to`
+    fn direction(&self, from: usize, to: usize) -> (i64, i64) {
+        let (fx, fz) = self.coords(self.columns[from]);
+        let (tx, tz) = self.coords(self.columns[to]);
+        (tx - fx, tz - fz)
+    }
+
+    /// Point on surface `node` at its column's center
+    fn position(&self, node: usize) -> Vector3 {
+        let [x, z] = self.column_center(self.columns[node]);
+        Vector3::new(x, self.surfaces[node].height, z)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(position: [f32; 3], size: [f32; 3]) -> StaticPart {
+        StaticPart {
+            shape: PartType::Block,
+            size,
+            position,
+            rotation: [0.0, 0.0, 0.0, 1.0],
+        }
+    }
+
+    const AGENT: AgentParams = AgentParams {
+        radius: 1.0,
+        height: 5.0,
+        can_jump: true,
+    };
+
+    #[test]
+    fn test_paths_go_around_walls_and_jump_onto_ledges() {
+        let floor = block([0.0, -0.5, 0.0], [40.0, 1.0, 40.0]);
+        // A wall across the floor with a gap at its +z end
+        let wall = block([0.0, 5.0, -4.0], [2.0, 10.0, 32.0]);
+        let navmesh = Navmesh::bake(&[floor.clone(), wall], AGENT);
+
+        let start = Vector3::new(-10.0, 2.5, 0.0);
+        let goal = Vector3::new(10.0, 0.0, 0.0);
+        let waypoints = navmesh.find_path(start, goal, 4.0).unwrap();
+        assert_eq!(waypoints[0].position, Vector3::new(-10.0, 0.0, 0.0));
+        assert_eq!(waypoints.last().unwrap().position, goal);
+        // Through the gap, with no waypoints further apart than the spacing
+        assert!(waypoints.iter().any(|waypoint| waypoint.position.z > 12.0));
+        for pair in waypoints.windows(2) {
+            let (a, b) = (pair[0].position, pair[1].position);
+            assert!((b.x - a.x).hypot(b.z - a.z) <= 4.0 + 1e-3);
+            assert_eq!(pair[1].action, PathWaypointAction::Walk);
+        }
+
+        // A 4-stud ledge is jumped onto, but not by agents that cannot jump
+        let ledge = block([10.0, 2.0, 0.0], [10.0, 4.0, 10.0]);
+        let navmesh = Navmesh::bake(&[floor.clone(), ledge.clone()], AGENT);
+        let goal = Vector3::new(10.0, 4.0, 0.0);
+        let waypoints = navmesh.find_path(start, goal, 4.0).unwrap();
+        let jumps: Vec<_> = waypoints
+            .iter()
+            .filter(|waypoint| waypoint.action == PathWaypointAction::Jump)
+            .collect();
+        assert_eq!(jumps.len(), 1);
+        assert_eq!(jumps[0].position.y, 4.0);
+        let grounded = AgentParams {
+            can_jump: false,
+            ..AGENT
+        };
+        assert!(Navmesh::bake(&[floor, ledge], grounded).find_path(start, goal, 4.0).is_none());
+    }
+
+    #[test]
+    fn test_signature_changes_with_geometry() {
+        let floor = block([0.0, -0.5, 0.0], [40.0, 1.0, 40.0]);
+        let mut moved = floor.clone();
+        let signature = geometry_signature(std::slice::from_ref(&floor));
+        assert_eq!(signature, geometry_signature(std::slice::from_ref(&moved)));
+        moved.position[0] += 1.0;
+        assert_ne!(signature, geometry_signature(&[moved]));
+    }
+}
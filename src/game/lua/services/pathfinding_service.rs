@@ -0,0 +1,174 @@
+//! PathfindingService: paths around the map's static geometry.
+//!
+//! Paths come from a grid navmesh (see `navmesh`) baked from the workspace's
+//! Static-tagged parts, one per agent size. Navmeshes are kept until the
+//! static geometry changes; parts without the tag (characters, doors, loose
+//! props) never block a path.
+
+use mlua::{Lua, Result, Table, UserData, UserDataFields, UserDataMethods};
+use std::sync::{Arc, Mutex};
+
+use super::collection_service::CollectionIndexHandle;
+use super::navmesh::{geometry_signature, AgentParams, Navmesh, StaticPart};
+use crate::game::constants::pathfinding as consts;
+use crate::game::lua::instance::Instance;
+use crate::game::lua::types::{PathStatus, PathWaypoint, Vector3};
+
+/// Tag of the parts paths are baked from
+pub const STATIC_TAG: &str = "Static";
+
+/// Baked navmeshes, valid for the static geometry with `signature`
+#[derive(Default)]
+struct NavmeshCache {
+    signature: Option<u64>,
+    navmeshes: Vec<(AgentParams, Arc<Navmesh>)>,
+}
+
+#[derive(Clone)]
+pub struct PathfindingService {
+    workspace: Instance,
+    collection: CollectionIndexHandle,
+    cache: Arc<Mutex<NavmeshCache>>,
+}
+
+impl PathfindingService {
+    pub fn new(workspace: Instance, collection: CollectionIndexHandle) -> Self {
+        Self {
+            workspace,
+            collection,
+            cache: Arc::default(),
+        }
+    }
+
+    /// Waypoints for an agent from `start` to `goal`, or None if no path joins them
+    pub fn find_path(
+        &self,
+        agent: AgentParams,
+        start: Vector3,
+        goal: Vector3,
+        spacing: f32,
+    ) -> Option<Vec<PathWaypoint>> {
+        self.navmesh(agent).find_path(start, goal, spacing)
+    }
+
+    /// The agent's navmesh, baked again if the static geometry changed since the last bake
+    fn navmesh(&self, agent: AgentParams) -> Arc<Navmesh> {
+        let parts = self.static_parts();
+        let signature = geometry_signature(&parts);
+        let mut cache = self.cache.lock().unwrap();
+        if cache.signature != Some(signature) {
+            cache.signature = Some(signature);
+            cache.navmeshes.clear();
+        }
+        if let Some((_, navmesh)) = cache.navmeshes.iter().find(|(params, _)| *params == agent) {
+            return navmesh.clone();
+        }
+        let navmesh = Arc::new(Navmesh::bake(&parts, agent));
+        cache.navmeshes.push((agent, navmesh.clone()));
+        navmesh
+    }
+
+    /// Collidable Static-tagged parts in the workspace, in creation order
+    fn static_parts(&self) -> Vec<StaticPart> {
+        let tagged = self.collection.lock().unwrap().tagged(STATIC_TAG);
+        tagged
+            .into_iter()
+            .filter(|part| part.is_descendant_of(&self.workspace))
+            .filter_map(|part| {
+                let data = part.data.lock().unwrap();
+                let part = data.part_data.as_ref().filter(|part| part.can_collide)?;
+                Some(StaticPart {
+                    shape: part.shape,
+                    size: part.size.to_array(),
+                    position: part.position.to_array(),
+                    rotation: part.cframe.to_quaternion(),
+                })
+            })
+            .collect()
+    }
+}
+
+impl UserData for PathfindingService {
+    fn add_fields<F: UserDataFields<Self>>(fields: &mut F) {
+        fields.add_field_method_get("Name", |_, _| Ok("PathfindingService".to_string()));
+        fields.add_field_method_get("ClassName", |_, _| Ok("PathfindingService".to_string()));
+    }
+
+    fn add_methods<M: UserDataMethods<Self>>(methods: &mut M) {
+        methods.add_method("CreatePath", |_, this, params: Option<Table>| {
+            let number = |name: &str, default: f32| -> Result<f32> {
+                let value = match &params {
+                    Some(params) => params.get::<Option<f32>>(name)?.unwrap_or(default),
+                    None => default,
+                };
+                if value > 0.0 {
+                    Ok(value)
+                } else {
+                    Err(mlua::Error::runtime(format!("CreatePath: {} must be positive", name)))
+                }
+            };
+            let agent = AgentParams {
+                radius: number("AgentRadius", consts::DEFAULT_AGENT_RADIUS)?,
+                height: number("AgentHeight", consts::DEFAULT_AGENT_HEIGHT)?,
+                can_jump: match &params {
+                    Some(params) => params.get::<Option<bool>>("AgentCanJump")?.unwrap_or(true),
+                    None => true,
+                },
+            };
+            let spacing = number("WaypointSpacing", consts::DEFAULT_WAYPOINT_SPACING)?;
+            Ok(Path {
+                service: this.clone(),
+                agent,
+                spacing,
+                state: Arc::new(Mutex::new(PathState {
+                    status: PathStatus::NoPath,
+                    waypoints: Vec::new(),
+                })),
+            })
+        });
+    }
+}
+
+struct PathState {
+    status: PathStatus,
+    waypoints: Vec<PathWaypoint>,
+}
+
+/// A path for one agent size, filled in by ComputeAsync
+#[derive(Clone)]
+pub struct Path {
+    service: PathfindingService,
+    agent: AgentParams,
+    spacing: f32,
+    state: Arc<Mutex<PathState>>,
+}
+
+impl UserData for Path {
+    fn add_fields<F: UserDataFields<Self>>(fields: &mut F) {
+        fields.add_field_method_get("Status", |_, this| Ok(this.state.lock().unwrap().status));
+        fields.add_field_method_get("Name", |_, _| Ok("Path".to_string()));
+        fields.add_field_method_get("ClassName", |_, _| Ok("Path".to_string()));
+    }
+
+    fn add_methods<M: UserDataMethods<Self>>(methods: &mut M) {
+        methods.add_method("ComputeAsync", |_, this, (start, goal): (Vector3, Vector3)| {
+            let waypoints = this.service.find_path(this.agent, start, goal, this.spacing);
+            let mut state = this.state.lock().unwrap();
+            state.status = if waypoints.is_some() {
+                PathStatus::Success
+            } else {
+                PathStatus::NoPath
+            };
+            state.waypoints = waypoints.unwrap_or_default();
+            Ok(())
+        });
+
+        methods.add_method("GetWaypoints", |lua: &Lua, this, ()| {
+            let list = lua.create_table()?;
+            for waypoint in &this.state.lock().unwrap().waypoints {
+                list.push(waypoint.clone())?;
+            }
+            Ok(list)
+        });
+    }
+}
@@ -0,0 +1,226 @@
+//! PhysicsService: named collision groups and which pairs of them collide.
+//!
+//! Parts name their group in `BasePart.CollisionGroup`; unknown names act as
+//! "Default". The table is shared with Workspace (raycasts) and read by the
+//! game loop, which maps each part's group onto its Rapier interaction groups.
+
+use mlua::{Lua, UserData, UserDataFields, UserDataMethods};
+use std::sync::{Arc, Mutex};
+
+use crate::game::physics::MAX_COLLISION_GROUPS;
+
+/// The group every part starts in; it cannot be unregistered
+pub const DEFAULT_COLLISION_GROUP: &str = "Default";
+
+/// Registered collision groups. A group's index is its bit in the masks.
+pub struct CollisionGroups {
+    /// Name and mask of the groups it collides with, per group
+    groups: Vec<(String, u16)>,
+}
+
+pub type CollisionGroupsHandle = Arc<Mutex<CollisionGroups>>;
+
+impl Default for CollisionGroups {
+    fn default() -> Self {
+        Self {
+            groups: vec![(DEFAULT_COLLISION_GROUP.to_string(), 1)],
+        }
+    }
+}
+
+impl CollisionGroups {
+    pub fn index(&self, name: &str) -> Option<usize> {
+        self.groups.iter().position(|(group, _)| group == name)
+    }
+
+    /// Index and mask of a group, falling back to Default for unknown names
+    pub fn resolve(&self, name: &str) -> (usize, u16) {
+        let index = self.index(name).unwrap_or(0);
+        (index, self.groups[index].1)
+    }
+
+    /// Whether parts in the two groups collide (unknown names act as Default)
+    pub fn collidable(&self, a: &str, b: &str) -> bool {
+        let (_, mask) = self.resolve(a);
+        let (b, _) = self.resolve(b);
+        mask & (1 << b) != 0
+    }
+
+    /// Adds a group that collides with every group; registering a name twice does nothing
+    pub fn register(&mut self, name: &str) -> Result<(), String> {
+        if self.index(name).is_some() {
+            return Ok(());
+        }
+        if name.is_empty() {
+            return Err("collision group name cannot be empty".to_string());
+        }
+        if self.groups.len() >= MAX_COLLISION_GROUPS {
+            return Err(format!(
+                "cannot register collision group '{}': the limit of {} groups is reached",
+                name, MAX_COLLISION_GROUPS
+            ));
+        }
+        let bit: u16 = 1 << self.groups.len();
+        for (_, mask) in &mut self.groups {
+            *mask |= bit;
+        }
+        self.groups.push((name.to_string(), bit | (bit - 1)));
+        Ok(())
+    }
+
+    /// Removes a group; parts still naming it act as Default
+    pub fn unregister(&mut self, name: &str) -> Result<(), String> {
+        if name == DEFAULT_COLLISION_GROUP {
+            return Err("the Default collision group cannot be unregistered".to_string());
+        }
+        let Some(index) = self.index(name) else {
+            return Ok(());
+        };
+        self.groups.remove(index);
+        // Later groups move down a bit
+        let low = (1u32 << index) - 1;
+        for (_, mask) in &mut self.groups {
+            let bits = *mask as u32;
+            *mask = ((bits & low) | ((bits >> (index + 1)) << index)) as u16;
+        }
+        Ok(())
+    }
+
+    pub fn set_collidable(
+        &mut self,
+        a: &str,
+        b: &str,
+        collidable: bool,
+    ) -> Result<(), String> {
+        let a = self.registered(a)?;
+        let b = self.registered(b)?;
+        for (group, other) in [(a, b), (b, a)] {
+            if collidable {
+                self.groups[group].1 |= 1 << other;
+            } else {
+                self.groups[group].1 &= !(1 << other);
+            }
+        }
+        Ok(())
+    }
+
+    fn registered(&self, name: &str) -> Result<usize, String> {
+        self.index(name)
+            .ok_or_else(|| format!("collision group '{}' is not registered", name))
+    }
+
+    /// Names and masks of the registered groups, in registration order
+    pub fn groups(&self) -> &[(String, u16)] {
+        &self.groups
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct PhysicsService {
+    pub collision_groups: CollisionGroupsHandle,
+}
+
+impl PhysicsService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl UserData for PhysicsService {
+    fn add_fields<F: UserDataFields<Self>>(fields: &mut F) {
+        fields.add_field_method_get("Name", |_, _| Ok("PhysicsService".to_string()));
+        fields.add_field_method_get("ClassName", |_, _| Ok("PhysicsService".to_string()));
+    }
+
+    fn add_methods<M: UserDataMethods<Self>>(methods: &mut M) {
+        methods.add_method("RegisterCollisionGroup", |_, this, name: String| {
+            this.collision_groups
+                .lock()
+                .unwrap()
+                .register(&name)
+                .map_err(mlua::Error::runtime)
+        });
+
+        methods.add_method("UnregisterCollisionGroup", |_, this, name: String| {
+            this.collision_groups
+                .lock()
+                .unwrap()
+                .unregister(&name)
+                .map_err(mlua::Error::runtime)
+        });
+
+        methods.add_method(
+            "CollisionGroupSetCollidable",
+            |_, this, (a, b, collidable): (String, String, bool)| {
+                this.collision_groups
+                    .lock()
+                    .unwrap()
+                    .set_collidable(&a, &b, collidable)
+                    .map_err(mlua::Error::runtime)
+            },
+        );
+
+        methods.add_method(
+            "CollisionGroupsAreCollidable",
+            |_, this, (a, b): (String, String)| {
+                let groups = this.collision_groups.lock().unwrap();
+                groups.registered(&a).map_err(mlua::Error::runtime)?;
+                groups.registered(&b).map_err(mlua::Error::runtime)?;
+                Ok(groups.collidable(&a, &b))
+            },
+        );
+
+        methods.add_method("IsCollisionGroupRegistered", |_, this, name: String| {
+            Ok(this.collision_groups.lock().unwrap().index(&name).is_some())
+        });
+
+        methods.add_method("GetRegisteredCollisionGroups", |lua: &Lua, this, ()| {
+            let groups = this.collision_groups.lock().unwrap();
+            let list = lua.create_table()?;
+            for (id, (name, mask)) in groups.groups().iter().enumerate() {
+                let entry = lua.create_table()?;
+                entry.set("id", id)?;
+                entry.set("name", name.as_str())?;
+                entry.set("mask", *mask)?;
+                list.push(entry)?;
+            }
+            Ok(list)
+        });
+
+        methods.add_method("GetMaxCollisionGroups", |_, _, ()| Ok(MAX_COLLISION_GROUPS));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_register_and_unregister_keep_masks_consistent() {
+        let mut groups = CollisionGroups::default();
+        groups.register("Players").unwrap();
+        groups.register("Doors").unwrap();
+        groups.register("Ghosts").unwrap();
+        groups.set_collidable("Players", "Doors", false).unwrap();
+        groups.set_collidable("Ghosts", "Ghosts", false).unwrap();
+        assert!(!groups.collidable("Doors", "Players"));
+        assert!(groups.collidable("Doors", "Ghosts"));
+        assert!(groups.set_collidable("Players", "Nobody", false).is_err());
+
+        // Later groups shift down a bit and keep their settings
+        groups.unregister("Players").unwrap();
+        assert_eq!(groups.index("Ghosts"), Some(2));
+        assert!(!groups.collidable("Ghosts", "Ghosts"));
+        assert!(groups.collidable("Doors", "Default"));
+        // Parts left in a removed group act as Default
+        assert!(groups.collidable("Players", "Doors"));
+        assert!(groups.unregister(DEFAULT_COLLISION_GROUP).is_err());
+
+        while groups.groups().len() < MAX_COLLISION_GROUPS {
+            let name = format!("Group{}", groups.groups().len());
+            groups.register(&name).unwrap();
+        }
+        assert!(groups.register("OneTooMany").is_err());
+        assert!(groups.collidable("Default", "Group15"));
+    }
+}
@@ -0,0 +1,178 @@
+use mlua::{UserData, UserDataFields, UserDataMethods};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+
+use crate::game::lua::instance::Instance;
+
+/// Cumulative reward for one player's current episode
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RewardStats {
+    /// Sum of every reward added this episode
+    pub total: f64,
+    /// Totals per reason passed to AddReward ("" when no reason was given)
+    pub by_reason: BTreeMap<String, f64>,
+    /// Number of AddReward calls this episode
+    pub count: u32,
+    /// Set once the script calls EndEpisode for the player
+    pub done: bool,
+    /// Outcome passed to EndEpisode, e.g. "win" or "loss"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub outcome: Option<String>,
+}
+
+pub struct RewardServiceData {
+    /// Stats per user_id
+    pub stats: HashMap<u64, RewardStats>,
+}
+
+/// RewardService lets game scripts score agents in a game-agnostic way for
+/// training and evaluation tooling
+#[derive(Clone)]
+pub struct RewardService {
+    pub data: Arc<Mutex<RewardServiceData>>,
+}
+
+impl RewardService {
+    pub fn new() -> Self {
+        Self {
+            data: Arc::new(Mutex::new(RewardServiceData {
+                stats: HashMap::new(),
+            })),
+        }
+    }
+
+    pub fn add_reward(&self, user_id: u64, value: f64, reason: Option<String>) {
+        let mut data = self.data.lock().unwrap();
+        let stats = data.stats.entry(user_id).or_default();
+        stats.total += value;
+        *stats.by_reason.entry(reason.unwrap_or_default()).or_insert(0.0) += value;
+        stats.count += 1;
+    }
+
+    pub fn end_episode(&self, user_id: u64, outcome: Option<String>) {
+        let mut data = self.data.lock().unwrap();
+        let stats = data.stats.entry(user_id).or_default();
+        stats.done = true;
+        stats.outcome = outcome;
+    }
+
+    /// Stats for a user (all zero if nothing was recorded yet)
+    pub fn stats(&self, user_id: u64) -> RewardStats {
+        let data = self.data.lock().unwrap();
+        data.stats.get(&user_id).cloned().unwrap_or_default()
+    }
+
+    /// Replaces a user's stats (used when restoring a snapshot)
+    pub fn set_stats(&self, user_id: u64, stats: RewardStats) {
+        self.data.lock().unwrap().stats.insert(user_id, stats);
+    }
+
+    /// Forgets a user's stats (called when the player leaves)
+    pub fn clear(&self, user_id: u64) {
+        self.data.lock().unwrap().stats.remove(&user_id);
+    }
+}
+
+impl Default for RewardService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn player_user_id(player: &Instance) -> mlua::Result<u64> {
+    player
+        .data
+        .lock()
+        .unwrap()
+        .player_data
+        .as_ref()
+        .map(|pd| pd.user_id)
+        .ok_or_else(|| mlua::Error::runtime("RewardService expects a Player"))
+}
+
+impl UserData for RewardService {
+    fn add_fields<F: UserDataFields<Self>>(fields: &mut F) {
+        fields.add_field_method_get("Name", |_, _| Ok("RewardService".to_string()));
+        fields.add_field_method_get("ClassName", |_, _| Ok("RewardService".to_string()));
+    }
+
+    fn add_methods<M: UserDataMethods<Self>>(methods: &mut M) {
+        // AddReward(player, value, reason?) - adds to the player's episode total
+        methods.add_method(
+            "AddReward",
+            |_, this, (player, value, reason): (Instance, f64, Option<String>)| {
+                if !value.is_finite() {
+                    return Err(mlua::Error::runtime("AddReward: value must be a finite number"));
+                }
+                this.add_reward(player_user_id(&player)?, value, reason);
+                Ok(())
+            },
+        );
+
+        // EndEpisode(player, outcome?) - marks the player's episode as done
+        methods.add_method("EndEpisode", |_, this, (player, outcome): (Instance, Option<String>)| {
+            this.end_episode(player_user_id(&player)?, outcome);
+            Ok(())
+        });
+
+        methods.add_method("GetTotalReward", |_, this, player: Instance| {
+            Ok(this.stats(player_user_id(&player)?).total)
+        });
+
+        // GetStats(player) - { Total, Count, Done, Outcome, Rewards = { [reason] = total } }
+        methods.add_method("GetStats", |lua, this, player: Instance| {
+            let stats = this.stats(player_user_id(&player)?);
+            let rewards = lua.create_table()?;
+            for (reason, total) in &stats.by_reason {
+                rewards.set(reason.as_str(), *total)?;
+            }
+            let result = lua.create_table()?;
+            result.set("Total", stats.total)?;
+            result.set("Count", stats.count)?;
+            result.set("Done", stats.done)?;
+            result.set("Outcome", stats.outcome)?;
+            result.set("Rewards", rewards)?;
+            Ok(result)
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rewards_accumulate_per_reason() {
+        let service = RewardService::new();
+
+        service.add_reward(1, 1.0, Some("checkpoint".to_string()));
+        service.add_reward(1, 2.5, Some("checkpoint".to_string()));
+        service.add_reward(1, -0.5, None);
+        service.add_reward(2, 10.0, None);
+
+        let stats = service.stats(1);
+        assert_eq!(stats.total, 3.0);
+        assert_eq!(stats.count, 3);
+        assert_eq!(stats.by_reason["checkpoint"], 3.5);
+        assert_eq!(stats.by_reason[""], -0.5);
+        assert!(!stats.done);
+
+        assert_eq!(service.stats(2).total, 10.0);
+        assert_eq!(service.stats(3), RewardStats::default());
+    }
+
+    #[test]
+    fn test_end_episode() {
+        let service = RewardService::new();
+
+        service.end_episode(1, Some("win".to_string()));
+
+        let stats = service.stats(1);
+        assert!(stats.done);
+        assert_eq!(stats.outcome.as_deref(), Some("win"));
+
+        service.clear(1);
+        assert!(!service.stats(1).done);
+    }
+}
@@ -0,0 +1,296 @@
+//! Rapier query pipeline over the workspace's parts, behind Workspace's shape
+//! casts and overlap queries.
+//!
+//! Scripts cannot reach the game loop's physics world, so the index keeps its
+//! own copy of each part's collider. Only the parts `WorkspaceIndex` marked
+//! dirty are upserted or removed, right before a query runs.
+
+use rapier3d::na::{Quaternion, Translation3, UnitQuaternion};
+use rapier3d::parry::query::{ShapeCastHit, ShapeCastOptions};
+use rapier3d::prelude::*;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::game::lua::instance::Instance;
+use crate::game::lua::types::{PartType, Vector3};
+use crate::game::physics::part_shape;
+
+/// Pose of a part from its position and CFrame rotation quaternion [x, y, z, w]
+pub fn part_pose(position: Vector3, rotation: [f32; 4]) -> Isometry<Real> {
+    Isometry::from_parts(
+        Translation3::new(position.x, position.y, position.z),
+        UnitQuaternion::new_unchecked(Quaternion::new(
+            rotation[3], rotation[0], rotation[1], rotation[2], // w, x, y, z
+        )),
+    )
+}
+
+/// A part in the index, with the properties queries filter on
+pub struct IndexedPart {
+    pub instance: Instance,
+    pub can_collide: bool,
+    pub collision_group: String,
+    /// Shape, size, position and rotation the collider was last built or moved with
+    geometry: (PartType, [f32; 3]),
+    pose: ([f32; 3], [f32; 4]),
+}
+
+#[derive(Default)]
+pub struct SpatialIndex {
+    /// Always empty: the colliders have no bodies, but queries take a body set
+    bodies: RigidBodySet,
+    colliders: ColliderSet,
+    pipeline: QueryPipeline,
+    parts: HashMap<ColliderHandle, IndexedPart>,
+    /// Collider of each part, keyed by Lua instance ID
+    handles: HashMap<u64, ColliderHandle>,
+    /// Colliders added, moved or removed since the last `commit`
+    modified: Vec<ColliderHandle>,
+    removed: Vec<ColliderHandle>,
+}
+
+pub type SpatialIndexHandle = Arc<Mutex<SpatialIndex>>;
+
+impl SpatialIndex {
+    /// Adds the part's collider, or moves and reshapes it to match the part
+    pub fn upsert(&mut self, instance: Instance) {
+        let (lua_id, can_collide, collision_group, geometry, pose) = {
+            let data = instance.data.lock().unwrap();
+            let Some(part) = &data.part_data else {
+                return;
+            };
+            (
+                data.id.0,
+                part.can_collide,
+                part.collision_group.clone(),
+                (part.shape, part.size.to_array()),
+                (part.position.to_array(), part.cframe.to_quaternion()),
+            )
+        };
+        let isometry = part_pose(Vector3::from_array(pose.0), pose.1);
+
+        let Some(&handle) = self.handles.get(&lua_id) else {
+            let collider = ColliderBuilder::new(part_shape(geometry.0, geometry.1))
+                .position(isometry)
+                .build();
+            let handle = self.colliders.insert(collider);
+            self.handles.insert(lua_id, handle);
+            self.parts.insert(
+                handle,
+                IndexedPart {
+                    instance,
+                    can_collide,
+                    collision_group,
+                    geometry,
+                    pose,
+                },
+            );
+            self.modified.push(handle);
+            return;
+        };
+
+        let part = self.parts.get_mut(&handle).unwrap();
+        part.can_collide = can_collide;
+        part.collision_group = collision_group;
+        if part.geometry == geometry && part.pose == pose {
+            return;
+        }
+        let collider = &mut self.colliders[handle];
+        if part.geometry != geometry {
+            collider.set_shape(part_shape(geometry.0, geometry.1));
+        }
+        collider.set_position(isometry);
+        part.geometry = geometry;
+        part.pose = pose;
+        self.modified.push(handle);
+    }
+
+    /// Drops the collider of a part that left the workspace
+    pub fn remove(&mut self, lua_id: u64) {
+        let Some(handle) = self.handles.remove(&lua_id) else {
+            return;
+        };
+        self.parts.remove(&handle);
+        self.colliders
+            .remove(handle, &mut IslandManager::new(), &mut self.bodies, false);
+        self.modified.retain(|modified| *modified != handle);
+        self.removed.push(handle);
+    }
+
+    /// Brings the query pipeline in line with the parts upserted and removed
+    /// since the last commit (each part at most once per commit)
+    pub fn commit(&mut self) {
+        if self.modified.is_empty() && self.removed.is_empty() {
+            return;
+        }
+        self.pipeline
+            .update_incremental(&self.colliders, &self.modified, &self.removed, true);
+        self.modified.clear();
+        self.removed.clear();
+    }
+
+    /// First part hit by `ray` within `max_distance`, with the hit's distance and normal.
+    /// Parts containing the ray's origin are never hit.
+    pub fn cast_ray(
+        &self,
+        ray: &Ray,
+        max_distance: f32,
+        accept: impl Fn(&IndexedPart) -> bool,
+    ) -> Option<(Instance, RayIntersection)> {
+        let mut inside = Vec::new();
+        let predicate = |handle: ColliderHandle, _: &Collider| accept(&self.parts[&handle]);
+        self.pipeline.intersections_with_point(
+            &self.bodies,
+            &self.colliders,
+            &ray.origin,
+            QueryFilter::new().predicate(&predicate),
+            |handle| {
+                inside.push(handle);
+                true
+            },
+        );
+
+        let predicate = |handle: ColliderHandle, _: &Collider| {
+            !inside.contains(&handle) && accept(&self.parts[&handle])
+        };
+        let (handle, hit) = self.pipeline.cast_ray_and_get_normal(
+            &self.bodies,
+            &self.colliders,
+            ray,
+            max_distance,
+            true,
+            QueryFilter::new().predicate(&predicate),
+        )?;
+        Some((self.parts[&handle].instance.clone(), hit))
+    }
+
+    /// Parts whose collision shape intersects `shape` at `pose`, up to `max_parts` (0 for no limit)
+    pub fn parts_in_shape(
+        &self,
+        pose: &Isometry<Real>,
+        shape: &dyn Shape,
+        accept: impl Fn(&IndexedPart) -> bool,
+        max_parts: usize,
+    ) -> Vec<Instance> {
+        let predicate = |handle: ColliderHandle, _: &Collider| accept(&self.parts[&handle]);
+        let filter = QueryFilter::new().predicate(&predicate);
+        let mut found = Vec::new();
+        self.pipeline
+            .intersections_with_shape(&self.bodies, &self.colliders, pose, shape, filter, |handle| {
+                found.push(self.parts[&handle].instance.clone());
+                max_parts == 0 || found.len() < max_parts
+            });
+        found
+    }
+
+    /// Parts whose bounding box passes `overlaps`, among those whose bounding box
+    /// intersects `aabb`, up to `max_parts` (0 for no limit)
+    pub fn parts_with_bounds(
+        &self,
+        aabb: &Aabb,
+        overlaps: impl Fn(&Aabb) -> bool,
+        accept: impl Fn(&IndexedPart) -> bool,
+        max_parts: usize,
+    ) -> Vec<Instance> {
+        let mut found = Vec::new();
+        self.pipeline.colliders_with_aabb_intersecting_aabb(aabb, |handle| {
+            let part = &self.parts[handle];
+            if accept(part) && overlaps(&self.colliders[*handle].compute_aabb()) {
+                found.push(part.instance.clone());
+            }
+            max_parts == 0 || found.len() < max_parts
+        });
+        found
+    }
+
+    /// First part hit by `shape` moving from `pose` along the unit vector `direction`.
+    /// Parts the shape overlaps at its start are never hit.
+    pub fn cast_shape(
+        &self,
+        pose: &Isometry<Real>,
+        shape: &dyn Shape,
+        direction: Vector<Real>,
+        max_distance: f32,
+        accept: impl Fn(&IndexedPart) -> bool,
+    ) -> Option<(Instance, ShapeCastHit)> {
+        let mut overlapping = Vec::new();
+        let predicate = |handle: ColliderHandle, _: &Collider| accept(&self.parts[&handle]);
+        self.pipeline.intersections_with_shape(
+            &self.bodies,
+            &self.colliders,
+            pose,
+            shape,
+            QueryFilter::new().predicate(&predicate),
+            |handle| {
+                overlapping.push(handle);
+                true
+            },
+        );
+
+        let predicate = |handle: ColliderHandle, _: &Collider| {
+            !overlapping.contains(&handle) && accept(&self.parts[&handle])
+        };
+        let (handle, hit) = self.pipeline.cast_shape(
+            &self.bodies,
+            &self.colliders,
+            pose,
+            &direction,
+            shape,
+            ShapeCastOptions::with_max_time_of_impact(max_distance),
+            QueryFilter::new().predicate(&predicate),
+        )?;
+        Some((self.parts[&handle].instance.clone(), hit))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::lua::instance::InstanceData;
+
+    fn move_to(part: &Instance, position: Vector3) {
+        let mut data = part.data.lock().unwrap();
+        let part_data = data.part_data.as_mut().unwrap();
+        part_data.position = position;
+        part_data.cframe.position = position;
+    }
+
+    fn part(position: Vector3) -> Instance {
+        let part = Instance::from_data(InstanceData::new_part("Part"));
+        move_to(&part, position);
+        part
+    }
+
+    #[test]
+    fn test_upsert_tracks_moved_and_removed_parts() {
+        let a = part(Vector3::new(0.0, 0.0, 0.0));
+        let b = part(Vector3::new(10.0, 0.0, 0.0));
+        let mut index = SpatialIndex::default();
+        index.upsert(a.clone());
+        index.upsert(b.clone());
+        index.commit();
+
+        let probe = |index: &SpatialIndex, x: f32| {
+            let pose = part_pose(Vector3::new(x, 0.0, 0.0), [0.0, 0.0, 0.0, 1.0]);
+            index
+                .parts_in_shape(&pose, &Ball::new(1.0), |_| true, 0)
+                .iter()
+                .map(|part| part.id())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(probe(&index, 10.0), vec![b.id()]);
+
+        // Moves only show once the part is upserted again
+        move_to(&b, Vector3::new(20.0, 0.0, 0.0));
+        assert_eq!(probe(&index, 10.0), vec![b.id()]);
+        index.upsert(b.clone());
+        index.commit();
+        assert!(probe(&index, 10.0).is_empty());
+        assert_eq!(probe(&index, 20.0), vec![b.id()]);
+
+        index.remove(a.id().0);
+        index.commit();
+        assert!(probe(&index, 0.0).is_empty());
+        assert_eq!(index.handles.len(), 1);
+    }
+}
@@ -0,0 +1,630 @@
+//! TweenService: animates instance properties towards goal values over time.
+//!
+//! Tweens are stepped once per tick from `LuaRuntime::tick` and write their
+//! values through the regular property setters, so a tweened anchored part is
+//! moved kinematically by the next physics sync just like a scripted one.
+
+use mlua::{
+    IntoLua, Lua, MultiValue, ObjectLike, Result, Table, Thread, UserData, UserDataFields,
+    UserDataMethods, Value,
+};
+use std::sync::{Arc, Mutex, Weak};
+
+use crate::game::instance::ErrorMode;
+use crate::game::lua::events::{create_signal, RBXScriptSignal};
+use crate::game::lua::instance::Instance;
+use crate::game::lua::types::tween_info::ease;
+use crate::game::lua::types::{
+    CFrame, Color3, EasingDirection, EasingStyle, PlaybackState, TweenInfo, UDim2, Vector3,
+};
+
+/// A property value a tween can interpolate
+#[derive(Debug, Clone, Copy)]
+enum TweenValue {
+    Number(f64),
+    Vector3(Vector3),
+    CFrame(CFrame),
+    Color3(Color3),
+    UDim2(UDim2),
+}
+
+impl TweenValue {
+    fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Number(n) => Some(TweenValue::Number(*n)),
+            Value::Integer(n) => Some(TweenValue::Number(*n as f64)),
+            Value::UserData(ud) => {
+                if let Ok(v) = ud.borrow::<Vector3>() {
+                    Some(TweenValue::Vector3(*v))
+                } else if let Ok(v) = ud.borrow::<CFrame>() {
+                    Some(TweenValue::CFrame(*v))
+                } else if let Ok(v) = ud.borrow::<Color3>() {
+                    Some(TweenValue::Color3(*v))
+                } else if let Ok(v) = ud.borrow::<UDim2>() {
+                    Some(TweenValue::UDim2(*v))
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+
+    fn type_name(&self) -> &'static str {
+        match self {
+            TweenValue::Number(_) => "number",
+            TweenValue::Vector3(_) => "Vector3",
+            TweenValue::CFrame(_) => "CFrame",
+            TweenValue::Color3(_) => "Color3",
+            TweenValue::UDim2(_) => "UDim2",
+        }
+    }
+
+    /// Value at `alpha` between self (0) and goal (1); None if the types differ
+    fn lerp(&self, goal: &TweenValue, alpha: f32) -> Option<TweenValue> {
+        Some(match (self, goal) {
+            (TweenValue::Number(a), TweenValue::Number(b)) => {
+                TweenValue::Number(a + (b - a) * alpha as f64)
+            }
+            (TweenValue::Vector3(a), TweenValue::Vector3(b)) => {
+                TweenValue::Vector3(a.lerp(b, alpha))
+            }
+            (TweenValue::CFrame(a), TweenValue::CFrame(b)) => {
+                TweenValue::CFrame(slerp_cframe(a, b, alpha))
+            }
+            (TweenValue::Color3(a), TweenValue::Color3(b)) => TweenValue::Color3(a.lerp(b, alpha)),
+            (TweenValue::UDim2(a), TweenValue::UDim2(b)) => TweenValue::UDim2(a.lerp(b, alpha)),
+            _ => return None,
+        })
+    }
+}
+
+impl IntoLua for TweenValue {
+    fn into_lua(self, lua: &Lua) -> Result<Value> {
+        match self {
+            TweenValue::Number(n) => Ok(Value::Number(n)),
+            TweenValue::Vector3(v) => v.into_lua(lua),
+            TweenValue::CFrame(v) => v.into_lua(lua),
+            TweenValue::Color3(v) => v.into_lua(lua),
+            TweenValue::UDim2(v) => v.into_lua(lua),
+        }
+    }
+}
+
+/// Interpolates position linearly and rotation along the shortest arc, so a
+/// rotating tween keeps the part rigid (`CFrame:Lerp` blends matrix entries)
+fn slerp_cframe(a: &CFrame, b: &CFrame, alpha: f32) -> CFrame {
+    let qa = a.to_quaternion();
+    let mut qb = b.to_quaternion();
+    let mut dot: f32 = qa.iter().zip(&qb).map(|(x, y)| x * y).sum();
+    if dot < 0.0 {
+        qb = qb.map(|c| -c);
+        dot = -dot;
+    }
+
+    let quat = if dot > 0.9995 {
+        // Nearly parallel: normalized linear blend avoids dividing by sin(0)
+        let q: Vec<f32> = qa
+            .iter()
+            .zip(&qb)
+            .map(|(x, y)| x + (y - x) * alpha)
+            .collect();
+        let len = q.iter().map(|c| c * c).sum::<f32>().sqrt();
+        [q[0] / len, q[1] / len, q[2] / len, q[3] / len]
+    } else {
+        let theta = dot.clamp(-1.0, 1.0).acos();
+        let wa = ((1.0 - alpha) * theta).sin() / theta.sin();
+        let wb = (alpha * theta).sin() / theta.sin();
+        [
+            qa[0] * wa + qb[0] * wb,
+            qa[1] * wa + qb[1] * wb,
+            qa[2] * wa + qb[2] * wb,
+            qa[3] * wa + qb[3] * wb,
+        ]
+    };
+
+    let mut cframe = CFrame::from_quaternion(quat);
+    cframe.position = a.position.lerp(&b.position, alpha);
+    cframe
+}
+
+/// Where a tween is after `elapsed` seconds of playing
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Progress {
+    /// Linear progress towards the goal to apply (None while waiting out the first delay)
+    alpha: Option<f32>,
+    delayed: bool,
+    finished: bool,
+}
+
+fn progress(info: &TweenInfo, elapsed: f64) -> Progress {
+    let duration = info.duration as f64;
+    let delay = info.delay as f64;
+    let play_length = if info.reverses {
+        duration * 2.0
+    } else {
+        duration
+    };
+    let cycle = delay + play_length;
+    let end_alpha = if info.reverses { 0.0 } else { 1.0 };
+
+    let finished = Progress {
+        alpha: Some(end_alpha),
+        delayed: false,
+        finished: true,
+    };
+    if cycle <= 0.0 {
+        return finished;
+    }
+    if info.repeat_count >= 0 && elapsed >= cycle * (info.repeat_count as f64 + 1.0) {
+        return finished;
+    }
+
+    let index = (elapsed / cycle).floor();
+    let t = elapsed - index * cycle;
+    if t < delay {
+        // Between repeats the previous play's end value holds
+        return Progress {
+            alpha: (index > 0.0).then_some(end_alpha),
+            delayed: true,
+            finished: false,
+        };
+    }
+
+    let t = t - delay;
+    let alpha = if t < duration {
+        t / duration
+    } else {
+        1.0 - (t - duration) / duration
+    };
+    Progress {
+        alpha: Some(alpha as f32),
+        delayed: false,
+        finished: false,
+    }
+}
+
+struct TweenData {
+    instance: Instance,
+    info: TweenInfo,
+    /// Goal values sorted by property name, so writes happen in a stable order
+    goals: Vec<(String, TweenValue)>,
+    /// Property values when the tween last started playing, in `goals` order
+    starts: Vec<TweenValue>,
+    elapsed: f64,
+    state: PlaybackState,
+    completed: RBXScriptSignal,
+}
+
+/// A tween created by TweenService:Create
+#[derive(Clone)]
+pub struct Tween {
+    data: Arc<Mutex<TweenData>>,
+    service: Weak<Mutex<TweenServiceData>>,
+}
+
+impl Tween {
+    fn is_same(&self, other: &Tween) -> bool {
+        Arc::ptr_eq(&self.data, &other.data)
+    }
+
+    fn play(&self, lua: &Lua) -> Result<()> {
+        let Some(service) = self.service.upgrade() else {
+            return Ok(());
+        };
+
+        let (instance, names) = {
+            let mut data = self.data.lock().unwrap();
+            match data.state {
+                PlaybackState::Playing | PlaybackState::Delayed => return Ok(()),
+                PlaybackState::Paused => {
+                    data.state = PlaybackState::Playing;
+                    drop(data);
+                    service.lock().unwrap().playing.push(self.clone());
+                    return Ok(());
+                }
+                _ => {}
+            }
+            let names: Vec<String> = data.goals.iter().map(|(name, _)| name.clone()).collect();
+            (data.instance.clone(), names)
+        };
+
+        // Each play starts from the property values at that moment
+        let ud = lua.create_userdata(instance.clone())?;
+        let mut starts = Vec::with_capacity(names.len());
+        for name in &names {
+            let value: Value = ud.get(name.as_str())?;
+            let start = TweenValue::from_value(&value).ok_or_else(|| {
+                mlua::Error::runtime(format!("Tween:Play: {} can no longer be tweened", name))
+            })?;
+            starts.push(start);
+        }
+
+        {
+            let mut data = self.data.lock().unwrap();
+            data.starts = starts;
+            data.elapsed = 0.0;
+            data.state = if data.info.delay > 0.0 {
+                PlaybackState::Delayed
+            } else {
+                PlaybackState::Playing
+            };
+        }
+
+        // A newer tween takes over the properties it shares with older ones,
+        // which are cancelled
+        let mut service = service.lock().unwrap();
+        let mut cancelled = Vec::new();
+        service.playing.retain(|other| {
+            if other.is_same(self) {
+                return false;
+            }
+            let mut other_data = other.data.lock().unwrap();
+            let overlaps = other_data.instance.id() == instance.id()
+                && other_data
+                    .goals
+                    .iter()
+                    .any(|(name, _)| names.contains(name));
+            if overlaps {
+                other_data.state = PlaybackState::Cancelled;
+                other_data.elapsed = 0.0;
+                cancelled.push(other_data.completed.clone());
+            }
+            !overlaps
+        });
+        service.finished.extend(
+            cancelled
+                .into_iter()
+                .map(|signal| (signal, PlaybackState::Cancelled)),
+        );
+        service.playing.push(self.clone());
+        Ok(())
+    }
+
+    fn pause(&self) {
+        let mut data = self.data.lock().unwrap();
+        if !matches!(data.state, PlaybackState::Playing | PlaybackState::Delayed) {
+            return;
+        }
+        data.state = PlaybackState::Paused;
+        drop(data);
+        self.remove_from_service();
+    }
+
+    fn cancel(&self) {
+        let mut data = self.data.lock().unwrap();
+        if !matches!(
+            data.state,
+            PlaybackState::Playing | PlaybackState::Delayed | PlaybackState::Paused
+        ) {
+            return;
+        }
+        data.state = PlaybackState::Cancelled;
+        data.elapsed = 0.0;
+        let completed = data.completed.clone();
+        drop(data);
+        self.remove_from_service();
+        if let Some(service) = self.service.upgrade() {
+            service
+                .lock()
+                .unwrap()
+                .finished
+                .push((completed, PlaybackState::Cancelled));
+        }
+    }
+
+    fn remove_from_service(&self) {
+        if let Some(service) = self.service.upgrade() {
+            service
+                .lock()
+                .unwrap()
+                .playing
+                .retain(|other| !other.is_same(self));
+        }
+    }
+}
+
+impl UserData for Tween {
+    fn add_fields<F: UserDataFields<Self>>(fields: &mut F) {
+        fields.add_field_method_get("Instance", |_, this| {
+            Ok(this.data.lock().unwrap().instance.clone())
+        });
+        fields.add_field_method_get("TweenInfo", |_, this| Ok(this.data.lock().unwrap().info));
+        fields.add_field_method_get("PlaybackState", |_, this| {
+            Ok(this.data.lock().unwrap().state)
+        });
+        fields.add_field_method_get("Completed", |_, this| {
+            Ok(this.data.lock().unwrap().completed.clone())
+        });
+        fields.add_field_method_get("Name", |_, _| Ok("Tween".to_string()));
+        fields.add_field_method_get("ClassName", |_, _| Ok("Tween".to_string()));
+    }
+
+    fn add_methods<M: UserDataMethods<Self>>(methods: &mut M) {
+        methods.add_method("Play", |lua, this, ()| this.play(lua));
+        methods.add_method("Pause", |_, this, ()| {
+            this.pause();
+            Ok(())
+        });
+        methods.add_method("Cancel", |_, this, ()| {
+            this.cancel();
+            Ok(())
+        });
+    }
+}
+
+#[derive(Default)]
+pub struct TweenServiceData {
+    /// Tweens that are playing or waiting out their delay
+    playing: Vec<Tween>,
+    /// Completed signals to fire on the next step, with the state they report
+    finished: Vec<(RBXScriptSignal, PlaybackState)>,
+}
+
+#[derive(Clone, Default)]
+pub struct TweenService {
+    pub data: Arc<Mutex<TweenServiceData>>,
+}
+
+impl TweenService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn create(
+        &self,
+        lua: &Lua,
+        instance: Instance,
+        info: TweenInfo,
+        goals: Table,
+    ) -> Result<Tween> {
+        let ud = lua.create_userdata(instance.clone())?;
+        let mut parsed = Vec::new();
+        for pair in goals.pairs::<String, Value>() {
+            let (name, goal) = pair?;
+            let current: Value = ud.get(name.as_str())?;
+            let Some(current) = TweenValue::from_value(&current) else {
+                return Err(mlua::Error::runtime(format!(
+                    "TweenService:Create: {} has no tweenable property '{}'",
+                    instance.class_name().as_str(),
+                    name
+                )));
+            };
+            let goal = TweenValue::from_value(&goal)
+                .filter(|goal| std::mem::discriminant(goal) == std::mem::discriminant(&current))
+                .ok_or_else(|| {
+                    mlua::Error::runtime(format!(
+                        "TweenService:Create: property '{}' is a {}, but the goal is a {}",
+                        name,
+                        current.type_name(),
+                        TweenValue::from_value(&goal).map_or(goal.type_name(), |g| g.type_name())
+                    ))
+                })?;
+            parsed.push((name, goal));
+        }
+        parsed.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        Ok(Tween {
+            data: Arc::new(Mutex::new(TweenData {
+                instance,
+                info,
+                goals: parsed,
+                starts: Vec::new(),
+                elapsed: 0.0,
+                state: PlaybackState::Begin,
+                completed: create_signal("Completed"),
+            })),
+            service: Arc::downgrade(&self.data),
+        })
+    }
+
+    /// Advances every playing tween by `dt`, writes their values and fires
+    /// Completed for tweens that finished or were cancelled since the last step.
+    /// Returns the Completed callbacks that yielded.
+    pub fn step(&self, lua: &Lua, dt: f32) -> Result<Vec<Thread>> {
+        let playing = self.data.lock().unwrap().playing.clone();
+
+        for tween in playing {
+            let (instance, writes, finished) = {
+                let mut data = tween.data.lock().unwrap();
+                // Paused or cancelled by a callback earlier in this step
+                if !matches!(data.state, PlaybackState::Playing | PlaybackState::Delayed) {
+                    continue;
+                }
+                data.elapsed += dt as f64;
+                let progress = progress(&data.info, data.elapsed);
+                data.state = if progress.finished {
+                    PlaybackState::Completed
+                } else if progress.delayed {
+                    PlaybackState::Delayed
+                } else {
+                    PlaybackState::Playing
+                };
+
+                let writes: Vec<(String, TweenValue)> = match progress.alpha {
+                    Some(alpha) => {
+                        let eased = ease(alpha, data.info.easing_style, data.info.easing_direction);
+                        data.goals
+                            .iter()
+                            .zip(&data.starts)
+                            .filter_map(|((name, goal), start)| {
+                                Some((name.clone(), start.lerp(goal, eased)?))
+                            })
+                            .collect()
+                    }
+                    None => Vec::new(),
+                };
+                (
+                    data.instance.clone(),
+                    writes,
+                    progress.finished.then(|| data.completed.clone()),
+                )
+            };
+
+            if let Err(e) = write_values(lua, &instance, writes) {
+                let error_mode = lua
+                    .app_data_ref::<ErrorMode>()
+                    .map(|m| *m)
+                    .unwrap_or(ErrorMode::Continue);
+                if error_mode == ErrorMode::Halt {
+                    return Err(e);
+                }
+                eprintln!("[Lua Error] Tween of {}: {}", instance.full_name(), e);
+                tween.cancel();
+                continue;
+            }
+
+            if let Some(completed) = finished {
+                tween.remove_from_service();
+                self.data
+                    .lock()
+                    .unwrap()
+                    .finished
+                    .push((completed, PlaybackState::Completed));
+            }
+        }
+
+        let finished = std::mem::take(&mut self.data.lock().unwrap().finished);
+        let mut threads = Vec::new();
+        for (completed, state) in finished {
+            threads.extend(
+                completed.fire_as_coroutines(lua, MultiValue::from_iter([state.into_lua(lua)?]))?,
+            );
+        }
+        Ok(threads)
+    }
+}
+
+fn write_values(lua: &Lua, instance: &Instance, writes: Vec<(String, TweenValue)>) -> Result<()> {
+    if writes.is_empty() {
+        return Ok(());
+    }
+    let ud = lua.create_userdata(instance.clone())?;
+    for (name, value) in writes {
+        ud.set(name, value)?;
+    }
+    Ok(())
+}
+
+impl UserData for TweenService {
+    fn add_fields<F: UserDataFields<Self>>(fields: &mut F) {
+        fields.add_field_method_get("Name", |_, _| Ok("TweenService".to_string()));
+        fields.add_field_method_get("ClassName", |_, _| Ok("TweenService".to_string()));
+    }
+
+    fn add_methods<M: UserDataMethods<Self>>(methods: &mut M) {
+        methods.add_method(
+            "Create",
+            |lua, this, (instance, info, goals): (Instance, TweenInfo, Table)| {
+                this.create(lua, instance, info, goals)
+            },
+        );
+
+        methods.add_method(
+            "GetValue",
+            |_, _, (alpha, style, direction): (f32, EasingStyle, EasingDirection)| {
+                Ok(ease(alpha, style, direction))
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(duration: f32, repeat_count: i32, reverses: bool, delay: f32) -> TweenInfo {
+        TweenInfo {
+            duration,
+            repeat_count,
+            reverses,
+            delay,
+            ..TweenInfo::default()
+        }
+    }
+
+    #[test]
+    fn test_easing_endpoints() {
+        for style in EasingStyle::ALL {
+            for direction in [
+                EasingDirection::In,
+                EasingDirection::Out,
+                EasingDirection::InOut,
+            ] {
+                assert!(
+                    ease(0.0, style, direction).abs() < 1e-4,
+                    "{:?} {:?} at 0",
+                    style,
+                    direction
+                );
+                assert!(
+                    (ease(1.0, style, direction) - 1.0).abs() < 1e-4,
+                    "{:?} {:?} at 1",
+                    style,
+                    direction
+                );
+            }
+        }
+        assert!((ease(0.5, EasingStyle::Linear, EasingDirection::In) - 0.5).abs() < 1e-6);
+        assert!((ease(0.5, EasingStyle::Quad, EasingDirection::In) - 0.25).abs() < 1e-6);
+        assert!((ease(0.5, EasingStyle::Quad, EasingDirection::Out) - 0.75).abs() < 1e-6);
+        assert!((ease(0.5, EasingStyle::Cubic, EasingDirection::InOut) - 0.5).abs() < 1e-6);
+        // Back overshoots below the start when easing in
+        assert!(ease(0.2, EasingStyle::Back, EasingDirection::In) < 0.0);
+    }
+
+    #[test]
+    fn test_progress_repeats_and_reverses() {
+        let once = info(2.0, 0, false, 0.0);
+        assert_eq!(progress(&once, 1.0).alpha, Some(0.5));
+        assert!(progress(&once, 2.0).finished);
+
+        // Delay before each play; the first one holds the start value
+        let delayed = info(1.0, 1, false, 0.5);
+        assert_eq!(
+            progress(&delayed, 0.25),
+            Progress {
+                alpha: None,
+                delayed: true,
+                finished: false
+            }
+        );
+        assert_eq!(progress(&delayed, 1.0).alpha, Some(0.5));
+        assert_eq!(
+            progress(&delayed, 1.75),
+            Progress {
+                alpha: Some(1.0),
+                delayed: true,
+                finished: false
+            }
+        );
+        assert_eq!(progress(&delayed, 2.5).alpha, Some(0.5));
+        assert!(progress(&delayed, 3.0).finished);
+
+        // Reversing plays run back to the start
+        let reverses = info(1.0, 0, true, 0.0);
+        assert_eq!(progress(&reverses, 1.5).alpha, Some(0.5));
+        assert_eq!(
+            progress(&reverses, 2.0),
+            Progress {
+                alpha: Some(0.0),
+                delayed: false,
+                finished: true
+            }
+        );
+
+        let forever = info(1.0, -1, false, 0.0);
+        assert!(!progress(&forever, 1000.5).finished);
+    }
+
+    #[test]
+    fn test_slerp_cframe_keeps_rotation_rigid() {
+        let a = CFrame::identity();
+        let mut b = CFrame::from_euler_angles_xyz(0.0, std::f32::consts::PI, 0.0);
+        b.position = Vector3::new(10.0, 0.0, 0.0);
+        let mid = slerp_cframe(&a, &b, 0.5);
+        assert!((mid.position.x - 5.0).abs() < 1e-4);
+        // Halfway through a half turn is a quarter turn, still a unit look vector
+        assert!((mid.look_vector().magnitude() - 1.0).abs() < 1e-4);
+    }
+}
@@ -1,9 +1,16 @@
 use mlua::{FromLua, Lua, Result, UserData, UserDataFields, UserDataMethods, Value};
+use rapier3d::na::{Point3, Vector3 as NaVector3};
+use rapier3d::parry::query::{intersection_test, Ray};
+use rapier3d::prelude::{Aabb, Ball, Cuboid, Isometry, PointQuery, Real, Shape, SharedShape};
 use std::sync::{Arc, Mutex};
 
 use crate::game::constants::physics as consts;
-use crate::game::lua::instance::{ClassName, Instance};
+use crate::game::lua::instance::{ClassName, Instance, InstanceId};
+use crate::game::physics::part_shape;
 use crate::game::lua::types::{CFrame, RaycastFilterType, Vector3};
+use super::physics_service::{CollisionGroups, CollisionGroupsHandle, DEFAULT_COLLISION_GROUP};
+use super::spatial_index::{part_pose, IndexedPart};
+use super::workspace_index::WorkspaceIndexHandle;
 
 #[derive(Clone)]
 pub struct RaycastResult {
@@ -36,7 +43,7 @@ impl Default for RaycastParams {
             filter_type: RaycastFilterType::Exclude,
             filter_instances: Vec::new(),
             ignore_water: false,
-            collision_group: "Default".to_string(),
+            collision_group: DEFAULT_COLLISION_GROUP.to_string(),
         }
     }
 }
@@ -100,6 +107,153 @@ pub fn register_raycast_params(lua: &Lua) -> Result<()> {
     Ok(())
 }
 
+#[derive(Clone)]
+pub struct OverlapParams {
+    pub filter_type: RaycastFilterType,
+    pub filter_instances: Vec<Instance>,
+    /// Most parts a query returns; 0 means no limit
+    pub max_parts: usize,
+    pub collision_group: String,
+    /// Skip parts with CanCollide=false
+    pub respect_can_collide: bool,
+}
+
+impl Default for OverlapParams {
+    fn default() -> Self {
+        Self {
+            filter_type: RaycastFilterType::Exclude,
+            filter_instances: Vec::new(),
+            max_parts: 0,
+            collision_group: DEFAULT_COLLISION_GROUP.to_string(),
+            respect_can_collide: false,
+        }
+    }
+}
+
+impl FromLua for OverlapParams {
+    fn from_lua(value: Value, _lua: &Lua) -> Result<Self> {
+        match value {
+            Value::UserData(ud) => ud.borrow::<OverlapParams>().map(|v| v.clone()),
+            _ => Err(mlua::Error::FromLuaConversionError {
+                from: value.type_name(),
+                to: "OverlapParams".to_string(),
+                message: Some("expected OverlapParams".to_string()),
+            }),
+        }
+    }
+}
+
+impl UserData for OverlapParams {
+    fn add_fields<F: UserDataFields<Self>>(fields: &mut F) {
+        fields.add_field_method_get("FilterType", |_, this| Ok(this.filter_type));
+        fields.add_field_method_set("FilterType", |_, this, filter_type: RaycastFilterType| {
+            this.filter_type = filter_type;
+            Ok(())
+        });
+
+        fields.add_field_method_get("FilterDescendantsInstances", |_, this| {
+            Ok(this.filter_instances.clone())
+        });
+        fields.add_field_method_set(
+            "FilterDescendantsInstances",
+            |_, this, instances: Vec<Instance>| {
+                this.filter_instances = instances;
+                Ok(())
+            },
+        );
+
+        fields.add_field_method_get("MaxParts", |_, this| Ok(this.max_parts));
+        fields.add_field_method_set("MaxParts", |_, this, max_parts: i64| {
+            if max_parts < 0 {
+                return Err(mlua::Error::runtime("MaxParts cannot be negative"));
+            }
+            this.max_parts = max_parts as usize;
+            Ok(())
+        });
+
+        fields.add_field_method_get("CollisionGroup", |_, this| Ok(this.collision_group.clone()));
+        fields.add_field_method_set("CollisionGroup", |_, this, collision_group: String| {
+            this.collision_group = collision_group;
+            Ok(())
+        });
+
+        fields.add_field_method_get("RespectCanCollide", |_, this| Ok(this.respect_can_collide));
+        fields.add_field_method_set("RespectCanCollide", |_, this, respect_can_collide: bool| {
+            this.respect_can_collide = respect_can_collide;
+            Ok(())
+        });
+    }
+}
+
+pub fn register_overlap_params(lua: &Lua) -> Result<()> {
+    let params_table = lua.create_table()?;
+
+    params_table.set(
+        "new",
+        lua.create_function(|_, ()| Ok(OverlapParams::default()))?,
+    )?;
+
+    lua.globals().set("OverlapParams", params_table)?;
+
+    Ok(())
+}
+
+/// Whether a RaycastParams/OverlapParams filter list drops an instance
+fn filtered_out(filter_type: RaycastFilterType, filter_instances: &[Instance], instance: &Instance) -> bool {
+    let listed = filter_instances
+        .iter()
+        .any(|i| i.id() == instance.id() || instance.is_descendant_of(i));
+    match filter_type {
+        RaycastFilterType::Exclude => listed,
+        RaycastFilterType::Include => !listed,
+    }
+}
+
+/// Which parts a shape cast or overlap query may return
+struct QueryRules<'a> {
+    filter_type: RaycastFilterType,
+    filter_instances: &'a [Instance],
+    collision_group: &'a str,
+    respect_can_collide: bool,
+    /// Part the query shape was taken from, which never finds itself
+    source: Option<InstanceId>,
+}
+
+impl RaycastParams {
+    /// Shape casts follow Raycast, which never hits CanCollide=false parts
+    fn rules(&self, source: Option<InstanceId>) -> QueryRules<'_> {
+        QueryRules {
+            filter_type: self.filter_type,
+            filter_instances: &self.filter_instances,
+            collision_group: &self.collision_group,
+            respect_can_collide: true,
+            source,
+        }
+    }
+}
+
+impl OverlapParams {
+    fn rules(&self, source: Option<InstanceId>) -> QueryRules<'_> {
+        QueryRules {
+            filter_type: self.filter_type,
+            filter_instances: &self.filter_instances,
+            collision_group: &self.collision_group,
+            respect_can_collide: self.respect_can_collide,
+            source,
+        }
+    }
+}
+
+/// Collision shape and pose of a part, for queries shaped like it
+fn part_geometry(part: &Instance) -> Option<(SharedShape, Isometry<Real>)> {
+    let data = part.data.lock().unwrap();
+    let part = data.part_data.as_ref()?;
+    Some((
+        part_shape(part.shape, part.size.to_array()),
+        part_pose(part.position, part.cframe.to_quaternion()),
+    ))
+}
+
 pub struct WorkspaceServiceData {
     pub gravity: f32,
     pub current_camera: Option<Instance>,
@@ -120,14 +274,23 @@ impl WorkspaceServiceData {
 pub struct WorkspaceService {
     pub instance: Instance,
     pub data: Arc<Mutex<WorkspaceServiceData>>,
+    /// PhysicsService's collision groups, which raycasts respect
+    pub collision_groups: CollisionGroupsHandle,
+    /// Parts, Humanoids and joints in the workspace; the parts back raycasts,
+    /// shape casts and overlap queries
+    pub index: WorkspaceIndexHandle,
 }
 
 impl WorkspaceService {
-    pub fn new() -> Self {
+    pub fn new(collision_groups: CollisionGroupsHandle) -> Self {
         let instance = Instance::new(ClassName::Workspace, "Workspace");
+        let index = WorkspaceIndexHandle::default();
+        instance.data.lock().unwrap().workspace_index = Some(index.clone());
         Self {
             instance,
             data: Arc::new(Mutex::new(WorkspaceServiceData::new())),
+            collision_groups,
+            index,
         }
     }
 
@@ -152,177 +315,182 @@ impl WorkspaceService {
     ) -> Option<RaycastResult> {
         let params = params.unwrap_or_default();
         let ray_length = direction.magnitude();
+        if ray_length == 0.0 {
+            return None;
+        }
         let ray_dir = direction.unit();
+        let ray = Ray::new(
+            Point3::new(origin.x, origin.y, origin.z),
+            NaVector3::new(ray_dir.x, ray_dir.y, ray_dir.z),
+        );
 
-        let mut closest: Option<(f32, Instance, Vector3)> = None;
-
-        for descendant in self.get_descendants() {
-            // Extract part data while holding the lock
-            let part_info = {
-                let data = descendant.data.lock().unwrap();
-                data.part_data.as_ref().map(|part| {
-                    (part.can_collide, part.size, part.position)
-                })
-            }; // Lock released here
-
-            let Some((can_collide, size, position)) = part_info else {
-                continue;
-            };
+        // Tested against the parts' true collision shapes (same as their physics colliders);
+        // rays starting inside a part don't hit it (Roblox behavior)
+        let rules = params.rules(None);
+        let groups = self.collision_groups.lock().unwrap();
+        let mut index = self.index.lock().unwrap();
+        let (instance, hit) = index
+            .spatial()
+            .cast_ray(&ray, ray_length, |part| self.accepts(&groups, &rules, part))?;
+
+        let dist = hit.time_of_impact;
+        Some(RaycastResult {
+            instance,
+            position: Vector3::new(
+                origin.x + ray_dir.x * dist,
+                origin.y + ray_dir.y * dist,
+                origin.z + ray_dir.z * dist,
+            ),
+            normal: Vector3::new(hit.normal.x, hit.normal.y, hit.normal.z),
+            distance: dist,
+        })
+    }
 
-            if !can_collide {
-                continue;
-            }
+    /// Whether a spatial query following `rules` may return an indexed part
+    fn accepts(&self, groups: &CollisionGroups, rules: &QueryRules, part: &IndexedPart) -> bool {
+        if rules.respect_can_collide && !part.can_collide {
+            return false;
+        }
+        if !groups.collidable(rules.collision_group, &part.collision_group) {
+            return false;
+        }
+        if rules.source == Some(part.instance.id()) {
+            return false;
+        }
+        !filtered_out(rules.filter_type, rules.filter_instances, &part.instance)
+    }
 
-            // Now check filtering without holding any lock
-            let should_filter = params.filter_instances.iter().any(|i| {
-                i.id() == descendant.id() || descendant.is_descendant_of(i)
-            });
+    /// Sweeps a shape along `direction` and returns the first part it hits.
+    /// Parts the shape overlaps at its start are not hit (like rays starting inside a part).
+    fn cast(
+        &self,
+        shape: &dyn Shape,
+        pose: Isometry<Real>,
+        direction: Vector3,
+        rules: QueryRules,
+    ) -> Option<RaycastResult> {
+        let distance = direction.magnitude();
+        if distance == 0.0 {
+            return None;
+        }
+        let dir = direction.unit();
+        let groups = self.collision_groups.lock().unwrap();
+        let mut index = self.index.lock().unwrap();
+        let (instance, hit) = index.spatial().cast_shape(
+            &pose,
+            shape,
+            NaVector3::new(dir.x, dir.y, dir.z),
+            distance,
+            |part| self.accepts(&groups, &rules, part),
+        )?;
+        Some(RaycastResult {
+            instance,
+            position: Vector3::new(hit.witness1.x, hit.witness1.y, hit.witness1.z),
+            normal: Vector3::new(hit.normal1.x, hit.normal1.y, hit.normal1.z),
+            distance: hit.time_of_impact,
+        })
+    }
 
-            let skip = match params.filter_type {
-                RaycastFilterType::Exclude => should_filter,
-                RaycastFilterType::Include => !should_filter,
-            };
+    pub fn spherecast(
+        &self,
+        position: Vector3,
+        radius: f32,
+        direction: Vector3,
+        params: Option<RaycastParams>,
+    ) -> Option<RaycastResult> {
+        let params = params.unwrap_or_default();
+        let pose = part_pose(position, [0.0, 0.0, 0.0, 1.0]);
+        self.cast(&Ball::new(radius), pose, direction, params.rules(None))
+    }
 
-            if skip {
-                continue;
-            }
+    pub fn blockcast(
+        &self,
+        cframe: CFrame,
+        size: Vector3,
+        direction: Vector3,
+        params: Option<RaycastParams>,
+    ) -> Option<RaycastResult> {
+        let params = params.unwrap_or_default();
+        let half = NaVector3::new(size.x / 2.0, size.y / 2.0, size.z / 2.0);
+        let pose = part_pose(cframe.position, cframe.to_quaternion());
+        self.cast(&Cuboid::new(half), pose, direction, params.rules(None))
+    }
 
-            let half_size = Vector3::new(
-                size.x / 2.0,
-                size.y / 2.0,
-                size.z / 2.0,
-            );
-
-            let center = position;
-
-            // Slab intersection with proper handling for zero direction components
-            // When ray is parallel to a slab (dir component = 0):
-            // - If origin is outside the slab, no intersection (t_near > t_far)
-            // - If origin is inside the slab, the slab doesn't constrain t (use -inf to +inf)
-            let (t1_x, t2_x) = if ray_dir.x.abs() < 1e-10 {
-                // Ray parallel to YZ plane
-                if origin.x < center.x - half_size.x || origin.x > center.x + half_size.x {
-                    (f32::INFINITY, f32::NEG_INFINITY) // No intersection
-                } else {
-                    (f32::NEG_INFINITY, f32::INFINITY) // Always inside this slab
-                }
-            } else {
-                let t_min = (center.x - half_size.x - origin.x) / ray_dir.x;
-                let t_max = (center.x + half_size.x - origin.x) / ray_dir.x;
-                (t_min.min(t_max), t_min.max(t_max))
-            };
-
-            let (t1_y, t2_y) = if ray_dir.y.abs() < 1e-10 {
-                // Ray parallel to XZ plane
-                if origin.y < center.y - half_size.y || origin.y > center.y + half_size.y {
-                    (f32::INFINITY, f32::NEG_INFINITY) // No intersection
-                } else {
-                    (f32::NEG_INFINITY, f32::INFINITY) // Always inside this slab
-                }
-            } else {
-                let t_min = (center.y - half_size.y - origin.y) / ray_dir.y;
-                let t_max = (center.y + half_size.y - origin.y) / ray_dir.y;
-                (t_min.min(t_max), t_min.max(t_max))
-            };
-
-            let (t1_z, t2_z) = if ray_dir.z.abs() < 1e-10 {
-                // Ray parallel to XY plane
-                if origin.z < center.z - half_size.z || origin.z > center.z + half_size.z {
-                    (f32::INFINITY, f32::NEG_INFINITY) // No intersection
-                } else {
-                    (f32::NEG_INFINITY, f32::INFINITY) // Always inside this slab
-                }
-            } else {
-                let t_min = (center.z - half_size.z - origin.z) / ray_dir.z;
-                let t_max = (center.z + half_size.z - origin.z) / ray_dir.z;
-                (t_min.min(t_max), t_min.max(t_max))
-            };
-
-            let t_near = t1_x.max(t1_y).max(t1_z);
-            let t_far = t2_x.min(t2_y).min(t2_z);
-
-            if t_near <= t_far && t_near >= 0.0 && t_near <= ray_length {
-                let hit_pos = Vector3::new(
-                    origin.x + ray_dir.x * t_near,
-                    origin.y + ray_dir.y * t_near,
-                    origin.z + ray_dir.z * t_near,
-                );
-
-                let epsilon = 0.001;
-                let normal = if (hit_pos.x - (center.x - half_size.x)).abs() < epsilon {
-                    Vector3::new(-1.0, 0.0, 0.0)
-                } else if (hit_pos.x - (center.x + half_size.x)).abs() < epsilon {
-                    Vector3::new(1.0, 0.0, 0.0)
-                } else if (hit_pos.y - (center.y - half_size.y)).abs() < epsilon {
-                    Vector3::new(0.0, -1.0, 0.0)
-                } else if (hit_pos.y - (center.y + half_size.y)).abs() < epsilon {
-                    Vector3::new(0.0, 1.0, 0.0)
-                } else if (hit_pos.z - (center.z - half_size.z)).abs() < epsilon {
-                    Vector3::new(0.0, 0.0, -1.0)
-                } else {
-                    Vector3::new(0.0, 0.0, 1.0)
-                };
-
-                if closest.is_none() || t_near < closest.as_ref().unwrap().0 {
-                    closest = Some((t_near, descendant.clone(), normal));
-                }
-            }
-        }
+    /// Sweeps a part's collision shape from where it is; the part itself is never hit
+    pub fn shapecast(
+        &self,
+        part: &Instance,
+        direction: Vector3,
+        params: Option<RaycastParams>,
+    ) -> Option<RaycastResult> {
+        let params = params.unwrap_or_default();
+        let (shape, pose) = part_geometry(part)?;
+        self.cast(&*shape, pose, direction, params.rules(Some(part.id())))
+    }
 
-        closest.map(|(dist, instance, normal)| {
-            let position = Vector3::new(
-                origin.x + ray_dir.x * dist,
-                origin.y + ray_dir.y * dist,
-                origin.z + ray_dir.z * dist,
-            );
-            RaycastResult {
-                instance,
-                position,
-                normal,
-                distance: dist,
-            }
-        })
+    /// Parts whose collision shape intersects the given part's (not the part itself)
+    pub fn get_parts_in_part(&self, part: &Instance, params: Option<OverlapParams>) -> Vec<Instance> {
+        let params = params.unwrap_or_default();
+        let Some((shape, pose)) = part_geometry(part) else {
+            return Vec::new();
+        };
+        let rules = params.rules(Some(part.id()));
+        let groups = self.collision_groups.lock().unwrap();
+        let mut index = self.index.lock().unwrap();
+        index.spatial().parts_in_shape(
+            &pose,
+            &*shape,
+            |part| self.accepts(&groups, &rules, part),
+            params.max_parts,
+        )
     }
 
-    pub fn get_part_bounds_in_box(&self, cframe: CFrame, size: Vector3) -> Vec<Instance> {
-        let center = cframe.position;
-        let half = Vector3::new(size.x / 2.0, size.y / 2.0, size.z / 2.0);
-
-        self.get_descendants()
-            .into_iter()
-            .filter(|inst| {
-                let data = inst.data.lock().unwrap();
-                if let Some(part) = &data.part_data {
-                    let pos = part.position;
-                    pos.x >= center.x - half.x
-                        && pos.x <= center.x + half.x
-                        && pos.y >= center.y - half.y
-                        && pos.y <= center.y + half.y
-                        && pos.z >= center.z - half.z
-                        && pos.z <= center.z + half.z
-                } else {
-                    false
-                }
-            })
-            .collect()
+    /// Parts whose bounding box overlaps the box at `cframe`
+    pub fn get_part_bounds_in_box(
+        &self,
+        cframe: CFrame,
+        size: Vector3,
+        params: Option<OverlapParams>,
+    ) -> Vec<Instance> {
+        let params = params.unwrap_or_default();
+        let query_box = Cuboid::new(NaVector3::new(size.x / 2.0, size.y / 2.0, size.z / 2.0));
+        let pose = part_pose(cframe.position, cframe.to_quaternion());
+        let overlaps = |bounds: &Aabb| {
+            let bounds_pose = Isometry::translation(bounds.center().x, bounds.center().y, bounds.center().z);
+            intersection_test(&pose, &query_box, &bounds_pose, &Cuboid::new(bounds.half_extents()))
+                .unwrap_or(false)
+        };
+        let rules = params.rules(None);
+        let groups = self.collision_groups.lock().unwrap();
+        let mut index = self.index.lock().unwrap();
+        index.spatial().parts_with_bounds(
+            &query_box.aabb(&pose),
+            overlaps,
+            |part| self.accepts(&groups, &rules, part),
+            params.max_parts,
+        )
     }
 
-    pub fn get_part_bounds_in_radius(&self, position: Vector3, radius: f32) -> Vec<Instance> {
-        let radius_sq = radius * radius;
-        self.get_descendants()
-            .into_iter()
-            .filter(|inst| {
-                let data = inst.data.lock().unwrap();
-                if let Some(part) = &data.part_data {
-                    let dx = part.position.x - position.x;
-                    let dy = part.position.y - position.y;
-                    let dz = part.position.z - position.z;
-                    dx * dx + dy * dy + dz * dz <= radius_sq
-                } else {
-                    false
-                }
-            })
-            .collect()
+    /// Parts whose bounding box overlaps the sphere around `position`
+    pub fn get_part_bounds_in_radius(
+        &self,
+        position: Vector3,
+        radius: f32,
+        params: Option<OverlapParams>,
+    ) -> Vec<Instance> {
+        let params = params.unwrap_or_default();
+        let center = Point3::new(position.x, position.y, position.z);
+        let query_bounds = Aabb::from_half_extents(center, NaVector3::repeat(radius));
+        let overlaps = |bounds: &Aabb| bounds.distance_to_local_point(&center, true) <= radius;
+        let rules = params.rules(None);
+        let groups = self.collision_groups.lock().unwrap();
+        let mut index = self.index.lock().unwrap();
+        index.spatial().parts_with_bounds(
+            &query_bounds,
+            overlaps,
+            |part| self.accepts(&groups, &rules, part),
+            params.max_parts,
+        )
     }
 }
 
@@ -350,17 +518,51 @@ impl UserData for WorkspaceService {
             },
         );
 
+        methods.add_method(
+            "Spherecast",
+            |_, this, (position, radius, direction, params): (Vector3, f32, Vector3, Option<RaycastParams>)| {
+                Ok(this.spherecast(position, radius, direction, params))
+            },
+        );
+
+        methods.add_method(
+            "Blockcast",
+            |_, this, (cframe, size, direction, params): (CFrame, Vector3, Vector3, Option<RaycastParams>)| {
+                Ok(this.blockcast(cframe, size, direction, params))
+            },
+        );
+
+        methods.add_method(
+            "Shapecast",
+            |_, this, (part, direction, params): (Instance, Vector3, Option<RaycastParams>)| {
+                if part.data.lock().unwrap().part_data.is_none() {
+                    return Err(mlua::Error::runtime("Shapecast expects a BasePart"));
+                }
+                Ok(this.shapecast(&part, direction, params))
+            },
+        );
+
+        methods.add_method(
+            "GetPartsInPart",
+            |_, this, (part, params): (Instance, Option<OverlapParams>)| {
+                if part.data.lock().unwrap().part_data.is_none() {
+                    return Err(mlua::Error::runtime("GetPartsInPart expects a BasePart"));
+                }
+                Ok(this.get_parts_in_part(&part, params))
+            },
+        );
+
         methods.add_method(
             "GetPartBoundsInBox",
-            |_, this, (cframe, size): (CFrame, Vector3)| {
-                Ok(this.get_part_bounds_in_box(cframe, size))
+            |_, this, (cframe, size, params): (CFrame, Vector3, Option<OverlapParams>)| {
+                Ok(this.get_part_bounds_in_box(cframe, size, params))
             },
         );
 
         methods.add_method(
             "GetPartBoundsInRadius",
-            |_, this, (position, radius): (Vector3, f32)| {
-                Ok(this.get_part_bounds_in_radius(position, radius))
+            |_, this, (position, radius, params): (Vector3, f32, Option<OverlapParams>)| {
+                Ok(this.get_part_bounds_in_radius(position, radius, params))
             },
         );
 
@@ -0,0 +1,113 @@
+//! Parts, Humanoids and joints in the workspace, so the game loop and spatial
+//! queries don't walk the Instance tree.
+//!
+//! Like CollectionService's tag index, the index is carried by the Workspace
+//! instance and kept current by `Instance::set_parent`. Parts whose position,
+//! shape or query filters change are marked dirty by the property setters
+//! (see `reflection`) and the game loop's physics sync; only those are moved
+//! in the spatial index, right before the next query.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::{Arc, Mutex};
+
+use super::spatial_index::SpatialIndex;
+use crate::game::lua::instance::{Instance, InstanceId, WeakInstanceRef};
+
+#[derive(Default)]
+pub struct WorkspaceIndex {
+    /// Keyed by id, so passes over them run in creation order
+    parts: BTreeMap<InstanceId, WeakInstanceRef>,
+    humanoids: BTreeMap<InstanceId, WeakInstanceRef>,
+    /// Welds and constraints
+    joints: BTreeMap<InstanceId, WeakInstanceRef>,
+    /// Parts added, changed or removed since the spatial index was last refreshed
+    dirty: BTreeSet<InstanceId>,
+    /// Parts that left the workspace since the last touch dispatch, held so
+    /// the parts they touched can still be told which part stopped touching
+    departed: BTreeMap<InstanceId, Instance>,
+    spatial: SpatialIndex,
+}
+
+pub type WorkspaceIndexHandle = Arc<Mutex<WorkspaceIndex>>;
+
+impl WorkspaceIndex {
+    /// Indexes `root` and its descendants (it entered the workspace)
+    pub fn insert_tree(&mut self, root: &Instance) {
+        for instance in std::iter::once(root.clone()).chain(root.get_descendants()) {
+            let (id, part, humanoid, joint) = {
+                let data = instance.data.lock().unwrap();
+                (
+                    data.id,
+                    data.part_data.is_some(),
+                    data.humanoid_data.is_some(),
+                    data.weld_data.is_some() || data.constraint_data.is_some(),
+                )
+            };
+            if part {
+                self.parts.insert(id, instance.weak_ref());
+                self.dirty.insert(id);
+                self.departed.remove(&id);
+            }
+            if humanoid {
+                self.humanoids.insert(id, instance.weak_ref());
+            }
+            if joint {
+                self.joints.insert(id, instance.weak_ref());
+            }
+        }
+    }
+
+    /// Drops `root` and its descendants (it left the workspace)
+    pub fn remove_tree(&mut self, root: &Instance) {
+        for instance in std::iter::once(root.clone()).chain(root.get_descendants()) {
+            let id = instance.id();
+            if self.parts.remove(&id).is_some() {
+                self.dirty.insert(id);
+                self.departed.insert(id, instance);
+            }
+            self.humanoids.remove(&id);
+            self.joints.remove(&id);
+        }
+    }
+
+    /// Queues a part for a refresh in the spatial index
+    pub fn mark_dirty(&mut self, id: InstanceId) {
+        if self.parts.contains_key(&id) {
+            self.dirty.insert(id);
+        }
+    }
+
+    /// The part with the given Lua instance ID, if it is in the workspace
+    pub fn part(&self, lua_id: u64) -> Option<Instance> {
+        upgrade(self.parts.get(&InstanceId(lua_id))?)
+    }
+
+    /// Takes the parts that left the workspace since the last call
+    pub fn take_departed(&mut self) -> BTreeMap<InstanceId, Instance> {
+        std::mem::take(&mut self.departed)
+    }
+
+    pub fn humanoids(&self) -> Vec<Instance> {
+        self.humanoids.values().filter_map(upgrade).collect()
+    }
+
+    pub fn joints(&self) -> Vec<Instance> {
+        self.joints.values().filter_map(upgrade).collect()
+    }
+
+    /// The spatial index, with the dirty parts brought up to date
+    pub fn spatial(&mut self) -> &SpatialIndex {
+        for id in std::mem::take(&mut self.dirty) {
+            match self.parts.get(&id).and_then(upgrade) {
+                Some(part) => self.spatial.upsert(part),
+                None => self.spatial.remove(id.0),
+            }
+        }
+        self.spatial.commit();
+        &self.spatial
+    }
+}
+
+fn upgrade(weak: &WeakInstanceRef) -> Option<Instance> {
+    weak.upgrade().map(Instance::from_ref)
+}
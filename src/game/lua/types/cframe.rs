@@ -1,8 +1,9 @@
 use mlua::{FromLua, Lua, MetaMethod, Result, UserData, UserDataFields, UserDataMethods, Value};
+use serde::{Deserialize, Serialize};
 
 use super::vector3::Vector3;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct CFrame {
     pub position: Vector3,
     pub rotation: [[f32; 3]; 3],
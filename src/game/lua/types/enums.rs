@@ -1,6 +1,7 @@
 use mlua::{FromLua, Lua, Result, UserData, UserDataMethods, Value};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum PartType {
     Ball,
     Block,
@@ -9,6 +10,13 @@ pub enum PartType {
 }
 
 impl PartType {
+    pub const ALL: [PartType; 4] = [
+        PartType::Ball,
+        PartType::Block,
+        PartType::Cylinder,
+        PartType::Wedge,
+    ];
+
     pub fn name(&self) -> &'static str {
         match self {
             PartType::Ball => "Ball",
@@ -52,7 +60,7 @@ impl UserData for PartType {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Material {
     Plastic,
     Wood,
@@ -70,9 +78,33 @@ pub enum Material {
     Slate,
     SmoothPlastic,
     ForceField,
+    Water,
+    /// No material: a Humanoid's FloorMaterial while it is off the ground
+    Air,
 }
 
 impl Material {
+    pub const ALL: [Material; 18] = [
+        Material::Plastic,
+        Material::Wood,
+        Material::Metal,
+        Material::Glass,
+        Material::Neon,
+        Material::Concrete,
+        Material::Brick,
+        Material::Granite,
+        Material::Grass,
+        Material::Ice,
+        Material::Sand,
+        Material::Fabric,
+        Material::Marble,
+        Material::Slate,
+        Material::SmoothPlastic,
+        Material::ForceField,
+        Material::Water,
+        Material::Air,
+    ];
+
     pub fn name(&self) -> &'static str {
         match self {
             Material::Plastic => "Plastic",
@@ -91,6 +123,8 @@ impl Material {
             Material::Slate => "Slate",
             Material::SmoothPlastic => "SmoothPlastic",
             Material::ForceField => "ForceField",
+            Material::Water => "Water",
+            Material::Air => "Air",
         }
     }
 
@@ -112,6 +146,8 @@ impl Material {
             Material::Slate => 800,
             Material::SmoothPlastic => 272,
             Material::ForceField => 1584,
+            Material::Water => 2048,
+            Material::Air => 1792,
         }
     }
 }
@@ -145,17 +181,32 @@ pub enum HumanoidStateType {
     Running,
     Jumping,
     Freefall,
+    Landed,
+    Swimming,
     Dead,
     Physics,
     None,
 }
 
 impl HumanoidStateType {
+    pub const ALL: [HumanoidStateType; 8] = [
+        HumanoidStateType::Running,
+        HumanoidStateType::Jumping,
+        HumanoidStateType::Freefall,
+        HumanoidStateType::Landed,
+        HumanoidStateType::Swimming,
+        HumanoidStateType::Dead,
+        HumanoidStateType::Physics,
+        HumanoidStateType::None,
+    ];
+
     pub fn name(&self) -> &'static str {
         match self {
             HumanoidStateType::Running => "Running",
             HumanoidStateType::Jumping => "Jumping",
             HumanoidStateType::Freefall => "Freefall",
+            HumanoidStateType::Landed => "Landed",
+            HumanoidStateType::Swimming => "Swimming",
             HumanoidStateType::Dead => "Dead",
             HumanoidStateType::Physics => "Physics",
             HumanoidStateType::None => "None",
@@ -195,6 +246,11 @@ pub enum RaycastFilterType {
 }
 
 impl RaycastFilterType {
+    pub const ALL: [RaycastFilterType; 2] = [
+        RaycastFilterType::Include,
+        RaycastFilterType::Exclude,
+    ];
+
     pub fn name(&self) -> &'static str {
         match self {
             RaycastFilterType::Include => "Include",
@@ -228,49 +284,643 @@ impl UserData for RaycastFilterType {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EasingStyle {
+    Linear,
+    Sine,
+    Back,
+    Quad,
+    Quart,
+    Quint,
+    Bounce,
+    Elastic,
+    Exponential,
+    Circular,
+    Cubic,
+}
+
+impl EasingStyle {
+    pub const ALL: [EasingStyle; 11] = [
+        EasingStyle::Linear,
+        EasingStyle::Sine,
+        EasingStyle::Back,
+        EasingStyle::Quad,
+        EasingStyle::Quart,
+        EasingStyle::Quint,
+        EasingStyle::Bounce,
+        EasingStyle::Elastic,
+        EasingStyle::Exponential,
+        EasingStyle::Circular,
+        EasingStyle::Cubic,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            EasingStyle::Linear => "Linear",
+            EasingStyle::Sine => "Sine",
+            EasingStyle::Back => "Back",
+            EasingStyle::Quad => "Quad",
+            EasingStyle::Quart => "Quart",
+            EasingStyle::Quint => "Quint",
+            EasingStyle::Bounce => "Bounce",
+            EasingStyle::Elastic => "Elastic",
+            EasingStyle::Exponential => "Exponential",
+            EasingStyle::Circular => "Circular",
+            EasingStyle::Cubic => "Cubic",
+        }
+    }
+}
+
+impl FromLua for EasingStyle {
+    fn from_lua(value: Value, _lua: &Lua) -> Result<Self> {
+        match value {
+            Value::UserData(ud) => ud.borrow::<EasingStyle>().map(|v| *v),
+            _ => Err(mlua::Error::FromLuaConversionError {
+                from: value.type_name(),
+                to: "EasingStyle".to_string(),
+                message: Some("expected EasingStyle".to_string()),
+            }),
+        }
+    }
+}
+
+impl UserData for EasingStyle {
+    fn add_methods<M: UserDataMethods<Self>>(methods: &mut M) {
+        methods.add_meta_method(mlua::MetaMethod::ToString, |_, this, ()| {
+            Ok(format!("Enum.EasingStyle.{}", this.name()))
+        });
+        methods.add_meta_method(mlua::MetaMethod::Eq, |_, this, other: EasingStyle| {
+            Ok(*this == other)
+        });
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EasingDirection {
+    In,
+    Out,
+    InOut,
+}
+
+impl EasingDirection {
+    pub const ALL: [EasingDirection; 3] = [
+        EasingDirection::In,
+        EasingDirection::Out,
+        EasingDirection::InOut,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            EasingDirection::In => "In",
+            EasingDirection::Out => "Out",
+            EasingDirection::InOut => "InOut",
+        }
+    }
+}
+
+impl FromLua for EasingDirection {
+    fn from_lua(value: Value, _lua: &Lua) -> Result<Self> {
+        match value {
+            Value::UserData(ud) => ud.borrow::<EasingDirection>().map(|v| *v),
+            _ => Err(mlua::Error::FromLuaConversionError {
+                from: value.type_name(),
+                to: "EasingDirection".to_string(),
+                message: Some("expected EasingDirection".to_string()),
+            }),
+        }
+    }
+}
+
+impl UserData for EasingDirection {
+    fn add_methods<M: UserDataMethods<Self>>(methods: &mut M) {
+        methods.add_meta_method(mlua::MetaMethod::ToString, |_, this, ()| {
+            Ok(format!("Enum.EasingDirection.{}", this.name()))
+        });
+        methods.add_meta_method(
+            mlua::MetaMethod::Eq,
+            |_, this, other: EasingDirection| Ok(*this == other),
+        );
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlaybackState {
+    Begin,
+    Delayed,
+    Playing,
+    Paused,
+    Completed,
+    Cancelled,
+}
+
+impl PlaybackState {
+    pub const ALL: [PlaybackState; 6] = [
+        PlaybackState::Begin,
+        PlaybackState::Delayed,
+        PlaybackState::Playing,
+        PlaybackState::Paused,
+        PlaybackState::Completed,
+        PlaybackState::Cancelled,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PlaybackState::Begin => "Begin",
+            PlaybackState::Delayed => "Delayed",
+            PlaybackState::Playing => "Playing",
+            PlaybackState::Paused => "Paused",
+            PlaybackState::Completed => "Completed",
+            PlaybackState::Cancelled => "Cancelled",
+        }
+    }
+}
+
+impl FromLua for PlaybackState {
+    fn from_lua(value: Value, _lua: &Lua) -> Result<Self> {
+        match value {
+            Value::UserData(ud) => ud.borrow::<PlaybackState>().map(|v| *v),
+            _ => Err(mlua::Error::FromLuaConversionError {
+                from: value.type_name(),
+                to: "PlaybackState".to_string(),
+                message: Some("expected PlaybackState".to_string()),
+            }),
+        }
+    }
+}
+
+impl UserData for PlaybackState {
+    fn add_methods<M: UserDataMethods<Self>>(methods: &mut M) {
+        methods.add_meta_method(mlua::MetaMethod::ToString, |_, this, ()| {
+            Ok(format!("Enum.PlaybackState.{}", this.name()))
+        });
+        methods.add_meta_method(
+            mlua::MetaMethod::Eq,
+            |_, this, other: PlaybackState| Ok(*this == other),
+        );
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ActuatorType {
+    None,
+    Motor,
+    Servo,
+}
+
+impl ActuatorType {
+    pub const ALL: [ActuatorType; 3] = [
+        ActuatorType::None,
+        ActuatorType::Motor,
+        ActuatorType::Servo,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ActuatorType::None => "None",
+            ActuatorType::Motor => "Motor",
+            ActuatorType::Servo => "Servo",
+        }
+    }
+}
+
+impl FromLua for ActuatorType {
+    fn from_lua(value: Value, _lua: &Lua) -> Result<Self> {
+        match value {
+            Value::UserData(ud) => ud.borrow::<ActuatorType>().map(|v| *v),
+            _ => Err(mlua::Error::FromLuaConversionError {
+                from: value.type_name(),
+                to: "ActuatorType".to_string(),
+                message: Some("expected ActuatorType".to_string()),
+            }),
+        }
+    }
+}
+
+impl UserData for ActuatorType {
+    fn add_methods<M: UserDataMethods<Self>>(methods: &mut M) {
+        methods.add_meta_method(mlua::MetaMethod::ToString, |_, this, ()| {
+            Ok(format!("Enum.ActuatorType.{}", this.name()))
+        });
+        methods.add_meta_method(
+            mlua::MetaMethod::Eq,
+            |_, this, other: ActuatorType| Ok(*this == other),
+        );
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ActuatorRelativeTo {
+    Attachment0,
+    Attachment1,
+    World,
+}
+
+impl ActuatorRelativeTo {
+    pub const ALL: [ActuatorRelativeTo; 3] = [
+        ActuatorRelativeTo::Attachment0,
+        ActuatorRelativeTo::Attachment1,
+        ActuatorRelativeTo::World,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ActuatorRelativeTo::Attachment0 => "Attachment0",
+            ActuatorRelativeTo::Attachment1 => "Attachment1",
+            ActuatorRelativeTo::World => "World",
+        }
+    }
+}
+
+impl FromLua for ActuatorRelativeTo {
+    fn from_lua(value: Value, _lua: &Lua) -> Result<Self> {
+        match value {
+            Value::UserData(ud) => ud.borrow::<ActuatorRelativeTo>().map(|v| *v),
+            _ => Err(mlua::Error::FromLuaConversionError {
+                from: value.type_name(),
+                to: "ActuatorRelativeTo".to_string(),
+                message: Some("expected ActuatorRelativeTo".to_string()),
+            }),
+        }
+    }
+}
+
+impl UserData for ActuatorRelativeTo {
+    fn add_methods<M: UserDataMethods<Self>>(methods: &mut M) {
+        methods.add_meta_method(mlua::MetaMethod::ToString, |_, this, ()| {
+            Ok(format!("Enum.ActuatorRelativeTo.{}", this.name()))
+        });
+        methods.add_meta_method(
+            mlua::MetaMethod::Eq,
+            |_, this, other: ActuatorRelativeTo| Ok(*this == other),
+        );
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PositionAlignmentMode {
+    OneAttachment,
+    TwoAttachment,
+}
+
+impl PositionAlignmentMode {
+    pub const ALL: [PositionAlignmentMode; 2] = [
+        PositionAlignmentMode::OneAttachment,
+        PositionAlignmentMode::TwoAttachment,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PositionAlignmentMode::OneAttachment => "OneAttachment",
+            PositionAlignmentMode::TwoAttachment => "TwoAttachment",
+        }
+    }
+}
+
+impl FromLua for PositionAlignmentMode {
+    fn from_lua(value: Value, _lua: &Lua) -> Result<Self> {
+        match value {
+            Value::UserData(ud) => ud.borrow::<PositionAlignmentMode>().map(|v| *v),
+            _ => Err(mlua::Error::FromLuaConversionError {
+                from: value.type_name(),
+                to: "PositionAlignmentMode".to_string(),
+                message: Some("expected PositionAlignmentMode".to_string()),
+            }),
+        }
+    }
+}
+
+impl UserData for PositionAlignmentMode {
+    fn add_methods<M: UserDataMethods<Self>>(methods: &mut M) {
+        methods.add_meta_method(mlua::MetaMethod::ToString, |_, this, ()| {
+            Ok(format!("Enum.PositionAlignmentMode.{}", this.name()))
+        });
+        methods.add_meta_method(
+            mlua::MetaMethod::Eq,
+            |_, this, other: PositionAlignmentMode| Ok(*this == other),
+        );
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PathStatus {
+    Success,
+    NoPath,
+}
+
+impl PathStatus {
+    pub const ALL: [PathStatus; 2] = [PathStatus::Success, PathStatus::NoPath];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PathStatus::Success => "Success",
+            PathStatus::NoPath => "NoPath",
+        }
+    }
+}
+
+impl FromLua for PathStatus {
+    fn from_lua(value: Value, _lua: &Lua) -> Result<Self> {
+        match value {
+            Value::UserData(ud) => ud.borrow::<PathStatus>().map(|v| *v),
+            _ => Err(mlua::Error::FromLuaConversionError {
+                from: value.type_name(),
+                to: "PathStatus".to_string(),
+                message: Some("expected PathStatus".to_string()),
+            }),
+        }
+    }
+}
+
+impl UserData for PathStatus {
+    fn add_methods<M: UserDataMethods<Self>>(methods: &mut M) {
+        methods.add_meta_method(mlua::MetaMethod::ToString, |_, this, ()| {
+            Ok(format!("Enum.PathStatus.{}", this.name()))
+        });
+        methods.add_meta_method(
+            mlua::MetaMethod::Eq,
+            |_, this, other: PathStatus| Ok(*this == other),
+        );
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PathWaypointAction {
+    Walk,
+    Jump,
+}
+
+impl PathWaypointAction {
+    pub const ALL: [PathWaypointAction; 2] = [PathWaypointAction::Walk, PathWaypointAction::Jump];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PathWaypointAction::Walk => "Walk",
+            PathWaypointAction::Jump => "Jump",
+        }
+    }
+}
+
+impl FromLua for PathWaypointAction {
+    fn from_lua(value: Value, _lua: &Lua) -> Result<Self> {
+        match value {
+            Value::UserData(ud) => ud.borrow::<PathWaypointAction>().map(|v| *v),
+            _ => Err(mlua::Error::FromLuaConversionError {
+                from: value.type_name(),
+                to: "PathWaypointAction".to_string(),
+                message: Some("expected PathWaypointAction".to_string()),
+            }),
+        }
+    }
+}
+
+impl UserData for PathWaypointAction {
+    fn add_methods<M: UserDataMethods<Self>>(methods: &mut M) {
+        methods.add_meta_method(mlua::MetaMethod::ToString, |_, this, ()| {
+            Ok(format!("Enum.PathWaypointAction.{}", this.name()))
+        });
+        methods.add_meta_method(
+            mlua::MetaMethod::Eq,
+            |_, this, other: PathWaypointAction| Ok(*this == other),
+        );
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum TextXAlignment {
+    Left,
+    #[default]
+    Center,
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum TextYAlignment {
+    Top,
+    #[default]
+    Center,
+    Bottom,
+}
+
+impl TextXAlignment {
+    pub const ALL: [TextXAlignment; 3] =
+        [TextXAlignment::Left, TextXAlignment::Center, TextXAlignment::Right];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TextXAlignment::Left => "Left",
+            TextXAlignment::Center => "Center",
+            TextXAlignment::Right => "Right",
+        }
+    }
+
+    /// Unknown names fall back to Center
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "Left" => TextXAlignment::Left,
+            "Right" => TextXAlignment::Right,
+            _ => TextXAlignment::Center,
+        }
+    }
+}
+
+impl TextYAlignment {
+    pub const ALL: [TextYAlignment; 3] =
+        [TextYAlignment::Top, TextYAlignment::Center, TextYAlignment::Bottom];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TextYAlignment::Top => "Top",
+            TextYAlignment::Center => "Center",
+            TextYAlignment::Bottom => "Bottom",
+        }
+    }
+
+    /// Unknown names fall back to Center
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "Top" => TextYAlignment::Top,
+            "Bottom" => TextYAlignment::Bottom,
+            _ => TextYAlignment::Center,
+        }
+    }
+}
+
+impl FromLua for TextXAlignment {
+    fn from_lua(value: Value, _lua: &Lua) -> Result<Self> {
+        match value {
+            Value::UserData(ud) => ud.borrow::<TextXAlignment>().map(|v| *v),
+            _ => Err(mlua::Error::FromLuaConversionError {
+                from: value.type_name(),
+                to: "TextXAlignment".to_string(),
+                message: Some("expected TextXAlignment".to_string()),
+            }),
+        }
+    }
+}
+
+impl UserData for TextXAlignment {
+    fn add_methods<M: UserDataMethods<Self>>(methods: &mut M) {
+        methods.add_meta_method(mlua::MetaMethod::ToString, |_, this, ()| {
+            Ok(format!("Enum.TextXAlignment.{}", this.name()))
+        });
+        methods.add_meta_method(mlua::MetaMethod::Eq, |_, this, other: TextXAlignment| {
+            Ok(*this == other)
+        });
+    }
+}
+
+impl FromLua for TextYAlignment {
+    fn from_lua(value: Value, _lua: &Lua) -> Result<Self> {
+        match value {
+            Value::UserData(ud) => ud.borrow::<TextYAlignment>().map(|v| *v),
+            _ => Err(mlua::Error::FromLuaConversionError {
+                from: value.type_name(),
+                to: "TextYAlignment".to_string(),
+                message: Some("expected TextYAlignment".to_string()),
+            }),
+        }
+    }
+}
+
+impl UserData for TextYAlignment {
+    fn add_methods<M: UserDataMethods<Self>>(methods: &mut M) {
+        methods.add_meta_method(mlua::MetaMethod::ToString, |_, this, ()| {
+            Ok(format!("Enum.TextYAlignment.{}", this.name()))
+        });
+        methods.add_meta_method(mlua::MetaMethod::Eq, |_, this, other: TextYAlignment| {
+            Ok(*this == other)
+        });
+    }
+}
+
+/// Every enum with the names of its items, in the order `Enum` lists them
+pub fn enum_items() -> Vec<(&'static str, Vec<&'static str>)> {
+    fn names<T: Copy>(all: &[T], name: fn(&T) -> &'static str) -> Vec<&'static str> {
+        all.iter().map(name).collect()
+    }
+    vec![
+        ("PartType", names(&PartType::ALL, PartType::name)),
+        ("Material", names(&Material::ALL, Material::name)),
+        (
+            "HumanoidStateType",
+            names(&HumanoidStateType::ALL, HumanoidStateType::name),
+        ),
+        (
+            "RaycastFilterType",
+            names(&RaycastFilterType::ALL, RaycastFilterType::name),
+        ),
+        ("EasingStyle", names(&EasingStyle::ALL, EasingStyle::name)),
+        (
+            "EasingDirection",
+            names(&EasingDirection::ALL, EasingDirection::name),
+        ),
+        ("PlaybackState", names(&PlaybackState::ALL, PlaybackState::name)),
+        ("ActuatorType", names(&ActuatorType::ALL, ActuatorType::name)),
+        (
+            "ActuatorRelativeTo",
+            names(&ActuatorRelativeTo::ALL, ActuatorRelativeTo::name),
+        ),
+        (
+            "PositionAlignmentMode",
+            names(&PositionAlignmentMode::ALL, PositionAlignmentMode::name),
+        ),
+        ("PathStatus", names(&PathStatus::ALL, PathStatus::name)),
+        (
+            "PathWaypointAction",
+            names(&PathWaypointAction::ALL, PathWaypointAction::name),
+        ),
+        ("TextXAlignment", names(&TextXAlignment::ALL, TextXAlignment::name)),
+        ("TextYAlignment", names(&TextYAlignment::ALL, TextYAlignment::name)),
+    ]
+}
+
 pub fn register_enums(lua: &Lua) -> Result<()> {
     let enum_table = lua.create_table()?;
 
     let part_type_table = lua.create_table()?;
-    part_type_table.set("Ball", PartType::Ball)?;
-    part_type_table.set("Block", PartType::Block)?;
-    part_type_table.set("Cylinder", PartType::Cylinder)?;
-    part_type_table.set("Wedge", PartType::Wedge)?;
+    for part_type in PartType::ALL {
+        part_type_table.set(part_type.name(), part_type)?;
+    }
     enum_table.set("PartType", part_type_table)?;
 
     let material_table = lua.create_table()?;
-    material_table.set("Plastic", Material::Plastic)?;
-    material_table.set("Wood", Material::Wood)?;
-    material_table.set("Metal", Material::Metal)?;
-    material_table.set("Glass", Material::Glass)?;
-    material_table.set("Neon", Material::Neon)?;
-    material_table.set("Concrete", Material::Concrete)?;
-    material_table.set("Brick", Material::Brick)?;
-    material_table.set("Granite", Material::Granite)?;
-    material_table.set("Grass", Material::Grass)?;
-    material_table.set("Ice", Material::Ice)?;
-    material_table.set("Sand", Material::Sand)?;
-    material_table.set("Fabric", Material::Fabric)?;
-    material_table.set("Marble", Material::Marble)?;
-    material_table.set("Slate", Material::Slate)?;
-    material_table.set("SmoothPlastic", Material::SmoothPlastic)?;
-    material_table.set("ForceField", Material::ForceField)?;
+    for material in Material::ALL {
+        material_table.set(material.name(), material)?;
+    }
     enum_table.set("Material", material_table)?;
 
     let state_type_table = lua.create_table()?;
-    state_type_table.set("Running", HumanoidStateType::Running)?;
-    state_type_table.set("Jumping", HumanoidStateType::Jumping)?;
-    state_type_table.set("Freefall", HumanoidStateType::Freefall)?;
-    state_type_table.set("Dead", HumanoidStateType::Dead)?;
-    state_type_table.set("Physics", HumanoidStateType::Physics)?;
-    state_type_table.set("None", HumanoidStateType::None)?;
+    for state in HumanoidStateType::ALL {
+        state_type_table.set(state.name(), state)?;
+    }
     enum_table.set("HumanoidStateType", state_type_table)?;
 
     let filter_type_table = lua.create_table()?;
-    filter_type_table.set("Include", RaycastFilterType::Include)?;
-    filter_type_table.set("Exclude", RaycastFilterType::Exclude)?;
+    for filter_type in RaycastFilterType::ALL {
+        filter_type_table.set(filter_type.name(), filter_type)?;
+    }
     enum_table.set("RaycastFilterType", filter_type_table)?;
 
+    let easing_style_table = lua.create_table()?;
+    for style in EasingStyle::ALL {
+        easing_style_table.set(style.name(), style)?;
+    }
+    enum_table.set("EasingStyle", easing_style_table)?;
+
+    let easing_direction_table = lua.create_table()?;
+    for direction in EasingDirection::ALL {
+        easing_direction_table.set(direction.name(), direction)?;
+    }
+    enum_table.set("EasingDirection", easing_direction_table)?;
+
+    let playback_state_table = lua.create_table()?;
+    for state in PlaybackState::ALL {
+        playback_state_table.set(state.name(), state)?;
+    }
+    enum_table.set("PlaybackState", playback_state_table)?;
+
+    let actuator_type_table = lua.create_table()?;
+    for actuator_type in ActuatorType::ALL {
+        actuator_type_table.set(actuator_type.name(), actuator_type)?;
+    }
+    enum_table.set("ActuatorType", actuator_type_table)?;
+
+    let relative_to_table = lua.create_table()?;
+    for relative_to in ActuatorRelativeTo::ALL {
+        relative_to_table.set(relative_to.name(), relative_to)?;
+    }
+    enum_table.set("ActuatorRelativeTo", relative_to_table)?;
+
+    let alignment_mode_table = lua.create_table()?;
+    for mode in PositionAlignmentMode::ALL {
+        alignment_mode_table.set(mode.name(), mode)?;
+    }
+    enum_table.set("PositionAlignmentMode", alignment_mode_table)?;
+
+    let path_status_table = lua.create_table()?;
+    for status in PathStatus::ALL {
+        path_status_table.set(status.name(), status)?;
+    }
+    enum_table.set("PathStatus", path_status_table)?;
+
+    let waypoint_action_table = lua.create_table()?;
+    for action in PathWaypointAction::ALL {
+        waypoint_action_table.set(action.name(), action)?;
+    }
+    enum_table.set("PathWaypointAction", waypoint_action_table)?;
+
+    let text_x_table = lua.create_table()?;
+    for alignment in TextXAlignment::ALL {
+        text_x_table.set(alignment.name(), alignment)?;
+    }
+    enum_table.set("TextXAlignment", text_x_table)?;
+
+    let text_y_table = lua.create_table()?;
+    for alignment in TextYAlignment::ALL {
+        text_y_table.set(alignment.name(), alignment)?;
+    }
+    enum_table.set("TextYAlignment", text_y_table)?;
+
     lua.globals().set("Enum", enum_table)?;
 
     Ok(())
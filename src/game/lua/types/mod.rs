@@ -1,13 +1,25 @@
 pub mod cframe;
 pub mod color3;
 pub mod enums;
+pub mod path_waypoint;
+pub mod physical_properties;
+pub mod random;
+pub mod tween_info;
 pub mod udim;
 pub mod udim2;
 pub mod vector3;
 
 pub use cframe::CFrame;
 pub use color3::Color3;
-pub use enums::{Material, PartType, RaycastFilterType};
+pub use enums::{
+    ActuatorRelativeTo, ActuatorType, EasingDirection, EasingStyle, HumanoidStateType, Material,
+    PartType, PathStatus, PathWaypointAction, PlaybackState, PositionAlignmentMode,
+    RaycastFilterType, TextXAlignment, TextYAlignment,
+};
+pub use path_waypoint::PathWaypoint;
+pub use physical_properties::PhysicalProperties;
+pub use random::Random;
+pub use tween_info::TweenInfo;
 pub use udim::UDim;
 pub use udim2::UDim2;
 pub use vector3::Vector3;
@@ -19,6 +31,10 @@ pub fn register_all_types(lua: &Lua) -> Result<()> {
     cframe::register_cframe(lua)?;
     color3::register_color3(lua)?;
     enums::register_enums(lua)?;
+    path_waypoint::register_path_waypoint(lua)?;
+    physical_properties::register_physical_properties(lua)?;
+    random::register_random(lua)?;
+    tween_info::register_tween_info(lua)?;
     udim::register_udim(lua)?;
     udim2::register_udim2(lua)?;
     Ok(())
@@ -0,0 +1,81 @@
+use mlua::{FromLua, Lua, Result, UserData, UserDataFields, UserDataMethods, Value};
+
+use super::enums::PathWaypointAction;
+use super::vector3::Vector3;
+
+/// A point along a computed Path and how to get there from the previous one
+#[derive(Debug, Clone, PartialEq)]
+pub struct PathWaypoint {
+    pub position: Vector3,
+    pub action: PathWaypointAction,
+    pub label: String,
+}
+
+impl PathWaypoint {
+    pub fn new(position: Vector3, action: PathWaypointAction) -> Self {
+        Self {
+            position,
+            action,
+            label: String::new(),
+        }
+    }
+}
+
+impl FromLua for PathWaypoint {
+    fn from_lua(value: Value, _lua: &Lua) -> Result<Self> {
+        match value {
+            Value::UserData(ud) => ud.borrow::<PathWaypoint>().map(|v| v.clone()),
+            _ => Err(mlua::Error::FromLuaConversionError {
+                from: value.type_name(),
+                to: "PathWaypoint".to_string(),
+                message: Some("expected PathWaypoint".to_string()),
+            }),
+        }
+    }
+}
+
+impl UserData for PathWaypoint {
+    fn add_fields<F: UserDataFields<Self>>(fields: &mut F) {
+        fields.add_field_method_get("Position", |_, this| Ok(this.position));
+        fields.add_field_method_get("Action", |_, this| Ok(this.action));
+        fields.add_field_method_get("Label", |_, this| Ok(this.label.clone()));
+    }
+
+    fn add_methods<M: UserDataMethods<Self>>(methods: &mut M) {
+        methods.add_meta_method(mlua::MetaMethod::Eq, |_, this, other: PathWaypoint| {
+            Ok(*this == other)
+        });
+
+        methods.add_meta_method(mlua::MetaMethod::ToString, |_, this, ()| {
+            Ok(format!(
+                "{}, {}, {}, Enum.PathWaypointAction.{}",
+                this.position.x,
+                this.position.y,
+                this.position.z,
+                this.action.name()
+            ))
+        });
+    }
+}
+
+pub fn register_path_waypoint(lua: &Lua) -> Result<()> {
+    let path_waypoint_table = lua.create_table()?;
+
+    // new(position?, action?, label?)
+    path_waypoint_table.set(
+        "new",
+        lua.create_function(
+            |_, (position, action, label): (Option<Vector3>, Option<PathWaypointAction>, Option<String>)| {
+                Ok(PathWaypoint {
+                    position: position.unwrap_or_else(Vector3::zero),
+                    action: action.unwrap_or(PathWaypointAction::Walk),
+                    label: label.unwrap_or_default(),
+                })
+            },
+        )?,
+    )?;
+
+    lua.globals().set("PathWaypoint", path_waypoint_table)?;
+
+    Ok(())
+}
@@ -0,0 +1,131 @@
+use mlua::{FromLua, Lua, MultiValue, Result, UserData, UserDataFields, UserDataMethods, Value};
+use serde::{Deserialize, Serialize};
+
+use super::enums::Material;
+
+/// Density, friction and bounciness of a part's surface
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PhysicalProperties {
+    pub density: f32,
+    pub friction: f32,
+    pub elasticity: f32,
+    pub friction_weight: f32,
+    pub elasticity_weight: f32,
+}
+
+impl PhysicalProperties {
+    pub fn new(density: f32, friction: f32, elasticity: f32) -> Self {
+        Self {
+            density,
+            friction,
+            elasticity,
+            friction_weight: 1.0,
+            elasticity_weight: 1.0,
+        }
+    }
+
+    /// Default properties of a material, matching Roblox's
+    pub fn from_material(material: Material) -> Self {
+        let (density, friction, elasticity) = match material {
+            Material::Plastic => (0.7, 0.3, 0.5),
+            Material::SmoothPlastic => (0.7, 0.2, 0.5),
+            Material::Wood => (0.35, 0.48, 0.2),
+            Material::Metal => (7.85, 0.4, 0.25),
+            Material::Glass => (2.4, 0.25, 0.2),
+            Material::Neon => (0.7, 0.3, 0.2),
+            Material::Concrete => (2.4, 0.7, 0.2),
+            Material::Brick => (1.92, 0.8, 0.15),
+            Material::Granite => (2.69, 0.4, 0.2),
+            Material::Grass => (0.9, 0.4, 0.1),
+            Material::Ice => (0.919, 0.02, 0.15),
+            Material::Sand => (1.6, 0.5, 0.05),
+            Material::Fabric => (0.7, 0.35, 0.05),
+            Material::Marble => (2.56, 0.2, 0.17),
+            Material::Slate => (2.69, 0.4, 0.2),
+            Material::ForceField => (2.4, 0.25, 0.2),
+            Material::Water => (1.0, 0.0, 0.01),
+            Material::Air => (0.01, 0.01, 0.01),
+        };
+        Self::new(density, friction, elasticity)
+    }
+}
+
+impl FromLua for PhysicalProperties {
+    fn from_lua(value: Value, _lua: &Lua) -> Result<Self> {
+        match value {
+            Value::UserData(ud) => ud.borrow::<PhysicalProperties>().map(|v| *v),
+            _ => Err(mlua::Error::FromLuaConversionError {
+                from: value.type_name(),
+                to: "PhysicalProperties".to_string(),
+                message: Some("expected PhysicalProperties".to_string()),
+            }),
+        }
+    }
+}
+
+impl UserData for PhysicalProperties {
+    fn add_fields<F: UserDataFields<Self>>(fields: &mut F) {
+        fields.add_field_method_get("Density", |_, this| Ok(this.density));
+        fields.add_field_method_get("Friction", |_, this| Ok(this.friction));
+        fields.add_field_method_get("Elasticity", |_, this| Ok(this.elasticity));
+        fields.add_field_method_get("FrictionWeight", |_, this| Ok(this.friction_weight));
+        fields.add_field_method_get("ElasticityWeight", |_, this| Ok(this.elasticity_weight));
+    }
+
+    fn add_methods<M: UserDataMethods<Self>>(methods: &mut M) {
+        methods.add_meta_method(mlua::MetaMethod::Eq, |_, this, other: PhysicalProperties| {
+            Ok(*this == other)
+        });
+
+        methods.add_meta_method(mlua::MetaMethod::ToString, |_, this, ()| {
+            Ok(format!(
+                "{}, {}, {}, {}, {}",
+                this.density,
+                this.friction,
+                this.elasticity,
+                this.friction_weight,
+                this.elasticity_weight
+            ))
+        });
+    }
+}
+
+pub fn register_physical_properties(lua: &Lua) -> Result<()> {
+    let physical_properties_table = lua.create_table()?;
+
+    // new(material) or new(density, friction, elasticity, frictionWeight?, elasticityWeight?)
+    physical_properties_table.set(
+        "new",
+        lua.create_function(|lua, args: MultiValue| {
+            let mut args = args.into_iter();
+            let first = args.next().unwrap_or(Value::Nil);
+            if matches!(first, Value::UserData(_)) {
+                return Ok(PhysicalProperties::from_material(Material::from_lua(first, lua)?));
+            }
+            let density = f32::from_lua(first, lua)?;
+            let mut number = |name: &str, default: Option<f32>| -> Result<f32> {
+                match (args.next().unwrap_or(Value::Nil), default) {
+                    (Value::Nil, Some(default)) => Ok(default),
+                    (value, _) => f32::from_lua(value, lua).map_err(|_| {
+                        mlua::Error::runtime(format!("PhysicalProperties.new: {} must be a number", name))
+                    }),
+                }
+            };
+            let friction = number("friction", None)?;
+            let elasticity = number("elasticity", None)?;
+            let friction_weight = number("frictionWeight", Some(1.0))?;
+            let elasticity_weight = number("elasticityWeight", Some(1.0))?;
+            Ok(PhysicalProperties {
+                density: density.max(0.01),
+                friction: friction.clamp(0.0, 2.0),
+                elasticity: elasticity.clamp(0.0, 1.0),
+                friction_weight: friction_weight.clamp(0.0, 100.0),
+                elasticity_weight: elasticity_weight.clamp(0.0, 100.0),
+            })
+        })?,
+    )?;
+
+    lua.globals().set("PhysicalProperties", physical_properties_table)?;
+
+    Ok(())
+}
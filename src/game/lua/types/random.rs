@@ -0,0 +1,92 @@
+use mlua::{Lua, Result, Table, UserData, UserDataMethods, Value};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+use super::Vector3;
+use crate::game::lua::determinism::script_rng;
+
+/// Random is an independent pseudo-random number generator
+#[derive(Debug, Clone)]
+pub struct Random {
+    rng: StdRng,
+}
+
+impl Random {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    fn next_number(&mut self, min: f64, max: f64) -> f64 {
+        min + self.rng.gen::<f64>() * (max - min)
+    }
+
+    fn next_integer(&mut self, min: i64, max: i64) -> Result<i64> {
+        if min > max {
+            return Err(mlua::Error::runtime(format!(
+                "NextInteger: min ({}) must not exceed max ({})",
+                min, max
+            )));
+        }
+        Ok(self.rng.gen_range(min..=max))
+    }
+
+    fn next_unit_vector(&mut self) -> Vector3 {
+        // Uniform on the sphere: uniform z and uniform angle around it
+        let z = self.next_number(-1.0, 1.0);
+        let theta = self.next_number(0.0, std::f64::consts::TAU);
+        let r = (1.0 - z * z).sqrt();
+        Vector3::new((r * theta.cos()) as f32, (r * theta.sin()) as f32, z as f32)
+    }
+}
+
+impl UserData for Random {
+    fn add_methods<M: UserDataMethods<Self>>(methods: &mut M) {
+        methods.add_method_mut("NextNumber", |_, this, (min, max): (Option<f64>, Option<f64>)| {
+            Ok(this.next_number(min.unwrap_or(0.0), max.unwrap_or(1.0)))
+        });
+
+        methods.add_method_mut("NextInteger", |_, this, (min, max): (i64, i64)| {
+            this.next_integer(min, max)
+        });
+
+        methods.add_method_mut("NextUnitVector", |_, this, ()| Ok(this.next_unit_vector()));
+
+        methods.add_method_mut("Shuffle", |_, this, tb: Table| {
+            let mut values: Vec<Value> = tb.sequence_values::<Value>().collect::<Result<_>>()?;
+            values.shuffle(&mut this.rng);
+            for (i, value) in values.into_iter().enumerate() {
+                tb.raw_set(i + 1, value)?;
+            }
+            Ok(())
+        });
+
+        methods.add_method("Clone", |_, this, ()| Ok(this.clone()));
+
+        methods.add_meta_method(mlua::MetaMethod::ToString, |_, _, ()| Ok("Random"));
+    }
+}
+
+pub fn register_random(lua: &Lua) -> Result<()> {
+    let random_table = lua.create_table()?;
+
+    random_table.set(
+        "new",
+        lua.create_function(|lua, seed: Option<f64>| {
+            // Unseeded generators draw from the VM's random source, so they are
+            // deterministic too when the instance is seeded
+            let seed = match seed {
+                Some(seed) => seed as i64 as u64,
+                None => script_rng(lua).next_seed(),
+            };
+            Ok(Random::new(seed))
+        })?,
+    )?;
+
+    lua.globals().set("Random", random_table)?;
+
+    Ok(())
+}
+
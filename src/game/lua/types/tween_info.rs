@@ -0,0 +1,170 @@
+use mlua::{FromLua, Lua, Result, UserData, UserDataFields, UserDataMethods, Value};
+use std::f32::consts::PI;
+
+use super::enums::{EasingDirection, EasingStyle};
+
+/// How a tween plays: its length, easing curve, repeats and delay
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TweenInfo {
+    pub duration: f32,
+    pub easing_style: EasingStyle,
+    pub easing_direction: EasingDirection,
+    /// Extra plays after the first one; negative repeats forever
+    pub repeat_count: i32,
+    /// Whether each play runs back to the start after reaching the goal
+    pub reverses: bool,
+    /// Seconds to wait before each play
+    pub delay: f32,
+}
+
+impl Default for TweenInfo {
+    fn default() -> Self {
+        Self {
+            duration: 1.0,
+            easing_style: EasingStyle::Quad,
+            easing_direction: EasingDirection::Out,
+            repeat_count: 0,
+            reverses: false,
+            delay: 0.0,
+        }
+    }
+}
+
+/// Maps linear progress `t` (0..1) onto the easing curve, like TweenService:GetValue.
+/// Back and Elastic overshoot, so the result may leave 0..1 in between.
+pub fn ease(t: f32, style: EasingStyle, direction: EasingDirection) -> f32 {
+    let t = t.clamp(0.0, 1.0);
+    match direction {
+        EasingDirection::In => ease_in(t, style),
+        EasingDirection::Out => 1.0 - ease_in(1.0 - t, style),
+        EasingDirection::InOut => {
+            if t < 0.5 {
+                ease_in(2.0 * t, style) / 2.0
+            } else {
+                1.0 - ease_in(2.0 - 2.0 * t, style) / 2.0
+            }
+        }
+    }
+}
+
+/// The "In" form of each style; Out and InOut are derived from it
+fn ease_in(t: f32, style: EasingStyle) -> f32 {
+    match style {
+        EasingStyle::Linear => t,
+        EasingStyle::Sine => 1.0 - (t * PI / 2.0).cos(),
+        EasingStyle::Quad => t * t,
+        EasingStyle::Cubic => t * t * t,
+        EasingStyle::Quart => t.powi(4),
+        EasingStyle::Quint => t.powi(5),
+        EasingStyle::Exponential => {
+            if t <= 0.0 {
+                0.0
+            } else {
+                2f32.powf(10.0 * t - 10.0)
+            }
+        }
+        EasingStyle::Circular => 1.0 - (1.0 - t * t).max(0.0).sqrt(),
+        EasingStyle::Back => {
+            let c1 = 1.70158;
+            (c1 + 1.0) * t * t * t - c1 * t * t
+        }
+        EasingStyle::Elastic => {
+            if t <= 0.0 || t >= 1.0 {
+                t
+            } else {
+                -(2f32.powf(10.0 * t - 10.0)) * ((10.0 * t - 10.75) * (2.0 * PI / 3.0)).sin()
+            }
+        }
+        EasingStyle::Bounce => 1.0 - bounce_out(1.0 - t),
+    }
+}
+
+fn bounce_out(t: f32) -> f32 {
+    let n1 = 7.5625;
+    let d1 = 2.75;
+    if t < 1.0 / d1 {
+        n1 * t * t
+    } else if t < 2.0 / d1 {
+        let t = t - 1.5 / d1;
+        n1 * t * t + 0.75
+    } else if t < 2.5 / d1 {
+        let t = t - 2.25 / d1;
+        n1 * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / d1;
+        n1 * t * t + 0.984375
+    }
+}
+
+impl FromLua for TweenInfo {
+    fn from_lua(value: Value, _lua: &Lua) -> Result<Self> {
+        match value {
+            Value::UserData(ud) => ud.borrow::<TweenInfo>().map(|v| *v),
+            _ => Err(mlua::Error::FromLuaConversionError {
+                from: value.type_name(),
+                to: "TweenInfo".to_string(),
+                message: Some("expected TweenInfo".to_string()),
+            }),
+        }
+    }
+}
+
+impl UserData for TweenInfo {
+    fn add_fields<F: UserDataFields<Self>>(fields: &mut F) {
+        fields.add_field_method_get("Time", |_, this| Ok(this.duration));
+        fields.add_field_method_get("EasingStyle", |_, this| Ok(this.easing_style));
+        fields.add_field_method_get("EasingDirection", |_, this| Ok(this.easing_direction));
+        fields.add_field_method_get("RepeatCount", |_, this| Ok(this.repeat_count));
+        fields.add_field_method_get("Reverses", |_, this| Ok(this.reverses));
+        fields.add_field_method_get("DelayTime", |_, this| Ok(this.delay));
+    }
+
+    fn add_methods<M: UserDataMethods<Self>>(methods: &mut M) {
+        methods.add_meta_method(mlua::MetaMethod::ToString, |_, this, ()| {
+            Ok(format!(
+                "TweenInfo({}, {}, {}, {}, {}, {})",
+                this.duration,
+                this.easing_style.name(),
+                this.easing_direction.name(),
+                this.repeat_count,
+                this.reverses,
+                this.delay
+            ))
+        });
+    }
+}
+
+/// Arguments of `TweenInfo.new`, all optional
+type TweenInfoArgs = (
+    Option<f32>,
+    Option<EasingStyle>,
+    Option<EasingDirection>,
+    Option<i32>,
+    Option<bool>,
+    Option<f32>,
+);
+
+pub fn register_tween_info(lua: &Lua) -> Result<()> {
+    let tween_info_table = lua.create_table()?;
+
+    tween_info_table.set(
+        "new",
+        lua.create_function(
+            |_, (duration, easing_style, easing_direction, repeat_count, reverses, delay): TweenInfoArgs| {
+                let defaults = TweenInfo::default();
+                Ok(TweenInfo {
+                    duration: duration.unwrap_or(defaults.duration).max(0.0),
+                    easing_style: easing_style.unwrap_or(defaults.easing_style),
+                    easing_direction: easing_direction.unwrap_or(defaults.easing_direction),
+                    repeat_count: repeat_count.unwrap_or(defaults.repeat_count),
+                    reverses: reverses.unwrap_or(defaults.reverses),
+                    delay: delay.unwrap_or(defaults.delay).max(0.0),
+                })
+            },
+        )?,
+    )?;
+
+    lua.globals().set("TweenInfo", tween_info_table)?;
+
+    Ok(())
+}
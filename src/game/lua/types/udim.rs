@@ -1,7 +1,8 @@
 use mlua::{FromLua, Lua, MetaMethod, Result, UserData, UserDataFields, UserDataMethods, Value};
+use serde::{Deserialize, Serialize};
 
 /// UDim represents a single dimension with scale (0-1 fraction) and offset (pixels)
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
 pub struct UDim {
     pub scale: f32,
     pub offset: i32,
@@ -1,9 +1,10 @@
 use mlua::{FromLua, Lua, MetaMethod, Result, UserData, UserDataFields, UserDataMethods, Value};
+use serde::{Deserialize, Serialize};
 
 use super::udim::UDim;
 
 /// UDim2 represents 2D positioning with scale and offset for both X and Y axes
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
 pub struct UDim2 {
     pub x: UDim,
     pub y: UDim,
@@ -1,20 +1,28 @@
 pub mod async_bridge;
 pub mod constants;
 pub mod instance;
+pub mod lockstep;
 pub mod lua;
 pub mod physics;
+pub mod replay;
+pub mod snapshot;
 
 use dashmap::DashMap;
 use parking_lot::RwLock;
 use rayon::prelude::*;
 use sqlx::PgPool;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::thread;
 use std::time::{Duration, Instant};
+use tokio::sync::oneshot;
 use uuid::Uuid;
 
 use async_bridge::AsyncBridge;
 use instance::{ErrorMode, GameInstance, GameStatus, MapInfo, PlayerObservation, SpectatorObservation};
+use lockstep::{StepRequest, StepResult};
+use lua::ScriptLimits;
+use snapshot::{InstanceSnapshot, SnapshotError};
 
 /// Handle to the game manager state
 pub type GameManagerHandle = Arc<GameManagerState>;
@@ -47,6 +55,10 @@ pub struct GameManagerState {
     pub error_mode: ErrorMode,
     /// When true, skip garbage collection of empty instances (used by CLI)
     pub disable_gc: bool,
+    /// Lua memory cap and execution budgets for new instances
+    pub script_limits: ScriptLimits,
+    /// Agents waiting for their lockstep round, keyed by (instance_id, agent_id)
+    pub lockstep_waiters: DashMap<(Uuid, Uuid), oneshot::Sender<StepResult>>,
 }
 
 impl GameManagerState {
@@ -61,6 +73,8 @@ impl GameManagerState {
             async_bridge,
             error_mode,
             disable_gc,
+            script_limits: ScriptLimits::from_env(),
+            lockstep_waiters: DashMap::new(),
         }
     }
 }
@@ -104,37 +118,17 @@ impl GameManager {
                 .par_iter()
                 .for_each(|(instance_id, instance_handle)| {
                     let mut instance = instance_handle.write();
-                    let game_id = instance.game_id;
 
-                    if instance.status == GameStatus::Playing {
-                        let players_before: std::collections::HashSet<Uuid> =
-                            instance.players.keys().copied().collect();
+                    // Lockstep instances advance only when their agents step them,
+                    // or when a round has waited out its timeout for stalled agents
+                    if instance.lockstep {
+                        run_lockstep_round_if_ready(&self.state, *instance_id, &mut instance);
+                    } else if instance.status == GameStatus::Playing {
+                        let players_before: HashSet<Uuid> = instance.players.keys().copied().collect();
 
                         instance.tick();
 
-                        let players_after: std::collections::HashSet<Uuid> =
-                            instance.players.keys().copied().collect();
-
-                        // Clean up kicked players
-                        for agent_id in players_before.difference(&players_after) {
-                            self.state
-                                .observation_cache
-                                .remove(&(*instance_id, *agent_id));
-                            self.state.player_instances.remove(&(*agent_id, game_id));
-                        }
-
-                        // Update observation cache
-                        for &agent_id in instance.players.keys() {
-                            if let Some(obs) = instance.get_player_observation(agent_id) {
-                                self.state
-                                    .observation_cache
-                                    .insert((*instance_id, agent_id), obs);
-                            }
-                        }
-
-                        // Update spectator cache
-                        let spectator_obs = instance.get_spectator_observation();
-                        self.state.spectator_cache.insert(*instance_id, spectator_obs);
+                        publish_tick(&self.state, *instance_id, &instance, &players_before);
                     }
                 });
 
@@ -155,6 +149,34 @@ impl GameManager {
     }
 }
 
+/// Refreshes the observation caches after an instance ticked, dropping
+/// players that were kicked during the tick.
+fn publish_tick(
+    state: &GameManagerState,
+    instance_id: Uuid,
+    instance: &GameInstance,
+    players_before: &HashSet<Uuid>,
+) {
+    // Clean up kicked players
+    for agent_id in players_before {
+        if !instance.players.contains_key(agent_id) {
+            state.observation_cache.remove(&(instance_id, *agent_id));
+            state.player_instances.remove(&(*agent_id, instance.game_id));
+        }
+    }
+
+    // Update observation cache
+    for &agent_id in instance.players.keys() {
+        if let Some(obs) = instance.get_player_observation(agent_id) {
+            state.observation_cache.insert((instance_id, agent_id), obs);
+        }
+    }
+
+    // Update spectator cache
+    let spectator_obs = instance.get_spectator_observation();
+    state.spectator_cache.insert(instance_id, spectator_obs);
+}
+
 // =============================================================================
 // Instance Management
 // =============================================================================
@@ -165,23 +187,31 @@ pub struct FindInstanceResult {
     pub created: bool,
 }
 
+/// Settings for a new instance
+#[derive(Debug, Clone, Copy, Default)]
+pub struct InstanceOptions {
+    /// Seed for deterministic mode (see `GameInstance::seed`)
+    pub seed: Option<u64>,
+    /// Advance only through lockstep steps (see `GameInstance::lockstep`)
+    pub lockstep: bool,
+}
+
 /// Creates a new instance for a game
-fn create_instance(
+pub fn create_instance(
     state: &GameManagerHandle,
     game_id: Uuid,
     max_players: u32,
     script: Option<&str>,
+    options: InstanceOptions,
 ) -> Uuid {
-    let instance = match script {
-        Some(code) => GameInstance::new_with_script_and_config(
-            game_id,
-            code,
-            max_players,
-            state.async_bridge.clone(),
-            state.error_mode,
-        ),
-        None => GameInstance::new_with_config(game_id, max_players, state.async_bridge.clone(), state.error_mode),
-    };
+    let mut instance =
+        GameInstance::new_with_config(game_id, max_players, state.async_bridge.clone(), state.error_mode);
+    instance.script_limits = state.script_limits;
+    instance.seed = options.seed;
+    instance.lockstep = options.lockstep;
+    if let Some(code) = script {
+        instance.load_script(code);
+    }
 
     let instance_id = instance.instance_id;
 
@@ -207,19 +237,59 @@ fn create_instance(
     instance_id
 }
 
+/// Restores a snapshotted instance under its original id, with its players
+/// routed back to it
+pub fn restore_instance(state: &GameManagerHandle, snapshot: &InstanceSnapshot) -> Result<Uuid, SnapshotError> {
+    let mut instance = GameInstance::new_with_config(
+        snapshot.game_id,
+        snapshot.max_players,
+        state.async_bridge.clone(),
+        state.error_mode,
+    );
+    instance.script_limits = state.script_limits;
+    instance.restore(snapshot)?;
+
+    let instance_id = instance.instance_id;
+    let game_id = instance.game_id;
+    for &agent_id in instance.players.keys() {
+        state.player_instances.insert((agent_id, game_id), instance_id);
+    }
+    publish_tick(state, instance_id, &instance, &HashSet::new());
+
+    state.instances.insert(instance_id, Arc::new(RwLock::new(instance)));
+    state.game_instances.entry(game_id).or_default().push(instance_id);
+
+    Ok(instance_id)
+}
+
 /// Finds an instance with capacity or creates a new one
 pub fn find_or_create_instance(
     state: &GameManagerHandle,
     game_id: Uuid,
     max_players: u32,
     script: Option<&str>,
+) -> FindInstanceResult {
+    find_or_create_instance_with_options(state, game_id, max_players, script, InstanceOptions::default())
+}
+
+/// Finds an instance with capacity in the same lockstep mode and with the same
+/// seed, or creates one with `options`
+pub fn find_or_create_instance_with_options(
+    state: &GameManagerHandle,
+    game_id: Uuid,
+    max_players: u32,
+    script: Option<&str>,
+    options: InstanceOptions,
 ) -> FindInstanceResult {
     // Check existing instances for capacity
     if let Some(instance_ids) = state.game_instances.get(&game_id) {
         for &instance_id in instance_ids.value() {
             if let Some(handle) = state.instances.get(&instance_id) {
                 let instance = handle.read();
-                if instance.has_capacity() {
+                if instance.has_capacity()
+                    && instance.lockstep == options.lockstep
+                    && instance.seed == options.seed
+                {
                     return FindInstanceResult {
                         instance_id,
                         created: false,
@@ -230,7 +300,7 @@ pub fn find_or_create_instance(
     }
 
     // Create new instance
-    let instance_id = create_instance(state, game_id, max_players, script);
+    let instance_id = create_instance(state, game_id, max_players, script, options);
     FindInstanceResult {
         instance_id,
         created: true,
@@ -316,6 +386,9 @@ pub fn leave_instance(
         if !instance.remove_player(agent_id) {
             return Err("Not in instance".to_string());
         }
+        // A pending step of the leaving agent is dropped; the others may now be complete
+        state.lockstep_waiters.remove(&(instance_id, agent_id));
+        run_lockstep_round_if_ready(state, instance_id, &mut instance);
         instance.game_id
     };
 
@@ -372,6 +445,133 @@ pub fn queue_input(
     Ok(())
 }
 
+// =============================================================================
+// Lockstep
+// =============================================================================
+
+/// Submits an agent's step to its lockstep instance.
+///
+/// The returned receiver resolves once every player in the instance has
+/// submitted, or the round's timeout has passed, and the round has run. When this submission completes the round,
+/// the round runs on the calling thread before returning, so async callers
+/// should call this from a blocking task. The receiver errors if the agent
+/// leaves or the instance is destroyed before the round runs.
+pub fn submit_step(
+    state: &GameManagerHandle,
+    game_id: Uuid,
+    agent_id: Uuid,
+    request: StepRequest,
+) -> Result<oneshot::Receiver<StepResult>, String> {
+    let instance_id = get_player_instance(state, agent_id, game_id)
+        .ok_or_else(|| "Not in any instance of this game".to_string())?;
+
+    let instance_handle = state
+        .instances
+        .get(&instance_id)
+        .map(|h| h.clone())
+        .ok_or_else(|| "Instance not found".to_string())?;
+
+    let mut instance = instance_handle.write();
+
+    if let Some(ref err) = instance.halted_error {
+        return Err(format!("Game halted: {}", err));
+    }
+    if !instance.lockstep {
+        return Err("Instance is not in lockstep mode".to_string());
+    }
+    if !instance.players.contains_key(&agent_id) {
+        return Err("Not in instance".to_string());
+    }
+
+    // Replaces (and so cancels) an earlier submission from the same agent
+    let (tx, rx) = oneshot::channel();
+    state.lockstep_waiters.insert((instance_id, agent_id), tx);
+    instance.submit_step(agent_id, request);
+    run_lockstep_round_if_ready(state, instance_id, &mut instance);
+
+    Ok(rx)
+}
+
+/// Runs the instance's lockstep round if every player has submitted (or the
+/// round timed out waiting for them), and hands each participant its result.
+fn run_lockstep_round_if_ready(state: &GameManagerState, instance_id: Uuid, instance: &mut GameInstance) {
+    if !instance.lockstep || !instance.lockstep_ready() {
+        return;
+    }
+
+    let players_before: HashSet<Uuid> = instance.players.keys().copied().collect();
+    let rewards_before: HashMap<Uuid, f64> = players_before
+        .iter()
+        .map(|&agent_id| (agent_id, instance.reward_stats(agent_id).map_or(0.0, |s| s.total)))
+        .collect();
+
+    let (agents, ticks) = instance.run_lockstep_round();
+    publish_tick(state, instance_id, instance, &players_before);
+
+    for agent_id in agents {
+        if let Some((_, tx)) = state.lockstep_waiters.remove(&(instance_id, agent_id)) {
+            // A player that left mid-round loses its stats, so it reports no reward
+            let reward = instance
+                .reward_stats(agent_id)
+                .map_or(0.0, |s| s.total - rewards_before.get(&agent_id).copied().unwrap_or(0.0));
+            let _ = tx.send(StepResult {
+                ticks,
+                reward,
+                observation: instance.get_player_observation(agent_id),
+                done: instance.episode_done(agent_id),
+            });
+        }
+    }
+}
+
+// =============================================================================
+// Episodes
+// =============================================================================
+
+/// Resets an instance to its post-load world (see `GameInstance::reset`),
+/// keeping its players. Lockstep steps submitted before the reset are cancelled.
+pub fn reset_instance(
+    state: &GameManagerHandle,
+    instance_id: Uuid,
+    params: Option<serde_json::Value>,
+) -> Result<(), String> {
+    let instance_handle = state
+        .instances
+        .get(&instance_id)
+        .map(|h| h.clone())
+        .ok_or_else(|| "Instance not found".to_string())?;
+
+    let mut instance = instance_handle.write();
+    let players_before: HashSet<Uuid> = instance.players.keys().copied().collect();
+
+    let result = instance.reset(params);
+
+    state.lockstep_waiters.retain(|key, _| key.0 != instance_id);
+    publish_tick(state, instance_id, &instance, &players_before);
+
+    result
+}
+
+/// Swaps the script of an instance (see `GameInstance::reload`), keeping its
+/// players. Lockstep steps submitted before the reload are cancelled.
+pub fn reload_instance(state: &GameManagerHandle, instance_id: Uuid, source: &str) -> Result<(), String> {
+    let instance_handle = state
+        .instances
+        .get(&instance_id)
+        .map(|h| h.clone())
+        .ok_or_else(|| "Instance not found".to_string())?;
+
+    let mut instance = instance_handle.write();
+    let players_before: HashSet<Uuid> = instance.players.keys().copied().collect();
+
+    let result = instance.reload(source);
+
+    state.lockstep_waiters.retain(|key, _| key.0 != instance_id);
+    publish_tick(state, instance_id, &instance, &players_before);
+
+    result
+}
+
 // =============================================================================
 // Observations
 // =============================================================================
@@ -579,6 +779,7 @@ pub fn destroy_instance(state: &GameManagerHandle, instance_id: Uuid) -> bool {
     }
 
     state.spectator_cache.remove(&instance_id);
+    state.lockstep_waiters.retain(|key, _| key.0 != instance_id);
 
     // Clean up observation cache
     let obs_keys: Vec<_> = state
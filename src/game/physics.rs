@@ -3,16 +3,101 @@ use rapier3d::control::{
 };
 use rapier3d::na::{Quaternion, UnitQuaternion};
 use rapier3d::prelude::*;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use super::constants::humanoid as humanoid_consts;
 use super::constants::physics as consts;
+use super::lua::types::{Material, PartType};
 
 // Collision groups for Roblox-style physics behavior
-// Characters don't collide with each other, only with static geometry
 // Note: rapier3d uses InteractionGroups (not CollisionGroups like bevy_rapier)
-const GROUP_STATIC: Group = Group::GROUP_1;    // Walls, floors, obstacles
-const GROUP_CHARACTER: Group = Group::GROUP_2; // Player characters
+// Each collision group (see PhysicsService) owns one bit in the low half of the
+// interaction groups for parts and the same bit in the high half for characters.
+// Characters only filter on part bits, so they never collide with each other.
+
+/// Most collision groups a game can register
+pub const MAX_COLLISION_GROUPS: usize = 16;
+
+/// Interaction groups of a part in collision group `group`, colliding with the groups in `mask`
+pub fn part_interaction_groups(group: usize, mask: u16) -> InteractionGroups {
+    let mask = mask as u32;
+    InteractionGroups::new(
+        Group::from_bits_truncate(1 << group),
+        Group::from_bits_truncate(mask | (mask << 16)),
+    )
+}
+
+/// Interaction groups of a character whose root part is in collision group `group`
+pub fn character_interaction_groups(group: usize, mask: u16) -> InteractionGroups {
+    InteractionGroups::new(
+        Group::from_bits_truncate(1 << (group + 16)),
+        Group::from_bits_truncate(mask as u32),
+    )
+}
+
+/// Builds the collision shape for a part (size is the full extents, like Part.Size).
+/// - Ball: sphere with diameter = smallest size component
+/// - Cylinder: Y-axis cylinder (height = size.Y, diameter = min(size.X, size.Z)), matching the renderer.
+///   Roblox cylinders lie along X instead; scripts port by swapping Size.X and Size.Y.
+/// - Wedge: convex wedge whose slope rises from the front (-Z) to the back (+Z)
+pub fn part_shape(shape: PartType, size: [f32; 3]) -> SharedShape {
+    let [hx, hy, hz] = [size[0] / 2.0, size[1] / 2.0, size[2] / 2.0];
+    match shape {
+        PartType::Block => SharedShape::cuboid(hx, hy, hz),
+        PartType::Ball => SharedShape::ball(hx.min(hy).min(hz)),
+        PartType::Cylinder => SharedShape::cylinder(hy, hx.min(hz)),
+        PartType::Wedge => {
+            let points = [
+                point![-hx, -hy, -hz],
+                point![hx, -hy, -hz],
+                point![-hx, -hy, hz],
+                point![hx, -hy, hz],
+                point![-hx, hy, hz],
+                point![hx, hy, hz],
+            ];
+            // Degenerate sizes (a zero extent) have no hull; fall back to a box
+            SharedShape::convex_hull(&points).unwrap_or_else(|| SharedShape::cuboid(hx, hy, hz))
+        }
+    }
+}
+
+/// Mass of a part's collider at the given density (what BasePart:GetMass() reports)
+pub fn part_mass(shape: PartType, size: [f32; 3], density: f32) -> f32 {
+    part_shape(shape, size).mass_properties(density).mass()
+}
+
+/// A part as it enters the physics world (see `PhysicsWorld::add_part`)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PartDesc {
+    pub position: [f32; 3],
+    /// Quaternion [x, y, z, w]
+    pub rotation: [f32; 4],
+    /// Full extents, like Part.Size
+    pub size: [f32; 3],
+    pub shape: PartType,
+    pub anchored: bool,
+    /// false adds the part as a sensor (no physical response)
+    pub can_collide: bool,
+}
+
+/// Density and surface response of a part's collider. Touching surfaces
+/// combine by averaging their friction and restitution.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColliderMaterial {
+    pub density: f32,
+    pub friction: f32,
+    pub restitution: f32,
+}
+
+impl Default for ColliderMaterial {
+    fn default() -> Self {
+        Self {
+            density: 1.0,
+            friction: 0.5,
+            restitution: 0.0,
+        }
+    }
+}
 
 /// State for a character controller (player or NPC)
 pub struct CharacterControllerState {
@@ -20,10 +105,204 @@ pub struct CharacterControllerState {
     pub collider_handle: ColliderHandle,
     pub body_handle: RigidBodyHandle,
     pub vertical_velocity: f32,
+    /// Horizontal velocity [x, z] from impulses, on top of walking; decays over time
+    pub knockback: [f32; 2],
+    /// Horizontal walking velocity [x, z] of the last step; lags behind the
+    /// input on slippery floors
+    pub walk_velocity: [f32; 2],
     pub target_position: Option<[f32; 3]>,
+    /// Seconds spent walking toward `target_position`
+    pub target_elapsed: f32,
     pub grounded: bool,
+    /// Collider right under the character at the start of its last move
+    pub ground: Option<ColliderHandle>,
     pub jump_requested: bool,
     pub jump_power: f32,
+    /// Collision group of the character's root part (see `character_interaction_groups`)
+    pub collision_groups: InteractionGroups,
+}
+
+/// Motion of a dynamic part's rigid body, as saved in instance snapshots
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct BodyState {
+    pub position: [f32; 3],
+    /// Quaternion [x, y, z, w]
+    pub rotation: [f32; 4],
+    pub linvel: [f32; 3],
+    pub angvel: [f32; 3],
+}
+
+/// Where a joint attaches to a part, relative to the part
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct JointFrame {
+    pub position: [f32; 3],
+    /// Quaternion [x, y, z, w]
+    pub rotation: [f32; 4],
+}
+
+impl JointFrame {
+    fn isometry(&self) -> Isometry<Real> {
+        let [x, y, z, w] = self.rotation;
+        Isometry::from_parts(
+            vector![self.position[0], self.position[1], self.position[2]].into(),
+            UnitQuaternion::new_normalize(Quaternion::new(w, x, y, z)),
+        )
+    }
+}
+
+/// What a joint allows between its parts
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum JointKind {
+    /// No relative motion (Weld)
+    Fixed,
+    /// Free rotation about the anchor (BallSocketConstraint)
+    Spherical,
+    /// Rotation about the frames' X axis, limits in radians (HingeConstraint)
+    Revolute {
+        limits: Option<[f32; 2]>,
+        motor: JointMotor,
+    },
+    /// Sliding along the frames' X axis, limits in studs (PrismaticConstraint)
+    Prismatic {
+        limits: Option<[f32; 2]>,
+        motor: JointMotor,
+    },
+    /// Keeps the anchors at most `length` apart (RopeConstraint)
+    Rope { length: f32 },
+    /// Pulls the anchors toward `free_length` apart (SpringConstraint)
+    Spring {
+        free_length: f32,
+        stiffness: f32,
+        damping: f32,
+    },
+}
+
+/// Actuator on the free axis of a revolute or prismatic joint
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum JointMotor {
+    None,
+    /// Drives the axis at `velocity` with at most `max_force`
+    Velocity { velocity: f32, max_force: f32 },
+    /// Pulls the axis toward `target` with at most `max_force`
+    Position { target: f32, max_force: f32 },
+}
+
+/// A joint between two parts, from a Weld or an attachment constraint
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct JointSpec {
+    pub part0: u64,
+    pub part1: u64,
+    pub frame0: JointFrame,
+    pub frame1: JointFrame,
+    pub kind: JointKind,
+}
+
+impl JointSpec {
+    fn build(&self) -> GenericJoint {
+        let (frame1, frame2) = (self.frame0.isometry(), self.frame1.isometry());
+        let generic = |axes: JointAxesMask| {
+            GenericJointBuilder::new(axes)
+                .local_frame1(frame1)
+                .local_frame2(frame2)
+        };
+        match self.kind {
+            // Welded parts move as one, so they never touch each other
+            JointKind::Fixed => generic(JointAxesMask::LOCKED_FIXED_AXES)
+                .contacts_enabled(false)
+                .build(),
+            JointKind::Spherical => generic(JointAxesMask::LOCKED_SPHERICAL_AXES).build(),
+            JointKind::Revolute { limits, motor } => {
+                actuate(generic(JointAxesMask::LOCKED_REVOLUTE_AXES), JointAxis::AngX, limits, motor)
+            }
+            JointKind::Prismatic { limits, motor } => {
+                actuate(generic(JointAxesMask::LOCKED_PRISMATIC_AXES), JointAxis::LinX, limits, motor)
+            }
+            JointKind::Rope { length } => RopeJointBuilder::new(length.max(consts::MIN_ROPE_LENGTH))
+                .local_anchor1(frame1.translation.vector.into())
+                .local_anchor2(frame2.translation.vector.into())
+                .build()
+                .into(),
+            JointKind::Spring {
+                free_length,
+                stiffness,
+                damping,
+            } => SpringJointBuilder::new(free_length, stiffness, damping)
+                .local_anchor1(frame1.translation.vector.into())
+                .local_anchor2(frame2.translation.vector.into())
+                .build()
+                .into(),
+        }
+    }
+}
+
+/// Adds limits and a motor to the free axis of a joint
+fn actuate(
+    builder: GenericJointBuilder,
+    axis: JointAxis,
+    limits: Option<[f32; 2]>,
+    motor: JointMotor,
+) -> GenericJoint {
+    let builder = match limits {
+        Some(limits) => builder.limits(axis, limits),
+        None => builder,
+    };
+    match motor {
+        JointMotor::None => builder,
+        JointMotor::Velocity { velocity, max_force } => builder
+            .motor_velocity(axis, velocity, consts::MOTOR_VELOCITY_GAIN)
+            .motor_max_force(axis, max_force),
+        JointMotor::Position { target, max_force } => builder
+            .motor_position(axis, target, consts::SERVO_STIFFNESS, consts::SERVO_DAMPING)
+            .motor_max_force(axis, max_force),
+    }
+    .build()
+}
+
+/// A mover constraint pushing a part, in world space
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MoverSpec {
+    pub part: u64,
+    pub kind: MoverKind,
+}
+
+/// How a mover pushes its part
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MoverKind {
+    /// Constant force at `point`, or at the center of mass when None (VectorForce)
+    Force {
+        force: [f32; 3],
+        point: Option<[f32; 3]>,
+    },
+    /// Drives the part toward `velocity` with at most `max_force` (LinearVelocity)
+    Velocity { velocity: [f32; 3], max_force: f32 },
+    /// Pulls `point` on the part toward `target` (AlignPosition). A rigid mover
+    /// ignores its limits and reaches the target in one step.
+    Position {
+        point: [f32; 3],
+        target: [f32; 3],
+        max_force: f32,
+        max_velocity: f32,
+        responsiveness: f32,
+        rigid: bool,
+    },
+}
+
+/// Scales `v` down to at most `max` long
+fn clamp_length(v: Vector<Real>, max: f32) -> Vector<Real> {
+    let length = v.norm();
+    if length > max {
+        v * (max / length)
+    } else {
+        v
+    }
+}
+
+/// A touch between two Lua parts that began or ended during a physics step
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TouchEvent {
+    pub part_a: u64,
+    pub part_b: u64,
+    pub started: bool,
 }
 
 /// Wrapper around Rapier3D physics world for game physics simulation.
@@ -48,11 +327,37 @@ pub struct PhysicsWorld {
     pub body_to_lua: HashMap<RigidBodyHandle, u64>,
     /// Character controllers for player movement
     pub character_controllers: HashMap<u64, CharacterControllerState>,
+    /// Shape and size each part's collider was built with (to detect runtime changes)
+    part_geometry: HashMap<u64, (PartType, [f32; 3])>,
+    /// Material each part's collider was built with (kept when the collider is rebuilt)
+    part_materials: HashMap<u64, ColliderMaterial>,
+    /// Material each part is made of, for characters' FloorMaterial and water
+    surface_materials: HashMap<u64, Material>,
+    /// Joints of Welds and constraints, keyed by the Lua instance ID of the Weld or constraint
+    joints: HashMap<u64, (ImpulseJointHandle, JointSpec)>,
+    /// Mover constraints, keyed by the Lua instance ID of the constraint
+    movers: HashMap<u64, MoverSpec>,
+    /// Bodies movers put forces on in the last step
+    pushed_bodies: Vec<RigidBodyHandle>,
+
+    /// Channels receiving Rapier collision events during step()
+    collision_send: crossbeam_channel::Sender<CollisionEvent>,
+    collision_recv: crossbeam_channel::Receiver<CollisionEvent>,
+    contact_force_send: crossbeam_channel::Sender<ContactForceEvent>,
+    /// Touch begin/end events collected since the last drain_touch_events()
+    touch_events: Vec<TouchEvent>,
+    /// Pairs of Lua parts touching as of the last step, as (smaller id, larger id)
+    touching: HashSet<(u64, u64)>,
+    /// Parts whose collider was rebuilt or removed since the last step
+    rebuilt: HashSet<u64>,
 }
 
 impl PhysicsWorld {
     /// Creates a new physics world with default gravity
     pub fn new() -> Self {
+        let (collision_send, collision_recv) = crossbeam_channel::unbounded();
+        // Contact force events are never enabled; the receiver is dropped
+        let (contact_force_send, _) = crossbeam_channel::unbounded();
         Self {
             gravity: vector![0.0, -consts::DEFAULT_GRAVITY, 0.0],
             rigid_body_set: RigidBodySet::new(),
@@ -69,6 +374,18 @@ impl PhysicsWorld {
             lua_to_body: HashMap::new(),
             body_to_lua: HashMap::new(),
             character_controllers: HashMap::new(),
+            part_geometry: HashMap::new(),
+            part_materials: HashMap::new(),
+            surface_materials: HashMap::new(),
+            joints: HashMap::new(),
+            movers: HashMap::new(),
+            pushed_bodies: Vec::new(),
+            collision_send,
+            collision_recv,
+            contact_force_send,
+            touch_events: Vec::new(),
+            touching: HashSet::new(),
+            rebuilt: HashSet::new(),
         }
     }
 
@@ -80,6 +397,9 @@ impl PhysicsWorld {
     /// Steps the physics simulation forward by dt seconds
     pub fn step(&mut self, dt: f32) {
         self.integration_parameters.dt = dt;
+        self.apply_movers(dt);
+        let event_handler =
+            ChannelEventCollector::new(self.collision_send.clone(), self.contact_force_send.clone());
         self.physics_pipeline.step(
             &self.gravity,
             &self.integration_parameters,
@@ -93,22 +413,94 @@ impl PhysicsWorld {
             &mut self.ccd_solver,
             Some(&mut self.query_pipeline),
             &(),
-            &(),
+            &event_handler,
         );
+        self.collect_touch_events();
+    }
+
+    /// Converts collision events from the last step into touch events between Lua parts.
+    /// Covers both solid contacts and sensor (CanCollide=false) intersections.
+    /// A rebuilt collider (see `set_geometry`) keeps the touches its new shape
+    /// still makes; the ones it lost end, as do all touches of a removed part.
+    fn collect_touch_events(&mut self) {
+        let rebuilt = std::mem::take(&mut self.rebuilt);
+        let mut kept = HashSet::new();
+        while let Ok(event) = self.collision_recv.try_recv() {
+            // Colliders removed by set_size/remove_part are not real touch changes
+            if event.removed() {
+                continue;
+            }
+            let (Some(part_a), Some(part_b)) = (
+                self.collider_to_lua(event.collider1()),
+                self.collider_to_lua(event.collider2()),
+            ) else {
+                continue;
+            };
+            if part_a == part_b {
+                continue;
+            }
+            let pair = (part_a.min(part_b), part_a.max(part_b));
+            if event.started() {
+                if !self.touching.insert(pair) {
+                    // A rebuilt collider found a touch its old one already had
+                    kept.insert(pair);
+                    continue;
+                }
+            } else if !self.touching.remove(&pair) {
+                continue;
+            }
+            self.touch_events.push(TouchEvent {
+                part_a,
+                part_b,
+                started: event.started(),
+            });
+        }
+
+        if rebuilt.is_empty() {
+            return;
+        }
+        let mut lost: Vec<(u64, u64)> = self
+            .touching
+            .iter()
+            .filter(|pair| (rebuilt.contains(&pair.0) || rebuilt.contains(&pair.1)) && !kept.contains(pair))
+            .copied()
+            .collect();
+        lost.sort_unstable();
+        for pair in lost {
+            self.touching.remove(&pair);
+            self.touch_events.push(TouchEvent {
+                part_a: pair.0,
+                part_b: pair.1,
+                started: false,
+            });
+        }
+    }
+
+
+    /// Maps a collider back to the Lua instance ID of its parent body
+    fn collider_to_lua(&self, handle: ColliderHandle) -> Option<u64> {
+        let parent = self.collider_set.get(handle)?.parent()?;
+        self.body_to_lua.get(&parent).copied()
+    }
+
+    /// Takes all touch events collected since the last call
+    pub fn drain_touch_events(&mut self) -> Vec<TouchEvent> {
+        std::mem::take(&mut self.touch_events)
     }
 
     /// Adds a part to the physics world
     /// - Anchored parts become kinematic (position-based, no physics simulation)
     /// - Non-anchored parts become dynamic (affected by gravity and collisions)
-    pub fn add_part(
-        &mut self,
-        lua_id: u64,
-        position: [f32; 3],
-        rotation: [f32; 4], // quaternion [x, y, z, w]
-        size: [f32; 3],
-        anchored: bool,
-        can_collide: bool,
-    ) -> RigidBodyHandle {
+    pub fn add_part(&mut self, lua_id: u64, part: &PartDesc) -> RigidBodyHandle {
+        let PartDesc {
+            position,
+            rotation,
+            size,
+            shape,
+            anchored,
+            can_collide,
+        } = *part;
+
         // Create rigid body with proper quaternion rotation
         let translation = vector![position[0], position[1], position[2]];
         let quat_rotation = UnitQuaternion::new_unchecked(Quaternion::new(
@@ -124,27 +516,53 @@ impl PhysicsWorld {
 
         let handle = self.rigid_body_set.insert(body);
 
-        // Create collider (box shape, half-extents)
-        // Static geometry collides with everything (characters and other static)
-        let collider = ColliderBuilder::cuboid(size[0] / 2.0, size[1] / 2.0, size[2] / 2.0)
-            .sensor(!can_collide) // If can_collide is false, make it a sensor (no physical response)
-            .collision_groups(InteractionGroups::new(GROUP_STATIC, Group::ALL))
-            .build();
-
-        self.collider_set
-            .insert_with_parent(collider, handle, &mut self.rigid_body_set);
+        let material = ColliderMaterial::default();
+        let groups = part_interaction_groups(0, u16::MAX);
+        self.insert_part_collider(handle, shape, size, !can_collide, material, groups);
 
         // Store mappings
         self.lua_to_body.insert(lua_id, handle);
         self.body_to_lua.insert(handle, lua_id);
+        self.part_geometry.insert(lua_id, (shape, size));
+        self.part_materials.insert(lua_id, material);
 
         handle
     }
 
+    /// Creates a part's collider from its shape and attaches it to the body
+    fn insert_part_collider(
+        &mut self,
+        handle: RigidBodyHandle,
+        shape: PartType,
+        size: [f32; 3],
+        sensor: bool,
+        material: ColliderMaterial,
+        groups: InteractionGroups,
+    ) {
+        let collider = ColliderBuilder::new(part_shape(shape, size))
+            .sensor(sensor) // If can_collide is false, make it a sensor (no physical response)
+            .density(material.density)
+            .friction(material.friction)
+            .restitution(material.restitution)
+            .friction_combine_rule(CoefficientCombineRule::Average)
+            .restitution_combine_rule(CoefficientCombineRule::Average)
+            .collision_groups(groups)
+            .active_events(ActiveEvents::COLLISION_EVENTS) // Drives Touched/TouchEnded
+            .build();
+
+        self.collider_set
+            .insert_with_parent(collider, handle, &mut self.rigid_body_set);
+    }
+
     /// Removes a part from the physics world
     pub fn remove_part(&mut self, lua_id: u64) -> bool {
         if let Some(handle) = self.lua_to_body.remove(&lua_id) {
             self.body_to_lua.remove(&handle);
+            self.part_geometry.remove(&lua_id);
+            self.part_materials.remove(&lua_id);
+            self.surface_materials.remove(&lua_id);
+            // Its touches end at the next step, unless a new body takes over the id
+            self.rebuilt.insert(lua_id);
             self.rigid_body_set.remove(
                 handle,
                 &mut self.island_manager,
@@ -172,6 +590,56 @@ impl PhysicsWorld {
         }
     }
 
+    /// Updates the density, friction and restitution of a part's collider
+    pub fn set_material(&mut self, lua_id: u64, material: ColliderMaterial) {
+        if self.part_materials.get(&lua_id) == Some(&material) {
+            return;
+        }
+        let Some(&handle) = self.lua_to_body.get(&lua_id) else {
+            return;
+        };
+        let Some(body) = self.rigid_body_set.get(handle) else {
+            return;
+        };
+        for &collider_handle in body.colliders() {
+            if let Some(collider) = self.collider_set.get_mut(collider_handle) {
+                collider.set_density(material.density);
+                collider.set_friction(material.friction);
+                collider.set_restitution(material.restitution);
+            }
+        }
+        self.part_materials.insert(lua_id, material);
+    }
+
+    /// Records the Material a part is made of
+    pub fn set_surface_material(&mut self, lua_id: u64, material: Material) {
+        self.surface_materials.insert(lua_id, material);
+    }
+
+    /// Puts a part or character in collision group `group`, colliding with the groups in `mask`
+    pub fn set_collision_group(&mut self, lua_id: u64, group: usize, mask: u16) {
+        let groups = match self.character_controllers.get_mut(&lua_id) {
+            Some(state) => {
+                state.collision_groups = character_interaction_groups(group, mask);
+                state.collision_groups
+            }
+            None => part_interaction_groups(group, mask),
+        };
+        let Some(&handle) = self.lua_to_body.get(&lua_id) else {
+            return;
+        };
+        let Some(body) = self.rigid_body_set.get(handle) else {
+            return;
+        };
+        for &collider_handle in body.colliders() {
+            if let Some(collider) = self.collider_set.get_mut(collider_handle) {
+                if collider.collision_groups() != groups {
+                    collider.set_collision_groups(groups);
+                }
+            }
+        }
+    }
+
     /// Updates the position of an anchored (kinematic) part
     pub fn set_kinematic_position(&mut self, handle: RigidBodyHandle, position: [f32; 3]) {
         if let Some(body) = self.rigid_body_set.get_mut(handle) {
@@ -202,6 +670,27 @@ impl PhysicsWorld {
         }
     }
 
+    /// Sets the angular velocity (radians/s) of a dynamic part
+    pub fn set_angular_velocity(&mut self, handle: RigidBodyHandle, angular_velocity: [f32; 3]) {
+        if let Some(body) = self.rigid_body_set.get_mut(handle) {
+            if body.is_dynamic() {
+                let [x, y, z] = angular_velocity;
+                body.set_angvel(vector![x, y, z], true);
+            }
+        }
+    }
+
+    /// Applies a linear impulse at the center of mass and an angular impulse to a dynamic part
+    pub fn apply_impulse(&mut self, handle: RigidBodyHandle, impulse: [f32; 3], angular_impulse: [f32; 3]) {
+        if let Some(body) = self.rigid_body_set.get_mut(handle) {
+            if body.is_dynamic() {
+                body.apply_impulse(vector![impulse[0], impulse[1], impulse[2]], true);
+                let [x, y, z] = angular_impulse;
+                body.apply_torque_impulse(vector![x, y, z], true);
+            }
+        }
+    }
+
     /// Gets the position of a rigid body
     pub fn get_position(&self, handle: RigidBodyHandle) -> Option<[f32; 3]> {
         self.rigid_body_set.get(handle).map(|body| {
@@ -226,6 +715,45 @@ impl PhysicsWorld {
         })
     }
 
+    /// Gets the angular velocity of a rigid body
+    pub fn get_angular_velocity(&self, handle: RigidBodyHandle) -> Option<[f32; 3]> {
+        self.rigid_body_set.get(handle).map(|body| {
+            let vel = body.angvel();
+            [vel.x, vel.y, vel.z]
+        })
+    }
+
+    /// Motion of a dynamic part's body (None for anchored parts, characters and unknown ids)
+    pub fn body_state(&self, lua_id: u64) -> Option<BodyState> {
+        let body = self.rigid_body_set.get(*self.lua_to_body.get(&lua_id)?)?;
+        if !body.is_dynamic() {
+            return None;
+        }
+        let (pos, rot, linvel, angvel) = (body.translation(), body.rotation(), body.linvel(), body.angvel());
+        Some(BodyState {
+            position: [pos.x, pos.y, pos.z],
+            rotation: [rot.i, rot.j, rot.k, rot.w],
+            linvel: [linvel.x, linvel.y, linvel.z],
+            angvel: [angvel.x, angvel.y, angvel.z],
+        })
+    }
+
+    /// Puts a dynamic part's body back into a saved state; other bodies are left alone
+    pub fn set_body_state(&mut self, lua_id: u64, state: &BodyState) {
+        let Some(&handle) = self.lua_to_body.get(&lua_id) else {
+            return;
+        };
+        if let Some(body) = self.rigid_body_set.get_mut(handle) {
+            if body.is_dynamic() {
+                let [x, y, z, w] = state.rotation;
+                body.set_translation(vector![state.position[0], state.position[1], state.position[2]], true);
+                body.set_rotation(UnitQuaternion::new_normalize(Quaternion::new(w, x, y, z)), true);
+                body.set_linvel(vector![state.linvel[0], state.linvel[1], state.linvel[2]], true);
+                body.set_angvel(vector![state.angvel[0], state.angvel[1], state.angvel[2]], true);
+            }
+        }
+    }
+
     /// Checks if a Lua instance has a physics body
     pub fn has_part(&self, lua_id: u64) -> bool {
         self.lua_to_body.contains_key(&lua_id)
@@ -245,6 +773,9 @@ impl PhysicsWorld {
     pub fn set_anchored(&mut self, lua_id: u64, anchored: bool) {
         if let Some(&handle) = self.lua_to_body.get(&lua_id) {
             if let Some(body) = self.rigid_body_set.get_mut(handle) {
+                if body.is_dynamic() != anchored {
+                    return;
+                }
                 if anchored {
                     body.set_body_type(RigidBodyType::KinematicPositionBased, true);
                 } else {
@@ -252,35 +783,207 @@ impl PhysicsWorld {
                 }
             }
         }
+        if !anchored {
+            // A part let go of by the script joins what it is welded to
+            let welds: Vec<JointSpec> = self
+                .joints
+                .values()
+                .map(|(_, spec)| *spec)
+                .filter(|spec| spec.part1 == lua_id)
+                .collect();
+            for spec in welds {
+                self.snap_to_joint(&spec);
+            }
+        }
     }
 
-    /// Updates the size of a part's collider
-    pub fn set_size(&mut self, lua_id: u64, size: [f32; 3]) {
-        if let Some(&handle) = self.lua_to_body.get(&lua_id) {
-            // Get colliders attached to this body
-            if let Some(body) = self.rigid_body_set.get(handle) {
-                let colliders: Vec<_> = body.colliders().iter().cloned().collect();
-
-                // Remove old colliders and add new ones with updated size
-                for collider_handle in colliders {
-                    self.collider_set.remove(
-                        collider_handle,
-                        &mut self.island_manager,
-                        &mut self.rigid_body_set,
-                        true,
-                    );
+    /// Creates or updates the joint of a Weld or constraint. A new or changed
+    /// fixed joint first moves a dynamic Part1 into place. Returns false,
+    /// removing any old joint, when a part has no body.
+    pub fn set_joint(&mut self, lua_id: u64, spec: JointSpec) -> bool {
+        let (Some(body0), Some(body1)) = (self.get_handle(spec.part0), self.get_handle(spec.part1))
+        else {
+            self.remove_joint(lua_id);
+            return false;
+        };
+        if body0 == body1 {
+            self.remove_joint(lua_id);
+            return false;
+        }
+
+        if let Some((handle, old)) = self.joints.get_mut(&lua_id) {
+            // Joints go away with their bodies, so the handle may be stale
+            let same_parts = old.part0 == spec.part0 && old.part1 == spec.part1;
+            if same_parts && self.impulse_joint_set.contains(*handle) {
+                if *old != spec {
+                    if let Some(joint) = self.impulse_joint_set.get_mut(*handle, true) {
+                        joint.data = spec.build();
+                    }
+                    *old = spec;
+                    self.snap_to_joint(&spec);
                 }
+                return true;
+            }
+        }
+        self.remove_joint(lua_id);
+
+        self.snap_to_joint(&spec);
+        let handle = self.impulse_joint_set.insert(body0, body1, spec.build(), true);
+        self.joints.insert(lua_id, (handle, spec));
+        true
+    }
+
+    /// Moves the dynamic Part1 of a fixed joint to where the joint holds it
+    fn snap_to_joint(&mut self, spec: &JointSpec) {
+        if spec.kind != JointKind::Fixed {
+            return;
+        }
+        let (Some(body0), Some(body1)) = (self.get_handle(spec.part0), self.get_handle(spec.part1))
+        else {
+            return;
+        };
+        let Some(position0) = self.rigid_body_set.get(body0).map(|body| *body.position()) else {
+            return;
+        };
+        if let Some(body) = self.rigid_body_set.get_mut(body1) {
+            if body.is_dynamic() {
+                body.set_position(position0 * spec.frame0.isometry() * spec.frame1.isometry().inverse(), true);
+            }
+        }
+    }
+
+    /// Removes the joint of a Weld or constraint
+    pub fn remove_joint(&mut self, lua_id: u64) {
+        if let Some((handle, _)) = self.joints.remove(&lua_id) {
+            self.impulse_joint_set.remove(handle, true);
+        }
+    }
 
-                // Add new collider with updated size (maintain static collision group)
-                let collider =
-                    ColliderBuilder::cuboid(size[0] / 2.0, size[1] / 2.0, size[2] / 2.0)
-                        .collision_groups(InteractionGroups::new(GROUP_STATIC, Group::ALL))
-                        .build();
+    /// Lua IDs of the Welds and constraints that have joints
+    pub fn joint_ids(&self) -> Vec<u64> {
+        self.joints.keys().copied().collect()
+    }
+
+    /// Checks if a Weld or constraint has a joint
+    pub fn has_joint(&self, lua_id: u64) -> bool {
+        self.joints
+            .get(&lua_id)
+            .is_some_and(|(handle, _)| self.impulse_joint_set.contains(*handle))
+    }
+
+    /// Creates or updates the mover of a constraint
+    pub fn set_mover(&mut self, lua_id: u64, spec: MoverSpec) {
+        self.movers.insert(lua_id, spec);
+    }
+
+    /// Removes the mover of a constraint
+    pub fn remove_mover(&mut self, lua_id: u64) {
+        self.movers.remove(&lua_id);
+    }
+
+    /// Lua IDs of the constraints that have movers
+    pub fn mover_ids(&self) -> Vec<u64> {
+        self.movers.keys().copied().collect()
+    }
+
+    /// Sets the forces of movers on their dynamic parts for a step of dt
+    /// seconds. Movers that drive velocity also hold their part up against gravity.
+    fn apply_movers(&mut self, dt: f32) {
+        // Rapier keeps forces between steps, so last step's are cleared first
+        for handle in self.pushed_bodies.drain(..) {
+            if let Some(body) = self.rigid_body_set.get_mut(handle) {
+                body.reset_forces(false);
+            }
+        }
 
-                self.collider_set
-                    .insert_with_parent(collider, handle, &mut self.rigid_body_set);
+        for spec in self.movers.values() {
+            let Some(&handle) = self.lua_to_body.get(&spec.part) else {
+                continue;
+            };
+            let Some(body) = self.rigid_body_set.get_mut(handle) else {
+                continue;
+            };
+            if !body.is_dynamic() {
+                continue;
             }
+            let mass = body.mass();
+            let velocity = *body.linvel();
+            // Force that changes the velocity to `target` over the step
+            let drive = |target: Vector<Real>| ((target - velocity) / dt - self.gravity) * mass;
+            let force = match spec.kind {
+                MoverKind::Force { force, point } => {
+                    let force = vector![force[0], force[1], force[2]];
+                    if let Some([x, y, z]) = point {
+                        body.add_force_at_point(force, point![x, y, z], true);
+                        self.pushed_bodies.push(handle);
+                        continue;
+                    }
+                    force
+                }
+                MoverKind::Velocity { velocity, max_force } => {
+                    clamp_length(drive(vector![velocity[0], velocity[1], velocity[2]]), max_force)
+                }
+                MoverKind::Position {
+                    point,
+                    target,
+                    max_force,
+                    max_velocity,
+                    responsiveness,
+                    rigid,
+                } => {
+                    let error = vector![target[0] - point[0], target[1] - point[1], target[2] - point[2]];
+                    if rigid {
+                        drive(error / dt)
+                    } else {
+                        let target = clamp_length(error * responsiveness, max_velocity);
+                        clamp_length(drive(target), max_force)
+                    }
+                }
+            };
+            body.add_force(force, true);
+            self.pushed_bodies.push(handle);
+        }
+    }
+
+    /// Updates the size of a part's collider
+    pub fn set_size(&mut self, lua_id: u64, size: [f32; 3]) {
+        let shape = self
+            .part_geometry
+            .get(&lua_id)
+            .map_or(PartType::Block, |&(shape, _)| shape);
+        self.set_geometry(lua_id, shape, size);
+    }
+
+    /// Rebuilds a part's collider if its shape or size changed
+    pub fn set_geometry(&mut self, lua_id: u64, shape: PartType, size: [f32; 3]) {
+        if self.part_geometry.get(&lua_id) == Some(&(shape, size)) {
+            return;
+        }
+        let Some(&handle) = self.lua_to_body.get(&lua_id) else {
+            return;
+        };
+        // Get colliders attached to this body
+        let Some(body) = self.rigid_body_set.get(handle) else {
+            return;
+        };
+        let colliders: Vec<_> = body.colliders().to_vec();
+        let old_collider = colliders.first().and_then(|&h| self.collider_set.get(h));
+        let is_sensor = old_collider.is_some_and(|c| c.is_sensor());
+        let groups = old_collider.map_or(part_interaction_groups(0, u16::MAX), |c| c.collision_groups());
+
+        // Remove old colliders and add a new one (keeping sensor state and collision groups)
+        for collider_handle in colliders {
+            self.collider_set.remove(
+                collider_handle,
+                &mut self.island_manager,
+                &mut self.rigid_body_set,
+                true,
+            );
         }
+        let material = self.part_materials.get(&lua_id).copied().unwrap_or_default();
+        self.insert_part_collider(handle, shape, size, is_sensor, material, groups);
+        self.part_geometry.insert(lua_id, (shape, size));
+        self.rebuilt.insert(lua_id);
     }
 
     /// Adds a character controller for player movement
@@ -299,10 +1002,16 @@ impl PhysicsWorld {
         let body_handle = self.rigid_body_set.insert(body);
 
         // Create capsule collider (half-height is the cylinder part, total height = 2*half_height + 2*radius)
-        // Characters only collide with static geometry, not other characters (Roblox FPS style)
+        // Characters only collide with parts, not other characters (Roblox FPS style)
         let half_height = (height - 2.0 * radius).max(0.0) / 2.0;
+        // Characters are kinematic like anchored parts, so kinematic pairs must be enabled
+        // for touch events; the contact skin reaches past the controller's ground offset.
+        let groups = character_interaction_groups(0, u16::MAX);
         let collider = ColliderBuilder::capsule_y(half_height, radius)
-            .collision_groups(InteractionGroups::new(GROUP_CHARACTER, GROUP_STATIC))
+            .collision_groups(groups)
+            .active_events(ActiveEvents::COLLISION_EVENTS)
+            .active_collision_types(ActiveCollisionTypes::all())
+            .contact_skin(consts::CHARACTER_TOUCH_SKIN)
             .build();
         let collider_handle = self
             .collider_set
@@ -324,10 +1033,15 @@ impl PhysicsWorld {
             collider_handle,
             body_handle,
             vertical_velocity: 0.0,
+            knockback: [0.0; 2],
+            walk_velocity: [0.0; 2],
             target_position: None,
+            target_elapsed: 0.0,
             grounded: false,
+            ground: None,
             jump_requested: false,
             jump_power: humanoid_consts::DEFAULT_JUMP_POWER,
+            collision_groups: groups,
         };
 
         self.character_controllers.insert(lua_id, state);
@@ -337,14 +1051,15 @@ impl PhysicsWorld {
         body_handle
     }
 
-    /// Sets the target position for a character (for Goto action)
+    /// Sets the target position for a character (for Goto action), restarting its MoveTo timeout
     pub fn set_character_target(&mut self, lua_id: u64, target: Option<[f32; 3]>) {
         if let Some(state) = self.character_controllers.get_mut(&lua_id) {
             state.target_position = target;
+            state.target_elapsed = 0.0;
         }
     }
 
-    /// Teleports a character to a specific position (clears target + vertical velocity)
+    /// Teleports a character to a specific position (clears target, vertical velocity and knockback)
     pub fn set_character_position(&mut self, lua_id: u64, position: [f32; 3]) {
         if let Some(state) = self.character_controllers.get_mut(&lua_id) {
             if let Some(body) = self.rigid_body_set.get_mut(state.body_handle) {
@@ -352,6 +1067,19 @@ impl PhysicsWorld {
             }
             state.target_position = None;
             state.vertical_velocity = 0.0;
+            state.knockback = [0.0; 2];
+            state.walk_velocity = [0.0; 2];
+            state.ground = None;
+        }
+    }
+
+    /// Adds velocity to a character from an impulse: the vertical part launches
+    /// it and the horizontal part knocks it back
+    pub fn add_character_velocity(&mut self, lua_id: u64, velocity: [f32; 3]) {
+        if let Some(state) = self.character_controllers.get_mut(&lua_id) {
+            state.vertical_velocity += velocity[1];
+            state.knockback[0] += velocity[0];
+            state.knockback[1] += velocity[2];
         }
     }
 
@@ -378,6 +1106,7 @@ impl PhysicsWorld {
         if let Some(state) = self.character_controllers.remove(&lua_id) {
             self.lua_to_body.remove(&lua_id);
             self.body_to_lua.remove(&state.body_handle);
+            self.rebuilt.insert(lua_id);
             self.rigid_body_set.remove(
                 state.body_handle,
                 &mut self.island_manager,
@@ -469,6 +1198,49 @@ impl PhysicsWorld {
         }
     }
 
+    /// Collider a character stood on at the start of its last move (found by
+    /// `move_character`, so reading it costs no extra shape cast)
+    fn ground_collider(&self, lua_id: u64) -> Option<ColliderHandle> {
+        self.character_controllers.get(&lua_id)?.ground
+    }
+
+    /// Friction of the floor under a character as of its last move, or None when nothing was below it
+    pub fn ground_friction(&self, lua_id: u64) -> Option<f32> {
+        let ground = self.ground_collider(lua_id)?;
+        Some(self.collider_set.get(ground)?.friction())
+    }
+
+    /// Material of the part under a character as of its last move, or None when nothing was below it
+    pub fn ground_material(&self, lua_id: u64) -> Option<Material> {
+        let part = self.collider_to_lua(self.ground_collider(lua_id)?)?;
+        self.surface_materials.get(&part).copied()
+    }
+
+    /// Whether a character's center is inside a Water part
+    pub fn character_in_water(&self, lua_id: u64) -> bool {
+        let Some(state) = self.character_controllers.get(&lua_id) else {
+            return false;
+        };
+        let Some(body) = self.rigid_body_set.get(state.body_handle) else {
+            return false;
+        };
+        let mut in_water = false;
+        self.query_pipeline.intersections_with_point(
+            &self.rigid_body_set,
+            &self.collider_set,
+            &Point::from(*body.translation()),
+            QueryFilter::new().exclude_rigid_body(state.body_handle),
+            |collider| {
+                in_water = self
+                    .collider_to_lua(collider)
+                    .and_then(|part| self.surface_materials.get(&part))
+                    == Some(&Material::Water);
+                !in_water
+            },
+        );
+        in_water
+    }
+
     /// Moves a character using the kinematic controller for full 3D translation.
     pub fn move_character(
         &mut self,
@@ -502,12 +1274,36 @@ impl PhysicsWorld {
             ..Default::default()
         };
 
-        // Match physics-world's collision filter: collide with all except other characters
-        let desired = vector![desired_translation[0], desired_translation[1], desired_translation[2]];
-        let filter = QueryFilter::default()
-            .exclude_rigid_body(body_handle)
-            .exclude_sensors() // Sensors (CanCollide=false) must not block character movement
-            .groups(InteractionGroups::new(GROUP_STATIC, Group::ALL & !GROUP_CHARACTER));
+        let mut desired = vector![desired_translation[0], desired_translation[1], desired_translation[2]];
+        let filter = character_filter(body_handle, state.collision_groups);
+
+        // On walkable slopes (wedges, tilted parts), walk along the ground plane with a slight lift.
+        // Anchored parts are kinematic, and the controller strips any separating motion against
+        // kinematic contacts, so purely horizontal input ends up stuck at the foot of a ramp.
+        let mut ground = None;
+        if desired.y <= 0.0 {
+            let ground_hit = self.query_pipeline.cast_shape(
+                &self.rigid_body_set,
+                &self.collider_set,
+                &current_pos,
+                &-Vector::y(),
+                shape,
+                rapier3d::parry::query::ShapeCastOptions::with_max_time_of_impact(consts::SNAP_TO_GROUND + 0.05),
+                filter,
+            );
+            if let Some((handle, hit)) = ground_hit {
+                ground = Some(handle);
+                let normal = hit.normal1;
+                let walkable = normal.y >= controller.max_slope_climb_angle.cos();
+                if walkable && normal.y < 1.0 - consts::EPSILON {
+                    let horizontal = vector![desired.x, 0.0, desired.z];
+                    let along_slope = horizontal - *normal * horizontal.dot(&normal);
+                    desired = along_slope
+                        + *normal * horizontal.norm() * consts::SLOPE_WALK_LIFT
+                        + Vector::y() * desired.y;
+                }
+            }
+        }
 
         let movement = controller.move_shape(
             dt,
@@ -530,11 +1326,21 @@ impl PhysicsWorld {
 
         if let Some(state) = self.character_controllers.get_mut(&lua_id) {
             state.grounded = movement.grounded;
+            state.ground = ground;
         }
         Some(movement)
     }
 }
 
+/// What characters collide with: parts their collision group collides with,
+/// never other characters or sensors (CanCollide=false must not block character movement)
+fn character_filter(body_handle: RigidBodyHandle, groups: InteractionGroups) -> QueryFilter<'static> {
+    QueryFilter::default()
+        .exclude_rigid_body(body_handle)
+        .exclude_sensors()
+        .groups(groups)
+}
+
 impl Default for PhysicsWorld {
     fn default() -> Self {
         Self::new()
@@ -557,11 +1363,14 @@ mod tests {
 
         let handle = world.add_part(
             1,
-            [0.0, 10.0, 0.0],
-            [0.0, 0.0, 0.0, 1.0],
-            [4.0, 1.0, 2.0],
-            true,  // anchored
-            true,  // can_collide
+            &PartDesc {
+                position: [0.0, 10.0, 0.0],
+                rotation: [0.0, 0.0, 0.0, 1.0],
+                size: [4.0, 1.0, 2.0],
+                shape: PartType::Block,
+                anchored: true,
+                can_collide: true,
+            },
         );
 
         assert!(world.has_part(1));
@@ -575,11 +1384,14 @@ mod tests {
 
         let handle = world.add_part(
             1,
-            [0.0, 10.0, 0.0],
-            [0.0, 0.0, 0.0, 1.0],
-            [1.0, 1.0, 1.0],
-            false, // not anchored - should fall
-            true,
+            &PartDesc {
+                position: [0.0, 10.0, 0.0],
+                rotation: [0.0, 0.0, 0.0, 1.0],
+                size: [1.0, 1.0, 1.0],
+                shape: PartType::Block,
+                anchored: false, // not anchored - should fall
+                can_collide: true,
+            },
         );
 
         let initial_pos = world.get_position(handle).unwrap();
@@ -595,6 +1407,280 @@ mod tests {
         assert!(final_pos[1] < initial_pos[1]);
     }
 
+    fn joint(part0: u64, part1: u64, offset: [f32; 3], kind: JointKind) -> JointSpec {
+        let identity = [0.0, 0.0, 0.0, 1.0];
+        JointSpec {
+            part0,
+            part1,
+            frame0: JointFrame {
+                position: offset,
+                rotation: identity,
+            },
+            frame1: JointFrame {
+                position: [0.0; 3],
+                rotation: identity,
+            },
+            kind,
+        }
+    }
+
+    #[test]
+    fn test_weld_joint_moves_parts_as_one() {
+        let mut world = PhysicsWorld::new();
+        let size = [1.0, 1.0, 1.0];
+        let part0 = world.add_part(1, &PartDesc { position: [0.0, 10.0, 0.0], rotation: [0.0, 0.0, 0.0, 1.0], size, shape: PartType::Block, anchored: false, can_collide: true });
+        let part1 = world.add_part(2, &PartDesc { position: [5.0, 0.0, 5.0], rotation: [0.0, 0.0, 0.0, 1.0], size, shape: PartType::Block, anchored: false, can_collide: true });
+
+        // Part1 is moved into place when the joint is created
+        assert!(world.set_joint(10, joint(1, 2, [0.0, 2.0, 0.0], JointKind::Fixed)));
+        assert_eq!(world.get_position(part1).unwrap(), [0.0, 12.0, 0.0]);
+
+        for _ in 0..30 {
+            world.step(1.0 / 60.0);
+        }
+        let pos0 = world.get_position(part0).unwrap();
+        let pos1 = world.get_position(part1).unwrap();
+        assert!(pos0[1] < 9.0, "the assembly falls");
+        assert!((pos1[1] - pos0[1] - 2.0).abs() < 0.05);
+        assert!((pos1[0] - pos0[0]).abs() < 0.05);
+
+        // Joints go away with their parts
+        world.remove_part(2);
+        assert!(!world.has_joint(10));
+        assert!(!world.set_joint(10, joint(1, 2, [0.0, 2.0, 0.0], JointKind::Fixed)));
+        assert!(world.joint_ids().is_empty());
+    }
+
+    #[test]
+    fn test_hinge_motor_spins_part() {
+        let mut world = PhysicsWorld::new();
+        let size = [1.0, 1.0, 1.0];
+        world.add_part(1, &PartDesc { position: [0.0, 10.0, 0.0], rotation: [0.0, 0.0, 0.0, 1.0], size, shape: PartType::Block, anchored: true, can_collide: false });
+        let wheel = world.add_part(2, &PartDesc { position: [0.0, 10.0, 0.0], rotation: [0.0, 0.0, 0.0, 1.0], size, shape: PartType::Block, anchored: false, can_collide: false });
+
+        let motor = JointMotor::Velocity {
+            velocity: 4.0,
+            max_force: 1000.0,
+        };
+        let hinge = JointKind::Revolute {
+            limits: None,
+            motor,
+        };
+        world.set_joint(10, joint(1, 2, [0.0; 3], hinge));
+        for _ in 0..60 {
+            world.step(1.0 / 60.0);
+        }
+
+        let body = &world.rigid_body_set[wheel];
+        assert!((body.angvel().x - 4.0).abs() < 0.1, "angvel {:?}", body.angvel());
+        assert!(body.angvel().y.abs() < 0.01 && body.angvel().z.abs() < 0.01);
+        assert!((body.translation().y - 10.0).abs() < 0.01, "the hinge holds the wheel up");
+
+        // Updating the joint keeps it and changes its motor
+        let stopped = JointKind::Revolute {
+            limits: None,
+            motor: JointMotor::Position {
+                target: 0.0,
+                max_force: 1000.0,
+            },
+        };
+        assert!(world.set_joint(10, joint(1, 2, [0.0; 3], stopped)));
+        assert_eq!(world.impulse_joint_set.len(), 1);
+    }
+
+    #[test]
+    fn test_rope_and_spring_joints() {
+        let mut world = PhysicsWorld::new();
+        let size = [1.0, 1.0, 1.0];
+        world.add_part(1, &PartDesc { position: [0.0, 20.0, 0.0], rotation: [0.0, 0.0, 0.0, 1.0], size, shape: PartType::Block, anchored: true, can_collide: false });
+        let roped = world.add_part(2, &PartDesc { position: [0.0, 19.0, 0.0], rotation: [0.0, 0.0, 0.0, 1.0], size, shape: PartType::Ball, anchored: false, can_collide: false });
+        world.add_part(3, &PartDesc { position: [10.0, 20.0, 0.0], rotation: [0.0, 0.0, 0.0, 1.0], size, shape: PartType::Block, anchored: true, can_collide: false });
+        let sprung = world.add_part(4, &PartDesc { position: [10.0, 19.0, 0.0], rotation: [0.0, 0.0, 0.0, 1.0], size, shape: PartType::Ball, anchored: false, can_collide: false });
+
+        world.set_joint(10, joint(1, 2, [0.0; 3], JointKind::Rope { length: 3.0 }));
+        let spring = JointKind::Spring {
+            free_length: 2.0,
+            stiffness: 500.0,
+            damping: 50.0,
+        };
+        world.set_joint(11, joint(3, 4, [0.0; 3], spring));
+        for _ in 0..180 {
+            world.step(1.0 / 60.0);
+        }
+
+        // The rope stops the fall at its length
+        let rope_y = world.get_position(roped).unwrap()[1];
+        assert!((rope_y - 17.0).abs() < 0.1, "rope end at {rope_y}");
+        // The spring settles past its free length, where it holds the weight
+        let spring_y = world.get_position(sprung).unwrap()[1];
+        assert!(spring_y < 18.0 && spring_y > 10.0, "spring end at {spring_y}");
+    }
+
+    #[test]
+    fn test_impulses_and_movers() {
+        let mut world = PhysicsWorld::new();
+        let size = [1.0, 1.0, 1.0];
+        let pushed = world.add_part(1, &PartDesc { position: [0.0, 50.0, 0.0], rotation: [0.0, 0.0, 0.0, 1.0], size, shape: PartType::Block, anchored: false, can_collide: false });
+        let floating = world.add_part(2, &PartDesc { position: [10.0, 10.0, 0.0], rotation: [0.0, 0.0, 0.0, 1.0], size, shape: PartType::Block, anchored: false, can_collide: false });
+        let aligned = world.add_part(3, &PartDesc { position: [20.0, 10.0, 0.0], rotation: [0.0, 0.0, 0.0, 1.0], size, shape: PartType::Block, anchored: false, can_collide: false });
+        assert_eq!(part_mass(PartType::Block, size, 1.0), 1.0);
+
+        // Impulses change velocity at once, by impulse / mass
+        world.apply_impulse(pushed, [0.0, 0.0, 3.0], [0.0, 2.0, 0.0]);
+        assert_eq!(world.get_velocity(pushed).unwrap(), [0.0, 0.0, 3.0]);
+        assert!(world.get_angular_velocity(pushed).unwrap()[1] > 0.0);
+
+        // A force cancelling gravity keeps a part floating
+        let lift = MoverKind::Force {
+            force: [0.0, consts::DEFAULT_GRAVITY, 0.0],
+            point: None,
+        };
+        world.set_mover(10, MoverSpec { part: 2, kind: lift });
+        for _ in 0..60 {
+            // Movers are updated with their attachment's position every tick
+            let align = MoverKind::Position {
+                point: world.get_position(aligned).unwrap(),
+                target: [20.0, 15.0, 0.0],
+                max_force: 1000.0,
+                max_velocity: 10.0,
+                responsiveness: 10.0,
+                rigid: false,
+            };
+            world.set_mover(11, MoverSpec { part: 3, kind: align });
+            world.step(1.0 / 60.0);
+        }
+        assert!((world.get_position(floating).unwrap()[1] - 10.0).abs() < 0.01);
+        let aligned_y = world.get_position(aligned).unwrap()[1];
+        assert!((aligned_y - 15.0).abs() < 0.1, "aligned part at {aligned_y}");
+
+        // Removed movers stop pushing
+        world.remove_mover(10);
+        assert_eq!(world.mover_ids(), vec![11]);
+        for _ in 0..10 {
+            world.step(1.0 / 60.0);
+        }
+        assert!(world.get_position(floating).unwrap()[1] < 9.9);
+    }
+
+    #[test]
+    fn test_collider_materials_bounce_and_slide() {
+        let mut world = PhysicsWorld::new();
+        let rotation = [0.0, 0.0, 0.0, 1.0];
+        let material = |density, friction, restitution| ColliderMaterial {
+            density,
+            friction,
+            restitution,
+        };
+        world.add_part(1, &PartDesc { position: [0.0, -0.5, 0.0], rotation, size: [200.0, 1.0, 200.0], shape: PartType::Block, anchored: true, can_collide: true });
+        world.set_material(1, material(1.0, 0.0, 0.0));
+
+        // Contacts average both surfaces: a fully elastic ball bounces at half speed
+        let ball = world.add_part(2, &PartDesc { position: [0.0, 10.0, 0.0], rotation, size: [2.0; 3], shape: PartType::Ball, anchored: false, can_collide: true });
+        world.set_material(2, material(1.0, 0.0, 1.0));
+        let dead = world.add_part(3, &PartDesc { position: [10.0, 10.0, 0.0], rotation, size: [2.0; 3], shape: PartType::Block, anchored: false, can_collide: true });
+        world.set_material(3, material(2.0, 0.0, 0.0));
+
+        // Both slide at 10 studs/s; only the grippy one is stopped by friction
+        let slippery = world.add_part(4, &PartDesc { position: [20.0, 1.0, 0.0], rotation, size: [2.0; 3], shape: PartType::Block, anchored: false, can_collide: true });
+        world.set_material(4, material(1.0, 0.0, 0.0));
+        let grippy = world.add_part(5, &PartDesc { position: [20.0, 1.0, 20.0], rotation, size: [2.0; 3], shape: PartType::Block, anchored: false, can_collide: true });
+        world.set_material(5, material(1.0, 2.0, 0.0));
+        world.set_velocity(slippery, [10.0, 0.0, 0.0]);
+        world.set_velocity(grippy, [10.0, 0.0, 0.0]);
+
+        let mut ball_peak = f32::MIN;
+        let mut dead_peak = f32::MIN;
+        let mut landed = false;
+        for _ in 0..60 {
+            world.step(1.0 / 60.0);
+            landed |= world.get_velocity(ball).unwrap()[1] > 0.0;
+            if landed {
+                ball_peak = ball_peak.max(world.get_position(ball).unwrap()[1]);
+                dead_peak = dead_peak.max(world.get_position(dead).unwrap()[1]);
+            }
+        }
+        assert!(ball_peak > 2.5, "ball should bounce, peaked at {ball_peak}");
+        assert!(dead_peak < 1.2, "block should not bounce, peaked at {dead_peak}");
+        assert!(world.get_position(slippery).unwrap()[0] > 28.0);
+        assert!(world.get_position(grippy).unwrap()[0] < 22.0);
+
+        // Density sets the mass of the body
+        assert_eq!(world.rigid_body_set[dead].mass(), 16.0);
+    }
+
+    #[test]
+    fn test_touch_events_for_contact_and_sensor() {
+        let mut world = PhysicsWorld::new();
+
+        // Anchored floor and a dynamic box dropped onto it
+        world.add_part(1, &PartDesc { position: [0.0, -0.5, 0.0], rotation: [0.0, 0.0, 0.0, 1.0], size: [100.0, 1.0, 100.0], shape: PartType::Block, anchored: true, can_collide: true });
+        world.add_part(2, &PartDesc { position: [0.0, 1.0, 0.0], rotation: [0.0, 0.0, 0.0, 1.0], size: [1.0, 1.0, 1.0], shape: PartType::Block, anchored: false, can_collide: true });
+
+        // Anchored non-colliding trigger overlapping the character spawn
+        world.add_part(3, &PartDesc { position: [10.0, 2.5, 0.0], rotation: [0.0, 0.0, 0.0, 1.0], size: [4.0, 4.0, 4.0], shape: PartType::Block, anchored: true, can_collide: false });
+        world.add_character(100, [10.0, 2.6, 0.0], 1.0, 5.0);
+
+        let mut events = Vec::new();
+        for _ in 0..60 {
+            world.step(1.0 / 60.0);
+            events.extend(world.drain_touch_events());
+        }
+
+        let touched = |a: u64, b: u64| {
+            events.iter().any(|e| {
+                e.started && ((e.part_a == a && e.part_b == b) || (e.part_a == b && e.part_b == a))
+            })
+        };
+        assert!(touched(1, 2), "Falling box should touch the floor: {:?}", events);
+        assert!(touched(3, 100), "Character should touch the sensor: {:?}", events);
+        assert!(touched(1, 100), "Character resting on floor should touch it: {:?}", events);
+        assert!(world.drain_touch_events().is_empty());
+    }
+
+    #[test]
+    fn test_ball_shape_line_of_sight_and_rebuild() {
+        let mut world = PhysicsWorld::new();
+
+        // Ball with radius 2 at the origin
+        world.add_part(1, &PartDesc { position: [0.0, 0.0, 0.0], rotation: [0.0, 0.0, 0.0, 1.0], size: [4.0, 4.0, 4.0], shape: PartType::Ball, anchored: true, can_collide: true });
+        world.query_pipeline.update(&world.collider_set);
+
+        // Ray through the bounding box corner region, ~2.55 studs from the center
+        let from = [-10.0, 1.8, 1.8];
+        let to = [10.0, 1.8, 1.8];
+        assert!(world.has_line_of_sight(from, to, None), "Ray should pass beside the sphere");
+        assert!(!world.has_line_of_sight([-10.0, 0.0, 0.0], [10.0, 0.0, 0.0], None));
+
+        // Switching to a block rebuilds the collider and blocks the same ray
+        world.set_geometry(1, PartType::Block, [4.0, 4.0, 4.0]);
+        world.query_pipeline.update(&world.collider_set);
+        assert!(!world.has_line_of_sight(from, to, None), "Block corner should block the ray");
+    }
+
+    #[test]
+    fn test_character_walks_up_wedge() {
+        let mut world = PhysicsWorld::new();
+
+        world.add_part(1, &PartDesc { position: [0.0, -0.5, 0.0], rotation: [0.0, 0.0, 0.0, 1.0], size: [100.0, 1.0, 100.0], shape: PartType::Block, anchored: true, can_collide: true });
+        // Ramp from Y=0 at Z=5 up to Y=4 at Z=25 (too tall to autostep as a block)
+        world.add_part(2, &PartDesc { position: [0.0, 2.0, 15.0], rotation: [0.0, 0.0, 0.0, 1.0], size: [10.0, 4.0, 20.0], shape: PartType::Wedge, anchored: true, can_collide: true });
+
+        let char_id = 100;
+        world.add_character(char_id, [0.0, 2.6, 0.0], 1.0, 5.0);
+
+        let dt = 1.0 / 60.0;
+        world.step(dt);
+        for _ in 0..90 {
+            world.query_pipeline.update(&world.collider_set);
+            world.move_character(char_id, [0.0, 0.0, 0.2], dt);
+            world.step(dt);
+        }
+
+        let final_pos = world.get_character_position(char_id).unwrap();
+        assert!(final_pos[2] > 15.0, "Character should travel up the ramp: {:?}", final_pos);
+        assert!(final_pos[1] > 4.5, "Character should gain height on the ramp: {:?}", final_pos);
+    }
+
     #[test]
     fn test_character_raycast_and_move() {
         let mut world = PhysicsWorld::new();
@@ -602,11 +1688,14 @@ mod tests {
         // Add a floor
         world.add_part(
             1,
-            [0.0, 0.0, 0.0],
-            [0.0, 0.0, 0.0, 1.0],
-            [100.0, 1.0, 100.0],
-            true,
-            true,
+            &PartDesc {
+                position: [0.0, 0.0, 0.0],
+                rotation: [0.0, 0.0, 0.0, 1.0],
+                size: [100.0, 1.0, 100.0],
+                shape: PartType::Block,
+                anchored: true,
+                can_collide: true,
+            },
         );
 
         // Add character above floor
@@ -645,11 +1734,14 @@ mod tests {
 
         world.add_part(
             1,
-            [0.0, 0.0, 0.0],
-            [0.0, 0.0, 0.0, 1.0],
-            [100.0, 1.0, 100.0],
-            true,
-            true,
+            &PartDesc {
+                position: [0.0, 0.0, 0.0],
+                rotation: [0.0, 0.0, 0.0, 1.0],
+                size: [100.0, 1.0, 100.0],
+                shape: PartType::Block,
+                anchored: true,
+                can_collide: true,
+            },
         );
 
         let char_id = 200;
@@ -672,21 +1764,27 @@ mod tests {
         // Floor at Y=0 (like the game)
         world.add_part(
             1,
-            [0.0, -1.0, 0.0],       // center at Y=-1
-            [0.0, 0.0, 0.0, 1.0],
-            [100.0, 2.0, 100.0],    // top at Y=0
-            true,
-            true,
+            &PartDesc {
+                position: [0.0, -1.0, 0.0], // center at Y=-1
+                rotation: [0.0, 0.0, 0.0, 1.0],
+                size: [100.0, 2.0, 100.0], // top at Y=0
+                shape: PartType::Block,
+                anchored: true,
+                can_collide: true,
+            },
         );
 
         // Thin platform at Y=0.1 (like base platform in tsunami game)
         world.add_part(
             2,
-            [0.0, 0.1, 0.0],        // center at Y=0.1
-            [0.0, 0.0, 0.0, 1.0],
-            [30.0, 0.2, 30.0],      // top at Y=0.2
-            true,
-            true,
+            &PartDesc {
+                position: [0.0, 0.1, 0.0], // center at Y=0.1
+                rotation: [0.0, 0.0, 0.0, 1.0],
+                size: [30.0, 0.2, 30.0], // top at Y=0.2
+                shape: PartType::Block,
+                anchored: true,
+                can_collide: true,
+            },
         );
 
         // Character spawns at Y=3 (above platform)
@@ -727,11 +1825,14 @@ mod tests {
         // Floor at Y=0 (top surface)
         world.add_part(
             1,
-            [0.0, -1.0, 0.0],
-            [0.0, 0.0, 0.0, 1.0],
-            [200.0, 2.0, 200.0],
-            true,
-            true,
+            &PartDesc {
+                position: [0.0, -1.0, 0.0],
+                rotation: [0.0, 0.0, 0.0, 1.0],
+                size: [200.0, 2.0, 200.0],
+                shape: PartType::Block,
+                anchored: true,
+                can_collide: true,
+            },
         );
 
         // Character with radius=1.0, height=5.0 (same as game)
@@ -807,21 +1908,27 @@ mod tests {
         // Floor
         world.add_part(
             1,
-            [0.0, -0.5, 0.0],
-            [0.0, 0.0, 0.0, 1.0],
-            [100.0, 1.0, 100.0],  // top at Y=0
-            true,
-            true,
+            &PartDesc {
+                position: [0.0, -0.5, 0.0],
+                rotation: [0.0, 0.0, 0.0, 1.0],
+                size: [100.0, 1.0, 100.0], // top at Y=0
+                shape: PartType::Block,
+                anchored: true,
+                can_collide: true,
+            },
         );
 
         // Small obstacle (0.3 studs tall, should be steppable with max_height=0.5)
         world.add_part(
             2,
-            [5.0, 0.15, 0.0],      // center at Y=0.15
-            [0.0, 0.0, 0.0, 1.0],
-            [1.0, 0.3, 4.0],       // top at Y=0.3
-            true,
-            true,
+            &PartDesc {
+                position: [5.0, 0.15, 0.0], // center at Y=0.15
+                rotation: [0.0, 0.0, 0.0, 1.0],
+                size: [1.0, 0.3, 4.0], // top at Y=0.3
+                shape: PartType::Block,
+                anchored: true,
+                can_collide: true,
+            },
         );
 
         // Character starts at X=0, grounded
@@ -866,21 +1973,27 @@ mod tests {
         // Floor
         world.add_part(
             1,
-            [0.0, -0.5, 0.0],
-            [0.0, 0.0, 0.0, 1.0],
-            [100.0, 1.0, 100.0],
-            true,
-            true,
+            &PartDesc {
+                position: [0.0, -0.5, 0.0],
+                rotation: [0.0, 0.0, 0.0, 1.0],
+                size: [100.0, 1.0, 100.0],
+                shape: PartType::Block,
+                anchored: true,
+                can_collide: true,
+            },
         );
 
         // Tall obstacle (1.0 stud tall, should block with max_height=0.5)
         world.add_part(
             2,
-            [5.0, 0.5, 0.0],
-            [0.0, 0.0, 0.0, 1.0],
-            [1.0, 1.0, 4.0],       // top at Y=1.0
-            true,
-            true,
+            &PartDesc {
+                position: [5.0, 0.5, 0.0],
+                rotation: [0.0, 0.0, 0.0, 1.0],
+                size: [1.0, 1.0, 4.0], // top at Y=1.0
+                shape: PartType::Block,
+                anchored: true,
+                can_collide: true,
+            },
         );
 
         let char_id = 100;
@@ -916,21 +2029,27 @@ mod tests {
         // Ground (lower level) - extends UNDER the platform to avoid seam
         world.add_part(
             1,
-            [0.0, -0.5, 0.0],        // center
-            [0.0, 0.0, 0.0, 1.0],
-            [200.0, 1.0, 100.0],     // top at Y=0, extends from X=-100 to X=100
-            true,
-            true,
+            &PartDesc {
+                position: [0.0, -0.5, 0.0], // center
+                rotation: [0.0, 0.0, 0.0, 1.0],
+                size: [200.0, 1.0, 100.0], // top at Y=0, extends from X=-100 to X=100
+                shape: PartType::Block,
+                anchored: true,
+                can_collide: true,
+            },
         );
 
         // Raised platform (like tsunami base) - sits ON TOP of ground
         world.add_part(
             2,
-            [50.0, 0.1, 0.0],        // center at Y=0.1
-            [0.0, 0.0, 0.0, 1.0],
-            [100.0, 0.2, 100.0],     // top at Y=0.2, bottom at Y=0, X from 0 to 100
-            true,
-            true,
+            &PartDesc {
+                position: [50.0, 0.1, 0.0], // center at Y=0.1
+                rotation: [0.0, 0.0, 0.0, 1.0],
+                size: [100.0, 0.2, 100.0], // top at Y=0.2, bottom at Y=0, X from 0 to 100
+                shape: PartType::Block,
+                anchored: true,
+                can_collide: true,
+            },
         );
 
         // Character starts on ground (left side), will try to step up onto platform
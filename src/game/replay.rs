@@ -0,0 +1,481 @@
+//! Session recording and replay.
+//!
+//! A replay file is JSON Lines: a `ReplayHeader` followed by one `ReplayRecord`
+//! per line. Records carry the tick they apply to, so re-simulating a seeded
+//! session reproduces it exactly; periodic spectator keyframes let the
+//! replayer detect where a re-simulation drifts from the original.
+
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+use super::instance::{ErrorMode, GameInstance, SpectatorObservation};
+
+/// Current replay file format version
+pub const REPLAY_VERSION: u32 = 1;
+
+/// How often a spectator keyframe is written while recording (60 ticks = 1 second)
+pub const KEYFRAME_INTERVAL_TICKS: u64 = 60;
+
+/// First line of a replay file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayHeader {
+    pub version: u32,
+    /// Hash of the main script (see `script_hash`)
+    pub script_hash: String,
+    /// Seed of the recorded instance; replays of unseeded sessions are best-effort
+    pub seed: Option<u64>,
+    pub max_players: u32,
+    /// Whether the recorded instance stopped on the first Lua error
+    pub halt_on_error: bool,
+}
+
+/// One recorded event, applied before the tick it names runs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ReplayRecord {
+    Join {
+        tick: u64,
+        agent_id: Uuid,
+        name: String,
+    },
+    Leave {
+        tick: u64,
+        agent_id: Uuid,
+    },
+    Input {
+        tick: u64,
+        agent_id: Uuid,
+        input_type: String,
+        data: serde_json::Value,
+    },
+    /// GameInstance::reset with its launch parameters
+    Reset {
+        tick: u64,
+        params: Option<serde_json::Value>,
+    },
+    /// Spectator observation after `tick` ticks have run
+    Keyframe {
+        tick: u64,
+        observation: serde_json::Value,
+    },
+}
+
+impl ReplayRecord {
+    pub fn tick(&self) -> u64 {
+        match self {
+            ReplayRecord::Join { tick, .. }
+            | ReplayRecord::Leave { tick, .. }
+            | ReplayRecord::Input { tick, .. }
+            | ReplayRecord::Reset { tick, .. }
+            | ReplayRecord::Keyframe { tick, .. } => *tick,
+        }
+    }
+}
+
+/// Stable hash of a script's source (FNV-1a, hex encoded)
+pub fn script_hash(source: &str) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in source.bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    format!("{:016x}", hash)
+}
+
+/// Spectator observation as stored in a keyframe. The instance id is cleared
+/// since it differs between the original run and the replay.
+pub fn keyframe_observation(mut observation: SpectatorObservation) -> serde_json::Value {
+    observation.instance_id = Uuid::nil();
+    serde_json::to_value(observation).unwrap_or(serde_json::Value::Null)
+}
+
+/// Streams records to a replay file
+pub struct ReplayRecorder {
+    writer: BufWriter<File>,
+}
+
+impl ReplayRecorder {
+    pub fn create(path: &Path, header: &ReplayHeader) -> std::io::Result<Self> {
+        let mut recorder = Self {
+            writer: BufWriter::new(File::create(path)?),
+        };
+        recorder.write_line(header)?;
+        recorder.writer.flush()?;
+        Ok(recorder)
+    }
+
+    pub fn record(&mut self, record: &ReplayRecord) -> std::io::Result<()> {
+        self.write_line(record)?;
+        // Keyframes are infrequent; flushing on them bounds what a crash can lose
+        if matches!(record, ReplayRecord::Keyframe { .. }) {
+            self.writer.flush()?;
+        }
+        Ok(())
+    }
+
+    fn write_line<T: Serialize>(&mut self, value: &T) -> std::io::Result<()> {
+        serde_json::to_writer(&mut self.writer, value)?;
+        self.writer.write_all(b"\n")
+    }
+}
+
+/// A parsed replay file
+#[derive(Debug, Clone)]
+pub struct Replay {
+    pub header: ReplayHeader,
+    pub records: Vec<ReplayRecord>,
+}
+
+impl Replay {
+    pub fn from_file(path: &Path) -> Result<Self, ReplayError> {
+        let file = File::open(path).map_err(|e| ReplayError::IoError(path.to_path_buf(), e))?;
+        let mut lines = BufReader::new(file).lines();
+
+        let header_line = lines
+            .next()
+            .ok_or(ReplayError::MissingHeader)?
+            .map_err(|e| ReplayError::IoError(path.to_path_buf(), e))?;
+        let header: ReplayHeader =
+            serde_json::from_str(&header_line).map_err(|e| ReplayError::ParseError(1, e))?;
+        if header.version != REPLAY_VERSION {
+            return Err(ReplayError::UnsupportedVersion(header.version));
+        }
+
+        let mut records = Vec::new();
+        for (i, line) in lines.enumerate() {
+            let line = line.map_err(|e| ReplayError::IoError(path.to_path_buf(), e))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            // A crash can leave a truncated last line; keep everything before it
+            match serde_json::from_str(&line) {
+                Ok(record) => records.push(record),
+                Err(e) if e.is_eof() => break,
+                Err(e) => return Err(ReplayError::ParseError(i + 2, e)),
+            }
+        }
+
+        Ok(Self { header, records })
+    }
+
+    /// Last tick covered by the recording
+    pub fn end_tick(&self) -> u64 {
+        self.records.iter().map(ReplayRecord::tick).max().unwrap_or(0)
+    }
+
+    pub fn keyframe_count(&self) -> usize {
+        self.records
+            .iter()
+            .filter(|r| matches!(r, ReplayRecord::Keyframe { .. }))
+            .count()
+    }
+}
+
+/// A keyframe the re-simulation did not reproduce
+#[derive(Debug, Clone)]
+pub struct Divergence {
+    pub tick: u64,
+    /// JSON path of the first differing value
+    pub path: String,
+}
+
+impl std::fmt::Display for Divergence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "tick {}: {} differs", self.tick, self.path)
+    }
+}
+
+/// Returns the path of the first value that differs between two JSON documents
+fn first_difference(expected: &serde_json::Value, actual: &serde_json::Value, path: &str) -> Option<String> {
+    use serde_json::Value;
+    match (expected, actual) {
+        (Value::Object(a), Value::Object(b)) => {
+            let keys: std::collections::BTreeSet<&String> = a.keys().chain(b.keys()).collect();
+            keys.into_iter().find_map(|key| match (a.get(key), b.get(key)) {
+                (Some(x), Some(y)) => first_difference(x, y, &format!("{}.{}", path, key)),
+                _ => Some(format!("{}.{}", path, key)),
+            })
+        }
+        (Value::Array(a), Value::Array(b)) if a.len() == b.len() => a
+            .iter()
+            .zip(b)
+            .enumerate()
+            .find_map(|(i, (x, y))| first_difference(x, y, &format!("{}[{}]", path, i))),
+        // Observations hold f32 values; JSON parsing may be off by an f64 ulp
+        (Value::Number(a), Value::Number(b))
+            if a.is_f64() && b.is_f64() && a.as_f64().map(|v| v as f32) == b.as_f64().map(|v| v as f32) =>
+        {
+            None
+        }
+        _ if expected == actual => None,
+        _ => Some(path.to_string()),
+    }
+}
+
+/// Re-simulates a replay tick by tick
+pub struct Replayer {
+    replay: Replay,
+    script: String,
+    instance: GameInstance,
+    /// Index of the next record to apply
+    cursor: usize,
+}
+
+impl Replayer {
+    /// Creates a replayer for `replay`, checking that `script` is the recorded script.
+    pub fn new(replay: Replay, script: &str) -> Result<Self, ReplayError> {
+        let found = script_hash(script);
+        if found != replay.header.script_hash {
+            return Err(ReplayError::ScriptMismatch {
+                expected: replay.header.script_hash.clone(),
+                found,
+            });
+        }
+
+        let instance = Self::fresh_instance(&replay.header, script);
+        Ok(Self {
+            replay,
+            script: script.to_string(),
+            instance,
+            cursor: 0,
+        })
+    }
+
+    fn fresh_instance(header: &ReplayHeader, script: &str) -> GameInstance {
+        let error_mode = if header.halt_on_error {
+            ErrorMode::Halt
+        } else {
+            ErrorMode::Continue
+        };
+        let mut instance = GameInstance::new_with_config(Uuid::new_v4(), header.max_players, None, error_mode);
+        instance.seed = header.seed;
+        instance.load_script(script);
+        instance
+    }
+
+    pub fn header(&self) -> &ReplayHeader {
+        &self.replay.header
+    }
+
+    pub fn instance(&self) -> &GameInstance {
+        &self.instance
+    }
+
+    /// Number of ticks simulated so far
+    pub fn tick(&self) -> u64 {
+        self.instance.tick
+    }
+
+    pub fn end_tick(&self) -> u64 {
+        self.replay.end_tick()
+    }
+
+    /// True once the recording is exhausted or the instance halted
+    pub fn finished(&self) -> bool {
+        self.instance.tick >= self.end_tick() || self.instance.halted_error.is_some()
+    }
+
+    /// Applies the events recorded for the current tick, runs it, and checks
+    /// the keyframe for the resulting tick if there is one.
+    pub fn step(&mut self) -> Option<Divergence> {
+        let tick = self.instance.tick;
+        while let Some(record) = self.replay.records.get(self.cursor) {
+            if record.tick() > tick {
+                break;
+            }
+            match record {
+                ReplayRecord::Join { agent_id, name, .. } => {
+                    self.instance.add_player(*agent_id, name);
+                }
+                ReplayRecord::Leave { agent_id, .. } => {
+                    self.instance.remove_player(*agent_id);
+                }
+                ReplayRecord::Reset { params, .. } => {
+                    // A failed reset halts or logs inside the instance, as it did when recorded
+                    let _ = self.instance.reset(params.clone());
+                }
+                ReplayRecord::Input {
+                    agent_id,
+                    input_type,
+                    data,
+                    ..
+                } => {
+                    // Mirrors game::queue_input
+                    if let Some(&user_id) = self.instance.players.get(agent_id) {
+                        self.instance.queue_agent_input(user_id, input_type.clone(), data.clone());
+                        self.instance.record_player_activity(*agent_id);
+                    }
+                }
+                // Keyframes at or before the current tick were checked by the previous step
+                ReplayRecord::Keyframe { .. } => {}
+            }
+            self.cursor += 1;
+        }
+
+        self.instance.tick();
+
+        let tick = self.instance.tick;
+        let expected = self.replay.records[self.cursor..]
+            .iter()
+            .take_while(|r| r.tick() == tick)
+            .find_map(|r| match r {
+                ReplayRecord::Keyframe { observation, .. } => Some(observation),
+                _ => None,
+            })?;
+        let actual = keyframe_observation(self.instance.get_spectator_observation());
+        first_difference(expected, &actual, "$").map(|path| Divergence { tick, path })
+    }
+
+    /// Moves playback to `tick`, restarting the simulation when seeking backwards.
+    pub fn seek(&mut self, tick: u64) {
+        let tick = tick.min(self.end_tick());
+        if tick < self.instance.tick {
+            self.instance = Self::fresh_instance(&self.replay.header, &self.script);
+            self.cursor = 0;
+        }
+        while self.instance.tick < tick && self.instance.halted_error.is_none() {
+            self.step();
+        }
+    }
+
+    /// Re-simulates the remaining recording and returns every divergent keyframe.
+    pub fn verify(&mut self) -> Vec<Divergence> {
+        let mut divergences = Vec::new();
+        while !self.finished() {
+            divergences.extend(self.step());
+        }
+        divergences
+    }
+}
+
+/// Errors that can occur when loading or replaying a recording
+#[derive(Debug)]
+pub enum ReplayError {
+    IoError(PathBuf, std::io::Error),
+    ParseError(usize, serde_json::Error),
+    MissingHeader,
+    UnsupportedVersion(u32),
+    ScriptMismatch { expected: String, found: String },
+}
+
+impl std::fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReplayError::IoError(path, e) => write!(f, "Failed to read {}: {}", path.display(), e),
+            ReplayError::ParseError(line, e) => write!(f, "Invalid replay record on line {}: {}", line, e),
+            ReplayError::MissingHeader => write!(f, "Replay file is empty"),
+            ReplayError::UnsupportedVersion(v) => {
+                write!(f, "Unsupported replay version {} (expected {})", v, REPLAY_VERSION)
+            }
+            ReplayError::ScriptMismatch { expected, found } => write!(
+                f,
+                "Script does not match the recording (recorded hash {}, current hash {})",
+                expected, found
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCRIPT: &str = r#"
+        local floor = Instance.new("Part")
+        floor.Size = Vector3.new(100, 1, 100)
+        floor.Anchored = true
+        floor.Parent = Workspace
+
+        for i = 1, 4 do
+            local crate = Instance.new("Part")
+            crate.Name = "Crate" .. i
+            crate.Position = Vector3.new(math.random(-10, 10), 8, math.random(-10, 10))
+            crate.Parent = Workspace
+        end
+
+        game:GetService("AgentInputService").InputReceived:Connect(function(player, inputType, data)
+            if inputType == "MoveTo" then
+                local humanoid = player.Character:FindFirstChild("Humanoid")
+                humanoid:MoveTo(Vector3.new(data.position[1], data.position[2], data.position[3]))
+            end
+        end)
+    "#;
+
+    /// Records a short seeded session and returns the replay file path
+    fn record_session(path: &Path) {
+        let mut instance = GameInstance::new(Uuid::new_v4(), None);
+        instance.seed = Some(99);
+        instance.load_script(SCRIPT);
+        instance.start_recording(path).unwrap();
+
+        let agent = Uuid::from_u128(5);
+        for tick in 0..200u64 {
+            if tick == 10 {
+                instance.add_player(agent, "Walker");
+            }
+            if tick == 20 {
+                let user_id = instance.players[&agent];
+                instance.queue_agent_input(user_id, "MoveTo".to_string(), serde_json::json!({ "position": [15.0, 0.0, 5.0] }));
+            }
+            if tick == 150 {
+                instance.remove_player(agent);
+            }
+            instance.tick();
+        }
+    }
+
+    fn temp_path() -> PathBuf {
+        std::env::temp_dir().join(format!("clawblox-replay-{}.jsonl", Uuid::new_v4()))
+    }
+
+    #[test]
+    fn test_replay_reproduces_recording() {
+        let path = temp_path();
+        record_session(&path);
+
+        let replay = Replay::from_file(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert_eq!(replay.header.seed, Some(99));
+        assert_eq!(replay.keyframe_count(), 3);
+        assert_eq!(replay.end_tick(), 180);
+
+        let mut replayer = Replayer::new(replay, SCRIPT).unwrap();
+        let divergences = replayer.verify();
+        assert!(divergences.is_empty(), "unexpected divergence: {}", divergences[0]);
+        assert_eq!(replayer.tick(), 180);
+
+        // Seeking backwards restarts the simulation and lands on the same state
+        replayer.seek(60);
+        assert_eq!(replayer.tick(), 60);
+        assert!(replayer.step().is_none());
+    }
+
+    #[test]
+    fn test_replay_detects_divergence() {
+        let path = temp_path();
+        record_session(&path);
+        let mut replay = Replay::from_file(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        for record in &mut replay.records {
+            if let ReplayRecord::Keyframe { tick: 120, observation } = record {
+                observation["entities"][1]["position"][1] = serde_json::json!(1000.0);
+            }
+        }
+
+        let divergences = Replayer::new(replay.clone(), SCRIPT).unwrap().verify();
+        assert_eq!(divergences.len(), 1);
+        assert_eq!(divergences[0].tick, 120);
+        assert_eq!(divergences[0].path, "$.entities[1].position[1]");
+
+        assert!(matches!(
+            Replayer::new(replay, "print('edited')"),
+            Err(ReplayError::ScriptMismatch { .. })
+        ));
+    }
+}
@@ -0,0 +1,435 @@
+//! Instance snapshots for crash recovery and dev restores.
+//!
+//! A snapshot holds what can be saved outside the Lua VM: the Instance trees
+//! under Workspace and Players (properties, attributes, tags), physics body
+//! states, timers waiting in the task scheduler and RewardService stats.
+//!
+//! Lua closures, coroutines and script locals can't be serialized, so a
+//! restore re-runs the script in a fresh VM (like `GameInstance::reset`),
+//! respawns the players and then brings the rebuilt world back to the snapshot:
+//! - instances are matched by path (name and class, in sibling order); matches
+//!   are updated in place so script references and connections keep working,
+//!   instances only in the snapshot are created, and the rest are destroyed
+//! - dynamic bodies and character controllers get their saved motion back
+//! - timers of the new VM are re-timed from the saved ones
+//!   (see `LuaRuntime::restore_schedule`)
+//!
+//! State a script keeps only in Lua variables starts over; keep it in
+//! attributes for it to survive a restore.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+use super::instance::GameStatus;
+use super::lua::instance::{AttributeValue, ClassName, Instance, InstanceData, WeakInstanceRef};
+use super::lua::reflection::{self, PropertyValue};
+use super::lua::services::RewardStats;
+use super::lua::types::Vector3;
+use super::lua::scripts::register_script_tree;
+use super::lua::ScheduledTask;
+use super::physics::BodyState;
+use super::{restore_instance, GameManagerHandle};
+
+/// Version written to snapshot files; bumped on incompatible format changes
+pub const SNAPSHOT_VERSION: u32 = 2;
+
+/// Everything needed to bring an instance back after a restart
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstanceSnapshot {
+    pub version: u32,
+    pub instance_id: Uuid,
+    pub game_id: Uuid,
+    /// Source of the script the instance runs, re-run on restore
+    pub script: String,
+    pub max_players: u32,
+    pub seed: Option<u64>,
+    pub lockstep: bool,
+    pub tick: u64,
+    /// Seconds since the instance was created
+    pub elapsed: f64,
+    /// Lua clock reading (tick(), task scheduling)
+    pub script_time: f64,
+    pub status: GameStatus,
+    pub launch_params: serde_json::Value,
+    pub players: Vec<PlayerSnapshot>,
+    /// Children of Workspace
+    pub workspace: Vec<NodeSnapshot>,
+    /// Children of Players (the Player instances)
+    pub player_instances: Vec<NodeSnapshot>,
+    pub bodies: Vec<BodySnapshot>,
+    pub characters: Vec<CharacterSnapshot>,
+    pub tasks: Vec<ScheduledTask>,
+}
+
+/// A connected agent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlayerSnapshot {
+    pub agent_id: Uuid,
+    pub name: String,
+    pub reward: RewardStats,
+}
+
+/// An Instance and its descendants. Properties are the ones Clone copies,
+/// read and written through the reflection registry. References to other
+/// instances are stored as the `id` of the referenced node.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeSnapshot {
+    pub id: u64,
+    pub name: String,
+    pub class_name: ClassName,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub attributes: BTreeMap<String, AttributeValue>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub properties: BTreeMap<String, PropertyValue>,
+    /// Instance-valued properties (PrimaryPart, Part0, Adornee...), nil when
+    /// the referenced instance isn't restored
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub references: BTreeMap<String, Option<u64>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub humanoid: Option<HumanoidSnapshot>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub player: Option<PlayerDataSnapshot>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<NodeSnapshot>,
+}
+
+/// Humanoid state that isn't a property
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HumanoidSnapshot {
+    pub move_to_target: Option<Vector3>,
+    /// Set by ChangeState(Physics)
+    #[serde(default)]
+    pub physics_controlled: bool,
+}
+
+/// Player fields scripts can't set
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlayerDataSnapshot {
+    pub user_id: u64,
+    pub display_name: String,
+    pub character: Option<u64>,
+}
+
+/// Motion of a dynamic part, keyed by the part's node id
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BodySnapshot {
+    pub id: u64,
+    #[serde(flatten)]
+    pub state: BodyState,
+}
+
+/// A character controller, keyed by its HumanoidRootPart's node id
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CharacterSnapshot {
+    pub id: u64,
+    pub position: [f32; 3],
+    pub vertical_velocity: f32,
+    #[serde(default)]
+    pub knockback: [f32; 2],
+    #[serde(default)]
+    pub walk_velocity: [f32; 2],
+}
+
+impl InstanceSnapshot {
+    pub fn from_file(path: &Path) -> Result<Self, SnapshotError> {
+        let json = std::fs::read_to_string(path).map_err(|e| SnapshotError::IoError(path.to_path_buf(), e))?;
+        let snapshot: Self = serde_json::from_str(&json).map_err(SnapshotError::ParseError)?;
+        if snapshot.version != SNAPSHOT_VERSION {
+            return Err(SnapshotError::UnsupportedVersion(snapshot.version));
+        }
+        Ok(snapshot)
+    }
+
+    /// Writes the snapshot through a temporary file, so a crash mid-write never
+    /// leaves a truncated snapshot behind.
+    pub fn write_to(&self, path: &Path) -> Result<(), SnapshotError> {
+        let json = serde_json::to_vec(self).map_err(SnapshotError::ParseError)?;
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, json)
+            .and_then(|()| std::fs::rename(&tmp, path))
+            .map_err(|e| SnapshotError::IoError(path.to_path_buf(), e))
+    }
+}
+
+// =============================================================================
+// Instance tree
+// =============================================================================
+
+fn ref_id(weak: &Option<WeakInstanceRef>) -> Option<u64> {
+    weak.as_ref()?.upgrade().map(|data| data.lock().unwrap().id.0)
+}
+
+/// Captures the children of `root` and their descendants
+pub fn capture_children(root: &Instance) -> Vec<NodeSnapshot> {
+    root.get_children().iter().map(capture_node).collect()
+}
+
+fn capture_node(instance: &Instance) -> NodeSnapshot {
+    let data = instance.data.lock().unwrap();
+    let mut attributes: BTreeMap<String, AttributeValue> =
+        data.attributes.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+    attributes.retain(|_, v| !matches!(v, AttributeValue::Nil));
+    let mut tags: Vec<String> = data.tags.iter().cloned().collect();
+    tags.sort_unstable();
+
+    let mut properties = BTreeMap::new();
+    let mut references = BTreeMap::new();
+    // The name is the node's own field, matched on restore
+    for property in reflection::properties_of(data.class_name).filter(|p| p.cloned() && p.name != "Name") {
+        match property.get(&data) {
+            Some(PropertyValue::Instance(weak)) => {
+                references.insert(property.name.to_string(), ref_id(&weak));
+            }
+            Some(value) => {
+                properties.insert(property.name.to_string(), value);
+            }
+            None => {}
+        }
+    }
+
+    let node = NodeSnapshot {
+        id: data.id.0,
+        name: data.name.clone(),
+        class_name: data.class_name,
+        attributes,
+        tags,
+        properties,
+        references,
+        humanoid: data.humanoid_data.as_ref().map(|h| HumanoidSnapshot {
+            move_to_target: h.move_to_target,
+            physics_controlled: h.physics_controlled,
+        }),
+        player: data.player_data.as_ref().map(|p| PlayerDataSnapshot {
+            user_id: p.user_id,
+            display_name: p.display_name.clone(),
+            character: ref_id(&p.character),
+        }),
+        children: Vec::new(),
+    };
+    drop(data);
+
+    NodeSnapshot {
+        children: capture_children(instance),
+        ..node
+    }
+}
+
+/// Instances matched or created while applying a snapshot, keyed by node id
+pub type RestoredIds = HashMap<u64, Instance>;
+
+/// Makes the children of `parent` match `nodes`: matching children are
+/// updated, missing ones created and leftover ones destroyed.
+pub fn apply_children(
+    lua: &mlua::Lua,
+    parent: &Instance,
+    nodes: &[NodeSnapshot],
+    restored: &mut RestoredIds,
+) -> mlua::Result<()> {
+    let mut unmatched = parent.get_children();
+    for node in nodes {
+        let position = unmatched.iter().position(|child| {
+            let data = child.data.lock().unwrap();
+            data.name == node.name && data.class_name == node.class_name
+        });
+        let instance = match position {
+            Some(i) => unmatched.remove(i),
+            None => {
+                let instance = new_instance(node);
+                instance.set_parent(Some(parent));
+                register_script_tree(lua, &instance);
+                instance
+            }
+        };
+        apply_node(lua, &instance, node, restored)?;
+    }
+    for leftover in unmatched {
+        leftover.destroy(lua)?;
+    }
+    Ok(())
+}
+
+/// Updates `instance` and its descendants from `node`
+pub fn apply_node(
+    lua: &mlua::Lua,
+    instance: &Instance,
+    node: &NodeSnapshot,
+    restored: &mut RestoredIds,
+) -> mlua::Result<()> {
+    {
+        let mut data = instance.data.lock().unwrap();
+        data.attributes = node.attributes.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+
+        if let (Some(humanoid), Some(saved)) = (data.humanoid_data.as_mut(), &node.humanoid) {
+            humanoid.move_to_target = saved.move_to_target;
+            humanoid.physics_controlled = saved.physics_controlled;
+        }
+        if let (Some(player), Some(saved)) = (data.player_data.as_mut(), &node.player) {
+            player.display_name = saved.display_name.clone();
+        }
+    }
+    // In declaration order, so MaxHealth is set before Health is clamped to it
+    for property in reflection::properties_of(node.class_name) {
+        if let Some(value) = node.properties.get(property.name) {
+            property.apply(instance, value.clone());
+        }
+    }
+    if let Some(part) = instance.data.lock().unwrap().part_data.as_mut() {
+        // Bodies get their saved motion after the restore instead
+        part.position_dirty = false;
+        part.velocity_dirty = false;
+    }
+    instance.set_tags(&node.tags.iter().cloned().collect());
+    restored.insert(node.id, instance.clone());
+    apply_children(lua, instance, &node.children, restored)
+}
+
+/// A new instance of the node's class, with the same defaults Instance.new uses
+fn new_instance(node: &NodeSnapshot) -> Instance {
+    Instance::from_data(InstanceData::for_class(node.class_name, &node.name))
+}
+
+/// Points the references of restored instances (PrimaryPart, Character, weld
+/// parts, constraint attachments, Adornee) at the restored instances. Call after every tree is applied.
+pub fn resolve_references(nodes: &[NodeSnapshot], restored: &RestoredIds) {
+    let weak = |id: Option<u64>| -> Option<WeakInstanceRef> {
+        restored.get(&id?).map(|instance| Arc::downgrade(&instance.data))
+    };
+    for node in nodes {
+        if let Some(instance) = restored.get(&node.id) {
+            for (name, id) in &node.references {
+                if let Some(property) = reflection::find_property(node.class_name, name) {
+                    property.apply(instance, PropertyValue::Instance(weak(*id)));
+                }
+            }
+            let mut data = instance.data.lock().unwrap();
+            if let (Some(player), Some(saved)) = (data.player_data.as_mut(), &node.player) {
+                player.character = weak(saved.character);
+            }
+        }
+        resolve_references(&node.children, restored);
+    }
+}
+
+// =============================================================================
+// Snapshot directory (crash recovery)
+// =============================================================================
+
+/// Directory for crash-recovery snapshots, from `CLAWBLOX_SNAPSHOT_DIR`
+/// (snapshots are disabled when unset)
+pub fn snapshot_dir_from_env() -> Option<PathBuf> {
+    std::env::var_os("CLAWBLOX_SNAPSHOT_DIR").map(PathBuf::from)
+}
+
+/// How often the server writes snapshots, from `CLAWBLOX_SNAPSHOT_INTERVAL_SECS`
+/// (default 10 seconds)
+pub fn snapshot_interval_from_env() -> Duration {
+    std::env::var("CLAWBLOX_SNAPSHOT_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.trim().parse().ok())
+        .filter(|&secs| secs > 0)
+        .map(Duration::from_secs)
+        .unwrap_or(Duration::from_secs(10))
+}
+
+fn snapshot_path(dir: &Path, instance_id: Uuid) -> PathBuf {
+    dir.join(format!("{}.json", instance_id))
+}
+
+/// Writes a snapshot of every scripted instance into `dir` and deletes the
+/// snapshots of instances that no longer exist. Returns the number written.
+pub fn save_all(state: &GameManagerHandle, dir: &Path) -> usize {
+    if let Err(e) = std::fs::create_dir_all(dir) {
+        eprintln!("[Snapshot] Failed to create {}: {}", dir.display(), e);
+        return 0;
+    }
+
+    let instances: Vec<_> = state.instances.iter().map(|e| (*e.key(), e.value().clone())).collect();
+    let mut written = 0;
+    for (instance_id, handle) in &instances {
+        let snapshot = handle.read().snapshot();
+        match snapshot.and_then(|s| s.write_to(&snapshot_path(dir, *instance_id))) {
+            Ok(()) => written += 1,
+            // Instances without a script have nothing to restore
+            Err(SnapshotError::NoScript) => {}
+            Err(e) => eprintln!("[Snapshot] Failed to snapshot {}: {}", instance_id, e),
+        }
+    }
+
+    if let Ok(entries) = std::fs::read_dir(dir) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let stale = path.extension().is_some_and(|ext| ext == "json")
+                && path
+                    .file_stem()
+                    .and_then(|stem| stem.to_str()?.parse::<Uuid>().ok())
+                    .is_some_and(|id| !state.instances.contains_key(&id));
+            if stale {
+                let _ = std::fs::remove_file(&path);
+            }
+        }
+    }
+
+    written
+}
+
+/// Restores every snapshot in `dir`. Returns the ids of the restored instances;
+/// snapshots that fail to restore are logged and deleted.
+pub fn restore_all(state: &GameManagerHandle, dir: &Path) -> Vec<Uuid> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+
+    let mut restored = Vec::new();
+    for path in paths {
+        match InstanceSnapshot::from_file(&path).and_then(|s| restore_instance(state, &s)) {
+            Ok(instance_id) => restored.push(instance_id),
+            Err(e) => {
+                eprintln!("[Snapshot] Failed to restore {}: {}", path.display(), e);
+                let _ = std::fs::remove_file(&path);
+            }
+        }
+    }
+    restored
+}
+
+/// Errors that can occur when taking, saving or restoring a snapshot
+#[derive(Debug)]
+pub enum SnapshotError {
+    IoError(PathBuf, std::io::Error),
+    ParseError(serde_json::Error),
+    UnsupportedVersion(u32),
+    NoScript,
+    AlreadyLoaded,
+    Lua(mlua::Error),
+    Halted(String),
+}
+
+impl std::fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SnapshotError::IoError(path, e) => write!(f, "Failed to access {}: {}", path.display(), e),
+            SnapshotError::ParseError(e) => write!(f, "Invalid snapshot: {}", e),
+            SnapshotError::UnsupportedVersion(v) => {
+                write!(f, "Unsupported snapshot version {} (expected {})", v, SNAPSHOT_VERSION)
+            }
+            SnapshotError::NoScript => write!(f, "Instance has no script loaded"),
+            SnapshotError::AlreadyLoaded => write!(f, "Snapshots can only be restored into a new instance"),
+            SnapshotError::Lua(e) => write!(f, "Lua error: {}", e),
+            SnapshotError::Halted(err) => write!(f, "Game halted while restoring: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
@@ -2,6 +2,7 @@ mod api;
 
 use clawblox::{db, game, r2};
 use game::instance::ErrorMode;
+use game::snapshot;
 
 use axum::Router;
 use std::net::SocketAddr;
@@ -18,11 +19,6 @@ async fn main() {
         .expect("Failed to connect to database");
     let pool = std::sync::Arc::new(pool);
 
-    // Clean up orphaned instances from previous server session
-    if let Err(e) = db::reconcile_instances(&pool).await {
-        eprintln!("[Startup] Warning: Failed to reconcile instances: {}", e);
-    }
-
     // Initialize R2 asset storage (optional — asset uploads disabled if not configured)
     let r2_client = r2::R2Client::from_env();
     if r2_client.is_some() {
@@ -33,6 +29,35 @@ async fn main() {
 
     let (game_manager, game_handle) = GameManager::new(60, pool.clone(), ErrorMode::Continue);
 
+    // Bring back the instances snapshotted before the last shutdown or crash
+    let snapshot_dir = snapshot::snapshot_dir_from_env();
+    let restored = match &snapshot_dir {
+        Some(dir) => {
+            let restored = snapshot::restore_all(&game_handle, dir);
+            println!("Snapshots: {} (restored {} instances)", dir.display(), restored.len());
+            restored
+        }
+        None => Vec::new(),
+    };
+
+    // Clean up orphaned instances from previous server session
+    if let Err(e) = db::reconcile_instances(&pool, &restored).await {
+        eprintln!("[Startup] Warning: Failed to reconcile instances: {}", e);
+    }
+
+    if let Some(dir) = snapshot_dir {
+        let snapshot_handle = game_handle.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(snapshot::snapshot_interval_from_env());
+            loop {
+                interval.tick().await;
+                let handle = snapshot_handle.clone();
+                let dir = dir.clone();
+                let _ = tokio::task::spawn_blocking(move || snapshot::save_all(&handle, &dir)).await;
+            }
+        });
+    }
+
     // Clone handle for background sync task
     let sync_handle = game_handle.clone();
     let sync_pool = pool.clone();
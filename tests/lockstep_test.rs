@@ -0,0 +1,294 @@
+//! Tests for lockstep instances
+//!
+//! Covers:
+//! - a lockstep round runs only once every player has submitted a step
+//! - the round advances the smallest tick count submitted
+//! - a leaving player releases the agents waiting on it
+//! - a round that waits out its timeout runs without the stalled agents
+//! - matchmaking never mixes realtime and lockstep instances, or different seeds
+//! - step results carry RewardService rewards and episode ends
+
+use std::time::{Duration, Instant};
+
+use tokio::sync::oneshot::{self, error::TryRecvError};
+use uuid::Uuid;
+
+use clawblox::game::{
+    self,
+    instance::ErrorMode,
+    lockstep::{StepAction, StepRequest, StepResult},
+    GameManager, GameManagerHandle, InstanceOptions,
+};
+
+const SCRIPT: &str = r#"
+    local floor = Instance.new("Part")
+    floor.Name = "Floor"
+    floor.Size = Vector3.new(100, 2, 100)
+    floor.Position = Vector3.new(0, -1, 0)
+    floor.Anchored = true
+    floor.Parent = Workspace
+"#;
+
+fn lockstep_instance(handle: &GameManagerHandle, game_id: Uuid) -> Uuid {
+    let options = InstanceOptions {
+        lockstep: true,
+        ..Default::default()
+    };
+    game::create_instance(handle, game_id, 8, Some(SCRIPT), options)
+}
+
+fn step(ticks: u32) -> StepRequest {
+    StepRequest {
+        actions: Vec::new(),
+        ticks,
+    }
+}
+
+fn instance_tick(handle: &GameManagerHandle, instance_id: Uuid) -> u64 {
+    handle.instances.get(&instance_id).unwrap().read().tick
+}
+
+#[test]
+fn test_single_agent_step_advances_requested_ticks() {
+    let (_manager, handle) = GameManager::new_without_db(60, ErrorMode::Continue);
+    let game_id = Uuid::new_v4();
+    let instance_id = lockstep_instance(&handle, game_id);
+
+    let agent = Uuid::new_v4();
+    game::join_instance(&handle, instance_id, game_id, agent, "Solo").unwrap();
+
+    let request = StepRequest {
+        actions: vec![StepAction {
+            input_type: "MoveTo".to_string(),
+            data: serde_json::json!({ "position": [10.0, 0.0, 10.0] }),
+        }],
+        ticks: 5,
+    };
+    let mut rx = game::submit_step(&handle, game_id, agent, request).unwrap();
+    let result = rx.try_recv().expect("a lone agent completes the round itself");
+
+    assert_eq!(result.ticks, 5);
+    assert!(!result.done);
+    assert_eq!(result.observation.unwrap().tick, 5);
+    assert_eq!(instance_tick(&handle, instance_id), 5);
+}
+
+#[test]
+fn test_round_waits_for_every_agent() {
+    let (_manager, handle) = GameManager::new_without_db(60, ErrorMode::Continue);
+    let game_id = Uuid::new_v4();
+    let instance_id = lockstep_instance(&handle, game_id);
+
+    let agent_a = Uuid::new_v4();
+    let agent_b = Uuid::new_v4();
+    game::join_instance(&handle, instance_id, game_id, agent_a, "A").unwrap();
+    game::join_instance(&handle, instance_id, game_id, agent_b, "B").unwrap();
+
+    let mut rx_a = game::submit_step(&handle, game_id, agent_a, step(4)).unwrap();
+    assert_eq!(rx_a.try_recv().unwrap_err(), TryRecvError::Empty);
+    assert_eq!(instance_tick(&handle, instance_id), 0, "Instance must not advance early");
+
+    let mut rx_b = game::submit_step(&handle, game_id, agent_b, step(2)).unwrap();
+    let result_a = rx_a.try_recv().unwrap();
+    let result_b = rx_b.try_recv().unwrap();
+
+    // The round runs the smaller of the two requests
+    assert_eq!(result_a.ticks, 2);
+    assert_eq!(result_b.ticks, 2);
+    assert_eq!(instance_tick(&handle, instance_id), 2);
+}
+
+#[test]
+fn test_leaving_agent_releases_round() {
+    let (_manager, handle) = GameManager::new_without_db(60, ErrorMode::Continue);
+    let game_id = Uuid::new_v4();
+    let instance_id = lockstep_instance(&handle, game_id);
+
+    let agent_a = Uuid::new_v4();
+    let agent_b = Uuid::new_v4();
+    game::join_instance(&handle, instance_id, game_id, agent_a, "A").unwrap();
+    game::join_instance(&handle, instance_id, game_id, agent_b, "B").unwrap();
+
+    let mut rx_a = game::submit_step(&handle, game_id, agent_a, step(3)).unwrap();
+    assert_eq!(rx_a.try_recv().unwrap_err(), TryRecvError::Empty);
+
+    game::leave_instance(&handle, instance_id, agent_b).unwrap();
+
+    let result = rx_a.try_recv().unwrap();
+    assert_eq!(result.ticks, 3);
+    assert!(!result.done);
+}
+
+/// Polls a step receiver until its round has run
+fn wait_for_round(rx: &mut oneshot::Receiver<StepResult>, timeout: Duration) -> StepResult {
+    let start = Instant::now();
+    loop {
+        match rx.try_recv() {
+            Ok(result) => return result,
+            Err(TryRecvError::Empty) if start.elapsed() < timeout => {
+                std::thread::sleep(Duration::from_millis(5))
+            }
+            Err(err) => panic!("Round did not run: {:?}", err),
+        }
+    }
+}
+
+#[test]
+fn test_round_times_out_without_stalled_agent() {
+    let (manager, handle) = GameManager::new_without_db(60, ErrorMode::Continue);
+    let game_id = Uuid::new_v4();
+    let instance_id = lockstep_instance(&handle, game_id);
+    handle
+        .instances
+        .get(&instance_id)
+        .unwrap()
+        .write()
+        .set_lockstep_round_timeout(Duration::from_millis(100));
+
+    let agent_a = Uuid::new_v4();
+    let agent_b = Uuid::new_v4();
+    game::join_instance(&handle, instance_id, game_id, agent_a, "A").unwrap();
+    game::join_instance(&handle, instance_id, game_id, agent_b, "B").unwrap();
+
+    // The game loop runs overdue rounds; B never submits
+    std::thread::spawn(move || manager.run());
+    let mut rx_a = game::submit_step(&handle, game_id, agent_a, step(3)).unwrap();
+    assert_eq!(rx_a.try_recv().unwrap_err(), TryRecvError::Empty);
+
+    let result = wait_for_round(&mut rx_a, Duration::from_secs(5));
+    assert_eq!(result.ticks, 3);
+    assert!(!result.done);
+    assert_eq!(instance_tick(&handle, instance_id), 3);
+
+    // B is still in the instance, and its late step opens the next round
+    let mut rx_b = game::submit_step(&handle, game_id, agent_b, step(2)).unwrap();
+    assert_eq!(rx_b.try_recv().unwrap_err(), TryRecvError::Empty);
+    let mut rx_a = game::submit_step(&handle, game_id, agent_a, step(2)).unwrap();
+    assert_eq!(rx_a.try_recv().unwrap().ticks, 2);
+    assert_eq!(rx_b.try_recv().unwrap().ticks, 2);
+    assert_eq!(instance_tick(&handle, instance_id), 5);
+}
+
+#[test]
+fn test_leaving_agent_cancels_own_step() {
+    let (_manager, handle) = GameManager::new_without_db(60, ErrorMode::Continue);
+    let game_id = Uuid::new_v4();
+    let instance_id = lockstep_instance(&handle, game_id);
+
+    let agent_a = Uuid::new_v4();
+    let agent_b = Uuid::new_v4();
+    game::join_instance(&handle, instance_id, game_id, agent_a, "A").unwrap();
+    game::join_instance(&handle, instance_id, game_id, agent_b, "B").unwrap();
+
+    let mut rx_a = game::submit_step(&handle, game_id, agent_a, step(1)).unwrap();
+    game::leave_instance(&handle, instance_id, agent_a).unwrap();
+
+    assert_eq!(rx_a.try_recv().unwrap_err(), TryRecvError::Closed);
+    assert_eq!(instance_tick(&handle, instance_id), 0);
+}
+
+#[test]
+fn test_step_rejected_for_realtime_instance() {
+    let (_manager, handle) = GameManager::new_without_db(60, ErrorMode::Continue);
+    let game_id = Uuid::new_v4();
+    let result = game::find_or_create_instance(&handle, game_id, 8, Some(SCRIPT));
+
+    let agent = Uuid::new_v4();
+    game::join_instance(&handle, result.instance_id, game_id, agent, "Realtime").unwrap();
+
+    let err = game::submit_step(&handle, game_id, agent, step(1)).unwrap_err();
+    assert!(err.contains("lockstep"), "Unexpected error: {}", err);
+}
+
+#[test]
+fn test_matchmaking_keeps_modes_apart() {
+    let (_manager, handle) = GameManager::new_without_db(60, ErrorMode::Continue);
+    let game_id = Uuid::new_v4();
+
+    let realtime = game::find_or_create_instance(&handle, game_id, 8, None);
+    let options = InstanceOptions {
+        lockstep: true,
+        ..Default::default()
+    };
+    let lockstep = game::find_or_create_instance_with_options(&handle, game_id, 8, None, options);
+    assert!(lockstep.created, "Lockstep join must not land in a realtime instance");
+    assert_ne!(realtime.instance_id, lockstep.instance_id);
+
+    let again = game::find_or_create_instance_with_options(&handle, game_id, 8, None, options);
+    assert_eq!(again.instance_id, lockstep.instance_id);
+
+    let seeded = InstanceOptions {
+        seed: Some(42),
+        ..options
+    };
+    let first = game::find_or_create_instance_with_options(&handle, game_id, 8, None, seeded);
+    assert!(first.created, "A seeded join must not land in an unseeded instance");
+    assert_eq!(handle.instances.get(&first.instance_id).unwrap().read().seed, Some(42));
+    let again = game::find_or_create_instance_with_options(&handle, game_id, 8, None, seeded);
+    assert_eq!(again.instance_id, first.instance_id);
+}
+
+#[test]
+fn test_step_reports_rewards_and_episode_end() {
+    let (_manager, handle) = GameManager::new_without_db(60, ErrorMode::Continue);
+    let game_id = Uuid::new_v4();
+    let script = format!(
+        "{}{}",
+        SCRIPT,
+        r#"
+        local RewardService = game:GetService("RewardService")
+        local AgentInputService = game:GetService("AgentInputService")
+
+        AgentInputService.InputReceived:Connect(function(player, inputType, data)
+            if inputType == "Score" then
+                RewardService:AddReward(player, data.amount, "score")
+            elseif inputType == "Finish" then
+                RewardService:EndEpisode(player, "win")
+            end
+        end)
+        "#
+    );
+    let options = InstanceOptions {
+        lockstep: true,
+        ..Default::default()
+    };
+    let instance_id = game::create_instance(&handle, game_id, 8, Some(&script), options);
+
+    let agent = Uuid::new_v4();
+    game::join_instance(&handle, instance_id, game_id, agent, "Scorer").unwrap();
+
+    let score = |amount: f64| StepRequest {
+        actions: vec![StepAction {
+            input_type: "Score".to_string(),
+            data: serde_json::json!({ "amount": amount }),
+        }],
+        ticks: 1,
+    };
+
+    let first = game::submit_step(&handle, game_id, agent, score(1.5)).unwrap().try_recv().unwrap();
+    assert_eq!(first.reward, 1.5);
+    let second = game::submit_step(&handle, game_id, agent, score(2.0)).unwrap().try_recv().unwrap();
+    assert_eq!(second.reward, 2.0);
+    assert!(!second.done);
+
+    let stats = second.observation.unwrap().reward;
+    assert_eq!(stats.total, 3.5);
+    assert_eq!(stats.count, 2);
+    assert_eq!(stats.by_reason["score"], 3.5);
+
+    let finish = StepRequest {
+        actions: vec![StepAction {
+            input_type: "Finish".to_string(),
+            data: serde_json::Value::Null,
+        }],
+        ticks: 1,
+    };
+    let last = game::submit_step(&handle, game_id, agent, finish).unwrap().try_recv().unwrap();
+    assert_eq!(last.reward, 0.0);
+    assert!(last.done);
+    assert_eq!(last.observation.unwrap().reward.outcome.as_deref(), Some("win"));
+
+    // /observe carries the same stats
+    let observation = game::get_observation(&handle, game_id, agent).unwrap();
+    assert!(observation.reward.done);
+}
@@ -0,0 +1,92 @@
+//! Tests for crash recovery from instance snapshots
+//!
+//! Covers:
+//! - save_all writes one snapshot per scripted instance and drops stale ones
+//! - restore_all brings instances back under their ids with players routed to them
+//! - unreadable snapshots are skipped and removed
+
+use std::path::PathBuf;
+use uuid::Uuid;
+
+use clawblox::game::{self, instance::ErrorMode, snapshot, GameManager, InstanceOptions};
+
+const SCRIPT: &str = r#"
+    local floor = Instance.new("Part")
+    floor.Name = "Floor"
+    floor.Size = Vector3.new(100, 2, 100)
+    floor.Position = Vector3.new(0, -1, 0)
+    floor.Anchored = true
+    floor.Parent = Workspace
+
+    game:GetService("Players").PlayerAdded:Connect(function(player)
+        player:SetAttribute("Coins", 0)
+    end)
+
+    game:GetService("AgentInputService").InputReceived:Connect(function(player, inputType)
+        if inputType == "Collect" then
+            player:SetAttribute("Coins", player:GetAttribute("Coins") + 5)
+        end
+    end)
+"#;
+
+fn snapshot_dir() -> PathBuf {
+    std::env::temp_dir().join(format!("clawblox-snapshots-{}", Uuid::new_v4()))
+}
+
+#[test]
+fn test_restart_restores_instances_from_snapshots() {
+    let dir = snapshot_dir();
+    let game_id = Uuid::new_v4();
+    let agent = Uuid::new_v4();
+
+    let instance_id = {
+        let (_manager, handle) = GameManager::new_without_db(60, ErrorMode::Continue);
+        let instance_id = game::create_instance(&handle, game_id, 8, Some(SCRIPT), InstanceOptions::default());
+        game::join_instance(&handle, instance_id, game_id, agent, "Collector").unwrap();
+        game::queue_input(&handle, game_id, agent, "Collect".to_string(), serde_json::Value::Null).unwrap();
+        handle.instances.get(&instance_id).unwrap().write().tick();
+
+        // Instances without a script have nothing to restore
+        game::create_instance(&handle, Uuid::new_v4(), 8, None, InstanceOptions::default());
+
+        assert_eq!(snapshot::save_all(&handle, &dir), 1);
+        instance_id
+    };
+
+    // A fresh manager stands in for the restarted server
+    let (_manager, handle) = GameManager::new_without_db(60, ErrorMode::Continue);
+    let restored = snapshot::restore_all(&handle, &dir);
+    assert_eq!(restored, vec![instance_id]);
+
+    let observation = game::get_observation(&handle, game_id, agent).expect("Agent is routed to the restored instance");
+    assert_eq!(observation.tick, 1);
+    assert_eq!(observation.player.attributes["Coins"], serde_json::json!(5.0));
+
+    // Script connections work again after the restore
+    game::queue_input(&handle, game_id, agent, "Collect".to_string(), serde_json::Value::Null).unwrap();
+    handle.instances.get(&instance_id).unwrap().write().tick();
+    let observation = handle.instances.get(&instance_id).unwrap().read().get_player_observation(agent).unwrap();
+    assert_eq!(observation.player.attributes["Coins"], serde_json::json!(10.0));
+
+    // Snapshots of instances that are gone are cleaned up on the next save
+    game::destroy_instance(&handle, instance_id);
+    assert_eq!(snapshot::save_all(&handle, &dir), 0);
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_unreadable_snapshot_is_skipped() {
+    let dir = snapshot_dir();
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(format!("{}.json", Uuid::new_v4()));
+    std::fs::write(&path, "{\"version\": 1").unwrap();
+
+    let (_manager, handle) = GameManager::new_without_db(60, ErrorMode::Continue);
+    assert!(snapshot::restore_all(&handle, &dir).is_empty());
+    assert!(handle.instances.is_empty());
+    assert!(!path.exists(), "Broken snapshot should be removed");
+
+    let _ = std::fs::remove_dir_all(&dir);
+}